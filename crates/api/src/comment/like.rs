@@ -4,8 +4,14 @@ use lemmy_api_common::{
   build_response::build_comment_response,
   comment::{CommentResponse, CreateCommentLike},
   context::LemmyContext,
+  metrics::{count_vote, VoteOrigin},
   send_activity::{ActivityChannel, SendActivityData},
-  utils::{check_bot_account, check_community_user_action, check_downvotes_enabled},
+  utils::{
+    check_bot_account,
+    check_community_user_action,
+    check_downvotes_enabled,
+    check_vote_cooldown,
+  },
 };
 use lemmy_db_schema::{
   newtypes::LocalUserId,
@@ -33,6 +39,7 @@ pub async fn like_comment(
   // Don't do a downvote if site has downvotes disabled
   check_downvotes_enabled(data.score, &local_site)?;
   check_bot_account(&local_user_view.person)?;
+  check_vote_cooldown(&local_user_view.person, &local_site)?;
 
   let comment_id = data.comment_id;
   let orig_comment = CommentView::read(
@@ -71,7 +78,7 @@ pub async fn like_comment(
   // Remove any likes first
   let person_id = local_user_view.person.id;
 
-  CommentLike::remove(&mut context.pool(), person_id, comment_id).await?;
+  let replaces_vote = CommentLike::remove(&mut context.pool(), person_id, comment_id).await? > 0;
 
   // Only add the like if the score isnt 0
   let do_add = like_form.score != 0 && (like_form.score == 1 || like_form.score == -1);
@@ -80,17 +87,22 @@ pub async fn like_comment(
       .await
       .with_lemmy_type(LemmyErrorType::CouldntLikeComment)?;
   }
+  count_vote(data.score, VoteOrigin::Local);
 
-  ActivityChannel::submit_activity(
-    SendActivityData::LikePostOrComment {
-      object_id: orig_comment.comment.ap_id,
-      actor: local_user_view.person.clone(),
-      community: orig_comment.community,
-      score: data.score,
-    },
-    &context,
-  )
-  .await?;
+  // Votes of shadow voters are only visible to themselves
+  if !local_user_view.person.shadow_vote {
+    ActivityChannel::submit_activity(
+      SendActivityData::LikePostOrComment {
+        object_id: orig_comment.comment.ap_id,
+        actor: local_user_view.person.clone(),
+        community: orig_comment.community,
+        score: data.score,
+        replaces_vote,
+      },
+      &context,
+    )
+    .await?;
+  }
 
   Ok(Json(
     build_comment_response(
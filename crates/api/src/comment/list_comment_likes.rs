@@ -2,8 +2,9 @@ use actix_web::web::{Data, Json, Query};
 use lemmy_api_common::{
   comment::{ListCommentLikes, ListCommentLikesResponse},
   context::LemmyContext,
-  utils::is_mod_or_admin,
+  utils::check_can_list_votes,
 };
+use lemmy_db_schema::source::local_site::LocalSite;
 use lemmy_db_views::structs::{CommentView, LocalUserView, VoteView};
 use lemmy_utils::{error::LemmyResult, LemmyErrorType};
 
@@ -22,10 +23,12 @@ pub async fn list_comment_likes(
   .await?
   .ok_or(LemmyErrorType::CouldntFindComment)?;
 
-  is_mod_or_admin(
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  check_can_list_votes(
     &mut context.pool(),
-    &local_user_view.person,
+    &local_user_view,
     comment_view.community.id,
+    &local_site,
   )
   .await?;
 
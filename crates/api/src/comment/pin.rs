@@ -0,0 +1,86 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  build_response::build_post_response,
+  comment::PinComment,
+  context::LemmyContext,
+  post::PostResponse,
+  send_activity::{update_post_activity, ActivityChannel},
+  utils::{check_community_mod_action, check_community_user_action},
+};
+use lemmy_db_schema::{
+  source::{
+    comment::Comment,
+    local_site::LocalSite,
+    post::{Post, PostUpdateForm},
+  },
+  traits::Crud,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+#[tracing::instrument(skip(context))]
+pub async fn pin_comment(
+  data: Json<PinComment>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<PostResponse>> {
+  let comment = Comment::read(&mut context.pool(), data.comment_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindComment)?;
+  if comment.deleted || comment.removed {
+    Err(LemmyErrorType::CouldntFindComment)?
+  }
+  let post_id = comment.post_id;
+  let orig_post = Post::read(&mut context.pool(), post_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindPost)?;
+
+  check_community_user_action(
+    &local_user_view.person,
+    orig_post.community_id,
+    &mut context.pool(),
+  )
+  .await?;
+
+  // Like edits, the pin is federated by the post creator or, for local posts, by a mod
+  let is_creator = Post::is_post_creator(local_user_view.person.id, orig_post.creator_id);
+  if !is_creator {
+    if !orig_post.local {
+      Err(LemmyErrorType::NoPostEditAllowed)?
+    }
+    check_community_mod_action(
+      &local_user_view.person,
+      orig_post.community_id,
+      false,
+      &mut context.pool(),
+    )
+    .await?;
+  }
+
+  // Unpinning a comment which isn't pinned leaves the current pin in place
+  let pinned_comment_id = if data.pinned {
+    Some(comment.id)
+  } else if orig_post.pinned_comment_id == Some(comment.id) {
+    None
+  } else {
+    orig_post.pinned_comment_id
+  };
+  if pinned_comment_id != orig_post.pinned_comment_id {
+    let form = PostUpdateForm {
+      pinned_comment_id: Some(pinned_comment_id),
+      updated_by: Some((!is_creator).then_some(local_user_view.person.id)),
+      ..Default::default()
+    };
+    let post = Post::update(&mut context.pool(), post_id, &form)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdatePost)?;
+
+    let local_site = LocalSite::read(&mut context.pool()).await?;
+    if let Some(activity) = update_post_activity(post, &local_site) {
+      ActivityChannel::submit_activity(activity, &context).await?;
+    }
+  }
+
+  build_post_response(&context, orig_post.community_id, local_user_view, post_id).await
+}
@@ -54,7 +54,7 @@ pub async fn create_comment_report(
   check_comment_deleted_or_removed(&comment_view.comment)?;
 
   let report_form = CommentReportForm {
-    creator_id: person_id,
+    creator_id: Some(person_id),
     comment_id,
     original_comment_text: comment_view.comment.content,
     reason,
@@ -71,7 +71,7 @@ pub async fn create_comment_report(
   // Email the admins
   if local_site.reports_email_admins {
     send_new_report_email_to_admins(
-      &comment_report_view.creator.name,
+      &local_user_view.person.name,
       &comment_report_view.comment_creator.name,
       &mut context.pool(),
       context.settings(),
@@ -0,0 +1,39 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  community::ApproveCommunityQuarantine,
+  context::LemmyContext,
+  utils::is_admin,
+  SuccessResponse,
+};
+use lemmy_db_schema::{
+  source::{
+    community::{Community, CommunityUpdateForm},
+    post::Post,
+  },
+  traits::Crud,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+#[tracing::instrument(skip(context))]
+pub async fn approve_community_quarantine(
+  data: Json<ApproveCommunityQuarantine>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  // Only admins can end the quarantine
+  is_admin(&local_user_view)?;
+
+  let community_form = CommunityUpdateForm {
+    quarantined_until: Some(None),
+    ..Default::default()
+  };
+  Community::update(&mut context.pool(), data.community_id, &community_form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdateCommunity)?;
+
+  Post::restore_quarantined(&mut context.pool(), data.community_id).await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
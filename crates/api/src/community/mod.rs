@@ -1,6 +1,8 @@
 pub mod add_mod;
+pub mod approve_quarantine;
 pub mod ban;
 pub mod block;
 pub mod follow;
 pub mod hide;
+pub mod post_frequency;
 pub mod transfer;
@@ -0,0 +1,47 @@
+use actix_web::web::{Data, Json, Query};
+use chrono::Duration;
+use lemmy_api_common::{
+  community::{GetCommunityPostFrequency, GetCommunityPostFrequencyResponse, PostFrequencyBucket},
+  context::LemmyContext,
+  utils::is_mod_or_admin,
+};
+use lemmy_db_schema::{source::post::Post, PostFrequencyInterval};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+/// Longest time range which can be requested, to keep the number of buckets reasonable.
+const MAX_RANGE_DAYS: i64 = 3 * 365;
+
+/// Returns the number of posts per day or week in a community, so that mods can plan their
+/// capacity.
+#[tracing::instrument(skip(context))]
+pub async fn get_community_post_frequency(
+  data: Query<GetCommunityPostFrequency>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<GetCommunityPostFrequencyResponse>> {
+  if data.start >= data.end || data.end - data.start > Duration::days(MAX_RANGE_DAYS) {
+    Err(LemmyErrorType::InvalidTimeWindow)?
+  }
+  is_mod_or_admin(
+    &mut context.pool(),
+    &local_user_view.person,
+    data.community_id,
+  )
+  .await?;
+
+  let interval = data.interval.unwrap_or(PostFrequencyInterval::Day);
+  let buckets = Post::frequency_in_community(
+    &mut context.pool(),
+    data.community_id,
+    interval,
+    data.start,
+    data.end,
+  )
+  .await?
+  .into_iter()
+  .map(|(start, post_count)| PostFrequencyBucket { start, post_count })
+  .collect();
+
+  Ok(Json(GetCommunityPostFrequencyResponse { buckets }))
+}
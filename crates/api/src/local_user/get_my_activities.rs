@@ -0,0 +1,33 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{GetMyActivities, GetMyActivitiesResponse, MyActivity},
+};
+use lemmy_db_schema::source::activity::SentActivity;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyResult;
+
+#[tracing::instrument(skip(context))]
+pub async fn get_my_activities(
+  data: Query<GetMyActivities>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<GetMyActivitiesResponse>> {
+  // Only ever list activities of the authenticated user
+  let activities = SentActivity::list_by_actor(
+    &mut context.pool(),
+    &local_user_view.person.actor_id,
+    data.page,
+    data.limit,
+  )
+  .await?
+  .into_iter()
+  .map(|a| MyActivity {
+    ap_id: a.ap_id,
+    data: a.data,
+    published: a.published,
+  })
+  .collect();
+
+  Ok(Json(GetMyActivitiesResponse { activities }))
+}
@@ -8,15 +8,18 @@ pub mod change_password;
 pub mod change_password_after_reset;
 pub mod generate_totp_secret;
 pub mod get_captcha;
+pub mod get_my_activities;
 pub mod list_banned;
 pub mod list_logins;
 pub mod list_media;
 pub mod login;
 pub mod logout;
 pub mod notifications;
+pub mod post_communities;
 pub mod report_count;
 pub mod reset_password;
 pub mod save_settings;
+pub mod shadow_vote_person;
 pub mod update_totp;
 pub mod validate_auth;
 pub mod verify_email;
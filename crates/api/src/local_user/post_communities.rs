@@ -0,0 +1,39 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{GetPersonPostCommunities, GetPersonPostCommunitiesResponse, PersonPostCommunity},
+  utils::check_community_mod_of_any_or_admin_action,
+};
+use lemmy_db_schema::{
+  source::{person::Person, post::Post},
+  traits::ApubActor,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+/// Lists the communities in which a person has posts on this instance, so that mods can see where
+/// else a new poster is active. Mods only see the communities which they moderate.
+#[tracing::instrument(skip(context))]
+pub async fn get_person_post_communities(
+  data: Query<GetPersonPostCommunities>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<GetPersonPostCommunitiesResponse>> {
+  check_community_mod_of_any_or_admin_action(&local_user_view, &mut context.pool()).await?;
+
+  let person = Person::read_from_apub_id(&mut context.pool(), &data.actor_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindPerson)?;
+  // Mods only see the communities which they moderate
+  let moderator_id = (!local_user_view.local_user.admin).then_some(local_user_view.person.id);
+  let communities = Post::communities_of_creator(&mut context.pool(), person.id, moderator_id)
+    .await?
+    .into_iter()
+    .map(|(community, post_count)| PersonPostCommunity {
+      community,
+      post_count,
+    })
+    .collect();
+
+  Ok(Json(GetPersonPostCommunitiesResponse { communities }))
+}
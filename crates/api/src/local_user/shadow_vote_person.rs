@@ -0,0 +1,34 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::ShadowVotePerson,
+  utils::is_admin,
+  SuccessResponse,
+};
+use lemmy_db_schema::{
+  source::person::{Person, PersonUpdateForm},
+  traits::Crud,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+#[tracing::instrument(skip(context))]
+pub async fn shadow_vote_person(
+  data: Json<ShadowVotePerson>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+
+  // Existing votes are removed from scores or added back by a database trigger
+  let form = PersonUpdateForm {
+    shadow_vote: Some(data.shadow_vote),
+    ..Default::default()
+  };
+  Person::update(&mut context.pool(), data.person_id, &form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdateUser)?;
+
+  Ok(Json(SuccessResponse::default()))
+}
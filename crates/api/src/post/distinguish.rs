@@ -0,0 +1,68 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  build_response::build_post_response,
+  context::LemmyContext,
+  post::{DistinguishPost, PostResponse},
+  send_activity::{update_post_activity, ActivityChannel},
+  utils::{check_community_mod_action, check_community_user_action},
+};
+use lemmy_db_schema::{
+  source::{
+    local_site::LocalSite,
+    post::{Post, PostUpdateForm},
+  },
+  traits::Crud,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+#[tracing::instrument(skip(context))]
+pub async fn distinguish_post(
+  data: Json<DistinguishPost>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<PostResponse>> {
+  let post_id = data.post_id;
+  let orig_post = Post::read(&mut context.pool(), post_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindPost)?;
+
+  check_community_user_action(
+    &local_user_view.person,
+    orig_post.community_id,
+    &mut context.pool(),
+  )
+  .await?;
+
+  // Verify that only the creator can distinguish
+  if local_user_view.person.id != orig_post.creator_id {
+    Err(LemmyErrorType::NoPostEditAllowed)?
+  }
+
+  // Verify that only a mod or admin can distinguish a post
+  check_community_mod_action(
+    &local_user_view.person,
+    orig_post.community_id,
+    false,
+    &mut context.pool(),
+  )
+  .await?;
+
+  // Update the post
+  let form = PostUpdateForm {
+    distinguished: Some(data.distinguished),
+    ..Default::default()
+  };
+  let post = Post::update(&mut context.pool(), post_id, &form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdatePost)?;
+
+  // The flag is part of the post object, so it federates as a regular edit
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  if let Some(activity) = update_post_activity(post, &local_site) {
+    ActivityChannel::submit_activity(activity, &context).await?;
+  }
+
+  build_post_response(&context, orig_post.community_id, local_user_view, post_id).await
+}
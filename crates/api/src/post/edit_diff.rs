@@ -0,0 +1,39 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  post::{GetPostEditDiff, GetPostEditDiffResponse},
+  utils::is_mod_or_admin,
+};
+use lemmy_db_schema::{
+  source::{post::Post, post_previous_version::PostPreviousVersion},
+  traits::Crud,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+/// Returns title and body of a post before and after its last edit, so that mods can review what
+/// changed.
+#[tracing::instrument(skip(context))]
+pub async fn get_post_edit_diff(
+  data: Query<GetPostEditDiff>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<GetPostEditDiffResponse>> {
+  let post = Post::read(&mut context.pool(), data.post_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindPost)?;
+  is_mod_or_admin(
+    &mut context.pool(),
+    &local_user_view.person,
+    post.community_id,
+  )
+  .await?;
+
+  let previous = PostPreviousVersion::read(&mut context.pool(), post.id).await?;
+
+  Ok(Json(GetPostEditDiffResponse {
+    previous,
+    name: post.name,
+    body: post.body,
+  }))
+}
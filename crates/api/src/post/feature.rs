@@ -44,8 +44,14 @@ pub async fn feature_post(
   // Update the post
   let post_id = data.post_id;
   let new_post: PostUpdateForm = if data.feature_type == PostFeatureType::Community {
+    let featured_position = if data.featured {
+      Post::next_featured_position(&mut context.pool(), orig_post.community_id).await?
+    } else {
+      0
+    };
     PostUpdateForm {
       featured_community: Some(data.featured),
+      featured_position: Some(featured_position),
       ..Default::default()
     }
   } else {
@@ -0,0 +1,52 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  post::{GetPostsByInstance, GetPostsByInstanceResponse},
+  utils::{check_community_mod_of_any_or_admin_action, proxy_post_attachments},
+};
+use lemmy_db_schema::{source::instance::Instance, ListingType, SortType};
+use lemmy_db_views::{
+  post_view::PostQuery,
+  structs::{LocalUserView, SiteView},
+};
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+/// Lists posts which originated on the given instance. Admins see all of them, mods only those in
+/// communities which they moderate.
+#[tracing::instrument(skip(context))]
+pub async fn get_posts_by_instance(
+  data: Query<GetPostsByInstance>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<GetPostsByInstanceResponse>> {
+  check_community_mod_of_any_or_admin_action(&local_user_view, &mut context.pool()).await?;
+
+  let site_view = SiteView::read_local(&mut context.pool())
+    .await?
+    .ok_or(LemmyErrorType::LocalSiteNotSetup)?;
+  let instance = Instance::read(&mut context.pool(), data.instance_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindInstance)?;
+
+  let listing_type = if local_user_view.local_user.admin {
+    ListingType::All
+  } else {
+    ListingType::ModeratorView
+  };
+
+  let mut posts = PostQuery {
+    local_user: Some(&local_user_view.local_user),
+    listing_type: Some(listing_type),
+    sort: Some(SortType::New),
+    origin_domain: Some(instance.domain),
+    page: data.page,
+    limit: data.limit,
+    ..Default::default()
+  }
+  .list(&site_view.site, &mut context.pool())
+  .await
+  .with_lemmy_type(LemmyErrorType::CouldntGetPosts)?;
+  proxy_post_attachments(&mut posts, &context).await?;
+
+  Ok(Json(GetPostsByInstanceResponse { posts }))
+}
@@ -0,0 +1,67 @@
+use actix_web::web::{Data, Json, Query};
+use chrono::{Duration, Utc};
+use lemmy_api_common::{
+  context::LemmyContext,
+  post::{GetInstanceDownvotedPosts, GetInstanceDownvotedPostsResponse, InstanceDownvotedPost},
+  utils::{is_admin, is_mod_or_admin},
+};
+use lemmy_db_schema::source::{instance::Instance, post::PostLike};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+const DEFAULT_MIN_DOWNVOTE_PERCENT: i64 = 50;
+const DEFAULT_MIN_DOWNVOTES: i64 = 5;
+const DEFAULT_DAYS: i64 = 7;
+const MAX_DAYS: i64 = 365;
+
+/// Lists posts where users of a single instance cast a large share of the downvotes, so that mods
+/// can detect coordinated downvoting.
+#[tracing::instrument(skip(context))]
+pub async fn get_instance_downvoted_posts(
+  data: Query<GetInstanceDownvotedPosts>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<GetInstanceDownvotedPostsResponse>> {
+  if let Some(community_id) = data.community_id {
+    is_mod_or_admin(&mut context.pool(), &local_user_view.person, community_id).await?;
+  } else {
+    is_admin(&local_user_view)?;
+  }
+
+  let min_percent = data
+    .min_downvote_percent
+    .unwrap_or(DEFAULT_MIN_DOWNVOTE_PERCENT);
+  if !(1..=100).contains(&min_percent) {
+    Err(LemmyErrorType::InvalidDownvotePercent)?
+  }
+  let days = data.days.unwrap_or(DEFAULT_DAYS);
+  if !(1..=MAX_DAYS).contains(&days) {
+    Err(LemmyErrorType::InvalidDownvoteDays)?
+  }
+  let instance = Instance::read(&mut context.pool(), data.instance_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindInstance)?;
+
+  let posts = PostLike::downvoted_by_instance(
+    &mut context.pool(),
+    instance.id,
+    data.community_id,
+    min_percent,
+    data.min_downvotes.unwrap_or(DEFAULT_MIN_DOWNVOTES),
+    Utc::now() - Duration::days(days),
+    data.page,
+    data.limit,
+  )
+  .await?
+  .into_iter()
+  .map(
+    |(post, instance_downvotes, total_downvotes)| InstanceDownvotedPost {
+      post,
+      instance_downvotes,
+      total_downvotes,
+    },
+  )
+  .collect();
+
+  Ok(Json(GetInstanceDownvotedPostsResponse { posts }))
+}
@@ -3,12 +3,15 @@ use actix_web::web::Json;
 use lemmy_api_common::{
   build_response::build_post_response,
   context::LemmyContext,
+  metrics::{count_vote, VoteOrigin},
   post::{CreatePostLike, PostResponse},
   send_activity::{ActivityChannel, SendActivityData},
   utils::{
     check_bot_account,
     check_community_user_action,
     check_downvotes_enabled,
+    check_vote_cooldown,
+    check_vote_post_age,
     mark_post_as_read,
   },
 };
@@ -35,6 +38,7 @@ pub async fn like_post(
   // Don't do a downvote if site has downvotes disabled
   check_downvotes_enabled(data.score, &local_site)?;
   check_bot_account(&local_user_view.person)?;
+  check_vote_cooldown(&local_user_view.person, &local_site)?;
 
   // Check for a community ban
   let post_id = data.post_id;
@@ -48,6 +52,16 @@ pub async fn like_post(
     &mut context.pool(),
   )
   .await?;
+  // Removing a vote is always allowed
+  if data.score != 0 {
+    check_vote_post_age(
+      &post,
+      local_user_view.person.id,
+      &local_site,
+      &mut context.pool(),
+    )
+    .await?;
+  }
 
   let like_form = PostLikeForm {
     post_id: data.post_id,
@@ -58,7 +72,7 @@ pub async fn like_post(
   // Remove any likes first
   let person_id = local_user_view.person.id;
 
-  PostLike::remove(&mut context.pool(), person_id, post_id).await?;
+  let replaces_vote = PostLike::remove(&mut context.pool(), person_id, post_id).await? > 0;
 
   // Only add the like if the score isnt 0
   let do_add = like_form.score != 0 && (like_form.score == 1 || like_form.score == -1);
@@ -67,6 +81,7 @@ pub async fn like_post(
       .await
       .with_lemmy_type(LemmyErrorType::CouldntLikePost)?;
   }
+  count_vote(data.score, VoteOrigin::Local);
 
   mark_post_as_read(person_id, post_id, &mut context.pool()).await?;
 
@@ -74,16 +89,20 @@ pub async fn like_post(
     .await?
     .ok_or(LemmyErrorType::CouldntFindCommunity)?;
 
-  ActivityChannel::submit_activity(
-    SendActivityData::LikePostOrComment {
-      object_id: post.ap_id,
-      actor: local_user_view.person.clone(),
-      community,
-      score: data.score,
-    },
-    &context,
-  )
-  .await?;
+  // Votes of shadow voters are only visible to themselves
+  if !local_user_view.person.shadow_vote {
+    ActivityChannel::submit_activity(
+      SendActivityData::LikePostOrComment {
+        object_id: post.ap_id,
+        actor: local_user_view.person.clone(),
+        community,
+        score: data.score,
+        replaces_vote,
+      },
+      &context,
+    )
+    .await?;
+  }
 
   build_post_response(context.deref(), post.community_id, local_user_view, post_id).await
 }
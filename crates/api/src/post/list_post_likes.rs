@@ -2,9 +2,12 @@ use actix_web::web::{Data, Json, Query};
 use lemmy_api_common::{
   context::LemmyContext,
   post::{ListPostLikes, ListPostLikesResponse},
-  utils::is_mod_or_admin,
+  utils::check_can_list_votes,
+};
+use lemmy_db_schema::{
+  source::{local_site::LocalSite, post::Post},
+  traits::Crud,
 };
-use lemmy_db_schema::{source::post::Post, traits::Crud};
 use lemmy_db_views::structs::{LocalUserView, VoteView};
 use lemmy_utils::{error::LemmyResult, LemmyErrorType};
 
@@ -18,10 +21,12 @@ pub async fn list_post_likes(
   let post = Post::read(&mut context.pool(), data.post_id)
     .await?
     .ok_or(LemmyErrorType::CouldntFindPost)?;
-  is_mod_or_admin(
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  check_can_list_votes(
     &mut context.pool(),
-    &local_user_view.person,
+    &local_user_view,
     post.community_id,
+    &local_site,
   )
   .await?;
 
@@ -44,6 +44,8 @@ pub async fn lock_post(
     post_id,
     &PostUpdateForm {
       locked: Some(locked),
+      // A manual lock or unlock replaces the automatic lock
+      close_at: Some(None),
       ..Default::default()
     },
   )
@@ -51,7 +53,7 @@ pub async fn lock_post(
 
   // Mod tables
   let form = ModLockPostForm {
-    mod_person_id: local_user_view.person.id,
+    mod_person_id: Some(local_user_view.person.id),
     post_id: data.post_id,
     locked: Some(locked),
   };
@@ -1,8 +1,16 @@
+pub mod distinguish;
+pub mod edit_diff;
 pub mod feature;
+pub mod get_by_instance;
 pub mod get_link_metadata;
 pub mod hide;
+pub mod instance_downvoted;
 pub mod like;
 pub mod list_post_likes;
 pub mod lock;
 pub mod mark_read;
+pub mod move_post;
 pub mod save;
+pub mod save_many;
+pub mod saved_ids;
+pub mod vote_delta;
@@ -0,0 +1,70 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  build_response::build_post_response,
+  context::LemmyContext,
+  post::{MovePost, PostResponse},
+  send_activity::{ActivityChannel, SendActivityData},
+  utils::check_community_mod_action,
+};
+use lemmy_db_schema::{
+  source::post::{Post, PostUpdateForm},
+  traits::Crud,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+#[tracing::instrument(skip(context))]
+pub async fn move_post(
+  data: Json<MovePost>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<PostResponse>> {
+  let post_id = data.post_id;
+  let orig_post = Post::read(&mut context.pool(), post_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindPost)?;
+  if orig_post.community_id == data.community_id {
+    Err(LemmyErrorType::PostAlreadyInCommunity)?
+  }
+
+  // Need to be a mod of both the current and the new community
+  check_community_mod_action(
+    &local_user_view.person,
+    orig_post.community_id,
+    false,
+    &mut context.pool(),
+  )
+  .await?;
+  check_community_mod_action(
+    &local_user_view.person,
+    data.community_id,
+    false,
+    &mut context.pool(),
+  )
+  .await?;
+
+  // Votes and comments reference the post, so they are moved along with it
+  let post = Post::update(
+    &mut context.pool(),
+    post_id,
+    &PostUpdateForm {
+      community_id: Some(data.community_id),
+      ..Default::default()
+    },
+  )
+  .await
+  .with_lemmy_type(LemmyErrorType::CouldntUpdatePost)?;
+
+  ActivityChannel::submit_activity(
+    SendActivityData::MovePost {
+      post,
+      moderator: local_user_view.person.clone(),
+      origin_community_id: orig_post.community_id,
+    },
+    &context,
+  )
+  .await?;
+
+  build_post_response(&context, data.community_id, local_user_view, post_id).await
+}
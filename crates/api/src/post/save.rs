@@ -2,7 +2,7 @@ use actix_web::web::{Data, Json};
 use lemmy_api_common::{
   context::LemmyContext,
   post::{PostResponse, SavePost},
-  utils::mark_post_as_read,
+  utils::{mark_post_as_read, proxy_post_attachments},
 };
 use lemmy_db_schema::{
   source::post::{PostSaved, PostSavedForm},
@@ -34,7 +34,7 @@ pub async fn save_post(
 
   let post_id = data.post_id;
   let person_id = local_user_view.person.id;
-  let post_view = PostView::read(
+  let mut post_view = PostView::read(
     &mut context.pool(),
     post_id,
     Some(&local_user_view.local_user),
@@ -44,6 +44,7 @@ pub async fn save_post(
   .ok_or(LemmyErrorType::CouldntFindPost)?;
 
   mark_post_as_read(person_id, post_id, &mut context.pool()).await?;
+  proxy_post_attachments(std::slice::from_mut(&mut post_view), &context).await?;
 
   Ok(Json(PostResponse { post_view }))
 }
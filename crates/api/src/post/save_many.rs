@@ -0,0 +1,33 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{context::LemmyContext, post::SavePosts, SuccessResponse};
+use lemmy_db_schema::source::post::PostSaved;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult, MAX_API_PARAM_ELEMENTS};
+use std::collections::HashSet;
+
+#[tracing::instrument(skip(context))]
+pub async fn save_posts(
+  data: Json<SavePosts>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  let post_ids = HashSet::from_iter(data.post_ids.clone());
+
+  if post_ids.len() > MAX_API_PARAM_ELEMENTS {
+    Err(LemmyErrorType::TooManyItems)?;
+  }
+
+  let person_id = local_user_view.person.id;
+
+  if data.save {
+    PostSaved::save_many(&mut context.pool(), post_ids, person_id)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntSavePost)?;
+  } else {
+    PostSaved::unsave_many(&mut context.pool(), post_ids, person_id)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntSavePost)?;
+  }
+
+  Ok(Json(SuccessResponse::default()))
+}
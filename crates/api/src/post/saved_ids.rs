@@ -0,0 +1,15 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{context::LemmyContext, post::ListSavedPostIdsResponse};
+use lemmy_db_schema::source::post::PostSaved;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyResult;
+
+/// Lets clients reconcile their saved posts on startup, without paging through all of them.
+#[tracing::instrument(skip(context))]
+pub async fn list_saved_post_ids(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ListSavedPostIdsResponse>> {
+  let post_ids = PostSaved::list_post_ids(&mut context.pool(), local_user_view.person.id).await?;
+  Ok(Json(ListSavedPostIdsResponse { post_ids }))
+}
@@ -0,0 +1,44 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  post::{GetPostVoteDelta, GetPostVoteDeltaResponse},
+  utils::is_mod_or_admin,
+};
+use lemmy_db_schema::{
+  source::post::{Post, PostLike},
+  traits::Crud,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+/// Returns the score change of a post within a time window, so that external services can
+/// calculate trends without polling the full aggregates.
+#[tracing::instrument(skip(context))]
+pub async fn get_post_vote_delta(
+  data: Query<GetPostVoteDelta>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<GetPostVoteDeltaResponse>> {
+  if data.start >= data.end {
+    Err(LemmyErrorType::InvalidTimeWindow)?
+  }
+  let post = Post::read(&mut context.pool(), data.post_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindPost)?;
+  is_mod_or_admin(
+    &mut context.pool(),
+    &local_user_view.person,
+    post.community_id,
+  )
+  .await?;
+
+  let (upvotes, downvotes) =
+    PostLike::score_delta(&mut context.pool(), post.id, data.start, data.end).await?;
+
+  Ok(Json(GetPostVoteDeltaResponse {
+    post_id: post.id,
+    upvotes,
+    downvotes,
+    score: upvotes - downvotes,
+  }))
+}
@@ -49,7 +49,7 @@ pub async fn create_post_report(
   check_post_deleted_or_removed(&post_view.post)?;
 
   let report_form = PostReportForm {
-    creator_id: person_id,
+    creator_id: Some(person_id),
     post_id,
     original_post_name: post_view.post.name,
     original_post_url: post_view.post.url,
@@ -68,7 +68,7 @@ pub async fn create_post_report(
   // Email the admins
   if local_site.reports_email_admins {
     send_new_report_email_to_admins(
-      &post_report_view.creator.name,
+      &local_user_view.person.name,
       &post_report_view.post_creator.name,
       &mut context.pool(),
       context.settings(),
@@ -0,0 +1,35 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{GetPostRawJson, GetPostRawJsonResponse},
+  utils::is_admin,
+};
+use lemmy_db_schema::source::post_raw_json::PostRawJson;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+use url::Url;
+
+/// Returns the json of a received post, if it was stored. This requires the
+/// `federation_store_raw_json` site setting, and is only available for a few days. Posts which
+/// couldn't be imported can be looked up by their ap_id.
+#[tracing::instrument(skip(context))]
+pub async fn get_post_raw_json(
+  data: Query<GetPostRawJson>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<GetPostRawJsonResponse>> {
+  is_admin(&local_user_view)?;
+
+  let post_raw_json = match (data.post_id, &data.ap_id) {
+    (Some(post_id), _) => PostRawJson::read(&mut context.pool(), post_id).await?,
+    (None, Some(ap_id)) => {
+      let ap_id = Url::parse(ap_id)
+        .with_lemmy_type(LemmyErrorType::InvalidUrl)?
+        .into();
+      PostRawJson::read_from_apub_id(&mut context.pool(), &ap_id).await?
+    }
+    (None, None) => None,
+  }
+  .ok_or(LemmyErrorType::CouldntFindPostRawJson)?;
+  Ok(Json(GetPostRawJsonResponse { post_raw_json }))
+}
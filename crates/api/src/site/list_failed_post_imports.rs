@@ -0,0 +1,24 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{ListFailedPostImports, ListFailedPostImportsResponse},
+  utils::is_admin,
+};
+use lemmy_db_schema::source::failed_post_import::FailedPostImport;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyResult;
+
+#[tracing::instrument(skip(context))]
+pub async fn list_failed_post_imports(
+  data: Query<ListFailedPostImports>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ListFailedPostImportsResponse>> {
+  is_admin(&local_user_view)?;
+
+  let failed_post_imports =
+    FailedPostImport::list(&mut context.pool(), data.page, data.limit).await?;
+  Ok(Json(ListFailedPostImportsResponse {
+    failed_post_imports,
+  }))
+}
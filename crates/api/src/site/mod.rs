@@ -1,7 +1,13 @@
 pub mod block;
 pub mod federated_instances;
+pub mod get_post_raw_json;
 pub mod leave_admin;
 pub mod list_all_media;
+pub mod list_failed_post_imports;
 pub mod mod_log;
 pub mod purge;
+pub mod recompute_post_aggregate;
 pub mod registration_applications;
+pub mod render_markdown;
+pub mod set_instance_vote_weight;
+pub mod test_federation;
@@ -0,0 +1,38 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{RecomputePostAggregate, RecomputePostAggregateResponse},
+  utils::is_admin,
+};
+use lemmy_db_schema::aggregates::structs::PostAggregates;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+use tracing::info;
+
+/// Rewrites the aggregates of a post from its stored votes and comments. This is a repair tool for
+/// scores which drifted, and can safely be called repeatedly.
+#[tracing::instrument(skip(context))]
+pub async fn recompute_post_aggregate(
+  data: Json<RecomputePostAggregate>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<RecomputePostAggregateResponse>> {
+  is_admin(&local_user_view)?;
+
+  let post_id = data.post_id;
+  let before = PostAggregates::read(&mut context.pool(), post_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindPost)?;
+  let post_aggregates = PostAggregates::recompute(&mut context.pool(), post_id).await?;
+  info!(
+    "Admin {} recomputed aggregates of post {}: score {} -> {}, comments {} -> {}",
+    local_user_view.person.name,
+    post_id,
+    before.score,
+    post_aggregates.score,
+    before.comments,
+    post_aggregates.comments,
+  );
+
+  Ok(Json(RecomputePostAggregateResponse { post_aggregates }))
+}
@@ -0,0 +1,21 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{RenderMarkdown, RenderMarkdownResponse},
+  utils::process_markdown_to_html,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::{error::LemmyResult, utils::validation::is_valid_body_field};
+
+#[tracing::instrument(skip(context))]
+pub async fn render_markdown(
+  data: Json<RenderMarkdown>,
+  _local_user_view: LocalUserView,
+  context: Data<LemmyContext>,
+) -> LemmyResult<Json<RenderMarkdownResponse>> {
+  is_valid_body_field(&data.markdown, true)?;
+
+  let html = process_markdown_to_html(&data.markdown, &context).await?;
+  Ok(Json(RenderMarkdownResponse { html }))
+}
@@ -0,0 +1,45 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{SetInstanceVoteWeight, SetInstanceVoteWeightResponse},
+  utils::is_admin,
+};
+use lemmy_db_schema::{
+  source::instance::{Instance, InstanceForm},
+  utils::naive_now,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::{
+  error::{LemmyErrorType, LemmyResult},
+  utils::validation::is_valid_vote_weight,
+};
+use tracing::info;
+
+#[tracing::instrument(skip(context))]
+pub async fn set_instance_vote_weight(
+  data: Json<SetInstanceVoteWeight>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SetInstanceVoteWeightResponse>> {
+  is_admin(&local_user_view)?;
+  is_valid_vote_weight(data.vote_weight)?;
+
+  let instance = Instance::read(&mut context.pool(), data.instance_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindInstance)?;
+  let form = InstanceForm::builder()
+    .domain(instance.domain.clone())
+    .vote_weight(Some(data.vote_weight))
+    .updated(Some(naive_now()))
+    .build();
+  Instance::update(&mut context.pool(), instance.id, form).await?;
+  info!(
+    "Admin {} changed vote weight of {} from {} to {}",
+    local_user_view.person.name, instance.domain, instance.vote_weight, data.vote_weight,
+  );
+
+  let instance = Instance::read(&mut context.pool(), instance.id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindInstance)?;
+  Ok(Json(SetInstanceVoteWeightResponse { instance }))
+}
@@ -0,0 +1,39 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  request::{check_federation, check_federation_url},
+  site::{TestFederation, TestFederationResponse},
+  utils::is_admin,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+use url::Url;
+
+/// Read-only diagnostics for admins, to find out why federation with an instance is not working.
+#[tracing::instrument(skip(context))]
+pub async fn test_federation(
+  data: Query<TestFederation>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<TestFederationResponse>> {
+  is_admin(&local_user_view)?;
+
+  // Only accept a plain domain, not an arbitrary url
+  let domain = data.domain.trim();
+  let base_url = Url::parse(&format!(
+    "{}://{domain}/",
+    context.settings().get_protocol_string()
+  ))
+  .map_err(|_| LemmyErrorType::InvalidUrl)?;
+  let host = match (base_url.host_str(), base_url.port()) {
+    (Some(host), Some(port)) => format!("{host}:{port}"),
+    (Some(host), None) => host.to_string(),
+    (None, _) => Err(LemmyErrorType::InvalidUrl)?,
+  };
+  if host != domain.to_lowercase() || base_url.path() != "/" || base_url.query().is_some() {
+    Err(LemmyErrorType::InvalidUrl)?
+  }
+  check_federation_url(&base_url, &context).await?;
+
+  Ok(Json(check_federation(&base_url, &context).await))
+}
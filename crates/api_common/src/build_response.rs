@@ -7,6 +7,7 @@ use crate::{
     check_person_instance_community_block,
     get_interface_language,
     is_mod_or_admin,
+    proxy_post_attachments,
     send_email_to_user,
   },
 };
@@ -81,7 +82,7 @@ pub async fn build_post_response(
   let is_mod_or_admin = is_mod_or_admin(&mut context.pool(), &local_user_view.person, community_id)
     .await
     .is_ok();
-  let post_view = PostView::read(
+  let mut post_view = PostView::read(
     &mut context.pool(),
     post_id,
     Some(&local_user),
@@ -89,6 +90,7 @@ pub async fn build_post_response(
   )
   .await?
   .ok_or(LemmyErrorType::CouldntFindPost)?;
+  proxy_post_attachments(std::slice::from_mut(&mut post_view), context).await?;
   Ok(Json(PostResponse { post_view }))
 }
 
@@ -51,6 +51,15 @@ pub struct DistinguishComment {
   pub distinguished: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Pin a comment to the top of its post, or unpin it. Only for the post creator and mods.
+pub struct PinComment {
+  pub comment_id: CommentId,
+  pub pinned: bool,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "full", derive(TS))]
@@ -129,6 +138,15 @@ pub struct GetCommentsResponse {
   pub comments: Vec<CommentView>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Fetch the direct replies of a remote comment from its origin instance. Replies in large remote
+/// threads are only fetched when the comment is expanded.
+pub struct FetchCommentReplies {
+  pub comment_id: CommentId,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
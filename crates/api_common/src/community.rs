@@ -1,8 +1,11 @@
+use chrono::{DateTime, Utc};
 use lemmy_db_schema::{
   newtypes::{CommunityId, LanguageId, PersonId},
   source::site::Site,
   CommunityVisibility,
+  DuplicateTitleAction,
   ListingType,
+  PostFrequencyInterval,
   SortType,
 };
 use lemmy_db_views_actor::structs::{CommunityModeratorView, CommunityView, PersonView};
@@ -56,6 +59,25 @@ pub struct CreateCommunity {
   pub posting_restricted_to_mods: Option<bool>,
   pub discussion_languages: Option<Vec<LanguageId>>,
   pub visibility: Option<CommunityVisibility>,
+  /// Hour (UTC) from which posting is allowed each day. Posting is always allowed if this is the
+  /// same as `posting_window_end`. Mods can post at any time.
+  pub posting_window_start: Option<i16>,
+  /// Hour (UTC) until which posting is allowed each day.
+  pub posting_window_end: Option<i16>,
+  /// Additional words which cause removal of new posts, on top of the site slur filter. A regex.
+  pub slur_filter_regex: Option<String>,
+  /// If false, new posts which are marked as NSFW get removed.
+  pub allow_nsfw: Option<bool>,
+  /// Number of hours during which a title can't be reused in the community. Zero disables the
+  /// check.
+  pub duplicate_title_hours: Option<i32>,
+  /// What happens to new posts with a duplicate title.
+  pub duplicate_title_action: Option<DuplicateTitleAction>,
+  /// Minimum karma, the sum of post and comment score, which users need to post. Zero disables
+  /// the check.
+  pub min_post_karma: Option<i32>,
+  /// Minimum time in seconds between two posts of the same user. Zero disables the check.
+  pub post_interval_secs: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -153,6 +175,37 @@ pub struct EditCommunity {
   pub posting_restricted_to_mods: Option<bool>,
   pub discussion_languages: Option<Vec<LanguageId>>,
   pub visibility: Option<CommunityVisibility>,
+  /// Hour (UTC) from which posting is allowed each day. Posting is always allowed if this is the
+  /// same as `posting_window_end`. Mods can post at any time.
+  pub posting_window_start: Option<i16>,
+  /// Hour (UTC) until which posting is allowed each day.
+  pub posting_window_end: Option<i16>,
+  /// Stop sending activities in this community to other instances.
+  pub federation_paused: Option<bool>,
+  /// Reject activities in this community which are received from other instances.
+  pub federation_incoming_paused: Option<bool>,
+  /// Additional words which cause removal of new posts, on top of the site slur filter. A regex.
+  pub slur_filter_regex: Option<String>,
+  /// If false, new posts which are marked as NSFW get removed.
+  pub allow_nsfw: Option<bool>,
+  /// Number of hours during which a title can't be reused in the community. Zero disables the
+  /// check.
+  pub duplicate_title_hours: Option<i32>,
+  /// What happens to new posts with a duplicate title.
+  pub duplicate_title_action: Option<DuplicateTitleAction>,
+  /// Minimum karma, the sum of post and comment score, which users need to post. Zero disables
+  /// the check.
+  pub min_post_karma: Option<i32>,
+  /// Minimum time in seconds between two posts of the same user. Zero disables the check.
+  pub post_interval_secs: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// End the quarantine of a newly federated community, and restore its posts.
+pub struct ApproveCommunityQuarantine {
+  pub community_id: CommunityId,
 }
 
 #[skip_serializing_none]
@@ -223,3 +276,34 @@ pub struct TransferCommunity {
   pub community_id: CommunityId,
   pub person_id: PersonId,
 }
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get the number of posts per day or week in a community within a time range. Only for mods and
+/// admins.
+pub struct GetCommunityPostFrequency {
+  pub community_id: CommunityId,
+  /// Defaults to days.
+  pub interval: Option<PostFrequencyInterval>,
+  pub start: DateTime<Utc>,
+  pub end: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The post counts of a community, oldest bucket first. Buckets without posts are included.
+pub struct GetCommunityPostFrequencyResponse {
+  pub buckets: Vec<PostFrequencyBucket>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The number of posts published in the day or week which begins at `start`.
+pub struct PostFrequencyBucket {
+  pub start: DateTime<Utc>,
+  pub post_count: i64,
+}
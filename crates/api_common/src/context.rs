@@ -59,7 +59,9 @@ impl LemmyContext {
     // call this to run migrations
     let pool = build_db_pool_for_tests().await;
 
-    let client = client_builder(&SETTINGS).build().expect("build client");
+    let client = client_builder(&SETTINGS, None)
+      .build()
+      .expect("build client");
 
     let client = ClientBuilder::new(client).build();
     let secret = Secret {
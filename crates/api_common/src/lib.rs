@@ -7,6 +7,8 @@ pub mod community;
 #[cfg(feature = "full")]
 pub mod context;
 pub mod custom_emoji;
+#[cfg(feature = "full")]
+pub mod metrics;
 pub mod person;
 pub mod post;
 pub mod private_message;
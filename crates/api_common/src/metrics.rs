@@ -0,0 +1,40 @@
+use once_cell::sync::Lazy;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+
+/// Counts processed votes on posts and comments, labeled by action (add or remove) and origin
+/// (local or federated). It is registered in the default registry, so it shows up on the
+/// Prometheus metrics endpoint.
+static VOTES: Lazy<IntCounterVec> = Lazy::new(|| {
+  register_int_counter_vec!(
+    "lemmy_votes_total",
+    "Number of processed votes",
+    &["action", "origin"]
+  )
+  .expect("vote counter can be registered")
+});
+
+/// Whether a vote was made on this instance or received from another instance.
+#[derive(Clone, Copy, Debug)]
+pub enum VoteOrigin {
+  Local,
+  Federated,
+}
+
+fn vote_labels(score: i16, origin: VoteOrigin) -> [&'static str; 2] {
+  let action = if score == 0 { "remove" } else { "add" };
+  let origin = match origin {
+    VoteOrigin::Local => "local",
+    VoteOrigin::Federated => "federated",
+  };
+  [action, origin]
+}
+
+/// Increments the vote counter. A score of 0 means that an existing vote was removed.
+pub fn count_vote(score: i16, origin: VoteOrigin) {
+  VOTES.with_label_values(&vote_labels(score, origin)).inc();
+}
+
+/// Current value of the vote counter for the given score and origin.
+pub fn vote_count(score: i16, origin: VoteOrigin) -> u64 {
+  VOTES.with_label_values(&vote_labels(score, origin)).get()
+}
@@ -1,7 +1,8 @@
+use chrono::{DateTime, Utc};
 use lemmy_db_schema::{
-  newtypes::{CommentReplyId, CommunityId, LanguageId, PersonId, PersonMentionId},
+  newtypes::{CommentReplyId, CommunityId, DbUrl, LanguageId, PersonId, PersonMentionId},
   sensitive::SensitiveString,
-  source::site::Site,
+  source::{community::Community, site::Site},
   CommentSortType,
   ListingType,
   PostListingMode,
@@ -244,6 +245,16 @@ pub struct BanPersonResponse {
   pub banned: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Make a person a shadow voter. Their votes are still stored, but don't change any scores and
+/// aren't federated. Admins-only.
+pub struct ShadowVotePerson {
+  pub person_id: PersonId,
+  pub shadow_vote: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -441,3 +452,56 @@ pub struct ListMedia {
 pub struct ListMediaResponse {
   pub images: Vec<LocalImageView>,
 }
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get the activities which your user has federated to other instances.
+pub struct GetMyActivities {
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+pub struct GetMyActivitiesResponse {
+  pub activities: Vec<MyActivity>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An activity which was sent by your user.
+pub struct MyActivity {
+  pub ap_id: DbUrl,
+  /// The full activity json, as it was sent to other instances.
+  #[cfg_attr(feature = "full", ts(type = "any"))]
+  pub data: serde_json::Value,
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get the communities in which a person has posts on this instance. Only for mods and admins.
+pub struct GetPersonPostCommunities {
+  pub actor_id: DbUrl,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+pub struct GetPersonPostCommunitiesResponse {
+  pub communities: Vec<PersonPostCommunity>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A community in which a person has posts, with the number of posts.
+pub struct PersonPostCommunity {
+  pub community: Community,
+  pub post_count: i64,
+}
@@ -1,5 +1,8 @@
+use chrono::{DateTime, Utc};
 use lemmy_db_schema::{
-  newtypes::{CommentId, CommunityId, DbUrl, LanguageId, PostId, PostReportId},
+  newtypes::{CommentId, CommunityId, DbUrl, InstanceId, LanguageId, PostId, PostReportId},
+  source::{post::Post, post_previous_version::PostPreviousVersion},
+  CommentSortType,
   ListingType,
   PostFeatureType,
   SortType,
@@ -30,6 +33,14 @@ pub struct CreatePost {
   pub language_id: Option<LanguageId>,
   /// Instead of fetching a thumbnail, use a custom one.
   pub custom_thumbnail: Option<String>,
+  /// Mark the post as original content (OC). Only the creator can change this.
+  pub original_content: Option<bool>,
+  /// Comment sort which clients should use for this post, unless the user selects another one.
+  pub default_comment_sort: Option<CommentSortType>,
+  /// Time after which the post is locked automatically.
+  pub close_at: Option<DateTime<Utc>>,
+  /// Language which the post was translated from. Ignored if it is the same as `language_id`.
+  pub original_language_id: Option<LanguageId>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -83,6 +94,9 @@ pub struct GetPosts {
   pub show_read: Option<bool>,
   /// If true, then show the nsfw posts (even if your user setting is to hide them)
   pub show_nsfw: Option<bool>,
+  /// Only show posts whose attachment has this media type, for example `application/pdf` or
+  /// `video/*`.
+  pub media_type: Option<String>,
   pub page_cursor: Option<PaginationCursor>,
 }
 
@@ -124,6 +138,19 @@ pub struct EditPost {
   pub language_id: Option<LanguageId>,
   /// Instead of fetching a thumbnail, use a custom one.
   pub custom_thumbnail: Option<String>,
+  /// Mark the post as original content (OC). Only the creator can change this.
+  pub original_content: Option<bool>,
+  /// Comment sort which clients should use for this post, unless the user selects another one.
+  /// Null removes the default sort.
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub default_comment_sort: Option<Option<CommentSortType>>,
+  /// Time after which the post is locked automatically. Null removes the close time.
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub close_at: Option<Option<DateTime<Utc>>>,
+  /// Language which the post was translated from. Ignored if it is the same as the post language,
+  /// null marks the post as not translated.
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub original_language_id: Option<Option<LanguageId>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
@@ -175,6 +202,15 @@ pub struct LockPost {
   pub locked: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Distinguish a post (IE speak as moderator).
+pub struct DistinguishPost {
+  pub post_id: PostId,
+  pub distinguished: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -185,6 +221,34 @@ pub struct FeaturePost {
   pub feature_type: PostFeatureType,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Move a post to another community. Only for moderators of both communities.
+pub struct MovePost {
+  pub post_id: PostId,
+  pub community_id: CommunityId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Export a post with all of its comments. Only for moderators and admins.
+pub struct ExportPost {
+  pub post_id: PostId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The exported post.
+pub struct ExportPostResponse {
+  /// Contains the post as `Page` in the field `post`, its comments as `Note` objects in
+  /// `comments` (parents before replies) and the post vote counts in `counts`.
+  #[cfg_attr(feature = "full", ts(type = "any"))]
+  pub bundle: serde_json::Value,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -194,6 +258,24 @@ pub struct SavePost {
   pub save: bool,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Save or unsave multiple posts at once, for example to sync saved posts between clients.
+pub struct SavePosts {
+  pub post_ids: Vec<PostId>,
+  pub save: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The ids of all posts saved by the user, most recently saved first.
+pub struct ListSavedPostIdsResponse {
+  pub post_ids: Vec<PostId>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -300,3 +382,116 @@ pub struct ListPostLikes {
 pub struct ListPostLikesResponse {
   pub post_likes: Vec<VoteView>,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get the score change of a post between two points in time. Only for mods and admins.
+pub struct GetPostVoteDelta {
+  pub post_id: PostId,
+  pub start: DateTime<Utc>,
+  pub end: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The votes which a post received within the requested time window.
+pub struct GetPostVoteDeltaResponse {
+  pub post_id: PostId,
+  pub upvotes: i64,
+  pub downvotes: i64,
+  /// Net score change, upvotes minus downvotes.
+  pub score: i64,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get posts which originated on a given instance. Only for mods and admins.
+pub struct GetPostsByInstance {
+  pub instance_id: InstanceId,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+pub struct GetPostsByInstanceResponse {
+  pub posts: Vec<PostView>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get posts where users of a given instance cast a large share of the downvotes, to detect
+/// coordinated downvoting. Only for admins, or for mods if a community is given.
+pub struct GetInstanceDownvotedPosts {
+  pub instance_id: InstanceId,
+  pub community_id: Option<CommunityId>,
+  /// Minimum percentage of downvotes from the instance, defaults to 50.
+  pub min_downvote_percent: Option<i64>,
+  /// Minimum number of downvotes of a post, defaults to 5.
+  pub min_downvotes: Option<i64>,
+  /// Only count downvotes cast within this many days, defaults to 7.
+  pub days: Option<i64>,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+pub struct GetInstanceDownvotedPostsResponse {
+  pub posts: Vec<InstanceDownvotedPost>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+pub struct InstanceDownvotedPost {
+  pub post: Post,
+  /// Downvotes from users of the instance.
+  pub instance_downvotes: i64,
+  /// Downvotes from all instances.
+  pub total_downvotes: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get the title and body of a post before and after its last edit. Only for mods and admins.
+pub struct GetPostEditDiff {
+  pub post_id: PostId,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+pub struct GetPostEditDiffResponse {
+  /// The version before the last edit of title or body. Missing if these were never edited.
+  pub previous: Option<PostPreviousVersion>,
+  /// The current title.
+  pub name: String,
+  /// The current body.
+  pub body: Option<String>,
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_edit_post_clear_close_at() {
+    // A missing field leaves the close time unchanged, null removes it
+    let edit: EditPost = serde_json::from_str(r#"{"post_id": 1}"#).unwrap();
+    assert_eq!(None, edit.close_at);
+    let edit: EditPost = serde_json::from_str(r#"{"post_id": 1, "close_at": null}"#).unwrap();
+    assert_eq!(Some(None), edit.close_at);
+  }
+}
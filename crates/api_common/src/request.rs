@@ -3,17 +3,21 @@ use crate::{
   lemmy_db_schema::traits::Crud,
   post::{LinkMetadata, OpenGraphData},
   send_activity::{ActivityChannel, SendActivityData},
-  utils::{local_site_opt_to_sensitive, proxy_image_link},
+  site::{FederationCheck, TestFederationResponse},
+  utils::{check_domain_federation, local_site_opt_to_sensitive, proxy_image_link},
 };
-use activitypub_federation::config::Data;
+use activitypub_federation::{config::Data, FEDERATION_CONTENT_TYPE};
 use chrono::{DateTime, Utc};
 use encoding_rs::{Encoding, UTF_8};
+use html2text::{from_read_with_decorator, render::text_renderer::TrivialDecorator};
 use lemmy_db_schema::{
-  newtypes::DbUrl,
+  newtypes::{DbUrl, PostId},
   source::{
     images::{ImageDetailsForm, LocalImage, LocalImageForm},
+    instance::Instance,
     local_site::LocalSite,
     post::{Post, PostUpdateForm},
+    post_thumbnail_retry::PostThumbnailRetry,
   },
 };
 use lemmy_utils::{
@@ -23,19 +27,42 @@ use lemmy_utils::{
   VERSION,
 };
 use mime::Mime;
-use reqwest::{header::CONTENT_TYPE, Client, ClientBuilder};
+use reqwest::{
+  header::{ACCEPT, CONTENT_TYPE},
+  Client,
+  ClientBuilder,
+  Response,
+};
 use reqwest_middleware::ClientWithMiddleware;
 use serde::{Deserialize, Serialize};
-use tracing::info;
-use url::Url;
+use std::{
+  future::Future,
+  net::Ipv4Addr,
+  time::{Duration, Instant},
+};
+use tracing::{info, warn};
+use url::{Host, Url};
 use urlencoding::encode;
-use webpage::HTML;
+use webpage::{OpengraphObject, HTML};
 
-pub fn client_builder(settings: &Settings) -> ClientBuilder {
-  let user_agent = format!("Lemmy/{VERSION}; +{}", settings.get_protocol_and_hostname());
+/// How long to wait before generating a thumbnail again, if pictrs was unavailable.
+const THUMBNAIL_RETRY_DELAY: Duration = Duration::from_secs(600);
 
+/// Maximum number of characters of the link description which is stored as post excerpt.
+const EMBED_DESCRIPTION_MAX_LENGTH: usize = 300;
+
+/// User-Agent which is sent with outgoing HTTP requests. By default this is
+/// `Lemmy/{version}; +{protocol_and_hostname}`, admins can override it with
+/// [LocalSite.federation_user_agent].
+pub fn user_agent(settings: &Settings, local_site: Option<&LocalSite>) -> String {
+  local_site
+    .and_then(|l| l.federation_user_agent.clone())
+    .unwrap_or_else(|| format!("Lemmy/{VERSION}; +{}", settings.get_protocol_and_hostname()))
+}
+
+pub fn client_builder(settings: &Settings, local_site: Option<&LocalSite>) -> ClientBuilder {
   Client::builder()
-    .user_agent(user_agent.clone())
+    .user_agent(user_agent(settings, local_site))
     .timeout(REQWEST_TIMEOUT)
     .connect_timeout(REQWEST_TIMEOUT)
 }
@@ -54,9 +81,14 @@ pub async fn fetch_link_metadata(url: &Url, context: &LemmyContext) -> LemmyResu
 
   // Can't use .text() here, because it only checks the content header, not the actual bytes
   // https://github.com/LemmyNet/lemmy/issues/1964
-  let html_bytes = response.bytes().await.map_err(LemmyError::from)?.to_vec();
+  let html_bytes = read_body_limited(response, context.settings().max_download_size).await?;
+  let content_type = resolve_content_type(content_type, &html_bytes);
 
-  let opengraph_data = extract_opengraph_data(&html_bytes, url)
+  let min_image_size = LocalSite::read(&mut context.pool())
+    .await
+    .ok()
+    .and_then(|l| l.thumbnail_min_size);
+  let opengraph_data = extract_opengraph_data(&html_bytes, url, min_image_size)
     .map_err(|e| info!("{e}"))
     .unwrap_or_default();
   Ok(LinkMetadata {
@@ -65,6 +97,101 @@ pub async fn fetch_link_metadata(url: &Url, context: &LemmyContext) -> LemmyResu
   })
 }
 
+/// Reads the response body, but aborts as soon as it exceeds `max_size` bytes. Otherwise a huge
+/// file would be held in memory completely.
+async fn read_body_limited(mut response: Response, max_size: usize) -> LemmyResult<Vec<u8>> {
+  let declared_size = response
+    .content_length()
+    .map(|l| usize::try_from(l).unwrap_or(usize::MAX));
+  if declared_size.is_some_and(|l| l > max_size) {
+    Err(LemmyErrorType::DownloadTooLarge)?
+  }
+  let mut bytes = vec![];
+  while let Some(chunk) = response.chunk().await.map_err(LemmyError::from)? {
+    if bytes.len() + chunk.len() > max_size {
+      Err(LemmyErrorType::DownloadTooLarge)?
+    }
+    bytes.extend_from_slice(&chunk);
+  }
+  Ok(bytes)
+}
+
+/// Detects the content type of a file from the magic bytes at its start.
+fn sniff_content_type(bytes: &[u8]) -> Option<Mime> {
+  let mime = match bytes {
+    [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => "image/png",
+    [0xFF, 0xD8, 0xFF, ..] => "image/jpeg",
+    [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => "image/gif",
+    [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => "image/webp",
+    [_, _, _, _, b'f', b't', b'y', b'p', b'a', b'v', b'i', b'f' | b's', ..] => "image/avif",
+    [b'%', b'P', b'D', b'F', b'-', ..] => "application/pdf",
+    [b'P', b'K', 0x03, 0x04, ..] => "application/zip",
+    [0x7F, b'E', b'L', b'F', ..] => "application/x-executable",
+    _ if is_windows_executable(bytes) => "application/x-msdownload",
+    _ => return None,
+  };
+  mime.parse().ok()
+}
+
+/// Windows executables start with `MZ`, followed by a header whose offset is stored at 0x3C and
+/// which starts with `PE\0\0`. The two bytes alone could also be the start of a text file.
+fn is_windows_executable(bytes: &[u8]) -> bool {
+  let pe_offset = bytes
+    .get(0x3C..0x40)
+    .and_then(|o| o.try_into().ok())
+    .and_then(|o| usize::try_from(u32::from_le_bytes(o)).ok());
+  bytes.starts_with(b"MZ")
+    && pe_offset
+      .and_then(|o| bytes.get(o..o.checked_add(4)?))
+      .is_some_and(|header| header == b"PE\0\0")
+}
+
+/// Determines the content type of a fetched file. The declared content type is replaced if the
+/// actual bytes are a different known type, so that eg an executable labeled as `image/png` is not
+/// treated as an image. Only a few types can be detected, so otherwise the declaration is kept.
+pub fn resolve_content_type(declared: Option<Mime>, bytes: &[u8]) -> Option<Mime> {
+  match (declared, sniff_content_type(bytes)) {
+    (Some(declared), Some(sniffed)) if declared.essence_str() != sniffed.essence_str() => {
+      Some(sniffed)
+    }
+    (declared, sniffed) => declared.or(sniffed),
+  }
+}
+
+/// Returns true if the media type declared for a federated attachment differs from the content
+/// type which was detected when fetching it.
+pub fn is_media_type_mismatch(declared: &str, actual: &str) -> bool {
+  match (declared.parse::<Mime>(), actual.parse::<Mime>()) {
+    (Ok(declared), Ok(actual)) => declared.essence_str() != actual.essence_str(),
+    _ => declared != actual,
+  }
+}
+
+/// Checks the media type of a federated attachment against the allowed and blocked media types
+/// of the local site. These can be full media types like `image/png`, or `image/*` for all
+/// subtypes. Only media attachments (images, videos and audio) are filtered, plain links are always
+/// allowed.
+pub fn is_media_type_allowed(media_type: &str, local_site: &LocalSite) -> bool {
+  let essence = media_type
+    .parse::<Mime>()
+    .map(|m| m.essence_str().to_string())
+    .unwrap_or_else(|_| media_type.to_lowercase());
+  let top_level = essence.split('/').next().unwrap_or_default();
+  if !["image", "video", "audio"]
+    .iter()
+    .any(|t| top_level.eq_ignore_ascii_case(t))
+  {
+    return true;
+  }
+  let matches = |pattern: &String| match pattern.strip_suffix("/*") {
+    Some(type_) => top_level.eq_ignore_ascii_case(type_),
+    None => essence.eq_ignore_ascii_case(pattern),
+  };
+  let allowed = &local_site.attachment_allowed_types;
+  (allowed.is_empty() || allowed.iter().any(matches))
+    && !local_site.attachment_blocked_types.iter().any(matches)
+}
+
 /// Generates and saves a post thumbnail and metadata.
 ///
 /// Takes a callback to generate a send activity task, so that post can be federated with metadata.
@@ -102,10 +229,7 @@ pub async fn generate_post_link_metadata(
   let thumbnail_url = if let (false, Some(url)) = (is_image_post, custom_thumbnail) {
     proxy_image_link(url, &context).await.ok()
   } else if let (true, Some(url)) = (allow_generate_thumbnail, image_url) {
-    generate_pictrs_thumbnail(&url, &context)
-      .await
-      .ok()
-      .map(Into::into)
+    generate_pictrs_thumbnail_or_retry(post.id, url.into(), local_site.as_ref(), &context).await?
   } else {
     metadata.opengraph_data.image.clone()
   };
@@ -126,7 +250,11 @@ pub async fn generate_post_link_metadata(
 }
 
 /// Extract site metadata from HTML Opengraph attributes.
-fn extract_opengraph_data(html_bytes: &[u8], url: &Url) -> LemmyResult<OpenGraphData> {
+fn extract_opengraph_data(
+  html_bytes: &[u8],
+  url: &Url,
+  min_image_size: Option<i32>,
+) -> LemmyResult<OpenGraphData> {
   let html = String::from_utf8_lossy(html_bytes);
 
   let mut page = HTML::from_string(html.to_string(), None)?;
@@ -155,10 +283,7 @@ fn extract_opengraph_data(html_bytes: &[u8], url: &Url) -> LemmyResult<OpenGraph
     .properties
     .get("title")
     .map(std::string::ToString::to_string);
-  let og_image = page
-    .opengraph
-    .images
-    .first()
+  let og_image = select_opengraph_image(&page.opengraph.images, min_image_size)
     // join also works if the target URL is absolute
     .and_then(|ogo| url.join(&ogo.url).ok());
   let og_embed_url = page
@@ -170,12 +295,63 @@ fn extract_opengraph_data(html_bytes: &[u8], url: &Url) -> LemmyResult<OpenGraph
 
   Ok(OpenGraphData {
     title: og_title.or(page_title),
-    description: og_description.or(page_description),
+    description: og_description
+      .or(page_description)
+      .as_deref()
+      .and_then(clean_description),
     image: og_image.map(Into::into),
     embed_video_url: og_embed_url.map(Into::into),
   })
 }
 
+/// Picks the thumbnail from the opengraph images of a page. With a minimum size, the first image
+/// which is at least that wide and high is preferred, otherwise the largest image with declared
+/// dimensions. Images without dimensions are only used if none of them declares any.
+fn select_opengraph_image(
+  images: &[OpengraphObject],
+  min_size: Option<i32>,
+) -> Option<&OpengraphObject> {
+  let Some(min_size) = min_size else {
+    return images.first();
+  };
+  let dimensions = |image: &OpengraphObject| {
+    let dimension = |key| image.properties.get(key)?.trim().parse::<i32>().ok();
+    Some((dimension("width")?, dimension("height")?))
+  };
+  let sized = images
+    .iter()
+    .filter_map(|image| Some((image, dimensions(image)?)))
+    .collect::<Vec<_>>();
+  sized
+    .iter()
+    .find(|(_, (width, height))| *width >= min_size && *height >= min_size)
+    .or_else(|| {
+      sized
+        .iter()
+        .max_by_key(|(_, (width, height))| i64::from(*width) * i64::from(*height))
+    })
+    .map(|(image, _)| *image)
+    .or_else(|| images.first())
+}
+
+/// Convert the page description to plain text on a single line, and shorten it to at most
+/// `EMBED_DESCRIPTION_MAX_LENGTH` characters without cutting words in half.
+fn clean_description(description: &str) -> Option<String> {
+  let text = from_read_with_decorator(description.as_bytes(), usize::MAX, TrivialDecorator::new());
+  let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+  if text.is_empty() {
+    return None;
+  }
+  if text.chars().count() <= EMBED_DESCRIPTION_MAX_LENGTH {
+    return Some(text);
+  }
+  let truncated: String = text.chars().take(EMBED_DESCRIPTION_MAX_LENGTH).collect();
+  let truncated = truncated
+    .rsplit_once(' ')
+    .map_or(truncated.as_str(), |(start, _)| start);
+  Some(format!("{}…", truncated.trim_end()))
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct PictrsResponse {
   pub files: Option<Vec<PictrsFile>>,
@@ -196,6 +372,27 @@ impl PictrsFile {
       self.file
     ))
   }
+
+  /// Same as [[PictrsFile::thumbnail_url]], but lets pictrs resize and convert the image to the
+  /// thumbnail size and format configured in local site.
+  pub fn generated_thumbnail_url(
+    &self,
+    protocol_and_hostname: &str,
+    local_site: Option<&LocalSite>,
+  ) -> Result<Url, url::ParseError> {
+    let mut url = self.thumbnail_url(protocol_and_hostname)?;
+    let max_size = local_site.and_then(|l| l.thumbnail_max_size);
+    let format = local_site.and_then(|l| l.thumbnail_format.as_deref());
+    if let Some(format) = format {
+      url.query_pairs_mut().append_pair("format", format);
+    }
+    if let Some(max_size) = max_size {
+      url
+        .query_pairs_mut()
+        .append_pair("thumbnail", &max_size.to_string());
+    }
+    Ok(url)
+  }
 }
 
 /// Stores extra details about a Pictrs image.
@@ -283,9 +480,60 @@ pub async fn delete_image_from_pictrs(
   Ok(())
 }
 
+/// Generates a thumbnail in pictrs. If pictrs is unavailable, the remote image is used as thumbnail
+/// for now, and thumbnail generation is retried by a scheduled task after some time.
+async fn generate_pictrs_thumbnail_or_retry(
+  post_id: PostId,
+  image_url: Url,
+  local_site: Option<&LocalSite>,
+  context: &LemmyContext,
+) -> LemmyResult<Option<DbUrl>> {
+  match generate_pictrs_thumbnail(&image_url, local_site, context).await {
+    Ok(thumbnail_url) => Ok(Some(thumbnail_url.into())),
+    Err(e) => {
+      warn!("Failed to generate thumbnail for {image_url}, retrying later: {e}");
+      let form = PostThumbnailRetry {
+        post_id,
+        image_url: image_url.into(),
+        retry_at: Utc::now() + THUMBNAIL_RETRY_DELAY,
+      };
+      PostThumbnailRetry::create(&mut context.pool(), &form).await?;
+      Ok(Some(image_url.into()))
+    }
+  }
+}
+
+/// Generates the thumbnails which failed earlier because pictrs was unavailable. Each thumbnail is
+/// only retried once.
+pub async fn retry_pictrs_thumbnails(context: &LemmyContext) -> LemmyResult<()> {
+  let local_site = LocalSite::read(&mut context.pool()).await.ok();
+  for retry in PostThumbnailRetry::take_due(&mut context.pool()).await? {
+    let image_url = retry.image_url.into();
+    match generate_pictrs_thumbnail(&image_url, local_site.as_ref(), context).await {
+      // Replaces the remote image which was used as thumbnail in the meantime
+      Ok(thumbnail_url) => {
+        let form = PostUpdateForm {
+          thumbnail_url: Some(Some(thumbnail_url.into())),
+          ..Default::default()
+        };
+        // The retries are already removed, so a failure must not prevent the others
+        if let Err(e) = Post::update(&mut context.pool(), retry.post_id, &form).await {
+          warn!("Failed to store thumbnail of post {:?}: {e}", retry.post_id);
+        }
+      }
+      Err(e) => warn!("Failed to generate thumbnail for {image_url}: {e}"),
+    }
+  }
+  Ok(())
+}
+
 /// Retrieves the image with local pict-rs and generates a thumbnail. Returns the thumbnail url.
 #[tracing::instrument(skip_all)]
-async fn generate_pictrs_thumbnail(image_url: &Url, context: &LemmyContext) -> LemmyResult<Url> {
+async fn generate_pictrs_thumbnail(
+  image_url: &Url,
+  local_site: Option<&LocalSite>,
+  context: &LemmyContext,
+) -> LemmyResult<Url> {
   let pictrs_config = context.settings().pictrs_config()?;
 
   match pictrs_config.image_mode() {
@@ -327,7 +575,7 @@ async fn generate_pictrs_thumbnail(image_url: &Url, context: &LemmyContext) -> L
     pictrs_delete_token: image.delete_token.clone(),
   };
   let protocol_and_hostname = context.settings().get_protocol_and_hostname();
-  let thumbnail_url = image.thumbnail_url(&protocol_and_hostname)?;
+  let thumbnail_url = image.generated_thumbnail_url(&protocol_and_hostname, local_site)?;
 
   // Also store the details for the image
   let details_form = image.details.build_image_details_form(&thumbnail_url);
@@ -415,6 +663,204 @@ pub async fn replace_image(
   Ok(())
 }
 
+#[derive(Deserialize)]
+struct NodeInfoWellKnown {
+  links: Vec<NodeInfoLink>,
+}
+
+#[derive(Deserialize)]
+struct NodeInfoLink {
+  rel: String,
+  href: Url,
+}
+
+#[derive(Deserialize)]
+struct NodeInfo {
+  software: NodeInfoSoftware,
+}
+
+#[derive(Deserialize)]
+struct NodeInfoSoftware {
+  name: String,
+  version: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ActorInbox {
+  #[allow(dead_code)]
+  inbox: Url,
+}
+
+/// Checks if the remote instance at `base_url` can be reached, by fetching its nodeinfo and its
+/// instance actor. Errors are returned as part of the response, together with the duration of
+/// each request.
+pub async fn check_federation(base_url: &Url, context: &LemmyContext) -> TestFederationResponse {
+  let (nodeinfo, software) = timed_check(fetch_nodeinfo_software(base_url, context)).await;
+  let (actor, _) = timed_check(fetch_instance_actor(base_url, context)).await;
+  TestFederationResponse {
+    nodeinfo,
+    actor,
+    software,
+  }
+}
+
+async fn timed_check<T>(
+  check: impl Future<Output = LemmyResult<T>>,
+) -> (FederationCheck, Option<T>) {
+  let start = Instant::now();
+  let res = check.await;
+  let duration_ms = i64::try_from(start.elapsed().as_millis()).unwrap_or(i64::MAX);
+  match res {
+    Ok(value) => (
+      FederationCheck {
+        success: true,
+        duration_ms,
+        error: None,
+      },
+      Some(value),
+    ),
+    Err(e) => {
+      info!("Federation check failed: {e}");
+      (
+        FederationCheck {
+          success: false,
+          duration_ms,
+          error: Some(federation_check_error(&e)),
+        },
+        None,
+      )
+    }
+  }
+}
+
+/// Describes why a check failed, without details such as resolved addresses which are only
+/// written to the log.
+fn federation_check_error(error: &LemmyError) -> String {
+  let LemmyErrorType::Unknown(_) = error.error_type else {
+    return error.error_type.to_string();
+  };
+  let reqwest_error = match error.inner.downcast_ref::<reqwest_middleware::Error>() {
+    Some(reqwest_middleware::Error::Reqwest(e)) => Some(e),
+    Some(reqwest_middleware::Error::Middleware(_)) => None,
+    None => error.inner.downcast_ref::<reqwest::Error>(),
+  };
+  let Some(e) = reqwest_error else {
+    return "RequestFailed".to_string();
+  };
+  if e.is_timeout() {
+    "Timeout".to_string()
+  } else if e.is_connect() {
+    "ConnectionFailed".to_string()
+  } else if let Some(status) = e.status() {
+    format!("HttpStatus {}", status.as_u16())
+  } else if e.is_decode() {
+    "InvalidResponse".to_string()
+  } else {
+    "RequestFailed".to_string()
+  }
+}
+
+/// Checks that requests to the url are allowed, with the same domain checks as for federation.
+/// Like the federation library, release builds also reject urls with loopback or private
+/// addresses, and urls with an explicit port.
+pub async fn check_federation_url(url: &Url, context: &LemmyContext) -> LemmyResult<()> {
+  if !cfg!(debug_assertions) {
+    check_public_host(url)?;
+  }
+  let domain = url.host_str().ok_or(LemmyErrorType::UrlWithoutDomain)?;
+  let pool = &mut context.pool();
+  let local_site = LocalSite::read(pool).await.ok();
+  let allowed_instances = Instance::allowlist(pool).await?;
+  let blocked_instances = Instance::blocklist(pool).await?;
+  check_domain_federation(
+    domain,
+    local_site.as_ref(),
+    &allowed_instances,
+    &blocked_instances,
+  )
+}
+
+fn check_public_host(url: &Url) -> LemmyResult<()> {
+  if url.port().is_some() {
+    Err(LemmyErrorType::InvalidUrl)?
+  }
+  let is_public_ipv4 = |ip: Ipv4Addr| {
+    !(ip.is_loopback()
+      || ip.is_private()
+      || ip.is_link_local()
+      || ip.is_unspecified()
+      || ip.is_broadcast())
+  };
+  let is_public = match url.host() {
+    Some(Host::Domain(domain)) => domain != "localhost" && !domain.ends_with(".localhost"),
+    Some(Host::Ipv4(ip)) => is_public_ipv4(ip),
+    Some(Host::Ipv6(ip)) => match ip.to_ipv4_mapped() {
+      Some(ip) => is_public_ipv4(ip),
+      None => {
+        let [first_segment, ..] = ip.segments();
+        // Unique local (fc00::/7) and link local (fe80::/10) addresses
+        !(ip.is_loopback()
+          || ip.is_unspecified()
+          || first_segment & 0xfe00 == 0xfc00
+          || first_segment & 0xffc0 == 0xfe80)
+      }
+    },
+    None => false,
+  };
+  if !is_public {
+    Err(LemmyErrorType::InvalidUrl)?
+  }
+  Ok(())
+}
+
+async fn fetch_nodeinfo_software(base_url: &Url, context: &LemmyContext) -> LemmyResult<String> {
+  let well_known: NodeInfoWellKnown = context
+    .client()
+    .get(base_url.join("/.well-known/nodeinfo")?)
+    .send()
+    .await?
+    .error_for_status()?
+    .json()
+    .await?;
+  let nodeinfo_url = well_known
+    .links
+    .into_iter()
+    .find(|l| {
+      l.rel
+        .starts_with("http://nodeinfo.diaspora.software/ns/schema/2.")
+    })
+    .ok_or(LemmyErrorType::CouldntFindNodeInfo)?
+    .href;
+  check_federation_url(&nodeinfo_url, context).await?;
+  let nodeinfo: NodeInfo = context
+    .client()
+    .get(nodeinfo_url)
+    .send()
+    .await?
+    .error_for_status()?
+    .json()
+    .await?;
+  let software = nodeinfo.software;
+  Ok(match software.version {
+    Some(version) => format!("{} {version}", software.name),
+    None => software.name,
+  })
+}
+
+/// Lemmy and most other platforms serve an instance actor at the root url.
+async fn fetch_instance_actor(base_url: &Url, context: &LemmyContext) -> LemmyResult<()> {
+  context
+    .client()
+    .get(base_url.clone())
+    .header(ACCEPT, FEDERATION_CONTENT_TYPE)
+    .send()
+    .await?
+    .error_for_status()?
+    .json::<ActorInbox>()
+    .await?;
+  Ok(())
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 #[allow(clippy::indexing_slicing)]
@@ -422,12 +868,86 @@ mod tests {
 
   use crate::{
     context::LemmyContext,
-    request::{extract_opengraph_data, fetch_link_metadata},
+    request::{
+      check_federation,
+      check_public_host,
+      client_builder,
+      extract_opengraph_data,
+      fetch_link_metadata,
+      generate_pictrs_thumbnail_or_retry,
+      is_media_type_allowed,
+      is_media_type_mismatch,
+      read_body_limited,
+      resolve_content_type,
+      PictrsFile,
+      PictrsFileDetails,
+      EMBED_DESCRIPTION_MAX_LENGTH,
+    },
+  };
+  use chrono::Utc;
+  use lemmy_db_schema::{
+    newtypes::DbUrl,
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      local_site::LocalSite,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+      post_thumbnail_retry::PostThumbnailRetry,
+    },
+    traits::Crud,
+  };
+  use lemmy_utils::{
+    error::{LemmyErrorType, LemmyResult},
+    settings::structs::Settings,
+    VERSION,
   };
   use pretty_assertions::assert_eq;
+  use reqwest::Client;
   use serial_test::serial;
+  use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+  };
   use url::Url;
 
+  #[tokio::test]
+  #[serial]
+  async fn test_thumbnail_without_pictrs() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person = Person::create(
+      pool,
+      &PersonInsertForm::test_form(instance.id, "thumbnail_person"),
+    )
+    .await?;
+    let community_form = CommunityInsertForm::builder()
+      .name("thumbnail_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+    let post_form = PostInsertForm::builder()
+      .name("Post with image".to_string())
+      .creator_id(person.id)
+      .community_id(community.id)
+      .build();
+    let post = Post::create(pool, &post_form).await?;
+
+    // Pictrs isn't available in tests, so the remote image is used until generation is retried
+    let image_url = Url::parse("https://example.com/image.png")?;
+    let thumbnail_url =
+      generate_pictrs_thumbnail_or_retry(post.id, image_url.clone(), None, &context).await?;
+    assert_eq!(Some(image_url.clone().into()), thumbnail_url);
+    let retry = PostThumbnailRetry::read(pool, post.id).await?;
+    assert_eq!(Some(image_url.into()), retry.map(|r| r.image_url));
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+
   // These helped with testing
   #[tokio::test]
   #[serial]
@@ -465,7 +985,8 @@ mod tests {
 
     // root relative url
     let html_bytes = b"<!DOCTYPE html><html><head><meta property='og:image' content='/image.jpg'></head><body></body></html>";
-    let metadata = extract_opengraph_data(html_bytes, &url).expect("Unable to parse metadata");
+    let metadata =
+      extract_opengraph_data(html_bytes, &url, None).expect("Unable to parse metadata");
     assert_eq!(
       metadata.image,
       Some(Url::parse("https://example.com/image.jpg").unwrap().into())
@@ -473,7 +994,8 @@ mod tests {
 
     // base relative url
     let html_bytes = b"<!DOCTYPE html><html><head><meta property='og:image' content='image.jpg'></head><body></body></html>";
-    let metadata = extract_opengraph_data(html_bytes, &url).expect("Unable to parse metadata");
+    let metadata =
+      extract_opengraph_data(html_bytes, &url, None).expect("Unable to parse metadata");
     assert_eq!(
       metadata.image,
       Some(
@@ -485,7 +1007,8 @@ mod tests {
 
     // absolute url
     let html_bytes = b"<!DOCTYPE html><html><head><meta property='og:image' content='https://cdn.host.com/image.jpg'></head><body></body></html>";
-    let metadata = extract_opengraph_data(html_bytes, &url).expect("Unable to parse metadata");
+    let metadata =
+      extract_opengraph_data(html_bytes, &url, None).expect("Unable to parse metadata");
     assert_eq!(
       metadata.image,
       Some(Url::parse("https://cdn.host.com/image.jpg").unwrap().into())
@@ -493,10 +1016,371 @@ mod tests {
 
     // protocol relative url
     let html_bytes = b"<!DOCTYPE html><html><head><meta property='og:image' content='//example.com/image.jpg'></head><body></body></html>";
-    let metadata = extract_opengraph_data(html_bytes, &url).expect("Unable to parse metadata");
+    let metadata =
+      extract_opengraph_data(html_bytes, &url, None).expect("Unable to parse metadata");
     assert_eq!(
       metadata.image,
       Some(Url::parse("https://example.com/image.jpg").unwrap().into())
     );
   }
+
+  #[test]
+  fn test_generated_thumbnail_url() {
+    let file = PictrsFile {
+      file: "abc.jpg".to_string(),
+      delete_token: "token".to_string(),
+      details: PictrsFileDetails {
+        width: 1024,
+        height: 768,
+        content_type: "image/jpeg".to_string(),
+        created_at: Utc::now(),
+      },
+    };
+    let protocol_and_hostname = "https://lemmy.example";
+
+    // without configured settings the original image is used
+    let url = file
+      .generated_thumbnail_url(protocol_and_hostname, Some(&LocalSite::default()))
+      .unwrap();
+    assert_eq!("https://lemmy.example/pictrs/image/abc.jpg", url.as_str());
+
+    let local_site = LocalSite {
+      thumbnail_max_size: Some(512),
+      thumbnail_format: Some("webp".to_string()),
+      ..Default::default()
+    };
+    let url = file
+      .generated_thumbnail_url(protocol_and_hostname, Some(&local_site))
+      .unwrap();
+    assert_eq!(
+      "https://lemmy.example/pictrs/image/abc.jpg?format=webp&thumbnail=512",
+      url.as_str()
+    );
+  }
+
+  #[test]
+  fn test_resolve_content_type() {
+    const PNG: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0];
+    const JPEG: &[u8] = &[0xFF, 0xD8, 0xFF, 0xE0, 0, 0];
+    let mut exe = vec![0; 0x44];
+    exe[..2].copy_from_slice(b"MZ");
+    exe[0x3C] = 0x40;
+    exe[0x40..].copy_from_slice(b"PE\0\0");
+    let png = Some(mime::IMAGE_PNG);
+
+    // matching declared type is kept
+    assert_eq!(png, resolve_content_type(png.clone(), PNG));
+    // missing declaration is filled in from file content
+    assert_eq!(png, resolve_content_type(None, PNG));
+    // wrong image type is corrected
+    assert_eq!(
+      Some(mime::IMAGE_JPEG),
+      resolve_content_type(png.clone(), JPEG)
+    );
+    // executable labeled as image is not treated as image
+    let exe = resolve_content_type(png.clone(), &exe).unwrap();
+    assert_eq!("application/x-msdownload", exe.essence_str());
+    assert_ne!(mime::IMAGE, exe.type_());
+    // text which happens to start with the executable magic bytes
+    assert_eq!(
+      Some(mime::TEXT_PLAIN),
+      resolve_content_type(Some(mime::TEXT_PLAIN), b"MZ is a postal code")
+    );
+    // image types which can't be sniffed keep their declaration
+    let bmp = "image/bmp".parse().ok();
+    assert_eq!(bmp, resolve_content_type(bmp.clone(), b"BM\x00\x00"));
+    assert_eq!(png, resolve_content_type(png.clone(), b"hello world"));
+    // animated avif
+    assert_eq!(
+      Some("image/avif".to_string()),
+      resolve_content_type(None, b"\x00\x00\x00\x1cftypavis").map(|m| m.to_string())
+    );
+    // html and svg can't be sniffed, so the declaration is trusted
+    assert_eq!(
+      Some(mime::TEXT_HTML_UTF_8),
+      resolve_content_type(Some(mime::TEXT_HTML_UTF_8), b"<html></html>")
+    );
+    assert_eq!(
+      Some(mime::IMAGE_SVG),
+      resolve_content_type(Some(mime::IMAGE_SVG), b"<svg></svg>")
+    );
+  }
+
+  /// Sends a request with a client from [client_builder] and returns its User-Agent header.
+  async fn sent_user_agent(local_site: Option<&LocalSite>) -> LemmyResult<Option<String>> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}/", listener.local_addr()?);
+    let server = tokio::spawn(async move {
+      let (mut stream, _) = listener.accept().await?;
+      let mut request = vec![];
+      let mut buf = [0; 1024];
+      while !request.ends_with(b"\r\n\r\n") {
+        let len = stream.read(&mut buf).await?;
+        request.extend(buf.get(..len).unwrap_or_default());
+      }
+      stream
+        .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+        .await?;
+      Ok::<_, std::io::Error>(String::from_utf8_lossy(&request).to_string())
+    });
+
+    let client = client_builder(&Settings::default(), local_site)
+      .no_proxy()
+      .build()?;
+    client.get(url).send().await?;
+    let request = server.await??;
+    Ok(request.lines().find_map(|l| {
+      l.strip_prefix("user-agent: ")
+        .or_else(|| l.strip_prefix("User-Agent: "))
+        .map(ToString::to_string)
+    }))
+  }
+
+  #[tokio::test]
+  async fn test_user_agent() -> LemmyResult<()> {
+    let default = format!("Lemmy/{VERSION}; +https://unset");
+    assert_eq!(Some(default), sent_user_agent(None).await?);
+
+    let local_site = LocalSite {
+      federation_user_agent: Some("Lemmy partner instance (admin@example.com)".to_string()),
+      ..Default::default()
+    };
+    assert_eq!(
+      local_site.federation_user_agent,
+      sent_user_agent(Some(&local_site)).await?
+    );
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_check_federation() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let base_url = Url::parse(&format!("http://{}/", listener.local_addr()?))?;
+    let nodeinfo_url = base_url.join("/nodeinfo/2.1")?;
+    let well_known = serde_json::json!({
+      "links": [{
+        "rel": "http://nodeinfo.diaspora.software/ns/schema/2.1",
+        "href": nodeinfo_url,
+      }]
+    });
+    let nodeinfo = serde_json::json!({
+      "software": { "name": "lemmy", "version": "0.19.5" }
+    });
+    let actor = serde_json::json!({
+      "id": base_url,
+      "type": "Application",
+      "inbox": base_url.join("/site_inbox")?,
+    });
+    let server = tokio::spawn(async move {
+      for _ in 0..3 {
+        let (mut stream, _) = listener.accept().await?;
+        let mut request = vec![];
+        let mut buf = [0; 1024];
+        while !request.ends_with(b"\r\n\r\n") {
+          let len = stream.read(&mut buf).await?;
+          request.extend(buf.get(..len).unwrap_or_default());
+        }
+        let request = String::from_utf8_lossy(&request).to_string();
+        let (status, body) = if request.starts_with("GET /.well-known/nodeinfo ") {
+          ("200 OK", well_known.to_string())
+        } else if request.starts_with("GET /nodeinfo/2.1 ") {
+          ("200 OK", nodeinfo.to_string())
+        } else if request.starts_with("GET / ") && request.contains("application/activity+json") {
+          ("200 OK", actor.to_string())
+        } else {
+          ("404 Not Found", String::new())
+        };
+        let response = format!(
+          "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+          body.len()
+        );
+        stream.write_all(response.as_bytes()).await?;
+      }
+      Ok::<_, std::io::Error>(())
+    });
+
+    let res = check_federation(&base_url, &context).await;
+    server.await??;
+    assert!(res.nodeinfo.success);
+    assert_eq!(None, res.nodeinfo.error);
+    assert_eq!(Some("lemmy 0.19.5".to_string()), res.software);
+    assert!(res.actor.success);
+    assert_eq!(None, res.actor.error);
+
+    // Nothing is listening anymore, so both checks fail, without details about the connection
+    let res = check_federation(&base_url, &context).await;
+    assert!(!res.nodeinfo.success);
+    assert_eq!(Some("ConnectionFailed".to_string()), res.nodeinfo.error);
+    assert!(!res.actor.success);
+    assert_eq!(None, res.software);
+    Ok(())
+  }
+
+  #[test]
+  fn test_check_public_host() -> LemmyResult<()> {
+    check_public_host(&Url::parse("https://lemmy.ml/")?)?;
+    check_public_host(&Url::parse("https://1.1.1.1/")?)?;
+    for url in [
+      "https://lemmy.ml:8536/",
+      "https://localhost/",
+      "https://127.0.0.1/",
+      "https://10.0.0.1/",
+      "https://192.168.1.1/",
+      "https://169.254.169.254/",
+      "https://[::1]/",
+      "https://[fd00::1]/",
+      "https://[fe80::1]/",
+      "https://[::ffff:127.0.0.1]/",
+    ] {
+      assert!(check_public_host(&Url::parse(url)?).is_err(), "{url}");
+    }
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_read_body_limited() -> LemmyResult<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}/image.png", listener.local_addr()?);
+    // Oversized bodies with and without content-length header, then one within the limit
+    let bodies = [(true, 1000), (false, 1000), (true, 100)];
+    let server = tokio::spawn(async move {
+      for (with_length, size) in bodies {
+        let (mut stream, _) = listener.accept().await?;
+        let mut request = vec![];
+        let mut buf = [0; 1024];
+        while !request.ends_with(b"\r\n\r\n") {
+          let len = stream.read(&mut buf).await?;
+          request.extend(buf.get(..len).unwrap_or_default());
+        }
+        let length = if with_length {
+          format!("content-length: {size}\r\n")
+        } else {
+          String::new()
+        };
+        let head = format!(
+          "HTTP/1.1 200 OK\r\ncontent-type: image/png\r\n{length}connection: close\r\n\r\n"
+        );
+        stream.write_all(head.as_bytes()).await?;
+        // The client hangs up early if the body is too large
+        stream.write_all(&vec![0; size]).await.ok();
+      }
+      Ok::<_, std::io::Error>(())
+    });
+
+    let client = Client::new();
+    for _ in 0..2 {
+      let response = client.get(&url).send().await?;
+      let res = read_body_limited(response, 500).await;
+      assert!(res.is_err_and(|e| e.error_type == LemmyErrorType::DownloadTooLarge));
+    }
+    let response = client.get(&url).send().await?;
+    assert_eq!(100, read_body_limited(response, 500).await?.len());
+
+    server.await??;
+    Ok(())
+  }
+
+  #[test]
+  fn test_is_media_type_allowed() {
+    let mut local_site = LocalSite::default();
+    assert!(is_media_type_allowed("video/mp4", &local_site));
+
+    local_site.attachment_allowed_types = vec!["image/*".to_string()];
+    assert!(is_media_type_allowed("image/png", &local_site));
+    assert!(!is_media_type_allowed("video/mp4", &local_site));
+    // Links are not media attachments
+    assert!(is_media_type_allowed(
+      "text/html; charset=utf-8",
+      &local_site
+    ));
+
+    local_site.attachment_blocked_types = vec!["image/gif".to_string(), "text/html".to_string()];
+    assert!(is_media_type_allowed("image/png", &local_site));
+    assert!(!is_media_type_allowed("IMAGE/GIF", &local_site));
+    assert!(is_media_type_allowed("text/html", &local_site));
+  }
+
+  #[test]
+  fn test_is_media_type_mismatch() {
+    assert!(!is_media_type_mismatch("image/png", "image/png"));
+    assert!(!is_media_type_mismatch(
+      "text/html",
+      "text/html; charset=utf-8"
+    ));
+    assert!(is_media_type_mismatch(
+      "image/png",
+      "application/x-msdownload"
+    ));
+    assert!(is_media_type_mismatch("image/png", "image/jpeg"));
+  }
+
+  #[test]
+  fn test_opengraph_description() -> LemmyResult<()> {
+    let url = Url::parse("https://example.com/article")?;
+    let html = r#"<html><head>
+      <title>Article</title>
+      <meta property="og:description" content="An &lt;b&gt;important&lt;/b&gt;
+        article   about &amp; things">
+      </head></html>"#;
+    let data = extract_opengraph_data(html.as_bytes(), &url, None)?;
+    assert_eq!(
+      Some("An important article about & things".to_string()),
+      data.description
+    );
+
+    // Long descriptions are shortened at a word boundary
+    let long = "word ".repeat(100);
+    let html =
+      format!(r#"<html><head><meta property="og:description" content="{long}"></head></html>"#);
+    let description = extract_opengraph_data(html.as_bytes(), &url, None)?
+      .description
+      .unwrap_or_default();
+    assert!(description.chars().count() <= EMBED_DESCRIPTION_MAX_LENGTH + 1);
+    assert!(description.ends_with("word…"));
+
+    let html = r#"<html><head><meta property="og:description" content="  "></head></html>"#;
+    assert_eq!(
+      None,
+      extract_opengraph_data(html.as_bytes(), &url, None)?.description
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_select_opengraph_image() -> LemmyResult<()> {
+    let url = Url::parse("https://example.com/article")?;
+    let html = r#"<html><head>
+      <meta property="og:image" content="/small.jpg">
+      <meta property="og:image:width" content="100">
+      <meta property="og:image:height" content="100">
+      <meta property="og:image" content="/banner.jpg">
+      <meta property="og:image:width" content="1200">
+      <meta property="og:image:height" content="250">
+      <meta property="og:image" content="/medium.jpg">
+      <meta property="og:image:width" content="500">
+      <meta property="og:image:height" content="500">
+      </head></html>"#;
+    let image = |min_size| -> LemmyResult<Option<DbUrl>> {
+      Ok(extract_opengraph_data(html.as_bytes(), &url, min_size)?.image)
+    };
+
+    // Without minimum size the first image is used
+    assert_eq!(
+      Some(Url::parse("https://example.com/small.jpg")?.into()),
+      image(None)?
+    );
+    // The first image which is large enough in both dimensions
+    assert_eq!(
+      Some(Url::parse("https://example.com/medium.jpg")?.into()),
+      image(Some(300))?
+    );
+    // If none is large enough, the largest one
+    assert_eq!(
+      Some(Url::parse("https://example.com/banner.jpg")?.into()),
+      image(Some(600))?
+    );
+    Ok(())
+  }
 }
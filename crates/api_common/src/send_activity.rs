@@ -1,15 +1,19 @@
 use crate::{community::BanFromCommunity, context::LemmyContext, post::DeletePost};
 use activitypub_federation::config::Data;
+use chrono::{Duration, Utc};
 use futures::future::BoxFuture;
 use lemmy_db_schema::{
   newtypes::{CommunityId, DbUrl, PersonId},
   source::{
     comment::Comment,
     community::Community,
+    local_site::LocalSite,
     person::Person,
     post::Post,
+    post_pending_federation::PostPendingFederation,
     private_message::PrivateMessage,
   },
+  utils::DbPool,
 };
 use lemmy_db_views::structs::PrivateMessageView;
 use lemmy_utils::error::LemmyResult;
@@ -42,7 +46,14 @@ pub enum SendActivityData {
     removed: bool,
   },
   LockPost(Post, Person, bool),
+  /// Automatic lock after the close time of the post
+  ClosePost(Post),
   FeaturePost(Post, Person, bool),
+  MovePost {
+    post: Post,
+    moderator: Person,
+    origin_community_id: CommunityId,
+  },
   CreateComment(Comment),
   UpdateComment(Comment),
   DeleteComment(Comment, Person, Community),
@@ -57,6 +68,8 @@ pub enum SendActivityData {
     actor: Person,
     community: Community,
     score: i16,
+    /// The actor already had a vote on the object, which is replaced by this one
+    replaces_vote: bool,
   },
   FollowCommunity(Community, Person, bool),
   UpdateCommunity(Person, Community),
@@ -141,3 +154,131 @@ impl ActivityChannel {
     Ok(())
   }
 }
+
+/// Returns true if a local post was created so recently that its Create activity is still held
+/// back by [[LocalSite::federation_edit_grace_period]].
+pub fn in_edit_grace_period(post: &Post, local_site: &LocalSite) -> bool {
+  let grace_period = Duration::seconds(local_site.federation_edit_grace_period.into());
+  post.local && Utc::now() < post.published + grace_period
+}
+
+/// Activity to federate a newly created post. Nothing is sent during the edit grace period, the
+/// Create activity is sent later by a scheduled task with the latest version of the post.
+pub fn create_post_activity(post: Post, local_site: &LocalSite) -> Option<SendActivityData> {
+  if post.deleted || post.removed {
+    return None;
+  }
+  (!in_edit_grace_period(&post, local_site)).then_some(SendActivityData::CreatePost(post))
+}
+
+/// Stores when the Create activity of a new post is due, if it is held back by the edit grace
+/// period. This needs to happen before any other activity for the post is submitted.
+pub async fn schedule_create_post_activity(
+  post: &Post,
+  local_site: &LocalSite,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<()> {
+  if in_edit_grace_period(post, local_site) {
+    let form = PostPendingFederation {
+      post_id: post.id,
+      send_at: post.published + Duration::seconds(local_site.federation_edit_grace_period.into()),
+    };
+    PostPendingFederation::create(pool, &form).await?;
+  }
+  Ok(())
+}
+
+/// Activity to federate an edited post. Nothing is sent during the edit grace period, because the
+/// edit is included in the delayed Create activity.
+pub fn update_post_activity(post: Post, local_site: &LocalSite) -> Option<SendActivityData> {
+  (!in_edit_grace_period(&post, local_site)).then_some(SendActivityData::UpdatePost(post))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+
+  use super::*;
+  use lemmy_db_schema::{
+    source::{
+      community::CommunityInsertForm,
+      instance::Instance,
+      person::PersonInsertForm,
+      post::{PostInsertForm, PostUpdateForm},
+    },
+    traits::Crud,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_update_post_activity_grace_period() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person = Person::create(
+      pool,
+      &PersonInsertForm::test_form(instance.id, "grace_period_person"),
+    )
+    .await?;
+    let community_form = CommunityInsertForm::builder()
+      .name("test_community_grace".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+    let post_form = PostInsertForm::builder()
+      .name("A test post".into())
+      .creator_id(person.id)
+      .community_id(community.id)
+      .local(Some(true))
+      .build();
+    let post = Post::create(pool, &post_form).await?;
+
+    // No update is sent for an edit right after creation
+    let local_site = LocalSite {
+      federation_edit_grace_period: 300,
+      ..Default::default()
+    };
+    assert!(in_edit_grace_period(&post, &local_site));
+    assert!(create_post_activity(post.clone(), &local_site).is_none());
+    assert!(update_post_activity(post.clone(), &local_site).is_none());
+
+    // The Create activity is sent later by the scheduled task
+    schedule_create_post_activity(&post, &local_site, pool).await?;
+    assert!(PostPendingFederation::is_pending(pool, post.id).await?);
+
+    // Edits after the grace period are federated normally
+    let update_form = PostUpdateForm {
+      published: Some(Utc::now() - Duration::minutes(10)),
+      ..Default::default()
+    };
+    let post = Post::update(pool, post.id, &update_form).await?;
+    assert!(matches!(
+      update_post_activity(post.clone(), &local_site),
+      Some(SendActivityData::UpdatePost(_))
+    ));
+
+    // Without grace period, all edits are federated
+    let local_site = LocalSite::default();
+    let post = Post::update(
+      pool,
+      post.id,
+      &PostUpdateForm {
+        published: Some(Utc::now()),
+        ..Default::default()
+      },
+    )
+    .await?;
+    assert!(!in_edit_grace_period(&post, &local_site));
+    assert!(matches!(
+      create_post_activity(post.clone(), &local_site),
+      Some(SendActivityData::CreatePost(_))
+    ));
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+}
@@ -1,12 +1,23 @@
 use crate::federate_retry_sleep_duration;
 use chrono::{DateTime, Utc};
 use lemmy_db_schema::{
-  newtypes::{CommentId, CommunityId, InstanceId, LanguageId, PersonId, PostId},
+  aggregates::structs::PostAggregates,
+  newtypes::{
+    CommentId,
+    CommunityId,
+    FailedPostImportId,
+    InstanceId,
+    LanguageId,
+    PersonId,
+    PostId,
+  },
   source::{
+    failed_post_import::FailedPostImport,
     federation_queue_state::FederationQueueState,
     instance::Instance,
     language::Language,
     local_site_url_blocklist::LocalSiteUrlBlocklist,
+    post_raw_json::PostRawJson,
     tagline::Tagline,
   },
   ListingType,
@@ -50,8 +61,10 @@ use lemmy_db_views_moderator::structs::{
   ModRemovePostView,
   ModTransferCommunityView,
 };
+use lemmy_utils::LemmyErrorType;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
+use std::collections::BTreeMap;
 #[cfg(feature = "full")]
 use ts_rs::TS;
 
@@ -107,6 +120,33 @@ pub struct ResolveObjectResponse {
   pub person: Option<PersonView>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Does an apub fetch for multiple objects at once.
+pub struct ResolveObjects {
+  /// Each item can be the full url, or a shortened version like: !fediverse@lemmy.ml
+  pub q: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response of a bulk apub object fetch, with one entry for each distinct query.
+pub struct ResolveObjectsResponse {
+  pub objects: BTreeMap<String, ResolvedObject>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Either the fetched object or the reason why it couldn't be fetched.
+pub struct ResolvedObject {
+  pub object: Option<ResolveObjectResponse>,
+  pub error: Option<LemmyErrorType>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "full", derive(TS))]
@@ -194,6 +234,51 @@ pub struct CreateSite {
   pub registration_mode: Option<RegistrationMode>,
   pub content_warning: Option<String>,
   pub default_post_listing_mode: Option<PostListingMode>,
+  pub post_title_from_summary: Option<bool>,
+  pub post_title_content_length: Option<i32>,
+  pub anonymize_votes: Option<bool>,
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub thumbnail_max_size: Option<Option<i32>>,
+  pub thumbnail_format: Option<String>,
+  pub federation_edit_grace_period: Option<i32>,
+  pub vote_cooldown_hours: Option<i32>,
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub federation_content_max_length: Option<Option<i32>>,
+  pub max_comment_fetch_depth: Option<i32>,
+  pub attachment_allowed_types: Option<Vec<String>>,
+  pub attachment_blocked_types: Option<Vec<String>>,
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub federation_dead_instance_threshold: Option<Option<i32>>,
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub vote_max_post_age_days: Option<Option<i32>>,
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub federation_backfill_request_limit: Option<Option<i32>>,
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub federation_community_quarantine_days: Option<Option<i32>>,
+  pub federation_user_agent: Option<String>,
+  pub tracking_pixel_domains: Option<Vec<String>>,
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub vote_batching_threshold: Option<Option<i32>>,
+  pub federation_reports: Option<bool>,
+  pub federation_reports_anonymous: Option<bool>,
+  pub federation_store_raw_json: Option<bool>,
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub federation_fallback_community_id: Option<Option<CommunityId>>,
+  pub proxy_post_attachments: Option<bool>,
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub federation_max_recipients: Option<Option<i32>>,
+  pub federation_future_tolerance: Option<i32>,
+  pub normalize_titles: Option<bool>,
+  pub federation_group_posts: Option<bool>,
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub thumbnail_min_size: Option<Option<i32>>,
+  pub federation_vote_switch_undo: Option<bool>,
+  pub federation_body_strip_patterns: Option<Vec<String>>,
+  pub federation_page_types: Option<Vec<String>>,
+  pub federation_preserve_html: Option<bool>,
+  pub federation_comments_enabled_default: Option<bool>,
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub backfill_max_comments_per_post: Option<Option<i32>>,
 }
 
 #[skip_serializing_none]
@@ -281,6 +366,108 @@ pub struct EditSite {
   pub content_warning: Option<String>,
   /// Default value for [LocalUser.post_listing_mode]
   pub default_post_listing_mode: Option<PostListingMode>,
+  /// Whether to use the summary of a federated microblog post as its title.
+  pub post_title_from_summary: Option<bool>,
+  /// Number of content characters used as title for federated posts without name or summary.
+  pub post_title_content_length: Option<i32>,
+  /// Whether individual votes are hidden from everyone except admins.
+  pub anonymize_votes: Option<bool>,
+  /// Maximum width or height of generated post thumbnails in pixels. Null keeps the original
+  /// size.
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub thumbnail_max_size: Option<Option<i32>>,
+  /// Image format of generated post thumbnails (for example `webp` or `avif`).
+  pub thumbnail_format: Option<String>,
+  /// Seconds after creating a post during which edits are not federated separately. The post is
+  /// federated once this period is over, including any edits.
+  pub federation_edit_grace_period: Option<i32>,
+  /// Number of hours after signup during which new accounts are not allowed to vote.
+  pub vote_cooldown_hours: Option<i32>,
+  /// If set, the content of outgoing posts is truncated to this many characters, with a link to
+  /// the full post. The full text is still federated as source.
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub federation_content_max_length: Option<Option<i32>>,
+  /// Maximum nesting depth of federated comments. Replies in deeper threads are attached to the
+  /// ancestor at this depth, and unknown parents aren't fetched beyond this depth.
+  pub max_comment_fetch_depth: Option<i32>,
+  /// Media types of federated media attachments which are accepted, like `image/png` or
+  /// `image/*`. If empty, all types which aren't blocked are accepted. Links are always accepted.
+  pub attachment_allowed_types: Option<Vec<String>>,
+  /// Media types of federated attachments which are rejected, like `video/*`.
+  pub attachment_blocked_types: Option<Vec<String>>,
+  /// Number of consecutive failed deliveries after which an instance is marked as dead. Must be
+  /// positive, disabled if not set.
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub federation_dead_instance_threshold: Option<Option<i32>>,
+  /// Votes on posts older than this many days (1 to 36500) are rejected, except from mods and
+  /// admins.
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub vote_max_post_age_days: Option<Option<i32>>,
+  /// Maximum number of HTTP requests for backfilling posts from an instance after it is unblocked.
+  /// Defaults to the fetch limit of a single object.
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub federation_backfill_request_limit: Option<Option<i32>>,
+  /// Posts from newly federated communities are stored as removed for this many days (at most
+  /// 36500), until an admin approves the community.
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub federation_community_quarantine_days: Option<Option<i32>>,
+  /// Custom User-Agent for outgoing HTTP requests. The default contains the Lemmy version and the
+  /// instance domain. Changes take effect after a restart.
+  pub federation_user_agent: Option<String>,
+  /// Images from these domains and their subdomains are removed from federated posts, as they
+  /// are likely used for tracking.
+  pub tracking_pixel_domains: Option<Vec<String>>,
+  /// Votes on posts from the last day which have at least this many votes are added to the
+  /// aggregates in batches every few seconds, to avoid lock contention on viral posts. Empty or
+  /// zero disables batching.
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub vote_batching_threshold: Option<Option<i32>>,
+  /// Whether reports of remote content are sent to the instance it comes from, so that its mods
+  /// can handle them.
+  pub federation_reports: Option<bool>,
+  /// Send federated reports from the instance actor instead of the reporter.
+  pub federation_reports_anonymous: Option<bool>,
+  /// Store the raw json of received posts for a few days, so that admins can debug federation
+  /// issues.
+  pub federation_store_raw_json: Option<bool>,
+  /// Local community which receives federated posts whose community can't be found, instead of
+  /// rejecting them. Null removes the fallback community.
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub federation_fallback_community_id: Option<Option<CommunityId>>,
+  /// Serve remote images of posts to clients through the image proxy, so that remote hosts can't
+  /// see the IP addresses of users.
+  pub proxy_post_attachments: Option<bool>,
+  /// Outgoing comments which mention more than this many users are addressed to the followers
+  /// collection of the community instead of listing each recipient.
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub federation_max_recipients: Option<Option<i32>>,
+  /// Seconds by which the timestamps of federated posts may be in the future (0 to 86400). Posts
+  /// which are further in the future are rejected.
+  pub federation_future_tolerance: Option<i32>,
+  /// Also check post titles against filters in a normalized form, to catch lookalike characters.
+  pub normalize_titles: Option<bool>,
+  /// Accept federated posts which are attributed only to a community, instead of rejecting them.
+  pub federation_group_posts: Option<bool>,
+  /// Minimum width and height of opengraph images to be preferred as post thumbnail.
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub thumbnail_min_size: Option<Option<i32>>,
+  /// Federate changed votes as Undo followed by the new vote, instead of a single activity.
+  pub federation_vote_switch_undo: Option<bool>,
+  /// Regular expressions for text which is removed from the body of federated posts, like
+  /// signatures.
+  pub federation_body_strip_patterns: Option<Vec<String>>,
+  /// Object types of federated posts which are accepted, like `Page` or `Video`. If empty, all
+  /// types are accepted.
+  pub federation_page_types: Option<Vec<String>>,
+  /// Keep the sanitized HTML of federated posts without markdown source, in addition to the body
+  /// converted to markdown.
+  pub federation_preserve_html: Option<bool>,
+  /// Whether comments are enabled on imported posts which don't specify it with `commentsEnabled`.
+  pub federation_comments_enabled_default: Option<bool>,
+  /// Maximum number of comments which are fetched for each post when backfilling a community.
+  /// Null uses the usual fetch limit, and 0 disables fetching comments.
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub backfill_max_comments_per_post: Option<Option<i32>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -467,6 +654,124 @@ pub struct GetUnreadRegistrationApplicationCountResponse {
   pub registration_applications: i64,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Fetches a list of incoming posts which failed to import.
+pub struct ListFailedPostImports {
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The list of failed post imports.
+pub struct ListFailedPostImportsResponse {
+  pub failed_post_imports: Vec<FailedPostImport>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Attempts to import a failed post again. On success it is removed from the failed imports.
+pub struct ReprocessFailedPostImport {
+  pub id: FailedPostImportId,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Fetches the stored raw json of a received post. Needs either the post id, or the ap_id for
+/// posts which couldn't be imported.
+pub struct GetPostRawJson {
+  pub post_id: Option<PostId>,
+  pub ap_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+pub struct GetPostRawJsonResponse {
+  pub post_raw_json: PostRawJson,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Recounts the votes and comments of a post, to repair its aggregates. Only for admins.
+pub struct RecomputePostAggregate {
+  pub post_id: PostId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+pub struct RecomputePostAggregateResponse {
+  pub post_aggregates: PostAggregates,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Sets the multiplier for votes from users of an instance, between 0 and 100. Zero means that
+/// their votes are ignored. Existing votes are reweighted as well. Only for admins.
+pub struct SetInstanceVoteWeight {
+  pub instance_id: InstanceId,
+  pub vote_weight: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+pub struct SetInstanceVoteWeightResponse {
+  pub instance: Instance,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Fetches recent posts of followed communities from an instance, for example after it was
+/// unblocked.
+pub struct BackfillInstance {
+  pub instance_id: InstanceId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+pub struct BackfillInstanceResponse {
+  /// Number of communities whose outbox was fetched.
+  pub fetched_communities: i64,
+  /// Number of communities whose outbox couldn't be fetched.
+  pub failed_communities: i64,
+  /// Number of communities which were skipped because the request limit was reached.
+  pub skipped_communities: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Imports posts of a remote community which were never received, for example because of
+/// downtime.
+pub struct BackfillCommunity {
+  pub community_id: CommunityId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+pub struct BackfillCommunityResponse {
+  /// Number of posts which were missing, and got imported.
+  pub imported_posts: i64,
+  /// Number of posts which already existed.
+  pub skipped_posts: i64,
+  /// Number of posts which couldn't be imported.
+  pub failed_posts: i64,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -483,3 +788,52 @@ pub struct BlockInstance {
 pub struct BlockInstanceResponse {
   pub blocked: bool,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Render markdown the same way as the server does for posts and comments. Requires login.
+pub struct RenderMarkdown {
+  pub markdown: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The sanitized HTML, as it is sent over federation.
+pub struct RenderMarkdownResponse {
+  pub html: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Checks if a remote instance can be reached, by fetching its nodeinfo and instance actor. Only
+/// for admins.
+pub struct TestFederation {
+  pub domain: String,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+pub struct TestFederationResponse {
+  pub nodeinfo: FederationCheck,
+  pub actor: FederationCheck,
+  /// Software name and version from the nodeinfo.
+  pub software: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Result of a single request to a remote instance.
+pub struct FederationCheck {
+  pub success: bool,
+  pub duration_ms: i64,
+  /// Short description of the failure, eg `Timeout` or `HttpStatus 404`. Details are only written
+  /// to the server log.
+  pub error: Option<String>,
+}
@@ -7,10 +7,10 @@ use crate::{
   },
   site::{FederatedInstances, InstanceWithFederationState},
 };
-use chrono::{DateTime, Days, Local, TimeZone, Utc};
+use chrono::{DateTime, Days, Duration, Local, TimeZone, Utc};
 use enum_map::{enum_map, EnumMap};
 use lemmy_db_schema::{
-  aggregates::structs::{PersonPostAggregates, PersonPostAggregatesForm},
+  aggregates::structs::{PersonAggregates, PersonPostAggregates, PersonPostAggregatesForm},
   newtypes::{CommunityId, DbUrl, InstanceId, PersonId, PostId},
   source::{
     comment::{Comment, CommentUpdateForm},
@@ -23,18 +23,21 @@ use lemmy_db_schema::{
     local_site::LocalSite,
     local_site_rate_limit::LocalSiteRateLimit,
     local_site_url_blocklist::LocalSiteUrlBlocklist,
+    moderator::{ModRemovePost, ModRemovePostForm},
     password_reset_request::PasswordResetRequest,
     person::{Person, PersonUpdateForm},
     person_block::PersonBlock,
-    post::{Post, PostRead},
+    post::{Post, PostRead, PostUpdateForm},
+    post_report::{PostReport, PostReportForm},
     site::Site,
   },
-  traits::Crud,
+  traits::{Crud, Reportable},
   utils::DbPool,
+  DuplicateTitleAction,
 };
 use lemmy_db_views::{
   comment_view::CommentQuery,
-  structs::{LocalImageView, LocalUserView},
+  structs::{LocalImageView, LocalUserView, PostView},
 };
 use lemmy_db_views_actor::structs::{
   CommunityModeratorView,
@@ -47,8 +50,8 @@ use lemmy_utils::{
   rate_limit::{ActionType, BucketConfig},
   settings::structs::{PictrsImageMode, Settings},
   utils::{
-    markdown::{markdown_check_for_blocked_urls, markdown_rewrite_image_links},
-    slurs::{build_slur_regex, remove_slurs},
+    markdown::{markdown_check_for_blocked_urls, markdown_rewrite_image_links, markdown_to_html},
+    slurs::{build_slur_regex, check_slurs_opt, check_title_slurs, remove_slurs},
   },
   CACHE_DURATION_FEDERATION,
 };
@@ -96,6 +99,23 @@ pub async fn is_mod_or_admin_opt(
   }
 }
 
+/// Check that a person is allowed to list individual votes in a community. This is normally
+/// allowed for mods and admins, but with [LocalSite.anonymize_votes] only admins can see who
+/// voted.
+#[tracing::instrument(skip_all)]
+pub async fn check_can_list_votes(
+  pool: &mut DbPool<'_>,
+  local_user_view: &LocalUserView,
+  community_id: CommunityId,
+  local_site: &LocalSite,
+) -> LemmyResult<()> {
+  if local_site.anonymize_votes {
+    is_admin(local_user_view)
+  } else {
+    is_mod_or_admin(pool, &local_user_view.person, community_id).await
+  }
+}
+
 /// Check that a person is either a mod of any community, or an admin
 ///
 /// Should only be used for read operations
@@ -233,6 +253,63 @@ async fn check_community_ban(
   Ok(())
 }
 
+/// Rejects posts outside of the community's posting window. Mods can post at any time.
+pub async fn check_community_posting_window(
+  community: &Community,
+  person_id: PersonId,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<()> {
+  if !community.is_posting_open(Utc::now())
+    && !CommunityModeratorView::is_community_moderator(pool, community.id, person_id).await?
+  {
+    Err(LemmyErrorType::PostingClosed)?
+  }
+  Ok(())
+}
+
+/// Rejects posts by users whose karma, the sum of their post and comment score, is below the
+/// community's minimum. For remote users the locally stored aggregates are used. Mods can always
+/// post.
+pub async fn check_community_min_karma(
+  community: &Community,
+  person_id: PersonId,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<()> {
+  if community.min_post_karma == 0 {
+    return Ok(());
+  }
+  let counts = PersonAggregates::read(pool, person_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindPerson)?;
+  if counts.post_score + counts.comment_score < i64::from(community.min_post_karma)
+    && !CommunityModeratorView::is_community_moderator(pool, community.id, person_id).await?
+  {
+    Err(LemmyErrorType::InsufficientKarma)?
+  }
+  Ok(())
+}
+
+/// Rejects posts which are published less than the community's post interval after the previous
+/// post of the same user. Mods are exempt.
+pub async fn check_community_post_interval(
+  community: &Community,
+  person_id: PersonId,
+  published: DateTime<Utc>,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<()> {
+  if community.post_interval_secs <= 0 {
+    return Ok(());
+  }
+  let interval = Duration::seconds(community.post_interval_secs.into());
+  let last_published = Post::latest_published_in_community(pool, community.id, person_id).await?;
+  if last_published.is_some_and(|last| published < last + interval)
+    && !CommunityModeratorView::is_community_moderator(pool, community.id, person_id).await?
+  {
+    Err(LemmyErrorType::PostingTooFast)?
+  }
+  Ok(())
+}
+
 /// Check that the given user can perform a mod action in the community.
 ///
 /// In particular it checks that he is an admin or mod, wasn't banned and the community isn't
@@ -338,6 +415,44 @@ pub fn check_downvotes_enabled(score: i16, local_site: &LocalSite) -> LemmyResul
   }
 }
 
+/// Dont allow voting for accounts which are younger than the site's vote cooldown
+#[tracing::instrument(skip_all)]
+pub fn check_vote_cooldown(person: &Person, local_site: &LocalSite) -> LemmyResult<()> {
+  let cooldown = chrono::Duration::hours(local_site.vote_cooldown_hours.into());
+  if Utc::now() < person.published + cooldown {
+    Err(LemmyErrorType::AccountTooNewToVote)?
+  } else {
+    Ok(())
+  }
+}
+
+/// Dont allow voting on posts which are older than the site's maximum post age for votes. Mods
+/// and admins can still vote.
+#[tracing::instrument(skip_all)]
+pub async fn check_vote_post_age(
+  post: &Post,
+  person_id: PersonId,
+  local_site: &LocalSite,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<()> {
+  let Some(max_age) = local_site.vote_max_post_age_days else {
+    return Ok(());
+  };
+  // A maximum age beyond the representable time range means there is no limit
+  let Some(oldest) = post
+    .published
+    .checked_add_signed(chrono::Duration::days(max_age.into()))
+  else {
+    return Ok(());
+  };
+  if Utc::now() > oldest
+    && !CommunityView::is_mod_or_admin(pool, person_id, post.community_id).await?
+  {
+    Err(LemmyErrorType::PostTooOldToVote)?
+  }
+  Ok(())
+}
+
 /// Dont allow bots to do certain actions, like voting
 #[tracing::instrument(skip_all)]
 pub fn check_bot_account(person: &Person) -> LemmyResult<()> {
@@ -538,6 +653,128 @@ pub fn local_site_opt_to_slur_regex(local_site: &Option<LocalSite>) -> Option<Re
     .unwrap_or(None)
 }
 
+/// Removes the post if its title or body matches the slur filter of its community, and writes a
+/// mod log entry without moderator. This is checked in addition to the site slur filter, so
+/// communities can only add words to it. With `normalize_titles`, the title is also checked in
+/// its normalized form.
+pub async fn check_community_slurs(
+  post: Post,
+  community: &Community,
+  normalize_titles: bool,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<Post> {
+  let slur_regex = build_slur_regex(community.slur_filter_regex.as_deref());
+  if post.removed
+    || (check_title_slurs(&post.name, &slur_regex, normalize_titles).is_ok()
+      && check_slurs_opt(&post.body, &slur_regex).is_ok())
+  {
+    return Ok(post);
+  }
+
+  remove_post_automatically(post, "Matched community slur filter", pool).await
+}
+
+/// Removes the post if it is marked as NSFW, but the community doesn't allow NSFW posts. Like
+/// [check_community_slurs] this writes a mod log entry without moderator.
+pub async fn check_community_nsfw(
+  post: Post,
+  community: &Community,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<Post> {
+  if post.removed || !post.nsfw || community.allow_nsfw {
+    return Ok(post);
+  }
+  remove_post_automatically(post, "NSFW posts are not allowed in this community", pool).await
+}
+
+/// Handles new posts whose title was already used in the community during the configured window.
+/// Depending on the community setting, the post is either reported or removed, both without a
+/// user or moderator. With `normalize_titles`, titles are compared in their normalized form.
+pub async fn check_community_duplicate_title(
+  post: Post,
+  community: &Community,
+  normalize_titles: bool,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<Post> {
+  if post.removed || community.duplicate_title_hours <= 0 {
+    return Ok(post);
+  }
+  let since = post.published - Duration::hours(community.duplicate_title_hours.into());
+  if Post::find_duplicate_title(pool, &post, since, normalize_titles)
+    .await?
+    .is_none()
+  {
+    return Ok(post);
+  }
+  let reason = "Duplicate title";
+  match community.duplicate_title_action {
+    DuplicateTitleAction::Remove => remove_post_automatically(post, reason, pool).await,
+    DuplicateTitleAction::Report => {
+      let form = PostReportForm {
+        creator_id: None,
+        post_id: post.id,
+        original_post_name: post.name.clone(),
+        original_post_url: post.url.clone(),
+        original_post_body: post.body.clone(),
+        reason: reason.to_string(),
+      };
+      PostReport::report(pool, &form).await?;
+      Ok(post)
+    }
+  }
+}
+
+async fn remove_post_automatically(
+  post: Post,
+  reason: &str,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<Post> {
+  let form = PostUpdateForm {
+    removed: Some(true),
+    ..Default::default()
+  };
+  let post = Post::update(pool, post.id, &form).await?;
+  let form = ModRemovePostForm {
+    mod_person_id: None,
+    post_id: post.id,
+    removed: Some(true),
+    reason: Some(reason.to_string()),
+  };
+  ModRemovePost::create(pool, &form).await?;
+  Ok(post)
+}
+
+/// Checks if federation with the domain is allowed. This fails if federation is disabled, if the
+/// domain is blocked, or if there is an allowlist which doesn't contain it.
+pub fn check_domain_federation(
+  domain: &str,
+  local_site: Option<&LocalSite>,
+  allowed_instances: &[Instance],
+  blocked_instances: &[Instance],
+) -> LemmyResult<()> {
+  if !local_site.map(|l| l.federation_enabled).unwrap_or(true) {
+    Err(LemmyErrorType::FederationDisabled)?
+  }
+
+  if blocked_instances
+    .iter()
+    .any(|i| domain.to_lowercase().eq(&i.domain.to_lowercase()))
+  {
+    Err(LemmyErrorType::DomainBlocked(domain.to_string()))?
+  }
+
+  // Only check this if there are instances in the allowlist
+  if !allowed_instances.is_empty()
+    && !allowed_instances
+      .iter()
+      .any(|i| domain.to_lowercase().eq(&i.domain.to_lowercase()))
+  {
+    Err(LemmyErrorType::DomainNotInAllowList(domain.to_string()))?
+  }
+
+  Ok(())
+}
+
 pub fn local_site_opt_to_sensitive(local_site: &Option<LocalSite>) -> bool {
   local_site
     .as_ref()
@@ -985,6 +1222,24 @@ pub async fn process_markdown_opt(
   }
 }
 
+/// Converts markdown to the HTML which Lemmy federates for a local post or comment with this body.
+/// Unlike [process_markdown], this has no side effects: image links are rewritten to the proxy,
+/// but their details are neither fetched from pictrs nor stored.
+pub async fn process_markdown_to_html(text: &str, context: &LemmyContext) -> LemmyResult<String> {
+  let local_site = LocalSite::read(&mut context.pool()).await.ok();
+  let slur_regex = local_site_opt_to_slur_regex(&local_site);
+  let url_blocklist = get_url_blocklist(context).await?;
+  let text = remove_slurs(text, &slur_regex);
+  markdown_check_for_blocked_urls(&text, &url_blocklist)?;
+  let text = if context.settings().pictrs_config()?.image_mode() == PictrsImageMode::ProxyAllImages
+  {
+    markdown_rewrite_image_links(text).0
+  } else {
+    text
+  };
+  Ok(markdown_to_html(&text))
+}
+
 /// A wrapper for `proxy_image_link` for use in tests.
 ///
 /// The parameter `force_image_proxy` is the config value of `pictrs.image_proxy`. Its necessary to
@@ -1060,6 +1315,41 @@ pub async fn proxy_image_link_opt_apub(
   }
 }
 
+/// Rewrites remote image urls of posts to go through the image proxy, if enabled by the local
+/// site. Only the response to clients is changed, federation uses the stored original urls.
+pub async fn proxy_post_attachments(
+  post_views: &mut [PostView],
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  let enabled = LocalSite::read(&mut context.pool())
+    .await
+    .is_ok_and(|l| l.proxy_post_attachments);
+  if !enabled {
+    return Ok(());
+  }
+  let links = post_views
+    .iter()
+    .filter(|p| {
+      p.post
+        .url_content_type
+        .as_deref()
+        .is_some_and(|t| t.starts_with("image/"))
+    })
+    .filter_map(|p| p.post.url.clone())
+    .collect();
+  // Images which weren't received over federation can't be proxied
+  let valid = RemoteImage::filter_valid(&mut context.pool(), links).await?;
+  let protocol_and_hostname = context.settings().get_protocol_and_hostname();
+  for post_view in post_views {
+    if let Some(url) = &mut post_view.post.url {
+      if valid.contains(url) {
+        *url = build_proxied_image_url(url.inner(), &protocol_and_hostname)?.into();
+      }
+    }
+  }
+  Ok(())
+}
+
 fn build_proxied_image_url(
   link: &Url,
   protocol_and_hostname: &str,
@@ -1077,6 +1367,17 @@ fn build_proxied_image_url(
 mod tests {
 
   use super::*;
+  use chrono::Timelike;
+  use lemmy_db_schema::{
+    source::{
+      community::{CommunityInsertForm, CommunityModeratorForm},
+      local_user::{LocalUser, LocalUserInsertForm},
+      person::PersonInsertForm,
+      post::{PostInsertForm, PostLike, PostLikeForm},
+    },
+    traits::{Joinable, Likeable},
+  };
+  use lemmy_db_views_moderator::structs::{ModRemovePostView, ModlogListParams};
   use pretty_assertions::assert_eq;
   use serial_test::serial;
 
@@ -1153,4 +1454,445 @@ mod tests {
         .is_err()
     );
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_process_markdown_to_html() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+
+    let link = process_markdown_to_html("[Lemmy](https://join-lemmy.org)", &context).await?;
+    assert_eq!(
+      "<p><a href=\"https://join-lemmy.org\" rel=\"nofollow\">Lemmy</a></p>\n",
+      link
+    );
+
+    // Mentions are federated as plain text, and resolved by the receiving instance
+    let mention = process_markdown_to_html("Hello @picard@enterprise.lemmy.ml", &context).await?;
+    assert_eq!("<p>Hello @picard@enterprise.lemmy.ml</p>\n", mention);
+
+    // Raw html and links with script urls are escaped
+    let script = process_markdown_to_html("<script>alert(1)</script>", &context).await?;
+    assert_eq!("<p>&lt;script&gt;alert(1)&lt;/script&gt;</p>\n", script);
+    let script_link = process_markdown_to_html("[click](javascript:alert(1))", &context).await?;
+    assert!(!script_link.contains("<a"));
+    let image = process_markdown_to_html("<img src=x onerror=alert(1)>", &context).await?;
+    assert!(!image.contains("<img"));
+    Ok(())
+  }
+
+  async fn create_local_user(
+    pool: &mut DbPool<'_>,
+    instance_id: InstanceId,
+    name: &str,
+    admin: bool,
+  ) -> LemmyResult<LocalUserView> {
+    let person = Person::create(pool, &PersonInsertForm::test_form(instance_id, name)).await?;
+    let form = LocalUserInsertForm {
+      admin: Some(admin),
+      ..LocalUserInsertForm::test_form(person.id)
+    };
+    let local_user = LocalUser::create(pool, &form, vec![]).await?;
+    Ok(
+      LocalUserView::read(pool, local_user.id)
+        .await?
+        .ok_or(LemmyErrorType::CouldntFindLocalUser)?,
+    )
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_check_can_list_votes() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+
+    let community_form = CommunityInsertForm::builder()
+      .name("test_community_votes".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+
+    let moderator = create_local_user(pool, instance.id, "vote_list_mod", false).await?;
+    let admin = create_local_user(pool, instance.id, "vote_list_admin", true).await?;
+    let moderator_form = CommunityModeratorForm {
+      community_id: community.id,
+      person_id: moderator.person.id,
+    };
+    CommunityModerator::join(pool, &moderator_form).await?;
+
+    // By default mods and admins can see votes
+    let local_site = LocalSite::default();
+    assert!(
+      check_can_list_votes(pool, &moderator, community.id, &local_site)
+        .await
+        .is_ok()
+    );
+    assert!(
+      check_can_list_votes(pool, &admin, community.id, &local_site)
+        .await
+        .is_ok()
+    );
+
+    // With anonymized votes only admins can
+    let local_site = LocalSite {
+      anonymize_votes: true,
+      ..Default::default()
+    };
+    assert!(
+      check_can_list_votes(pool, &moderator, community.id, &local_site)
+        .await
+        .is_err()
+    );
+    assert!(
+      check_can_list_votes(pool, &admin, community.id, &local_site)
+        .await
+        .is_ok()
+    );
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_check_vote_cooldown() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person = Person::create(
+      pool,
+      &PersonInsertForm::test_form(instance.id, "vote_cooldown_person"),
+    )
+    .await?;
+
+    // Without cooldown the new account can vote
+    assert!(check_vote_cooldown(&person, &LocalSite::default()).is_ok());
+
+    // A new account is inside the cooldown
+    let local_site = LocalSite {
+      vote_cooldown_hours: 24,
+      ..Default::default()
+    };
+    assert!(check_vote_cooldown(&person, &local_site)
+      .is_err_and(|e| e.error_type == LemmyErrorType::AccountTooNewToVote));
+
+    // An older account can vote
+    let old_person = Person {
+      published: Utc::now() - Days::new(2),
+      ..person
+    };
+    assert!(check_vote_cooldown(&old_person, &local_site).is_ok());
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_check_vote_post_age() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person = Person::create(
+      pool,
+      &PersonInsertForm::test_form(instance.id, "vote_post_age_person"),
+    )
+    .await?;
+    let moderator = Person::create(
+      pool,
+      &PersonInsertForm::test_form(instance.id, "vote_post_age_mod"),
+    )
+    .await?;
+    let form = CommunityInsertForm::builder()
+      .name("vote_post_age".to_string())
+      .title("vote post age".to_string())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &form).await?;
+    CommunityModerator::join(
+      pool,
+      &CommunityModeratorForm {
+        community_id: community.id,
+        person_id: moderator.id,
+      },
+    )
+    .await?;
+    let form = PostInsertForm::builder()
+      .name("Old news".to_string())
+      .creator_id(person.id)
+      .community_id(community.id)
+      .build();
+    let post = Post::create(pool, &form).await?;
+    let old_post = Post {
+      published: Utc::now() - Days::new(31),
+      ..post.clone()
+    };
+
+    // Without a limit, votes on any post are allowed
+    check_vote_post_age(&old_post, person.id, &LocalSite::default(), pool).await?;
+
+    // With a limit, only recent posts can be voted on
+    let local_site = LocalSite {
+      vote_max_post_age_days: Some(30),
+      ..Default::default()
+    };
+    check_vote_post_age(&post, person.id, &local_site, pool).await?;
+    assert!(check_vote_post_age(&old_post, person.id, &local_site, pool)
+      .await
+      .is_err_and(|e| e.error_type == LemmyErrorType::PostTooOldToVote));
+
+    // Mods can still vote on old posts
+    check_vote_post_age(&old_post, moderator.id, &local_site, pool).await?;
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_check_community_posting_window() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person = Person::create(
+      pool,
+      &PersonInsertForm::test_form(instance.id, "posting_window_person"),
+    )
+    .await?;
+    let moderator = Person::create(
+      pool,
+      &PersonInsertForm::test_form(instance.id, "posting_window_mod"),
+    )
+    .await?;
+
+    // Posting is allowed during the current hour
+    let hour = i16::try_from(Utc::now().hour())?;
+    let form = CommunityInsertForm::builder()
+      .name("posting_window".to_string())
+      .title("posting window".to_string())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .posting_window_start(Some(hour))
+      .posting_window_end(Some((hour + 1) % 24))
+      .build();
+    let community = Community::create(pool, &form).await?;
+    CommunityModerator::join(
+      pool,
+      &CommunityModeratorForm {
+        community_id: community.id,
+        person_id: moderator.id,
+      },
+    )
+    .await?;
+    check_community_posting_window(&community, person.id, pool).await?;
+
+    // Outside of the window, only mods can post
+    let form = CommunityUpdateForm {
+      posting_window_start: Some((hour + 1) % 24),
+      posting_window_end: Some((hour + 2) % 24),
+      ..Default::default()
+    };
+    let community = Community::update(pool, community.id, &form).await?;
+    assert!(check_community_posting_window(&community, person.id, pool)
+      .await
+      .is_err_and(|e| e.error_type == LemmyErrorType::PostingClosed));
+    check_community_posting_window(&community, moderator.id, pool).await?;
+
+    // Windows which extend past midnight
+    let night = Community {
+      posting_window_start: 22,
+      posting_window_end: 2,
+      ..community
+    };
+    let at = |hour| Utc.with_ymd_and_hms(2024, 7, 15, hour, 30, 0).single();
+    assert!(at(23).is_some_and(|t| night.is_posting_open(t)));
+    assert!(at(1).is_some_and(|t| night.is_posting_open(t)));
+    assert!(at(2).is_some_and(|t| !night.is_posting_open(t)));
+    assert!(at(12).is_some_and(|t| !night.is_posting_open(t)));
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_check_community_min_karma() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person = Person::create(
+      pool,
+      &PersonInsertForm::test_form(instance.id, "min_karma_person"),
+    )
+    .await?;
+    let voter = Person::create(
+      pool,
+      &PersonInsertForm::test_form(instance.id, "min_karma_voter"),
+    )
+    .await?;
+    let moderator = Person::create(
+      pool,
+      &PersonInsertForm::test_form(instance.id, "min_karma_mod"),
+    )
+    .await?;
+    let form = CommunityInsertForm::builder()
+      .name("min_karma".to_string())
+      .title("min karma".to_string())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .min_post_karma(Some(1))
+      .build();
+    let community = Community::create(pool, &form).await?;
+    CommunityModerator::join(
+      pool,
+      &CommunityModeratorForm {
+        community_id: community.id,
+        person_id: moderator.id,
+      },
+    )
+    .await?;
+
+    // New users have no karma
+    assert!(check_community_min_karma(&community, person.id, pool)
+      .await
+      .is_err_and(|e| e.error_type == LemmyErrorType::InsufficientKarma));
+    check_community_min_karma(&community, moderator.id, pool).await?;
+
+    // An upvote on one of their posts is enough
+    let form = PostInsertForm::builder()
+      .name("karma".to_string())
+      .creator_id(person.id)
+      .community_id(community.id)
+      .build();
+    let post = Post::create(pool, &form).await?;
+    let form = PostLikeForm {
+      post_id: post.id,
+      person_id: voter.id,
+      score: 1,
+    };
+    PostLike::like(pool, &form).await?;
+    check_community_min_karma(&community, person.id, pool).await?;
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_check_community_post_interval() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person = Person::create(
+      pool,
+      &PersonInsertForm::test_form(instance.id, "post_interval_person"),
+    )
+    .await?;
+    let moderator = Person::create(
+      pool,
+      &PersonInsertForm::test_form(instance.id, "post_interval_mod"),
+    )
+    .await?;
+    let form = CommunityInsertForm::builder()
+      .name("post_interval".to_string())
+      .title("post interval".to_string())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .post_interval_secs(Some(60))
+      .build();
+    let community = Community::create(pool, &form).await?;
+    CommunityModerator::join(
+      pool,
+      &CommunityModeratorForm {
+        community_id: community.id,
+        person_id: moderator.id,
+      },
+    )
+    .await?;
+
+    // The first post is always allowed
+    check_community_post_interval(&community, person.id, Utc::now(), pool).await?;
+    for creator_id in [person.id, moderator.id] {
+      let form = PostInsertForm::builder()
+        .name("first".to_string())
+        .creator_id(creator_id)
+        .community_id(community.id)
+        .build();
+      Post::create(pool, &form).await?;
+    }
+
+    // A second post within the interval is rejected, except for mods
+    assert!(check_community_post_interval(&community, person.id, Utc::now(), pool)
+      .await
+      .is_err_and(|e| e.error_type == LemmyErrorType::PostingTooFast));
+    check_community_post_interval(&community, moderator.id, Utc::now(), pool).await?;
+    let later = Utc::now() + Duration::seconds(61);
+    check_community_post_interval(&community, person.id, later, pool).await?;
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_check_community_slurs() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person = Person::create(
+      pool,
+      &PersonInsertForm::test_form(instance.id, "community_slurs_person"),
+    )
+    .await?;
+    let form = CommunityInsertForm::builder()
+      .name("community_slurs".to_string())
+      .title("community slurs".to_string())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .slur_filter_regex(Some("spoiler".to_string()))
+      .build();
+    let community = Community::create(pool, &form).await?;
+
+    let form = PostInsertForm::builder()
+      .name("Ending explained".to_string())
+      .creator_id(person.id)
+      .community_id(community.id)
+      .build();
+    let post = Post::create(pool, &form).await?;
+    let post = check_community_slurs(post, &community, false, pool).await?;
+    assert!(!post.removed);
+
+    // A word from the community filter causes removal, with a mod log entry without moderator,
+    // even though the community has no mods
+    let form = PostInsertForm::builder()
+      .name("Ending explained".to_string())
+      .body(Some("Contains SPOILERS".to_string()))
+      .creator_id(person.id)
+      .community_id(community.id)
+      .build();
+    let post = Post::create(pool, &form).await?;
+    let post = check_community_slurs(post, &community, false, pool).await?;
+    assert!(post.removed);
+    let params = ModlogListParams {
+      community_id: None,
+      mod_person_id: None,
+      other_person_id: None,
+      post_id: Some(post.id),
+      comment_id: None,
+      page: None,
+      limit: None,
+      hide_modlog_names: false,
+    };
+    let modlog = ModRemovePostView::list(pool, params).await?;
+    assert_eq!(1, modlog.len());
+    let entry = modlog.first().ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert_eq!(None, entry.mod_remove_post.mod_person_id);
+    assert!(entry.moderator.is_none());
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
 }
@@ -37,7 +37,13 @@ use lemmy_utils::{
   error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
   utils::{
     slurs::check_slurs,
-    validation::{is_valid_actor_name, is_valid_body_field},
+    validation::{
+      build_and_check_regex,
+      is_valid_actor_name,
+      is_valid_body_field,
+      is_valid_duplicate_title_hours,
+      is_valid_posting_window_hour,
+    },
   },
 };
 
@@ -74,6 +80,10 @@ pub async fn create_community(
   if let Some(desc) = &data.description {
     is_valid_body_field(desc, false)?;
   }
+  is_valid_posting_window_hour(data.posting_window_start)?;
+  is_valid_posting_window_hour(data.posting_window_end)?;
+  is_valid_duplicate_title_hours(data.duplicate_title_hours)?;
+  build_and_check_regex(&data.slur_filter_regex.as_deref())?;
 
   // Double check for duplicate community actor_ids
   let community_actor_id = generate_local_apub_endpoint(
@@ -106,6 +116,14 @@ pub async fn create_community(
     .posting_restricted_to_mods(data.posting_restricted_to_mods)
     .instance_id(site_view.site.instance_id)
     .visibility(data.visibility)
+    .posting_window_start(data.posting_window_start)
+    .posting_window_end(data.posting_window_end)
+    .slur_filter_regex(data.slur_filter_regex.clone().filter(|r| !r.is_empty()))
+    .allow_nsfw(data.allow_nsfw)
+    .duplicate_title_hours(data.duplicate_title_hours)
+    .duplicate_title_action(data.duplicate_title_action)
+    .min_post_karma(data.min_post_karma)
+    .post_interval_secs(data.post_interval_secs)
     .build();
 
   let inserted_community = Community::create(&mut context.pool(), &community_form)
@@ -26,7 +26,15 @@ use lemmy_db_schema::{
 use lemmy_db_views::structs::LocalUserView;
 use lemmy_utils::{
   error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
-  utils::{slurs::check_slurs_opt, validation::is_valid_body_field},
+  utils::{
+    slurs::check_slurs_opt,
+    validation::{
+      build_and_check_regex,
+      is_valid_body_field,
+      is_valid_duplicate_title_hours,
+      is_valid_posting_window_hour,
+    },
+  },
 };
 
 #[tracing::instrument(skip(context))]
@@ -50,6 +58,10 @@ pub async fn update_community(
   if let Some(Some(desc)) = &description {
     is_valid_body_field(desc, false)?;
   }
+  is_valid_posting_window_hour(data.posting_window_start)?;
+  is_valid_posting_window_hour(data.posting_window_end)?;
+  is_valid_duplicate_title_hours(data.duplicate_title_hours)?;
+  build_and_check_regex(&data.slur_filter_regex.as_deref())?;
 
   let old_community = Community::read(&mut context.pool(), data.community_id)
     .await?
@@ -92,6 +104,16 @@ pub async fn update_community(
     nsfw: data.nsfw,
     posting_restricted_to_mods: data.posting_restricted_to_mods,
     visibility: data.visibility,
+    posting_window_start: data.posting_window_start,
+    posting_window_end: data.posting_window_end,
+    federation_paused: data.federation_paused,
+    federation_incoming_paused: data.federation_incoming_paused,
+    slur_filter_regex: diesel_string_update(data.slur_filter_regex.as_deref()),
+    allow_nsfw: data.allow_nsfw,
+    duplicate_title_hours: data.duplicate_title_hours,
+    duplicate_title_action: data.duplicate_title_action,
+    min_post_karma: data.min_post_karma,
+    post_interval_secs: data.post_interval_secs,
     updated: Some(Some(naive_now())),
     ..Default::default()
   };
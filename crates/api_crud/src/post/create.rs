@@ -5,8 +5,14 @@ use lemmy_api_common::{
   context::LemmyContext,
   post::{CreatePost, PostResponse},
   request::generate_post_link_metadata,
-  send_activity::SendActivityData,
+  send_activity::{create_post_activity, schedule_create_post_activity},
   utils::{
+    check_community_duplicate_title,
+    check_community_min_karma,
+    check_community_nsfw,
+    check_community_post_interval,
+    check_community_posting_window,
+    check_community_slurs,
     check_community_user_action,
     get_url_blocklist,
     honeypot_check,
@@ -16,7 +22,7 @@ use lemmy_api_common::{
   },
 };
 use lemmy_db_schema::{
-  impls::actor_language::default_post_language,
+  impls::actor_language::{default_post_language, UNDETERMINED_ID},
   source::{
     actor_language::CommunityLanguage,
     community::Community,
@@ -24,7 +30,7 @@ use lemmy_db_schema::{
     post::{Post, PostInsertForm, PostLike, PostLikeForm},
   },
   traits::{Crud, Likeable},
-  utils::diesel_url_create,
+  utils::{diesel_url_create, naive_now},
   CommunityVisibility,
 };
 use lemmy_db_views::structs::LocalUserView;
@@ -33,7 +39,7 @@ use lemmy_utils::{
   error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
   spawn_try_task,
   utils::{
-    slurs::check_slurs,
+    slurs::check_title_slurs,
     validation::{
       check_url_scheme,
       is_url_blocked,
@@ -58,7 +64,7 @@ pub async fn create_post(
   honeypot_check(&data.honeypot)?;
 
   let slur_regex = local_site_to_slur_regex(&local_site);
-  check_slurs(&data.name, &slur_regex)?;
+  check_title_slurs(&data.name, &slur_regex, local_site.normalize_titles)?;
   let url_blocklist = get_url_blocklist(&context).await?;
 
   let body = process_markdown_opt(&data.body, &slur_regex, &url_blocklist, &context).await?;
@@ -107,6 +113,16 @@ pub async fn create_post(
       Err(LemmyErrorType::OnlyModsCanPostInCommunity)?
     }
   }
+  check_community_posting_window(&community, local_user_view.person.id, &mut context.pool())
+    .await?;
+  check_community_min_karma(&community, local_user_view.person.id, &mut context.pool()).await?;
+  check_community_post_interval(
+    &community,
+    local_user_view.person.id,
+    naive_now(),
+    &mut context.pool(),
+  )
+  .await?;
 
   // Only need to check if language is allowed in case user set it explicitly. When using default
   // language, it already only returns allowed languages.
@@ -130,25 +146,59 @@ pub async fn create_post(
     }
   };
 
+  // Posts with the same link are grouped as crossposts, also on other instances
+  let crosspost_group = match &url {
+    Some(url) => Post::crosspost_group_for_url(&mut context.pool(), url).await?,
+    None => None,
+  };
+
   let post_form = PostInsertForm::builder()
     .name(data.name.trim().to_string())
-    .url(url.map(Into::into))
+    .url(url)
     .body(body)
     .alt_text(data.alt_text.clone())
     .community_id(data.community_id)
     .creator_id(local_user_view.person.id)
     .nsfw(data.nsfw)
     .language_id(language_id)
+    .crosspost_group(crosspost_group)
+    .original_content(data.original_content)
+    .default_comment_sort(data.default_comment_sort)
+    .close_at(data.close_at)
+    .original_language_id(
+      data
+        .original_language_id
+        .filter(|id| Some(*id) != language_id && *id != UNDETERMINED_ID),
+    )
     .build();
 
   let inserted_post = Post::create(&mut context.pool(), &post_form)
     .await
     .with_lemmy_type(LemmyErrorType::CouldntCreatePost)?;
+  let normalize_titles = local_site.normalize_titles;
+  let inserted_post = check_community_slurs(
+    inserted_post,
+    &community,
+    normalize_titles,
+    &mut context.pool(),
+  )
+  .await?;
+  let inserted_post = check_community_nsfw(inserted_post, &community, &mut context.pool()).await?;
+  let inserted_post = check_community_duplicate_title(
+    inserted_post,
+    &community,
+    normalize_titles,
+    &mut context.pool(),
+  )
+  .await?;
+
+  schedule_create_post_activity(&inserted_post, &local_site, &mut context.pool()).await?;
 
+  let local_site_ = local_site.clone();
   generate_post_link_metadata(
     inserted_post.clone(),
     custom_thumbnail.map(Into::into),
-    |post| Some(SendActivityData::CreatePost(post)),
+    move |post| create_post_activity(post, &local_site_),
     Some(local_site),
     context.reset_request_count(),
   )
@@ -2,7 +2,13 @@ use actix_web::web::{Data, Json, Query};
 use lemmy_api_common::{
   context::LemmyContext,
   post::{GetPost, GetPostResponse},
-  utils::{check_private_instance, is_mod_or_admin_opt, mark_post_as_read, update_read_comments},
+  utils::{
+    check_private_instance,
+    is_mod_or_admin_opt,
+    mark_post_as_read,
+    proxy_post_attachments,
+    update_read_comments,
+  },
 };
 use lemmy_db_schema::{
   source::{comment::Comment, post::Post},
@@ -56,7 +62,7 @@ pub async fn get_post(
   .is_ok();
 
   let local_user = local_user_view.map(|l| l.local_user);
-  let post_view = PostView::read(
+  let mut post_view = PostView::read(
     &mut context.pool(),
     post_id,
     local_user.as_ref(),
@@ -91,7 +97,7 @@ pub async fn get_post(
   let moderators = CommunityModeratorView::for_community(&mut context.pool(), community_id).await?;
 
   // Fetch the cross_posts
-  let cross_posts = if let Some(url) = &post_view.post.url {
+  let mut cross_posts = if let Some(url) = &post_view.post.url {
     let mut x_posts = PostQuery {
       url_search: Some(url.inner().as_str().into()),
       local_user: local_user.as_ref(),
@@ -100,12 +106,30 @@ pub async fn get_post(
     .list(&local_site.site, &mut context.pool())
     .await?;
 
-    // Don't return this post as one of the cross_posts
-    x_posts.retain(|x| x.post.id != post_id);
     x_posts
   } else {
     Vec::new()
   };
+  // Crossposts from other instances may have a different url, but share the federated group
+  if let Some(crosspost_group) = &post_view.post.crosspost_group {
+    let group_posts = PostQuery {
+      crosspost_group: Some(crosspost_group.clone()),
+      local_user: local_user.as_ref(),
+      ..Default::default()
+    }
+    .list(&local_site.site, &mut context.pool())
+    .await?;
+    for x in group_posts {
+      if !cross_posts.iter().any(|c| c.post.id == x.post.id) {
+        cross_posts.push(x);
+      }
+    }
+  }
+  // Don't return this post as one of the cross_posts
+  cross_posts.retain(|x| x.post.id != post_id);
+  // The original urls are needed above to find the cross_posts
+  proxy_post_attachments(std::slice::from_mut(&mut post_view), &context).await?;
+  proxy_post_attachments(&mut cross_posts, &context).await?;
 
   // Return the jwt
   Ok(Json(GetPostResponse {
@@ -55,7 +55,7 @@ pub async fn remove_post(
 
   // Mod tables
   let form = ModRemovePostForm {
-    mod_person_id: local_user_view.person.id,
+    mod_person_id: Some(local_user_view.person.id),
     post_id: data.post_id,
     removed: Some(removed),
     reason: data.reason.clone(),
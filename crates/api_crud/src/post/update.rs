@@ -5,8 +5,9 @@ use lemmy_api_common::{
   context::LemmyContext,
   post::{EditPost, PostResponse},
   request::generate_post_link_metadata,
-  send_activity::SendActivityData,
+  send_activity::update_post_activity,
   utils::{
+    check_community_mod_action,
     check_community_user_action,
     get_url_blocklist,
     local_site_to_slur_regex,
@@ -14,10 +15,12 @@ use lemmy_api_common::{
   },
 };
 use lemmy_db_schema::{
+  impls::actor_language::UNDETERMINED_ID,
   source::{
     actor_language::CommunityLanguage,
     local_site::LocalSite,
     post::{Post, PostUpdateForm},
+    post_previous_version::PostPreviousVersion,
   },
   traits::Crud,
   utils::{diesel_string_update, diesel_url_update, naive_now},
@@ -26,7 +29,7 @@ use lemmy_db_views::structs::LocalUserView;
 use lemmy_utils::{
   error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
   utils::{
-    slurs::check_slurs,
+    slurs::check_title_slurs,
     validation::{
       check_url_scheme,
       is_url_blocked,
@@ -64,7 +67,7 @@ pub async fn update_post(
 
   if let Some(name) = &data.name {
     is_valid_post_title(name)?;
-    check_slurs(name, &slur_regex)?;
+    check_title_slurs(name, &slur_regex, local_site.normalize_titles)?;
   }
 
   if let Some(Some(body)) = &body {
@@ -96,9 +99,24 @@ pub async fn update_post(
   )
   .await?;
 
-  // Verify that only the creator can edit
-  if !Post::is_post_creator(local_user_view.person.id, orig_post.creator_id) {
-    Err(LemmyErrorType::NoPostEditAllowed)?
+  // Verify that only the creator or a mod can edit. Edits by mods are sent by the mod, so they
+  // can only federate for local posts.
+  let is_creator = Post::is_post_creator(local_user_view.person.id, orig_post.creator_id);
+  if !is_creator {
+    if !orig_post.local {
+      Err(LemmyErrorType::NoPostEditAllowed)?
+    }
+    check_community_mod_action(
+      &local_user_view.person,
+      orig_post.community_id,
+      false,
+      &mut context.pool(),
+    )
+    .await?;
+    // Marking as original content is a claim about authorship
+    if data.original_content.is_some() {
+      Err(LemmyErrorType::NoPostEditAllowed)?
+    }
   }
 
   let language_id = data.language_id;
@@ -109,6 +127,21 @@ pub async fn update_post(
   )
   .await?;
 
+  // A changed link moves the post to the crossposts of the new link
+  let crosspost_group = match &url {
+    Some(Some(url)) if Some(url) != orig_post.url.as_ref() => {
+      Some(Post::crosspost_group_for_url(&mut context.pool(), url).await?)
+    }
+    Some(None) => Some(None),
+    _ => None,
+  };
+
+  // Only meaningful for translations, so it must differ from the post language
+  let post_language_id = language_id.unwrap_or(orig_post.language_id);
+  let original_language_id = data
+    .original_language_id
+    .map(|id| id.filter(|id| *id != post_language_id && *id != UNDETERMINED_ID));
+
   let post_form = PostUpdateForm {
     name: data.name.clone(),
     url,
@@ -116,7 +149,13 @@ pub async fn update_post(
     alt_text,
     nsfw: data.nsfw,
     language_id: data.language_id,
+    original_content: data.original_content,
+    default_comment_sort: data.default_comment_sort,
+    close_at: data.close_at,
+    original_language_id,
+    crosspost_group,
     updated: Some(Some(naive_now())),
+    updated_by: Some((!is_creator).then_some(local_user_view.person.id)),
     ..Default::default()
   };
 
@@ -124,11 +163,13 @@ pub async fn update_post(
   let updated_post = Post::update(&mut context.pool(), post_id, &post_form)
     .await
     .with_lemmy_type(LemmyErrorType::CouldntUpdatePost)?;
+  PostPreviousVersion::store_if_changed(&mut context.pool(), &orig_post, &updated_post).await?;
 
+  let local_site_ = local_site.clone();
   generate_post_link_metadata(
     updated_post.clone(),
     custom_thumbnail.flatten().map(Into::into),
-    |post| Some(SendActivityData::UpdatePost(post)),
+    move |post| update_post_activity(post, &local_site_),
     Some(local_site),
     context.reset_request_count(),
   )
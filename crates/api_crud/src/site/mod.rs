@@ -1,4 +1,11 @@
-use lemmy_db_schema::{ListingType, RegistrationMode};
+use lemmy_db_schema::{
+  newtypes::CommunityId,
+  source::community::Community,
+  traits::Crud,
+  utils::DbPool,
+  ListingType,
+  RegistrationMode,
+};
 use lemmy_utils::error::{LemmyErrorType, LemmyResult};
 
 pub mod create;
@@ -40,6 +47,22 @@ pub fn application_question_check(
   }
 }
 
+/// Checks that the fallback community for federated posts is a local community.
+pub async fn fallback_community_check(
+  fallback_community_id: Option<Option<CommunityId>>,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<()> {
+  if let Some(Some(community_id)) = fallback_community_id {
+    let community = Community::read(pool, community_id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindCommunity)?;
+    if !community.local {
+      Err(LemmyErrorType::ObjectNotLocal)?
+    }
+  }
+  Ok(())
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 #[allow(clippy::indexing_slicing)]
@@ -1,4 +1,8 @@
-use crate::site::{application_question_check, site_default_post_listing_type_check};
+use crate::site::{
+  application_question_check,
+  fallback_community_check,
+  site_default_post_listing_type_check,
+};
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_api_common::{
@@ -36,12 +40,21 @@ use lemmy_utils::{
   utils::{
     slurs::check_slurs_opt,
     validation::{
+      backfill_max_comments_check,
+      body_strip_patterns_check,
       build_and_check_regex,
       check_site_visibility_valid,
       check_urls_are_valid,
+      community_quarantine_days_check,
+      federation_dead_instance_threshold_check,
+      federation_future_tolerance_check,
       is_valid_body_field,
+      page_types_check,
       site_description_length_check,
       site_name_length_check,
+      thumbnail_settings_check,
+      user_agent_check,
+      vote_max_post_age_check,
     },
   },
 };
@@ -62,6 +75,7 @@ pub async fn update_site(
   is_admin(&local_user_view)?;
 
   validate_update_payload(&local_site, &data)?;
+  fallback_community_check(data.federation_fallback_community_id, &mut context.pool()).await?;
 
   if let Some(discussion_languages) = data.discussion_languages.clone() {
     SiteLanguage::update(&mut context.pool(), discussion_languages.clone(), &site).await?;
@@ -122,6 +136,40 @@ pub async fn update_site(
     captcha_difficulty: data.captcha_difficulty.clone(),
     reports_email_admins: data.reports_email_admins,
     default_post_listing_mode: data.default_post_listing_mode,
+    post_title_from_summary: data.post_title_from_summary,
+    post_title_content_length: data.post_title_content_length,
+    anonymize_votes: data.anonymize_votes,
+    thumbnail_max_size: data.thumbnail_max_size,
+    thumbnail_format: diesel_string_update(data.thumbnail_format.as_deref()),
+    federation_edit_grace_period: data.federation_edit_grace_period,
+    vote_cooldown_hours: data.vote_cooldown_hours,
+    federation_content_max_length: data.federation_content_max_length,
+    max_comment_fetch_depth: data.max_comment_fetch_depth,
+    attachment_allowed_types: data.attachment_allowed_types.clone(),
+    attachment_blocked_types: data.attachment_blocked_types.clone(),
+    federation_dead_instance_threshold: data.federation_dead_instance_threshold,
+    vote_max_post_age_days: data.vote_max_post_age_days,
+    federation_backfill_request_limit: data.federation_backfill_request_limit,
+    federation_community_quarantine_days: data.federation_community_quarantine_days,
+    federation_user_agent: diesel_string_update(data.federation_user_agent.as_deref()),
+    tracking_pixel_domains: data.tracking_pixel_domains.clone(),
+    vote_batching_threshold: data.vote_batching_threshold,
+    federation_reports: data.federation_reports,
+    federation_reports_anonymous: data.federation_reports_anonymous,
+    federation_store_raw_json: data.federation_store_raw_json,
+    federation_fallback_community_id: data.federation_fallback_community_id,
+    proxy_post_attachments: data.proxy_post_attachments,
+    federation_max_recipients: data.federation_max_recipients,
+    federation_future_tolerance: data.federation_future_tolerance,
+    normalize_titles: data.normalize_titles,
+    federation_group_posts: data.federation_group_posts,
+    thumbnail_min_size: data.thumbnail_min_size,
+    federation_vote_switch_undo: data.federation_vote_switch_undo,
+    federation_body_strip_patterns: data.federation_body_strip_patterns.clone(),
+    federation_page_types: data.federation_page_types.clone(),
+    federation_preserve_html: data.federation_preserve_html,
+    federation_comments_enabled_default: data.federation_comments_enabled_default,
+    backfill_max_comments_per_post: data.backfill_max_comments_per_post,
     ..Default::default()
   };
 
@@ -240,6 +288,20 @@ fn validate_update_payload(local_site: &LocalSite, edit_site: &EditSite) -> Lemm
     is_valid_body_field(body, false)?;
   }
 
+  thumbnail_settings_check(
+    edit_site.thumbnail_max_size.flatten(),
+    edit_site.thumbnail_min_size.flatten(),
+    edit_site.thumbnail_format.as_deref(),
+  )?;
+  user_agent_check(edit_site.federation_user_agent.as_deref())?;
+  body_strip_patterns_check(edit_site.federation_body_strip_patterns.as_deref())?;
+  page_types_check(edit_site.federation_page_types.as_deref())?;
+  backfill_max_comments_check(edit_site.backfill_max_comments_per_post.flatten())?;
+  federation_dead_instance_threshold_check(edit_site.federation_dead_instance_threshold.flatten())?;
+  federation_future_tolerance_check(edit_site.federation_future_tolerance)?;
+  community_quarantine_days_check(edit_site.federation_community_quarantine_days.flatten())?;
+  vote_max_post_age_check(edit_site.vote_max_post_age_days.flatten())?;
+
   application_question_check(
     &local_site.application_question,
     &edit_site.application_question,
@@ -612,6 +674,40 @@ mod tests {
       reports_email_admins: None,
       content_warning: None,
       default_post_listing_mode: None,
+      post_title_from_summary: None,
+      post_title_content_length: None,
+      anonymize_votes: None,
+      thumbnail_max_size: None,
+      thumbnail_format: None,
+      federation_edit_grace_period: None,
+      vote_cooldown_hours: None,
+      federation_content_max_length: None,
+      max_comment_fetch_depth: None,
+      attachment_allowed_types: None,
+      attachment_blocked_types: None,
+      federation_dead_instance_threshold: None,
+      vote_max_post_age_days: None,
+      federation_backfill_request_limit: None,
+      federation_community_quarantine_days: None,
+      federation_user_agent: None,
+      tracking_pixel_domains: None,
+      vote_batching_threshold: None,
+      federation_reports: None,
+      federation_reports_anonymous: None,
+      federation_store_raw_json: None,
+      federation_fallback_community_id: None,
+      proxy_post_attachments: None,
+      federation_max_recipients: None,
+      federation_future_tolerance: None,
+      normalize_titles: None,
+      federation_group_posts: None,
+      thumbnail_min_size: None,
+      federation_vote_switch_undo: None,
+      federation_body_strip_patterns: None,
+      federation_page_types: None,
+      federation_preserve_html: None,
+      federation_comments_enabled_default: None,
+      backfill_max_comments_per_post: None,
     }
   }
 }
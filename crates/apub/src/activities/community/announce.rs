@@ -11,6 +11,7 @@ use crate::{
   objects::community::ApubCommunity,
   protocol::{
     activities::community::announce::{AnnounceActivity, RawAnnouncableActivities},
+    objects::page::is_fallback_community,
     Id,
     IdOrNestedObject,
     InCommunity,
@@ -67,7 +68,10 @@ impl ActivityHandler for RawAnnouncableActivities {
 
     // if community is local, send activity to followers
     if let Some(community) = community {
-      if community.local {
+      if community.local
+        && !self.is_misdirected(&community, context).await
+        && !is_removed_post(&activity, context).await
+      {
         let actor_id = activity.actor().clone().into();
         verify_person_in_community(&actor_id, &community, context).await?;
         AnnounceActivity::send(self, &community, context).await?;
@@ -77,6 +81,47 @@ impl ActivityHandler for RawAnnouncableActivities {
   }
 }
 
+impl RawAnnouncableActivities {
+  /// Activities which only ended up in the fallback community, because their actual community
+  /// couldn't be found, are not announced. Its followers didn't ask for them, and the sender
+  /// didn't address the community.
+  pub(crate) async fn is_misdirected(
+    &self,
+    community: &ApubCommunity,
+    context: &Data<LemmyContext>,
+  ) -> bool {
+    if !is_fallback_community(community, context).await {
+      return false;
+    }
+    let community_id = community.actor_id.as_str();
+    !["to", "cc", "audience"]
+      .iter()
+      .filter_map(|field| self.other.get(*field))
+      .any(|value| match value {
+        Value::String(id) => id == community_id,
+        Value::Array(ids) => ids.iter().any(|id| id.as_str() == Some(community_id)),
+        _ => false,
+      })
+  }
+}
+
+/// Posts which were removed while receiving them, eg by the community slur filter, are not
+/// announced. Otherwise the followers of the community would get them anyway.
+pub(crate) async fn is_removed_post(
+  activity: &AnnouncableActivities,
+  context: &Data<LemmyContext>,
+) -> bool {
+  let AnnouncableActivities::CreateOrUpdatePost(create_or_update) = activity else {
+    return false;
+  };
+  create_or_update
+    .object
+    .id
+    .dereference_local(context)
+    .await
+    .is_ok_and(|post| post.removed)
+}
+
 impl AnnounceActivity {
   pub(crate) fn new(
     object: RawAnnouncableActivities,
@@ -203,7 +248,8 @@ impl TryFrom<AnnouncableActivities> for RawAnnouncableActivities {
 }
 
 /// Check if an activity in the given community can be accepted. To return true, the community must
-/// either be local to this instance, or it must have at least one local follower.
+/// either be local to this instance, or it must have at least one local follower. Activities are
+/// also rejected while incoming federation of the community is paused.
 ///
 /// TODO: This means mentions dont work if the community has no local followers. Can be fixed
 ///       by checking if any local user is in to/cc fields of activity. Anyway this is a minor
@@ -222,6 +268,9 @@ async fn can_accept_activity_in_community(
     if community.visibility != CommunityVisibility::Public {
       return Err(LemmyErrorType::CouldntFindCommunity.into());
     }
+    if community.federation_incoming_paused {
+      Err(LemmyErrorType::CommunityFederationPaused)?
+    }
   }
   Ok(())
 }
@@ -30,7 +30,7 @@ use lemmy_db_schema::{
   source::{
     activity::ActivitySendTargets,
     community::{Community, CommunityModerator, CommunityModeratorForm},
-    moderator::{ModAddCommunity, ModAddCommunityForm},
+    moderator::{ModAddCommunity, ModAddCommunityForm, ModFeaturePost, ModFeaturePostForm},
     person::Person,
     post::{Post, PostUpdateForm},
   },
@@ -169,9 +169,22 @@ impl ActivityHandler for CollectionAdd {
           .await?;
         let form = PostUpdateForm {
           featured_community: Some(true),
+          featured_position: Some(
+            Post::next_featured_position(&mut context.pool(), post.community_id).await?,
+          ),
           ..Default::default()
         };
         Post::update(&mut context.pool(), post.id, &form).await?;
+
+        // write mod log
+        let actor = self.actor.dereference(context).await?;
+        let form = ModFeaturePostForm {
+          mod_person_id: actor.id,
+          post_id: post.id,
+          featured: true,
+          is_featured_community: true,
+        };
+        ModFeaturePost::create(&mut context.pool(), &form).await?;
       }
     }
     Ok(())
@@ -219,3 +232,100 @@ pub(crate) async fn send_feature_post(
     CollectionRemove::send_remove_featured_post(&community, &post, &actor, &context).await
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    objects::{
+      community::tests::parse_lemmy_community,
+      person::tests::parse_lemmy_person,
+      post::ApubPost,
+    },
+    protocol::{objects::page::Page, tests::file_to_json_object},
+  };
+  use activitypub_federation::{kinds::activity::RemoveType, traits::Object};
+  use lemmy_db_schema::source::site::Site;
+  use lemmy_db_views_moderator::structs::{ModFeaturePostView, ModlogListParams};
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_receive_featured_post() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+    let page: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    let post = ApubPost::from_json(page, &context).await?;
+    assert!(!post.featured_community);
+
+    let featured_url: Url = community
+      .featured_url
+      .clone()
+      .ok_or(LemmyErrorType::CouldntFindCommunity)?
+      .into();
+    let add = CollectionAdd {
+      actor: person.id().into(),
+      to: vec![public()],
+      object: post.ap_id.clone().into(),
+      target: featured_url.clone(),
+      cc: vec![community.id()],
+      kind: AddType::Add,
+      id: generate_activity_id(
+        AddType::Add,
+        &context.settings().get_protocol_and_hostname(),
+      )?,
+      audience: Some(community.id().into()),
+    };
+    add.receive(&context).await?;
+    let featured = Post::read(&mut context.pool(), post.id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert!(featured.featured_community);
+
+    let remove = CollectionRemove {
+      actor: person.id().into(),
+      to: vec![public()],
+      object: post.ap_id.clone().into(),
+      target: featured_url,
+      cc: vec![community.id()],
+      kind: RemoveType::Remove,
+      id: generate_activity_id(
+        RemoveType::Remove,
+        &context.settings().get_protocol_and_hostname(),
+      )?,
+      audience: Some(community.id().into()),
+    };
+    remove.receive(&context).await?;
+    let unfeatured = Post::read(&mut context.pool(), post.id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert!(!unfeatured.featured_community);
+
+    // Both actions are written to the mod log
+    let params = ModlogListParams {
+      community_id: None,
+      mod_person_id: None,
+      other_person_id: None,
+      post_id: Some(post.id),
+      comment_id: None,
+      page: None,
+      limit: None,
+      hide_modlog_names: false,
+    };
+    let modlog = ModFeaturePostView::list(&mut context.pool(), params)
+      .await?
+      .into_iter()
+      .map(|m| m.mod_feature_post)
+      .map(|m| (m.mod_person_id, m.featured))
+      .collect::<Vec<_>>();
+    assert_eq!(vec![(person.id, false), (person.id, true)], modlog);
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+}
@@ -3,15 +3,20 @@ use crate::{
     check_community_deleted_or_removed,
     community::send_activity_in_community,
     generate_activity_id,
+    verify_community_matches,
     verify_is_public,
     verify_mod_action,
     verify_person_in_community,
   },
   activity_lists::AnnouncableActivities,
+  fetcher::user_or_community::UserOrCommunity,
   insert_received_activity,
   objects::community::ApubCommunity,
   protocol::{
-    activities::community::lock_page::{LockPage, LockType, UndoLockPage},
+    activities::community::{
+      announce::AnnounceActivity,
+      lock_page::{LockPage, LockType, UndoLockPage},
+    },
     InCommunity,
   },
 };
@@ -23,6 +28,7 @@ use activitypub_federation::{
 };
 use lemmy_api_common::context::LemmyContext;
 use lemmy_db_schema::{
+  newtypes::PersonId,
   source::{
     activity::ActivitySendTargets,
     community::Community,
@@ -31,6 +37,7 @@ use lemmy_db_schema::{
     post::{Post, PostUpdateForm},
   },
   traits::Crud,
+  CommunityVisibility,
 };
 use lemmy_utils::{
   error::{LemmyError, LemmyResult},
@@ -54,9 +61,8 @@ impl ActivityHandler for LockPage {
   async fn verify(&self, context: &Data<Self::DataType>) -> Result<(), Self::Error> {
     verify_is_public(&self.to, &self.cc)?;
     let community = self.community(context).await?;
-    verify_person_in_community(&self.actor, &community, context).await?;
     check_community_deleted_or_removed(&community)?;
-    verify_mod_action(&self.actor, &community, context).await?;
+    verify_lock_actor(&self.actor, &community, context).await?;
     Ok(())
   }
 
@@ -65,13 +71,14 @@ impl ActivityHandler for LockPage {
     let locked = Some(true);
     let form = PostUpdateForm {
       locked,
+      close_at: Some(None),
       ..Default::default()
     };
     let post = self.object.dereference(context).await?;
     Post::update(&mut context.pool(), post.id, &form).await?;
 
     let form = ModLockPostForm {
-      mod_person_id: self.actor.dereference(context).await?.id,
+      mod_person_id: lock_mod_person_id(&self.actor, context).await?,
       post_id: post.id,
       locked,
     };
@@ -97,9 +104,8 @@ impl ActivityHandler for UndoLockPage {
   async fn verify(&self, context: &Data<Self::DataType>) -> Result<(), Self::Error> {
     verify_is_public(&self.to, &self.cc)?;
     let community = self.community(context).await?;
-    verify_person_in_community(&self.actor, &community, context).await?;
     check_community_deleted_or_removed(&community)?;
-    verify_mod_action(&self.actor, &community, context).await?;
+    verify_lock_actor(&self.actor, &community, context).await?;
     Ok(())
   }
 
@@ -108,13 +114,14 @@ impl ActivityHandler for UndoLockPage {
     let locked = Some(false);
     let form = PostUpdateForm {
       locked,
+      close_at: Some(None),
       ..Default::default()
     };
     let post = self.object.object.dereference(context).await?;
     Post::update(&mut context.pool(), post.id, &form).await?;
 
     let form = ModLockPostForm {
-      mod_person_id: self.actor.dereference(context).await?.id,
+      mod_person_id: lock_mod_person_id(&self.actor, context).await?,
       post_id: post.id,
       locked,
     };
@@ -124,6 +131,38 @@ impl ActivityHandler for UndoLockPage {
   }
 }
 
+/// Locks can be sent by a moderator, or by the community itself on platforms which don't expose the
+/// moderator who took the action.
+async fn verify_lock_actor(
+  actor: &ObjectId<UserOrCommunity>,
+  community: &ApubCommunity,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  match actor.dereference(context).await? {
+    UserOrCommunity::User(person) => {
+      let person_id = person.actor_id.clone().into();
+      verify_person_in_community(&person_id, community, context).await?;
+      verify_mod_action(&person_id, community, context).await?;
+    }
+    UserOrCommunity::Community(c) => {
+      verify_community_matches(&c.actor_id.clone().into(), community.actor_id.clone())?
+    }
+  }
+  Ok(())
+}
+
+/// The person to show as moderator in the modlog. Locks sent by the community itself are logged
+/// without moderator.
+async fn lock_mod_person_id(
+  actor: &ObjectId<UserOrCommunity>,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<Option<PersonId>> {
+  Ok(match actor.dereference(context).await? {
+    UserOrCommunity::User(person) => Some(person.id),
+    UserOrCommunity::Community(_) => None,
+  })
+}
+
 pub(crate) async fn send_lock_post(
   post: Post,
   actor: Person,
@@ -177,3 +216,167 @@ pub(crate) async fn send_lock_post(
   .await?;
   Ok(())
 }
+
+/// Sends the automatic lock of a post whose close time has passed. No moderator is involved, so
+/// the community itself is the actor. Only posts in local communities are announced, for others
+/// the community's instance sends the lock.
+pub(crate) async fn send_close_post(post: Post, context: Data<LemmyContext>) -> LemmyResult<()> {
+  let community: ApubCommunity = Community::read(&mut context.pool(), post.community_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindCommunity)?
+    .into();
+  if !community.local
+    || community.visibility != CommunityVisibility::Public
+    || community.federation_paused
+  {
+    return Ok(());
+  }
+  let id = generate_activity_id(
+    LockType::Lock,
+    &context.settings().get_protocol_and_hostname(),
+  )?;
+  let community_id = community.actor_id.inner().clone();
+  let lock = LockPage {
+    actor: community.actor_id.clone().into(),
+    to: vec![public()],
+    object: ObjectId::from(post.ap_id),
+    cc: vec![community_id.clone()],
+    kind: LockType::Lock,
+    id,
+    audience: Some(community_id.into()),
+  };
+  let activity = AnnouncableActivities::LockPost(lock);
+  AnnounceActivity::send(activity.try_into()?, &community, &context).await?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::protocol::tests::file_to_json_object;
+  use lemmy_api_common::lemmy_db_views_moderator::structs::{ModLockPostView, ModlogListParams};
+  use lemmy_db_schema::{
+    source::{
+      community::{CommunityInsertForm, CommunityModerator, CommunityModeratorForm},
+      instance::Instance,
+      person::PersonInsertForm,
+      post::PostInsertForm,
+    },
+    traits::Joinable,
+  };
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  async fn lock_log(post: &Post, context: &Data<LemmyContext>) -> LemmyResult<Vec<ModLockPost>> {
+    let params = ModlogListParams {
+      community_id: None,
+      mod_person_id: None,
+      other_person_id: None,
+      post_id: Some(post.id),
+      comment_id: None,
+      page: None,
+      limit: None,
+      hide_modlog_names: false,
+    };
+    Ok(
+      ModLockPostView::list(&mut context.pool(), params)
+        .await?
+        .into_iter()
+        .map(|v| v.mod_lock_post)
+        .collect(),
+    )
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_receive_lock_records_locker() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let instance = Instance::read_or_create(&mut context.pool(), "other.tld".to_string()).await?;
+    let top_mod = Person::create(
+      &mut context.pool(),
+      &PersonInsertForm::test_form(instance.id, "lock_top_mod"),
+    )
+    .await?;
+    let locker = Person::create(
+      &mut context.pool(),
+      &PersonInsertForm::test_form(instance.id, "lock_mod"),
+    )
+    .await?;
+    let form = CommunityInsertForm::builder()
+      .name("lock_community".to_string())
+      .title("lock_community".to_string())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .actor_id(Some(
+        Url::parse("https://other.tld/c/lock_community")?.into(),
+      ))
+      .build();
+    let community = Community::create(&mut context.pool(), &form).await?;
+    for person_id in [top_mod.id, locker.id] {
+      let form = CommunityModeratorForm {
+        community_id: community.id,
+        person_id,
+      };
+      CommunityModerator::join(&mut context.pool(), &form).await?;
+    }
+    let form = PostInsertForm::builder()
+      .name("locked post".to_string())
+      .creator_id(top_mod.id)
+      .community_id(community.id)
+      .build();
+    let post = Post::create(&mut context.pool(), &form).await?;
+
+    // The remote moderator who sent the lock is shown in the modlog
+    let mut lock: LockPage =
+      file_to_json_object("assets/lemmy/activities/community/lock_page.json")?;
+    lock.actor = locker.actor_id.clone().into();
+    lock.object = post.ap_id.clone().into();
+    lock.cc = vec![community.actor_id.clone().into()];
+    lock.audience = Some(community.actor_id.clone().into());
+    lock.verify(&context).await?;
+    lock.clone().receive(&context).await?;
+    let log = lock_log(&post, &context).await?;
+    assert_eq!(
+      vec![(Some(locker.id), true)],
+      log
+        .iter()
+        .map(|l| (l.mod_person_id, l.locked))
+        .collect::<Vec<_>>()
+    );
+
+    // Without a moderator actor the action is logged without moderator
+    let mut undo: UndoLockPage =
+      file_to_json_object("assets/lemmy/activities/community/undo_lock_page.json")?;
+    undo.actor = community.actor_id.clone().into();
+    undo.cc = lock.cc.clone();
+    undo.audience = lock.audience.clone();
+    undo.object = LockPage {
+      actor: community.actor_id.clone().into(),
+      ..lock
+    };
+    undo.verify(&context).await?;
+    undo.receive(&context).await?;
+    let log = lock_log(&post, &context).await?;
+    assert!(log.iter().any(|l| l.mod_person_id.is_none() && !l.locked));
+    let post = Post::read(&mut context.pool(), post.id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert!(!post.locked);
+
+    // Other persons can't lock posts
+    let mut lock: LockPage =
+      file_to_json_object("assets/lemmy/activities/community/lock_page.json")?;
+    lock.actor = Person::create(
+      &mut context.pool(),
+      &PersonInsertForm::test_form(instance.id, "lock_user"),
+    )
+    .await?
+    .actor_id
+    .into();
+    lock.object = post.ap_id.clone().into();
+    assert!(lock.verify(&context).await.is_err());
+
+    Instance::delete(&mut context.pool(), instance.id).await?;
+    Ok(())
+  }
+}
@@ -16,6 +16,7 @@ pub mod announce;
 pub mod collection_add;
 pub mod collection_remove;
 pub mod lock_page;
+pub mod move_page;
 pub mod report;
 pub mod update;
 
@@ -41,8 +42,8 @@ pub(crate) async fn send_activity_in_community(
   is_mod_action: bool,
   context: &Data<LemmyContext>,
 ) -> LemmyResult<()> {
-  // If community is local only, don't send anything out
-  if community.visibility != CommunityVisibility::Public {
+  // If community is local only or its federation is paused, don't send anything out
+  if community.visibility != CommunityVisibility::Public || community.federation_paused {
     return Ok(());
   }
 
@@ -70,3 +71,88 @@ pub(crate) async fn send_activity_in_community(
   send_lemmy_activity(context, activity.clone(), actor, inboxes, false).await?;
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use crate::activities::voting::send_like_activity;
+  use lemmy_api_common::context::LemmyContext;
+  use lemmy_db_schema::{
+    source::{
+      activity::SentActivity,
+      community::{Community, CommunityInsertForm, CommunityUpdateForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+    },
+    traits::Crud,
+  };
+  use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_federation_paused() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let instance =
+      Instance::read_or_create(&mut context.pool(), "my_domain.tld".to_string()).await?;
+    let person = Person::create(
+      &mut context.pool(),
+      &PersonInsertForm::test_form(instance.id, "federation_paused_person"),
+    )
+    .await?;
+    let form = CommunityInsertForm::builder()
+      .name("federation_paused".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(&mut context.pool(), &form).await?;
+    let form = PostInsertForm::builder()
+      .name("paused post".to_string())
+      .creator_id(person.id)
+      .community_id(community.id)
+      .build();
+    let post = Post::create(&mut context.pool(), &form).await?;
+
+    let form = CommunityUpdateForm {
+      federation_paused: Some(true),
+      ..Default::default()
+    };
+    let community = Community::update(&mut context.pool(), community.id, &form).await?;
+    send_like_activity(
+      post.ap_id.clone(),
+      person.clone(),
+      community.clone(),
+      1,
+      false,
+      context.reset_request_count(),
+    )
+    .await?;
+    let sent =
+      SentActivity::list_by_actor(&mut context.pool(), &person.actor_id, None, None).await?;
+    assert_eq!(0, sent.len());
+
+    // Once federation is resumed, activities are sent again
+    let form = CommunityUpdateForm {
+      federation_paused: Some(false),
+      ..Default::default()
+    };
+    let community = Community::update(&mut context.pool(), community.id, &form).await?;
+    send_like_activity(
+      post.ap_id.clone(),
+      person.clone(),
+      community,
+      1,
+      false,
+      context.reset_request_count(),
+    )
+    .await?;
+    let sent =
+      SentActivity::list_by_actor(&mut context.pool(), &person.actor_id, None, None).await?;
+    assert_eq!(1, sent.len());
+
+    Instance::delete(&mut context.pool(), instance.id).await?;
+    Ok(())
+  }
+}
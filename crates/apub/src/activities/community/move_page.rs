@@ -0,0 +1,233 @@
+use crate::{
+  activities::{
+    check_community_deleted_or_removed,
+    community::send_activity_in_community,
+    generate_activity_id,
+    verify_is_public,
+    verify_mod_action,
+    verify_person_in_community,
+  },
+  activity_lists::AnnouncableActivities,
+  insert_received_activity,
+  objects::community::ApubCommunity,
+  protocol::{activities::community::move_page::MovePage, InCommunity},
+};
+use activitypub_federation::{
+  config::Data,
+  kinds::{activity::MoveType, public},
+  traits::{ActivityHandler, Actor},
+};
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::{
+  newtypes::CommunityId,
+  source::{
+    activity::ActivitySendTargets,
+    community::Community,
+    person::Person,
+    post::{Post, PostUpdateForm},
+  },
+  traits::Crud,
+};
+use lemmy_utils::{
+  error::{LemmyError, LemmyResult},
+  LemmyErrorType,
+};
+use url::Url;
+
+impl MovePage {
+  pub(crate) fn new(
+    post: &Post,
+    actor: &Person,
+    origin: &Community,
+    target: &Community,
+    context: &Data<LemmyContext>,
+  ) -> LemmyResult<MovePage> {
+    let id = generate_activity_id(
+      MoveType::Move,
+      &context.settings().get_protocol_and_hostname(),
+    )?;
+    let origin_id = origin.actor_id.inner().clone();
+    let target_id = target.actor_id.inner().clone();
+    Ok(MovePage {
+      actor: actor.actor_id.clone().into(),
+      to: vec![public()],
+      object: post.ap_id.clone().into(),
+      cc: vec![origin_id.clone(), target_id.clone()],
+      origin: origin_id.clone().into(),
+      target: target_id.into(),
+      kind: MoveType::Move,
+      id,
+      audience: Some(origin_id.into()),
+    })
+  }
+}
+
+#[async_trait::async_trait]
+impl ActivityHandler for MovePage {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  async fn verify(&self, context: &Data<Self::DataType>) -> Result<(), Self::Error> {
+    verify_is_public(&self.to, &self.cc)?;
+    let community = self.community(context).await?;
+    verify_person_in_community(&self.actor, &community, context).await?;
+    check_community_deleted_or_removed(&community)?;
+    verify_mod_action(&self.actor, &community, context).await?;
+
+    // Moving a post also requires mod permissions in the new community
+    let target = self.target.dereference(context).await?;
+    verify_person_in_community(&self.actor, &target, context).await?;
+    check_community_deleted_or_removed(&target)?;
+    verify_mod_action(&self.actor, &target, context).await?;
+    Ok(())
+  }
+
+  async fn receive(self, context: &Data<Self::DataType>) -> Result<(), Self::Error> {
+    insert_received_activity(&self.id, context).await?;
+    let post = self.object.dereference(context).await?;
+    let target = self.target.dereference(context).await?;
+    let form = PostUpdateForm {
+      community_id: Some(target.id),
+      ..Default::default()
+    };
+    Post::update(&mut context.pool(), post.id, &form).await?;
+    Ok(())
+  }
+}
+
+pub(crate) async fn send_move_post(
+  post: Post,
+  actor: Person,
+  origin_community_id: CommunityId,
+  context: Data<LemmyContext>,
+) -> LemmyResult<()> {
+  let origin: ApubCommunity = Community::read(&mut context.pool(), origin_community_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindCommunity)?
+    .into();
+  let target: ApubCommunity = Community::read(&mut context.pool(), post.community_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindCommunity)?
+    .into();
+  let move_page = MovePage::new(&post, &actor, &origin, &target, &context)?;
+
+  // Followers of the origin community get the activity via announce, and the target community is
+  // informed directly so it can relocate the post as well.
+  let mut inboxes = ActivitySendTargets::empty();
+  if !target.local {
+    inboxes.add_inbox(target.shared_inbox_or_inbox());
+  }
+  send_activity_in_community(
+    AnnouncableActivities::MovePost(move_page),
+    &actor.into(),
+    &origin,
+    inboxes,
+    true,
+    &context,
+  )
+  .await?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use activitypub_federation::fetch::object_id::ObjectId;
+  use lemmy_db_schema::{
+    aggregates::structs::PostAggregates,
+    source::{
+      community::{CommunityInsertForm, CommunityModerator, CommunityModeratorForm},
+      instance::Instance,
+      person::PersonInsertForm,
+      post::PostInsertForm,
+    },
+    traits::Joinable,
+  };
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  async fn create_community(
+    name: &str,
+    instance: &Instance,
+    context: &Data<LemmyContext>,
+  ) -> LemmyResult<Community> {
+    let form = CommunityInsertForm::builder()
+      .name(name.to_string())
+      .title(name.to_string())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .actor_id(Some(
+        Url::parse(&format!("https://other.tld/c/{name}"))?.into(),
+      ))
+      .build();
+    Ok(Community::create(&mut context.pool(), &form).await?)
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_move_post() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let instance = Instance::read_or_create(&mut context.pool(), "other.tld".to_string()).await?;
+    let person = Person::create(
+      &mut context.pool(),
+      &PersonInsertForm::test_form(instance.id, "move_post_mod"),
+    )
+    .await?;
+    let origin = create_community("move_origin", &instance, &context).await?;
+    let target = create_community("move_target", &instance, &context).await?;
+    for community_id in [origin.id, target.id] {
+      let form = CommunityModeratorForm {
+        community_id,
+        person_id: person.id,
+      };
+      CommunityModerator::join(&mut context.pool(), &form).await?;
+    }
+    let form = PostInsertForm::builder()
+      .name("moved post".to_string())
+      .creator_id(person.id)
+      .community_id(origin.id)
+      .build();
+    let post = Post::create(&mut context.pool(), &form).await?;
+
+    let move_page = MovePage::new(&post, &person, &origin, &target, &context)?;
+    assert_eq!(move_page.object, ObjectId::from(post.ap_id.clone()));
+    assert_eq!(move_page.origin, ObjectId::from(origin.actor_id.clone()));
+    assert_eq!(move_page.target, ObjectId::from(target.actor_id.clone()));
+    assert_eq!(
+      move_page.audience,
+      Some(ObjectId::from(origin.actor_id.clone()))
+    );
+
+    move_page.verify(&context).await?;
+    move_page.receive(&context).await?;
+
+    let moved = Post::read(&mut context.pool(), post.id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert_eq!(moved.community_id, target.id);
+    let aggregates = PostAggregates::read(&mut context.pool(), post.id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert_eq!(aggregates.community_id, target.id);
+
+    // Moving it back is rejected without mod permissions in the origin community
+    let form = CommunityModeratorForm {
+      community_id: origin.id,
+      person_id: person.id,
+    };
+    CommunityModerator::leave(&mut context.pool(), &form).await?;
+    let move_back = MovePage::new(&moved, &person, &target, &origin, &context)?;
+    assert!(move_back.verify(&context).await.is_err());
+
+    Instance::delete(&mut context.pool(), instance.id).await?;
+    Ok(())
+  }
+}
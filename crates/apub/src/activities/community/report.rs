@@ -1,5 +1,6 @@
 use crate::{
   activities::{generate_activity_id, send_lemmy_activity, verify_person_in_community},
+  fetcher::{site_or_community_or_user::SiteOrCommunityOrUser, user_or_community::UserOrCommunity},
   insert_received_activity,
   objects::{community::ApubCommunity, instance::ApubSite, person::ApubPerson},
   protocol::{
@@ -23,12 +24,14 @@ use lemmy_db_schema::{
     activity::ActivitySendTargets,
     comment_report::{CommentReport, CommentReportForm},
     community::Community,
+    local_site::LocalSite,
     person::Person,
     post_report::{PostReport, PostReportForm},
     site::Site,
   },
   traits::{Crud, Reportable},
 };
+use lemmy_db_views::structs::SiteView;
 use lemmy_utils::{
   error::{LemmyError, LemmyResult},
   LemmyErrorType,
@@ -44,6 +47,13 @@ impl Report {
     reason: String,
     context: Data<LemmyContext>,
   ) -> LemmyResult<()> {
+    // Report reasons may contain sensitive details, so admins can choose not to federate them
+    let local_site = LocalSite::read(&mut context.pool()).await.ok();
+    if local_site.as_ref().is_some_and(|l| !l.federation_reports) {
+      return Ok(());
+    }
+    let anonymous = local_site.is_some_and(|l| l.federation_reports_anonymous);
+
     let actor: ApubPerson = actor.into();
     let community: ApubCommunity = community.into();
     let kind = FlagType::Flag;
@@ -51,7 +61,7 @@ impl Report {
       kind.clone(),
       &context.settings().get_protocol_and_hostname(),
     )?;
-    let report = Report {
+    let mut report = Report {
       actor: actor.id().into(),
       to: [community.id().into()],
       object: ReportObject::Lemmy(object_id.clone()),
@@ -81,7 +91,18 @@ impl Report {
       inboxes.add_inbox(inbox);
     }
 
-    send_lemmy_activity(&context, report, &actor, inboxes, false).await
+    if anonymous {
+      // Like Mastodon, anonymous reports are sent by the instance actor
+      let site: ApubSite = SiteView::read_local(&mut context.pool())
+        .await?
+        .ok_or(LemmyErrorType::LocalSiteNotSetup)?
+        .site
+        .into();
+      report.actor = ObjectId::from(site.id());
+      send_lemmy_activity(&context, report, &site, inboxes, true).await
+    } else {
+      send_lemmy_activity(&context, report, &actor, inboxes, true).await
+    }
   }
 }
 
@@ -101,21 +122,33 @@ impl ActivityHandler for Report {
   #[tracing::instrument(skip_all)]
   async fn verify(&self, context: &Data<Self::DataType>) -> LemmyResult<()> {
     let community = self.community(context).await?;
-    verify_person_in_community(&self.actor, &community, context).await?;
+    match self.actor.dereference(context).await? {
+      // Anonymous report, like Lemmy and Mastodon send it
+      SiteOrCommunityOrUser::Site(_) => {}
+      SiteOrCommunityOrUser::UserOrCommunity(UserOrCommunity::User(person)) => {
+        verify_person_in_community(&person.id().into(), &community, context).await?
+      }
+      SiteOrCommunityOrUser::UserOrCommunity(UserOrCommunity::Community(_)) => {
+        Err(LemmyErrorType::CouldntFindPerson)?
+      }
+    }
     Ok(())
   }
 
   #[tracing::instrument(skip_all)]
   async fn receive(self, context: &Data<Self::DataType>) -> LemmyResult<()> {
     insert_received_activity(&self.id, context).await?;
-    let actor = self.actor.dereference(context).await?;
+    let creator_id = match self.actor.dereference(context).await? {
+      SiteOrCommunityOrUser::UserOrCommunity(UserOrCommunity::User(person)) => Some(person.id),
+      _ => None,
+    };
     let reason = self.reason()?;
     match self.object.dereference(context).await? {
       PostOrComment::Post(post) => {
         check_post_deleted_or_removed(&post)?;
 
         let report_form = PostReportForm {
-          creator_id: actor.id,
+          creator_id,
           post_id: post.id,
           original_post_name: post.name.clone(),
           original_post_url: post.url.clone(),
@@ -128,7 +161,7 @@ impl ActivityHandler for Report {
         check_comment_deleted_or_removed(&comment)?;
 
         let report_form = CommentReportForm {
-          creator_id: actor.id,
+          creator_id,
           comment_id: comment.id,
           original_comment_text: comment.content.clone(),
           reason,
@@ -139,3 +172,125 @@ impl ActivityHandler for Report {
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    objects::{
+      community::tests::parse_lemmy_community,
+      person::tests::parse_lemmy_person,
+      post::ApubPost,
+    },
+    protocol::{objects::page::Page, tests::file_to_json_object},
+  };
+  use activitypub_federation::traits::Object;
+  use lemmy_db_schema::source::{
+    activity::SentActivity,
+    instance::Instance,
+    local_site::{LocalSiteInsertForm, LocalSiteUpdateForm},
+    person::PersonInsertForm,
+    post::Post,
+  };
+  use lemmy_db_views::structs::PostReportView;
+  use lemmy_utils::CACHE_DURATION_API;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+  use tokio::time::sleep;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_send_report_of_remote_post() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+    let page: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    let post = ApubPost::from_json(page, &context).await?;
+    let instance =
+      Instance::read_or_create(&mut context.pool(), "my_domain.tld".to_string()).await?;
+    let reporter = Person::create(
+      &mut context.pool(),
+      &PersonInsertForm::test_form(instance.id, "report_sender"),
+    )
+    .await?;
+
+    Report::send(
+      ObjectId::from(post.ap_id.clone()),
+      reporter.clone(),
+      (*community).clone(),
+      "Spam".to_string(),
+      context.reset_request_count(),
+    )
+    .await?;
+
+    // Flag is queued for the origin community
+    let sent =
+      SentActivity::list_by_actor(&mut context.pool(), &reporter.actor_id, None, None).await?;
+    let flag = sent.first().ok_or(LemmyErrorType::CouldntFindObject)?;
+    assert_eq!(1, sent.len());
+    assert_eq!(Some("Flag"), flag.data["type"].as_str());
+    assert_eq!(Some(post.ap_id.as_str()), flag.data["object"].as_str());
+    assert_eq!(Some("Spam"), flag.data["summary"].as_str());
+    assert!(flag
+      .send_inboxes
+      .contains(&Some(community.shared_inbox_or_inbox().into())));
+    assert!(flag.sensitive);
+
+    // Anonymous reports are sent by the instance actor
+    let form = LocalSiteInsertForm::builder()
+      .site_id(site.id)
+      .federation_reports_anonymous(Some(true))
+      .build();
+    LocalSite::create(&mut context.pool(), &form).await?;
+    sleep(CACHE_DURATION_API).await;
+    Report::send(
+      ObjectId::from(post.ap_id.clone()),
+      reporter.clone(),
+      (*community).clone(),
+      "Anonymous spam".to_string(),
+      context.reset_request_count(),
+    )
+    .await?;
+    let sent = SentActivity::list_by_actor(&mut context.pool(), &site.actor_id, None, None).await?;
+    let flag = sent.first().ok_or(LemmyErrorType::CouldntFindObject)?;
+    assert_eq!(1, sent.len());
+    assert_eq!(Some(site.actor_id.as_str()), flag.data["actor"].as_str());
+
+    // The receiving instance accepts it, and stores the report without reporter
+    let flag: Report = serde_json::from_value(flag.data.clone())?;
+    flag.verify(&context).await?;
+    flag.receive(&context).await?;
+    let reports =
+      PostReportView::get_report_count(&mut context.pool(), person.id, true, Some(community.id))
+        .await?;
+    assert_eq!(1, reports);
+
+    // Nothing is sent if admins disable federated reports
+    let form = LocalSiteUpdateForm {
+      federation_reports: Some(false),
+      ..Default::default()
+    };
+    LocalSite::update(&mut context.pool(), &form).await?;
+    sleep(CACHE_DURATION_API).await;
+    Report::send(
+      ObjectId::from(post.ap_id.clone()),
+      reporter.clone(),
+      (*community).clone(),
+      "Sensitive reason".to_string(),
+      context.reset_request_count(),
+    )
+    .await?;
+    for actor_id in [&reporter.actor_id, &site.actor_id] {
+      let sent = SentActivity::list_by_actor(&mut context.pool(), actor_id, None, None).await?;
+      assert_eq!(1, sent.len());
+    }
+
+    LocalSite::delete(&mut context.pool()).await?;
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), reporter.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+}
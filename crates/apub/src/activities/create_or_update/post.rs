@@ -20,7 +20,7 @@ use activitypub_federation::{
   protocol::verification::{verify_domains_match, verify_urls_match},
   traits::{ActivityHandler, Actor, Object},
 };
-use lemmy_api_common::context::LemmyContext;
+use lemmy_api_common::{context::LemmyContext, utils::is_mod_or_admin};
 use lemmy_db_schema::{
   aggregates::structs::PostAggregates,
   newtypes::PersonId,
@@ -28,7 +28,7 @@ use lemmy_db_schema::{
     activity::ActivitySendTargets,
     community::Community,
     person::Person,
-    post::{Post, PostLike, PostLikeForm},
+    post::{Post, PostLike, PostLikeForm, PostUpdateForm},
   },
   traits::{Crud, Likeable},
 };
@@ -111,7 +111,14 @@ impl ActivityHandler for CreateOrUpdatePage {
     verify_person_in_community(&self.actor, &community, context).await?;
     check_community_deleted_or_removed(&community)?;
     verify_domains_match(self.actor.inner(), self.object.id.inner())?;
-    verify_urls_match(self.actor.inner(), self.object.creator()?.inner())?;
+    if let Err(e) = verify_urls_match(self.actor.inner(), self.object.creator()?.inner()) {
+      // Mods can edit posts of other users
+      if self.kind != CreateOrUpdateType::Update {
+        Err(e)?
+      }
+      let actor = self.actor.dereference(context).await?;
+      is_mod_or_admin(&mut context.pool(), &actor, community.id).await?;
+    }
     ApubPost::verify(&self.object, self.actor.inner(), context).await?;
     Ok(())
   }
@@ -119,8 +126,31 @@ impl ActivityHandler for CreateOrUpdatePage {
   #[tracing::instrument(skip_all)]
   async fn receive(self, context: &Data<LemmyContext>) -> LemmyResult<()> {
     insert_received_activity(&self.id, context).await?;
+    self.receive_post(context).await
+  }
+}
+
+impl CreateOrUpdatePage {
+  /// Stores the post of a verified activity, without checking whether the activity was received
+  /// before. This allows importing posts from the community outbox.
+  pub(crate) async fn receive_post(self, context: &Data<LemmyContext>) -> LemmyResult<()> {
+    let actor = self.actor.clone();
+    let kind = self.kind.clone();
     let post = ApubPost::from_json(self.object, context).await?;
 
+    // Remember if the post was last edited by a mod, so that it can be shown to users
+    if kind == CreateOrUpdateType::Update {
+      let actor = actor.dereference(context).await?;
+      let updated_by = (actor.id != post.creator_id).then_some(actor.id);
+      if updated_by != post.updated_by {
+        let form = PostUpdateForm {
+          updated_by: Some(updated_by),
+          ..Default::default()
+        };
+        Post::update(&mut context.pool(), post.id, &form).await?;
+      }
+    }
+
     // author likes their own post by default
     let like_form = PostLikeForm {
       post_id: post.id,
@@ -135,3 +165,216 @@ impl ActivityHandler for CreateOrUpdatePage {
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    objects::{community::tests::parse_lemmy_community, person::tests::parse_lemmy_person},
+    protocol::{objects::page::Page, tests::file_to_json_object},
+  };
+  use lemmy_db_schema::{
+    source::{
+      comment::{Comment, CommentInsertForm},
+      community::{CommunityModerator, CommunityModeratorForm},
+      person::PersonInsertForm,
+      post::PostInsertForm,
+      site::Site,
+    },
+    traits::Joinable,
+  };
+  use serial_test::serial;
+
+  fn create_page(page: Page) -> LemmyResult<CreateOrUpdatePage> {
+    Ok(CreateOrUpdatePage {
+      actor: page.creator()?,
+      to: vec![public()],
+      cc: vec![],
+      kind: CreateOrUpdateType::Create,
+      id: Url::parse("https://enterprise.lemmy.ml/activities/create/1")?,
+      audience: None,
+      object: page,
+    })
+  }
+
+  fn update_page(page: Page, actor: &Person) -> LemmyResult<CreateOrUpdatePage> {
+    Ok(CreateOrUpdatePage {
+      actor: actor.actor_id.clone().into(),
+      kind: CreateOrUpdateType::Update,
+      id: Url::parse("https://enterprise.lemmy.ml/activities/update/1")?,
+      ..create_page(page)?
+    })
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_receive_distinguished_post() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+    let mut page: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    page.distinguished = Some(true);
+
+    // Only mods can distinguish their posts, also when the post is fetched without activity
+    let res = ApubPost::from_json(page.clone(), &context).await;
+    assert!(res.is_err_and(|e| e.error_type == LemmyErrorType::NotAModOrAdmin));
+    let res = create_page(page.clone())?.receive_post(&context).await;
+    assert!(res.is_err_and(|e| e.error_type == LemmyErrorType::NotAModOrAdmin));
+    let post = Post::read_from_apub_id(&mut context.pool(), page.id.inner().clone()).await?;
+    assert!(post.is_none());
+
+    let form = CommunityModeratorForm {
+      community_id: community.id,
+      person_id: person.id,
+    };
+    CommunityModerator::join(&mut context.pool(), &form).await?;
+    create_page(page.clone())?.receive_post(&context).await?;
+    let post = Post::read_from_apub_id(&mut context.pool(), page.id.inner().clone())
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert!(post.distinguished);
+
+    // Edits which leave the flag unchanged don't need a mod
+    CommunityModerator::leave(&mut context.pool(), &form).await?;
+    create_page(page)?.receive_post(&context).await?;
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_receive_post_edited_by_mod() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+    let page: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    create_page(page.clone())?.receive_post(&context).await?;
+
+    let form = PersonInsertForm {
+      actor_id: Some(Url::parse("https://enterprise.lemmy.ml/u/riker")?.into()),
+      local: Some(false),
+      ..PersonInsertForm::test_form(site.instance_id, "riker")
+    };
+    let moderator = Person::create(&mut context.pool(), &form).await?;
+
+    // Other users can't edit the post
+    let mut edited = page.clone();
+    edited.name = Some("Fixed title".to_string());
+    let update = update_page(edited.clone(), &moderator)?;
+    let res = update.verify(&context).await;
+    assert!(res.is_err_and(|e| e.error_type == LemmyErrorType::NotAModOrAdmin));
+
+    // Mod edits are marked as such
+    let form = CommunityModeratorForm {
+      community_id: community.id,
+      person_id: moderator.id,
+    };
+    CommunityModerator::join(&mut context.pool(), &form).await?;
+    let update = update_page(edited.clone(), &moderator)?;
+    update.verify(&context).await?;
+    update.receive_post(&context).await?;
+    let post = Post::read_from_apub_id(&mut context.pool(), page.id.inner().clone())
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert_eq!("Fixed title", post.name);
+    assert_eq!(Some(moderator.id), post.updated_by);
+
+    // A later edit by the author clears the mark
+    update_page(edited, &person)?.receive_post(&context).await?;
+    let post = Post::read(&mut context.pool(), post.id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert_eq!(None, post.updated_by);
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), moderator.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_receive_pinned_comment() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+    let page: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    create_page(page.clone())?.receive_post(&context).await?;
+    let post = Post::read_from_apub_id(&mut context.pool(), page.id.inner().clone())
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    let form = CommentInsertForm::builder()
+      .content("A pinned comment".into())
+      .creator_id(person.id)
+      .post_id(post.id)
+      .build();
+    let comment = Comment::create(&mut context.pool(), &form, None).await?;
+    let mut pinned = page.clone();
+    pinned.pinned_comment = Some(comment.ap_id.clone().into());
+
+    // Other users can't pin comments
+    let form = PersonInsertForm {
+      actor_id: Some(Url::parse("https://enterprise.lemmy.ml/u/riker")?.into()),
+      local: Some(false),
+      ..PersonInsertForm::test_form(site.instance_id, "riker")
+    };
+    let other_person = Person::create(&mut context.pool(), &form).await?;
+    let res = update_page(pinned.clone(), &other_person)?
+      .verify(&context)
+      .await;
+    assert!(res.is_err_and(|e| e.error_type == LemmyErrorType::NotAModOrAdmin));
+
+    // The post creator can
+    let update = update_page(pinned, &person)?;
+    update.verify(&context).await?;
+    update.receive_post(&context).await?;
+    let post = Post::read(&mut context.pool(), post.id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert_eq!(Some(comment.id), post.pinned_comment_id);
+
+    // Leaving out the field clears the pin
+    update_page(page.clone(), &person)?
+      .receive_post(&context)
+      .await?;
+    let post = Post::read(&mut context.pool(), post.id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert_eq!(None, post.pinned_comment_id);
+
+    // Comments of other posts are ignored
+    let form = PostInsertForm::builder()
+      .name("Another post".into())
+      .creator_id(person.id)
+      .community_id(community.id)
+      .build();
+    let other_post = Post::create(&mut context.pool(), &form).await?;
+    let form = CommentInsertForm::builder()
+      .content("A comment elsewhere".into())
+      .creator_id(person.id)
+      .post_id(other_post.id)
+      .build();
+    let other_comment = Comment::create(&mut context.pool(), &form, None).await?;
+    let mut pinned = page;
+    pinned.pinned_comment = Some(other_comment.ap_id.into());
+    update_page(pinned, &person)?.receive_post(&context).await?;
+    let post = Post::read(&mut context.pool(), post.id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert_eq!(None, post.pinned_comment_id);
+
+    Post::delete(&mut context.pool(), other_post.id).await?;
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), other_person.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+}
@@ -140,7 +140,7 @@ pub(in crate::activities) async fn receive_remove_action(
     DeletableObjects::Post(post) => {
       PostReport::resolve_all_for_object(&mut context.pool(), post.id, actor.id).await?;
       let form = ModRemovePostForm {
-        mod_person_id: actor.id,
+        mod_person_id: Some(actor.id),
         post_id: post.id,
         removed: Some(true),
         reason,
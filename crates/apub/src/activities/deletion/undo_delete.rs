@@ -121,7 +121,7 @@ impl UndoDelete {
       }
       DeletableObjects::Post(post) => {
         let form = ModRemovePostForm {
-          mod_person_id: actor.id,
+          mod_person_id: Some(actor.id),
           post_id: post.id,
           removed: Some(false),
           reason: None,
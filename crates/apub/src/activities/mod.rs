@@ -4,7 +4,8 @@ use crate::{
     block::{send_ban_from_community, send_ban_from_site},
     community::{
       collection_add::{send_add_mod_to_community, send_feature_post},
-      lock_page::send_lock_post,
+      lock_page::{send_close_post, send_lock_post},
+      move_page::send_move_post,
       update::send_update_community,
     },
     create_or_update::private_message::send_create_or_update_pm,
@@ -35,9 +36,13 @@ use lemmy_api_common::{
   send_activity::{ActivityChannel, SendActivityData},
 };
 use lemmy_db_schema::{
+  newtypes::PostId,
   source::{
     activity::{ActivitySendTargets, ActorType, SentActivity, SentActivityForm},
+    comment::Comment,
     community::Community,
+    post::Post,
+    post_pending_federation::PostPendingFederation,
   },
   traits::Crud,
 };
@@ -53,6 +58,7 @@ pub mod community;
 pub mod create_or_update;
 pub mod deletion;
 pub mod following;
+pub mod read;
 pub mod voting;
 
 /// Checks that the specified Url actually identifies a Person (by fetching it), and that the person
@@ -228,11 +234,49 @@ pub async fn handle_outgoing_activities(context: Data<LemmyContext>) {
   }
 }
 
+/// Returns the post which the activity depends on, if any.
+async fn depends_on_post(
+  data: &SendActivityData,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<Option<PostId>> {
+  use SendActivityData::*;
+  Ok(match data {
+    UpdatePost(post)
+    | DeletePost(post, ..)
+    | RemovePost { post, .. }
+    | LockPost(post, ..)
+    | ClosePost(post)
+    | FeaturePost(post, ..)
+    | MovePost { post, .. } => Some(post.id),
+    CreateComment(comment)
+    | UpdateComment(comment)
+    | DeleteComment(comment, ..)
+    | RemoveComment { comment, .. } => Some(comment.post_id),
+    LikePostOrComment { object_id, .. } => {
+      let object_id: Url = object_id.clone().into();
+      match Post::read_from_apub_id(&mut context.pool(), object_id.clone()).await? {
+        Some(post) => Some(post.id),
+        None => Comment::read_from_apub_id(&mut context.pool(), object_id)
+          .await?
+          .map(|c| c.post_id),
+      }
+    }
+    _ => None,
+  })
+}
+
 pub async fn match_outgoing_activities(
   data: SendActivityData,
   context: &Data<LemmyContext>,
 ) -> LemmyResult<()> {
   let context = context.reset_request_count();
+  // Activities for a post whose Create activity is still held back are dropped. The current state
+  // is sent together with the Create activity once the edit grace period is over.
+  if let Some(post_id) = depends_on_post(&data, &context).await? {
+    if PostPendingFederation::is_pending(&mut context.pool(), post_id).await? {
+      return Ok(());
+    }
+  }
   let fed_task = async {
     use SendActivityData::*;
     match data {
@@ -241,8 +285,9 @@ pub async fn match_outgoing_activities(
         CreateOrUpdatePage::send(post, creator_id, CreateOrUpdateType::Create, context).await
       }
       UpdatePost(post) => {
-        let creator_id = post.creator_id;
-        CreateOrUpdatePage::send(post, creator_id, CreateOrUpdateType::Update, context).await
+        // Edits by mods are sent by the mod
+        let actor_id = post.updated_by.unwrap_or(post.creator_id);
+        CreateOrUpdatePage::send(post, actor_id, CreateOrUpdateType::Update, context).await
       }
       DeletePost(post, person, data) => {
         let community = Community::read(&mut context.pool(), post.community_id)
@@ -278,7 +323,13 @@ pub async fn match_outgoing_activities(
         .await
       }
       LockPost(post, actor, locked) => send_lock_post(post, actor, locked, context).await,
+      ClosePost(post) => send_close_post(post, context).await,
       FeaturePost(post, actor, featured) => send_feature_post(post, actor, featured, context).await,
+      MovePost {
+        post,
+        moderator,
+        origin_community_id,
+      } => send_move_post(post, moderator, origin_community_id, context).await,
       CreateComment(comment) => {
         let creator_id = comment.creator_id;
         CreateOrUpdateNote::send(comment, creator_id, CreateOrUpdateType::Create, context).await
@@ -310,7 +361,8 @@ pub async fn match_outgoing_activities(
         actor,
         community,
         score,
-      } => send_like_activity(object_id, actor, community, score, context).await,
+        replaces_vote,
+      } => send_like_activity(object_id, actor, community, score, replaces_vote, context).await,
       FollowCommunity(community, person, follow) => {
         send_follow_community(community, person, follow, &context).await
       }
@@ -0,0 +1,114 @@
+use crate::{insert_received_activity, protocol::activities::read::Read};
+use activitypub_federation::{
+  config::Data,
+  protocol::verification::verify_domains_match,
+  traits::ActivityHandler,
+};
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::source::post::{Post, PostRead};
+use lemmy_utils::error::{LemmyError, LemmyResult};
+use std::collections::HashSet;
+use url::Url;
+
+/// Marks local posts as read for the remote user. Read activities for anything else are accepted
+/// and ignored.
+#[async_trait::async_trait]
+impl ActivityHandler for Read {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(&self, _context: &Data<LemmyContext>) -> LemmyResult<()> {
+    verify_domains_match(self.actor.inner(), &self.id)?;
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn receive(self, context: &Data<LemmyContext>) -> LemmyResult<()> {
+    insert_received_activity(&self.id, context).await?;
+    let post = Post::read_from_apub_id(&mut context.pool(), self.object).await?;
+    if let Some(post) = post.filter(|p| p.local) {
+      let person = self.actor.dereference(context).await?;
+      PostRead::mark_as_read(&mut context.pool(), HashSet::from([post.id]), person.id).await?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{objects::person::tests::parse_lemmy_person, protocol::tests::file_to_json_object};
+  use lemmy_db_schema::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::PostInsertForm,
+      site::Site,
+    },
+    traits::Crud,
+  };
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_receive_read_for_local_post() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (reader, site) = parse_lemmy_person(&context).await?;
+    let instance =
+      Instance::read_or_create(&mut context.pool(), "my_domain.tld".to_string()).await?;
+    let creator = Person::create(
+      &mut context.pool(),
+      &PersonInsertForm::test_form(instance.id, "read_post_creator"),
+    )
+    .await?;
+    let form = CommunityInsertForm::builder()
+      .name("read_post_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(&mut context.pool(), &form).await?;
+    let form = PostInsertForm::builder()
+      .name("Read post".to_string())
+      .creator_id(creator.id)
+      .community_id(community.id)
+      .build();
+    let post = Post::create(&mut context.pool(), &form).await?;
+
+    let mut read: Read = file_to_json_object("assets/lemmy/activities/read.json")?;
+    read.actor = reader.actor_id.clone().into();
+    read.object = post.ap_id.clone().into();
+    read.verify(&context).await?;
+    read.receive(&context).await?;
+
+    // The post was marked as read for the remote user
+    let unread =
+      PostRead::mark_as_unread(&mut context.pool(), HashSet::from([post.id]), reader.id).await?;
+    assert_eq!(1, unread);
+
+    // Read activities for unknown objects are ignored
+    let mut read: Read = file_to_json_object("assets/lemmy/activities/read.json")?;
+    read.actor = reader.actor_id.clone().into();
+    read.id = Url::parse("https://enterprise.lemmy.ml/activities/read/2")?;
+    read.receive(&context).await?;
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Person::delete(&mut context.pool(), creator.id).await?;
+    Person::delete(&mut context.pool(), reader.id).await?;
+    Instance::delete(&mut context.pool(), instance.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+}
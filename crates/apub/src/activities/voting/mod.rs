@@ -9,13 +9,17 @@ use crate::{
   },
 };
 use activitypub_federation::{config::Data, fetch::object_id::ObjectId};
-use lemmy_api_common::context::LemmyContext;
+use lemmy_api_common::{
+  context::LemmyContext,
+  metrics::{count_vote, VoteOrigin},
+};
 use lemmy_db_schema::{
   newtypes::DbUrl,
   source::{
     activity::ActivitySendTargets,
     comment::{CommentLike, CommentLikeForm},
     community::Community,
+    local_site::LocalSite,
     person::Person,
     post::{PostLike, PostLikeForm},
   },
@@ -26,30 +30,47 @@ use lemmy_utils::error::LemmyResult;
 pub mod undo_vote;
 pub mod vote;
 
+/// Sends a vote, or an undo if the score is 0. A vote which replaces a previous one is sent as a
+/// single activity, because receivers replace the existing vote. Only if the
+/// `federation_vote_switch_undo` site setting is enabled, the previous vote is undone first.
 pub(crate) async fn send_like_activity(
   object_id: DbUrl,
   actor: Person,
   community: Community,
   score: i16,
+  replaces_vote: bool,
   context: Data<LemmyContext>,
 ) -> LemmyResult<()> {
   let object_id: ObjectId<PostOrComment> = object_id.into();
   let actor: ApubPerson = actor.into();
   let community: ApubCommunity = community.into();
 
-  let empty = ActivitySendTargets::empty();
+  let undo_first = replaces_vote
+    && LocalSite::read(&mut context.pool())
+      .await
+      .is_ok_and(|l| l.federation_vote_switch_undo);
   // score of 1 means upvote, -1 downvote, 0 undo a previous vote
-  if score != 0 {
-    let vote = Vote::new(object_id, &actor, &community, score.try_into()?, &context)?;
-    let activity = AnnouncableActivities::Vote(vote);
-    send_activity_in_community(activity, &actor, &community, empty, false, &context).await
-  } else {
+  if score == 0 || undo_first {
     // Lemmy API doesn't distinguish between Undo/Like and Undo/Dislike, so we hardcode it here.
-    let vote = Vote::new(object_id, &actor, &community, VoteType::Like, &context)?;
+    let vote = Vote::new(
+      object_id.clone(),
+      &actor,
+      &community,
+      VoteType::Like,
+      &context,
+    )?;
     let undo_vote = UndoVote::new(vote, &actor, &community, &context)?;
     let activity = AnnouncableActivities::UndoVote(undo_vote);
-    send_activity_in_community(activity, &actor, &community, empty, false, &context).await
+    let empty = ActivitySendTargets::empty();
+    send_activity_in_community(activity, &actor, &community, empty, false, &context).await?;
   }
+  if score != 0 {
+    let vote = Vote::new(object_id, &actor, &community, score.try_into()?, &context)?;
+    let activity = AnnouncableActivities::Vote(vote);
+    let empty = ActivitySendTargets::empty();
+    send_activity_in_community(activity, &actor, &community, empty, false, &context).await?;
+  }
+  Ok(())
 }
 
 #[tracing::instrument(skip_all)]
@@ -69,6 +90,7 @@ async fn vote_comment(
   let person_id = actor.id;
   CommentLike::remove(&mut context.pool(), person_id, comment_id).await?;
   CommentLike::like(&mut context.pool(), &like_form).await?;
+  count_vote(like_form.score, VoteOrigin::Federated);
   Ok(())
 }
 
@@ -88,6 +110,7 @@ async fn vote_post(
   let person_id = actor.id;
   PostLike::remove(&mut context.pool(), person_id, post_id).await?;
   PostLike::like(&mut context.pool(), &like_form).await?;
+  count_vote(like_form.score, VoteOrigin::Federated);
   Ok(())
 }
 
@@ -100,6 +123,7 @@ async fn undo_vote_comment(
   let comment_id = comment.id;
   let person_id = actor.id;
   CommentLike::remove(&mut context.pool(), person_id, comment_id).await?;
+  count_vote(0, VoteOrigin::Federated);
   Ok(())
 }
 
@@ -112,5 +136,136 @@ async fn undo_vote_post(
   let post_id = post.id;
   let person_id = actor.id;
   PostLike::remove(&mut context.pool(), person_id, post_id).await?;
+  count_vote(0, VoteOrigin::Federated);
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    objects::{community::tests::parse_lemmy_community, person::tests::parse_lemmy_person},
+    protocol::{objects::page::Page, tests::file_to_json_object},
+  };
+  use activitypub_federation::traits::Object;
+  use lemmy_api_common::metrics::vote_count;
+  use lemmy_db_schema::{
+    source::{
+      activity::SentActivity,
+      community::CommunityInsertForm,
+      instance::Instance,
+      local_site::LocalSiteInsertForm,
+      person::PersonInsertForm,
+      post::{Post, PostInsertForm},
+      site::Site,
+    },
+    traits::Crud,
+  };
+  use lemmy_utils::CACHE_DURATION_API;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+  use tokio::time::sleep;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_vote_metrics() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let context = context.reset_request_count();
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+    let json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    let post = ApubPost::from_json(json, &context).await?;
+
+    let added = vote_count(1, VoteOrigin::Federated);
+    let removed = vote_count(0, VoteOrigin::Federated);
+    let local_added = vote_count(1, VoteOrigin::Local);
+
+    vote_post(&VoteType::Like, person.clone(), &post, &context).await?;
+    vote_post(&VoteType::Dislike, person.clone(), &post, &context).await?;
+    undo_vote_post(person.clone(), &post, &context).await?;
+    assert_eq!(added + 2, vote_count(1, VoteOrigin::Federated));
+    assert_eq!(removed + 1, vote_count(0, VoteOrigin::Federated));
+    assert_eq!(local_added, vote_count(1, VoteOrigin::Local));
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  async fn sent_count(context: &LemmyContext, person: &Person) -> LemmyResult<usize> {
+    let sent =
+      SentActivity::list_by_actor(&mut context.pool(), &person.actor_id, None, None).await?;
+    Ok(sent.len())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_send_vote_switch() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (remote_person, site) = parse_lemmy_person(&context).await?;
+    let instance =
+      Instance::read_or_create(&mut context.pool(), "my_domain.tld".to_string()).await?;
+    let person = Person::create(
+      &mut context.pool(),
+      &PersonInsertForm::test_form(instance.id, "vote_switch_person"),
+    )
+    .await?;
+    let form = CommunityInsertForm::builder()
+      .name("vote_switch".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(&mut context.pool(), &form).await?;
+    let form = PostInsertForm::builder()
+      .name("vote switch post".to_string())
+      .creator_id(person.id)
+      .community_id(community.id)
+      .build();
+    let post = Post::create(&mut context.pool(), &form).await?;
+    let switch_vote = |score| {
+      send_like_activity(
+        post.ap_id.clone(),
+        person.clone(),
+        community.clone(),
+        score,
+        true,
+        context.reset_request_count(),
+      )
+    };
+
+    // A changed vote is sent as a single activity
+    send_like_activity(
+      post.ap_id.clone(),
+      person.clone(),
+      community.clone(),
+      1,
+      false,
+      context.reset_request_count(),
+    )
+    .await?;
+    switch_vote(-1).await?;
+    assert_eq!(2, sent_count(&context, &person).await?);
+
+    // With the fallback enabled, the previous vote is undone first
+    let form = LocalSiteInsertForm::builder()
+      .site_id(site.id)
+      .federation_vote_switch_undo(Some(true))
+      .build();
+    LocalSite::create(&mut context.pool(), &form).await?;
+    sleep(CACHE_DURATION_API).await;
+    switch_vote(1).await?;
+    assert_eq!(4, sent_count(&context, &person).await?);
+
+    // Removing a vote is a single Undo in both modes
+    switch_vote(0).await?;
+    assert_eq!(5, sent_count(&context, &person).await?);
+
+    Instance::delete(&mut context.pool(), instance.id).await?;
+    Person::delete(&mut context.pool(), remote_person.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+}
@@ -17,9 +17,12 @@ use activitypub_federation::{
   fetch::object_id::ObjectId,
   traits::{ActivityHandler, Actor},
 };
-use lemmy_api_common::{context::LemmyContext, utils::check_bot_account};
+use lemmy_api_common::{
+  context::LemmyContext,
+  utils::{check_bot_account, check_vote_post_age},
+};
 use lemmy_db_schema::source::local_site::LocalSite;
-use lemmy_utils::error::{LemmyError, LemmyResult};
+use lemmy_utils::error::{LemmyError, LemmyErrorType, LemmyResult};
 use url::Url;
 
 impl Vote {
@@ -68,10 +71,19 @@ impl ActivityHandler for Vote {
 
     check_bot_account(&actor.0)?;
 
-    let enable_downvotes = LocalSite::read(&mut context.pool())
-      .await
+    let local_site = LocalSite::read(&mut context.pool()).await.ok();
+    let enable_downvotes = local_site
+      .as_ref()
       .map(|l| l.enable_downvotes)
       .unwrap_or(true);
+
+    // Silently drop votes on posts which are too old
+    if let (PostOrComment::Post(p), Some(local_site)) = (&object, &local_site) {
+      match check_vote_post_age(p, actor.id, local_site, &mut context.pool()).await {
+        Err(e) if e.error_type == LemmyErrorType::PostTooOldToVote => return Ok(()),
+        res => res?,
+      }
+    }
     if self.kind == VoteType::Dislike && !enable_downvotes {
       // If this is a downvote but downvotes are ignored, only undo any existing vote
       match object {
@@ -8,6 +8,7 @@ use crate::{
         collection_add::CollectionAdd,
         collection_remove::CollectionRemove,
         lock_page::{LockPage, UndoLockPage},
+        move_page::MovePage,
         report::Report,
         update::UpdateCommunity,
       },
@@ -18,6 +19,7 @@ use crate::{
       },
       deletion::{delete::Delete, undo_delete::UndoDelete},
       following::{accept::AcceptFollow, follow::Follow, undo_follow::UndoFollow},
+      read::Read,
       voting::{undo_vote::UndoVote, vote::Vote},
     },
     objects::page::Page,
@@ -45,6 +47,7 @@ pub enum SharedInboxActivities {
   CreateOrUpdatePrivateMessage(CreateOrUpdateChatMessage),
   Report(Report),
   AnnounceActivity(AnnounceActivity),
+  Read(Read),
   /// This is a catch-all and needs to be last
   RawAnnouncableActivities(RawAnnouncableActivities),
 }
@@ -73,6 +76,7 @@ pub enum PersonInboxActivities {
   Delete(Delete),
   UndoDelete(UndoDelete),
   AnnounceActivity(AnnounceActivity),
+  Read(Read),
   /// User can also receive some "announcable" activities, eg a comment mention.
   AnnouncableActivities(AnnouncableActivities),
 }
@@ -94,6 +98,7 @@ pub enum AnnouncableActivities {
   CollectionRemove(CollectionRemove),
   LockPost(LockPage),
   UndoLockPost(UndoLockPage),
+  MovePost(MovePage),
   // For compatibility with Pleroma/Mastodon (send only)
   Page(Page),
 }
@@ -117,6 +122,7 @@ impl InCommunity for AnnouncableActivities {
       CollectionRemove(a) => a.community(context).await,
       LockPost(a) => a.community(context).await,
       UndoLockPost(a) => a.community(context).await,
+      MovePost(a) => a.community(context).await,
       Page(_) => unimplemented!(),
     }
   }
@@ -153,6 +159,7 @@ mod tests {
       "assets/lemmy/activities/create_or_update/create_private_message.json",
     )?;
     test_json::<PersonInboxActivities>("assets/mastodon/activities/follow.json")?;
+    test_parse_lemmy_item::<PersonInboxActivities>("assets/lemmy/activities/read.json")?;
     Ok(())
   }
 
@@ -161,6 +168,7 @@ mod tests {
     test_parse_lemmy_item::<SharedInboxActivities>(
       "assets/lemmy/activities/deletion/delete_user.json",
     )?;
+    test_parse_lemmy_item::<SharedInboxActivities>("assets/lemmy/activities/read.json")?;
     Ok(())
   }
 }
@@ -0,0 +1,265 @@
+use crate::{
+  activity_lists::AnnouncableActivities,
+  check_apub_id_valid_with_strictness,
+  fetcher::replies::{fetch_replies, MAX_FETCHED_REPLIES},
+  protocol::{
+    activities::create_or_update::page::CreateOrUpdatePage,
+    collections::group_outbox::GroupOutbox,
+    objects::page::Replies,
+  },
+};
+use activitypub_federation::{
+  config::Data,
+  fetch::fetch_object_http,
+  protocol::verification::verify_domains_match,
+  traits::ActivityHandler,
+};
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{BackfillCommunity, BackfillCommunityResponse},
+  utils::{generate_outbox_url, is_admin},
+};
+use lemmy_db_schema::{
+  source::{community::Community, local_site::LocalSite, post::Post},
+  traits::Crud,
+  utils::FETCH_LIMIT_MAX,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+use tracing::warn;
+
+/// Fetches the outbox of a remote community and imports the posts which were never received, for
+/// example because announce activities were missed during downtime.
+#[tracing::instrument(skip(context))]
+pub async fn backfill_community(
+  data: Json<BackfillCommunity>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<BackfillCommunityResponse>> {
+  is_admin(&local_user_view)?;
+
+  let community = Community::read(&mut context.pool(), data.community_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindCommunity)?;
+  if community.local {
+    Err(LemmyErrorType::CantBackfillLocalCommunity)?
+  }
+  check_apub_id_valid_with_strictness(community.actor_id.inner(), false, &context).await?;
+
+  let outbox_url = generate_outbox_url(&community.actor_id)?;
+  let outbox: GroupOutbox = fetch_object_http(&outbox_url.into(), &context)
+    .await?
+    .object;
+  verify_domains_match(community.actor_id.inner(), &outbox.id)?;
+
+  let mut activities = vec![];
+  for announce in outbox
+    .ordered_items
+    .into_iter()
+    .take(FETCH_LIMIT_MAX as usize)
+  {
+    let inner = announce
+      .object
+      .object(&context)
+      .await
+      .map(TryInto::try_into);
+    if let Ok(Ok(AnnouncableActivities::CreateOrUpdatePost(inner))) = inner {
+      activities.push(inner);
+    }
+  }
+
+  Ok(Json(import_missing_posts(activities, &context).await?))
+}
+
+/// Imports the posts of the given activities which don't exist yet. Existing posts are left
+/// untouched, so that they aren't overwritten with older versions from the outbox. Comments of
+/// imported posts are fetched up to the backfill limit of the local site.
+pub(crate) async fn import_missing_posts(
+  activities: Vec<CreateOrUpdatePage>,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<BackfillCommunityResponse> {
+  let max_comments = LocalSite::read(&mut context.pool())
+    .await
+    .ok()
+    .and_then(|l| l.backfill_max_comments_per_post)
+    .and_then(|l| usize::try_from(l).ok())
+    .unwrap_or(MAX_FETCHED_REPLIES);
+  let mut res = BackfillCommunityResponse {
+    imported_posts: 0,
+    skipped_posts: 0,
+    failed_posts: 0,
+  };
+  for mut activity in activities {
+    let post_id = activity.object.id.inner().clone();
+    if Post::read_from_apub_id(&mut context.pool(), post_id.clone())
+      .await?
+      .is_some()
+    {
+      res.skipped_posts += 1;
+      continue;
+    }
+    // Only the comment count is left in the post, so that receiving it doesn't fetch all comments
+    let replies = activity.object.replies.as_mut().map(|r| Replies {
+      id: r.id.take(),
+      total_items: r.total_items,
+      first: r.first.take(),
+    });
+    let imported = match activity.verify(context).await {
+      Ok(()) => activity.receive_post(context).await,
+      Err(e) => Err(e),
+    };
+    match imported {
+      Ok(()) => {
+        res.imported_posts += 1;
+        if let Some(replies) = replies.filter(|_| max_comments > 0) {
+          if let Err(e) = fetch_replies(replies, &post_id, max_comments, context).await {
+            warn!("Failed to backfill comments of post {post_id}: {e}");
+          }
+        }
+      }
+      Err(e) => {
+        warn!("Failed to backfill post {post_id}: {e}");
+        res.failed_posts += 1;
+      }
+    }
+  }
+  Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    objects::{community::tests::parse_lemmy_community, person::tests::parse_lemmy_person},
+    protocol::{
+      objects::{
+        note::Note,
+        page::{Page, RepliesPage},
+      },
+      tests::file_to_json_object,
+      IdOrNestedObject,
+    },
+  };
+  use lemmy_db_schema::source::{
+    comment::Comment,
+    local_site::LocalSiteInsertForm,
+    person::Person,
+    site::Site,
+  };
+  use lemmy_utils::CACHE_DURATION_API;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+  use tokio::time::sleep;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_import_missing_posts() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+
+    let page: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    let mut missing_page = page.clone();
+    missing_page.id = Url::parse("https://enterprise.lemmy.ml/post/55144")?.into();
+    let mut create: CreateOrUpdatePage =
+      file_to_json_object("assets/lemmy/activities/create_or_update/create_page.json")?;
+    create.actor = page.creator()?;
+    create.audience = page.audience.first().cloned();
+    let activities = [page, missing_page]
+      .into_iter()
+      .map(|page| CreateOrUpdatePage {
+        object: page,
+        ..create.clone()
+      })
+      .collect::<Vec<_>>();
+
+    // Only the post which wasn't received before gets imported
+    activities
+      .first()
+      .ok_or(LemmyErrorType::CouldntFindPost)?
+      .clone()
+      .receive_post(&context)
+      .await?;
+    let res = import_missing_posts(activities, &context).await?;
+    assert_eq!(1, res.imported_posts);
+    assert_eq!(1, res.skipped_posts);
+    assert_eq!(0, res.failed_posts);
+
+    let posts = [
+      "https://enterprise.lemmy.ml/post/55143",
+      "https://enterprise.lemmy.ml/post/55144",
+    ];
+    for ap_id in posts {
+      let post = Post::read_from_apub_id(&mut context.pool(), Url::parse(ap_id)?)
+        .await?
+        .ok_or(LemmyErrorType::CouldntFindPost)?;
+      Post::delete(&mut context.pool(), post.id).await?;
+    }
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_backfill_max_comments_per_post() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+    let form = LocalSiteInsertForm::builder()
+      .site_id(site.id)
+      .backfill_max_comments_per_post(Some(1))
+      .build();
+    LocalSite::create(&mut context.pool(), &form).await?;
+    sleep(CACHE_DURATION_API).await;
+
+    // A post with two top-level comments
+    let first: Note = file_to_json_object("assets/lemmy/objects/note.json")?;
+    let mut second = first.clone();
+    second.id = Url::parse("https://enterprise.lemmy.ml/comment/38743")?.into();
+    let mut page: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    page.replies = Some(Replies {
+      id: None,
+      total_items: Some(2),
+      first: Some(RepliesPage {
+        items: vec![
+          IdOrNestedObject::NestedObject(first.clone()),
+          IdOrNestedObject::NestedObject(second.clone()),
+        ],
+        next: None,
+      }),
+    });
+    let mut create: CreateOrUpdatePage =
+      file_to_json_object("assets/lemmy/activities/create_or_update/create_page.json")?;
+    create.actor = page.creator()?;
+    create.audience = page.audience.first().cloned();
+    create.object = page;
+
+    // Only the first comment is fetched, the total is still known
+    let res = import_missing_posts(vec![create], &context).await?;
+    assert_eq!(1, res.imported_posts);
+    let post = Post::read_from_apub_id(
+      &mut context.pool(),
+      Url::parse("https://enterprise.lemmy.ml/post/55143")?,
+    )
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert_eq!(Some(2), post.remote_comments);
+    let first = Comment::read_from_apub_id(&mut context.pool(), first.id.into_inner())
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindComment)?;
+    let second = Comment::read_from_apub_id(&mut context.pool(), second.id.into_inner()).await?;
+    assert!(second.is_none());
+
+    Comment::delete(&mut context.pool(), first.id).await?;
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    sleep(CACHE_DURATION_API).await;
+    Ok(())
+  }
+}
@@ -0,0 +1,173 @@
+use crate::{
+  check_apub_id_valid_with_strictness,
+  collections::community_outbox::ApubCommunityOutbox,
+  objects::community::ApubCommunity,
+  FEDERATION_HTTP_FETCH_LIMIT,
+};
+use activitypub_federation::{config::Data, fetch::collection_id::CollectionId};
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{BackfillInstance, BackfillInstanceResponse},
+  utils::{generate_outbox_url, is_admin},
+};
+use lemmy_db_schema::source::{community::Community, instance::Instance, local_site::LocalSite};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+use tracing::warn;
+
+/// Fetches the outboxes of followed communities from the given instance, so that posts which were
+/// rejected while the instance was blocked become available. Communities are processed in order
+/// of local followers, until the request limit for backfilling is used up.
+#[tracing::instrument(skip(context))]
+pub async fn backfill_instance(
+  data: Json<BackfillInstance>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<BackfillInstanceResponse>> {
+  is_admin(&local_user_view)?;
+
+  let instance = Instance::read(&mut context.pool(), data.instance_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindInstance)?;
+  let request_limit = LocalSite::read(&mut context.pool())
+    .await?
+    .federation_backfill_request_limit
+    .and_then(|l| u32::try_from(l).ok())
+    .unwrap_or(FEDERATION_HTTP_FETCH_LIMIT);
+  let communities = Community::list_followed_for_instance(&mut context.pool(), instance.id).await?;
+
+  let mut fetched_communities = 0;
+  let mut failed_communities = 0;
+  let mut skipped_communities = 0;
+  for community in communities {
+    if context.request_count() >= request_limit {
+      skipped_communities += 1;
+      continue;
+    }
+    // Fails if the instance is still blocked
+    check_apub_id_valid_with_strictness(community.actor_id.inner(), false, &context).await?;
+    let outbox: CollectionId<ApubCommunityOutbox> =
+      generate_outbox_url(&community.actor_id)?.into();
+    let community: ApubCommunity = community.into();
+    match outbox.dereference(&community, &context).await {
+      Ok(_) => fetched_communities += 1,
+      Err(e) => {
+        warn!("Failed to backfill community {}: {e}", community.actor_id);
+        failed_communities += 1;
+      }
+    }
+  }
+
+  Ok(Json(BackfillInstanceResponse {
+    fetched_communities,
+    failed_communities,
+    skipped_communities,
+  }))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::objects::person::tests::parse_lemmy_person;
+  use lemmy_db_schema::{
+    source::{
+      community::{CommunityFollower, CommunityFollowerForm, CommunityInsertForm},
+      federation_blocklist::FederationBlockList,
+      local_site::LocalSiteInsertForm,
+      local_user::{LocalUser, LocalUserInsertForm},
+      person::{Person, PersonInsertForm},
+      site::Site,
+    },
+    traits::{Crud, Followable},
+  };
+  use lemmy_utils::{CACHE_DURATION_API, CACHE_DURATION_FEDERATION};
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+  use tokio::time::sleep;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_backfill_instance() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let form = LocalSiteInsertForm::builder()
+      .site_id(site.id)
+      .federation_backfill_request_limit(Some(2))
+      .build();
+    LocalSite::create(&mut context.pool(), &form).await?;
+
+    let local_instance =
+      Instance::read_or_create(&mut context.pool(), "example.com".to_string()).await?;
+    let admin = Person::create(
+      &mut context.pool(),
+      &PersonInsertForm::test_form(local_instance.id, "backfill_admin"),
+    )
+    .await?;
+    let user_form = LocalUserInsertForm::builder()
+      .person_id(admin.id)
+      .password_encrypted("pass".to_string())
+      .admin(Some(true))
+      .build();
+    let local_user = LocalUser::create(&mut context.pool(), &user_form, vec![]).await?;
+    let admin_view = LocalUserView::read(&mut context.pool(), local_user.id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindLocalUser)?;
+
+    // Three followed communities on a remote instance, and one without followers
+    let domain = "refederated.invalid";
+    let instance = Instance::read_or_create(&mut context.pool(), domain.to_string()).await?;
+    for name in ["one", "two", "three", "unfollowed"] {
+      let form = CommunityInsertForm::builder()
+        .name(name.to_string())
+        .title(name.to_string())
+        .public_key("pubkey".to_string())
+        .instance_id(instance.id)
+        .actor_id(Some(
+          Url::parse(&format!("https://{domain}/c/{name}"))?.into(),
+        ))
+        .local(Some(false))
+        .build();
+      let community = Community::create(&mut context.pool(), &form).await?;
+      if name != "unfollowed" {
+        let form = CommunityFollowerForm {
+          community_id: community.id,
+          person_id: admin.id,
+          pending: false,
+        };
+        CommunityFollower::follow(&mut context.pool(), &form).await?;
+      }
+    }
+    sleep(CACHE_DURATION_API).await;
+
+    // Nothing is fetched while the instance is blocked
+    FederationBlockList::replace(&mut context.pool(), Some(vec![domain.to_string()])).await?;
+    sleep(CACHE_DURATION_FEDERATION).await;
+    let data = BackfillInstance {
+      instance_id: instance.id,
+    };
+    let res = backfill_instance(
+      Json(data),
+      context.reset_request_count(),
+      admin_view.clone(),
+    )
+    .await;
+    assert!(res.is_err_and(|e| e.error_type == LemmyErrorType::DomainBlocked(domain.to_string())));
+
+    // After unblocking, the backfill stops once the request limit is reached
+    FederationBlockList::replace(&mut context.pool(), Some(vec![])).await?;
+    sleep(CACHE_DURATION_FEDERATION).await;
+    let res = backfill_instance(Json(data), context.reset_request_count(), admin_view).await?;
+    // The remote instance can't be reached, so both attempted fetches fail
+    assert_eq!(0, res.fetched_communities);
+    assert_eq!(2, res.failed_communities);
+    assert_eq!(1, res.skipped_communities);
+
+    Instance::delete(&mut context.pool(), instance.id).await?;
+    Person::delete(&mut context.pool(), admin.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+}
@@ -0,0 +1,155 @@
+use crate::{
+  objects::{comment::ApubComment, post::ApubPost},
+  protocol::objects::{note::Note, page::Page},
+};
+use activitypub_federation::{config::Data, traits::Object};
+use actix_web::web::{Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  post::{ExportPost, ExportPostResponse},
+  utils::is_mod_or_admin,
+};
+use lemmy_db_schema::{
+  aggregates::structs::PostAggregates,
+  source::{comment::Comment, post::Post},
+  traits::Crud,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+use serde::{Deserialize, Serialize};
+
+/// Self-contained export of a post, which can be used for backups or to import the post again.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PostBundle {
+  /// The post in the same format as it is federated.
+  pub(crate) post: Page,
+  /// All comments of the post, ordered so that parents come before their replies.
+  pub(crate) comments: Vec<Note>,
+  /// Vote and comment counts of the post.
+  pub(crate) counts: PostAggregates,
+}
+
+impl PostBundle {
+  pub(crate) async fn new(post: Post, context: &Data<LemmyContext>) -> LemmyResult<Self> {
+    let counts = PostAggregates::read(&mut context.pool(), post.id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    let mut comments = vec![];
+    for comment in Comment::list_for_post(&mut context.pool(), post.id).await? {
+      comments.push(ApubComment(comment).into_json(context).await?);
+    }
+    Ok(PostBundle {
+      post: ApubPost(post).into_json(context).await?,
+      comments,
+      counts,
+    })
+  }
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn export_post(
+  data: Query<ExportPost>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ExportPostResponse>> {
+  let post = Post::read(&mut context.pool(), data.post_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindPost)?;
+  is_mod_or_admin(
+    &mut context.pool(),
+    &local_user_view.person,
+    post.community_id,
+  )
+  .await?;
+
+  let bundle = PostBundle::new(post, &context).await?;
+  Ok(Json(ExportPostResponse {
+    bundle: serde_json::to_value(bundle)?,
+  }))
+}
+
+#[cfg(test)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+  use super::*;
+  use lemmy_db_schema::{
+    source::{
+      comment::CommentInsertForm,
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{PostInsertForm, PostLike, PostLikeForm},
+    },
+    traits::Likeable,
+  };
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_export_post() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person = Person::create(
+      pool,
+      &PersonInsertForm::test_form(instance.id, "export_post_person"),
+    )
+    .await?;
+    let community_form = CommunityInsertForm::builder()
+      .name("export_post_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+    let post_form = PostInsertForm::builder()
+      .name("Exported post".to_string())
+      .creator_id(person.id)
+      .community_id(community.id)
+      .build();
+    let post = Post::create(pool, &post_form).await?;
+    let like_form = PostLikeForm {
+      post_id: post.id,
+      person_id: person.id,
+      score: 1,
+    };
+    PostLike::like(pool, &like_form).await?;
+
+    let comment_form = CommentInsertForm::builder()
+      .creator_id(person.id)
+      .post_id(post.id)
+      .content("top level comment".to_string())
+      .build();
+    let comment = Comment::create(pool, &comment_form, None).await?;
+    let reply_form = CommentInsertForm::builder()
+      .creator_id(person.id)
+      .post_id(post.id)
+      .content("reply".to_string())
+      .build();
+    Comment::create(pool, &reply_form, Some(&comment.path)).await?;
+
+    let bundle = serde_json::to_value(PostBundle::new(post.clone(), &context).await?)?;
+
+    assert_eq!(bundle["post"]["type"], "Page");
+    assert_eq!(bundle["post"]["id"], post.ap_id.to_string());
+    assert_eq!(bundle["post"]["name"], "Exported post");
+    let comments = bundle["comments"]
+      .as_array()
+      .ok_or(LemmyErrorType::CouldntFindComment)?;
+    assert_eq!(comments.len(), 2);
+    assert_eq!(comments[0]["type"], "Note");
+    assert_eq!(comments[0]["inReplyTo"], post.ap_id.to_string());
+    assert_eq!(comments[1]["inReplyTo"], comments[0]["id"]);
+    assert_eq!(bundle["counts"]["score"], 1);
+    assert_eq!(bundle["counts"]["comments"], 2);
+
+    // Parses again as bundle
+    let parsed: PostBundle = serde_json::from_value(bundle)?;
+    assert_eq!(parsed.comments.len(), 2);
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+}
@@ -0,0 +1,63 @@
+use crate::{
+  fetcher::replies::{fetch_replies, MAX_FETCHED_REPLIES},
+  protocol::objects::note::Note,
+};
+use activitypub_federation::{
+  config::Data,
+  fetch::fetch_object_http,
+  protocol::verification::verify_domains_match,
+};
+use actix_web::web::{Json, Query};
+use lemmy_api_common::{
+  comment::{FetchCommentReplies, GetCommentsResponse},
+  context::LemmyContext,
+};
+use lemmy_db_schema::{source::comment::Comment, traits::Crud};
+use lemmy_db_views::structs::{CommentView, LocalUserView};
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+/// Fetches the direct replies of a remote comment, for clients expanding a thread which was only
+/// partially fetched. Returns the fetched replies.
+#[tracing::instrument(skip(context))]
+pub async fn fetch_comment_replies(
+  data: Query<FetchCommentReplies>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<GetCommentsResponse>> {
+  let comment = Comment::read(&mut context.pool(), data.comment_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindComment)?;
+  if comment.local {
+    Err(LemmyErrorType::CantFetchRepliesOfLocalComment)?
+  }
+
+  // The replies collection isn't stored, so the comment needs to be fetched again
+  let note: Note = fetch_object_http(comment.ap_id.inner(), &context)
+    .await?
+    .object;
+  verify_domains_match(note.id.inner(), comment.ap_id.inner())?;
+  let replies = match note.replies {
+    Some(replies) => {
+      fetch_replies(
+        replies,
+        comment.ap_id.inner(),
+        MAX_FETCHED_REPLIES,
+        &context,
+      )
+      .await?
+    }
+    None => vec![],
+  };
+
+  let mut comments = vec![];
+  for reply in replies {
+    let view = CommentView::read(
+      &mut context.pool(),
+      reply.id,
+      Some(&local_user_view.local_user),
+    )
+    .await?;
+    comments.extend(view);
+  }
+  Ok(Json(GetCommentsResponse { comments }))
+}
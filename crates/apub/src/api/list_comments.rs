@@ -11,7 +11,7 @@ use lemmy_api_common::{
   utils::check_private_instance,
 };
 use lemmy_db_schema::{
-  source::{comment::Comment, community::Community, local_site::LocalSite},
+  source::{comment::Comment, community::Community, local_site::LocalSite, post::Post},
   traits::Crud,
 };
 use lemmy_db_views::{comment_view::CommentQuery, structs::LocalUserView};
@@ -35,7 +35,13 @@ pub async fn list_comments(
   } else {
     data.community_id
   };
-  let sort = data.sort;
+  // Without explicit sort, use the one which the creator chose for the post
+  let sort = match (data.sort, data.post_id) {
+    (None, Some(post_id)) => Post::read(&mut context.pool(), post_id)
+      .await?
+      .and_then(|p| p.default_comment_sort),
+    (sort, _) => sort,
+  };
   let max_depth = data.max_depth;
   let saved_only = data.saved_only;
 
@@ -8,7 +8,7 @@ use actix_web::web::{Json, Query};
 use lemmy_api_common::{
   context::LemmyContext,
   post::{GetPosts, GetPostsResponse},
-  utils::check_private_instance,
+  utils::{check_private_instance, proxy_post_attachments},
 };
 use lemmy_db_schema::source::community::Community;
 use lemmy_db_views::{
@@ -44,6 +44,7 @@ pub async fn list_posts(
   let show_hidden = data.show_hidden;
   let show_read = data.show_read;
   let show_nsfw = data.show_nsfw;
+  let media_type = data.media_type.clone();
 
   let liked_only = data.liked_only;
   let disliked_only = data.disliked_only;
@@ -72,7 +73,7 @@ pub async fn list_posts(
     None
   };
 
-  let posts = PostQuery {
+  let mut posts = PostQuery {
     local_user,
     listing_type,
     sort,
@@ -86,6 +87,7 @@ pub async fn list_posts(
     show_hidden,
     show_read,
     show_nsfw,
+    media_type,
     ..Default::default()
   }
   .list(&local_site.site, &mut context.pool())
@@ -94,5 +96,6 @@ pub async fn list_posts(
 
   // if this page wasn't empty, then there is a next page after the last post on this page
   let next_page = posts.last().map(PaginationCursor::after_post);
+  proxy_post_attachments(&mut posts, &context).await?;
   Ok(Json(GetPostsResponse { posts, next_page }))
 }
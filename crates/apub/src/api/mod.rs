@@ -5,11 +5,17 @@ use lemmy_db_schema::{
   SortType,
 };
 
+pub mod backfill_community;
+pub mod backfill_instance;
+pub mod export_post;
+pub mod fetch_comment_replies;
 pub mod list_comments;
 pub mod list_posts;
 pub mod read_community;
 pub mod read_person;
+pub mod reprocess_failed_post_import;
 pub mod resolve_object;
+pub mod resolve_objects;
 pub mod search;
 pub mod user_settings_backup;
 
@@ -4,7 +4,7 @@ use actix_web::web::{Json, Query};
 use lemmy_api_common::{
   context::LemmyContext,
   person::{GetPersonDetails, GetPersonDetailsResponse},
-  utils::{check_private_instance, read_site_for_actor},
+  utils::{check_private_instance, proxy_post_attachments, read_site_for_actor},
 };
 use lemmy_db_schema::{source::person::Person, utils::post_to_comment_sort_type};
 use lemmy_db_views::{
@@ -67,7 +67,7 @@ pub async fn read_person(
 
   let local_user = local_user_view.as_ref().map(|l| &l.local_user);
 
-  let posts = PostQuery {
+  let mut posts = PostQuery {
     sort,
     saved_only,
     local_user,
@@ -79,6 +79,7 @@ pub async fn read_person(
   }
   .list(&local_site.site, &mut context.pool())
   .await?;
+  proxy_post_attachments(&mut posts, &context).await?;
 
   let comments = CommentQuery {
     local_user,
@@ -0,0 +1,114 @@
+use crate::{objects::post::ApubPost, protocol::objects::page::Page};
+use activitypub_federation::{config::Data, traits::Object};
+use actix_web::web::Json;
+use lemmy_api_common::{
+  build_response::build_post_response,
+  context::LemmyContext,
+  post::PostResponse,
+  site::ReprocessFailedPostImport,
+  utils::is_admin,
+};
+use lemmy_db_schema::source::failed_post_import::FailedPostImport;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+#[tracing::instrument(skip(context))]
+pub async fn reprocess_failed_post_import(
+  data: Json<ReprocessFailedPostImport>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<PostResponse>> {
+  is_admin(&local_user_view)?;
+
+  let failed = FailedPostImport::read(&mut context.pool(), data.id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindFailedPostImport)?;
+  let page: Page = serde_json::from_str(&failed.data)?;
+  ApubPost::verify(&page, page.id.inner(), &context).await?;
+  // If this fails again, the stored error is updated
+  let post = ApubPost::from_json(page, &context).await?;
+  FailedPostImport::delete(&mut context.pool(), failed.id).await?;
+
+  build_post_response(&context, post.community_id, local_user_view, post.id).await
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    objects::{
+      community::tests::parse_lemmy_community,
+      person::tests::parse_lemmy_person,
+      post::store_failed_import,
+    },
+    protocol::tests::file_to_json_object,
+  };
+  use lemmy_db_schema::{
+    source::{
+      community::Community,
+      instance::Instance,
+      local_site::{LocalSite, LocalSiteInsertForm},
+      local_user::{LocalUser, LocalUserInsertForm},
+      person::{Person, PersonInsertForm},
+      post::Post,
+      site::Site,
+    },
+    traits::Crud,
+  };
+  use lemmy_utils::error::LemmyError;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_reprocess_failed_post_import() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+    let form = LocalSiteInsertForm::builder().site_id(site.id).build();
+    LocalSite::create(&mut context.pool(), &form).await?;
+
+    let instance = Instance::read_or_create(&mut context.pool(), "example.com".to_string()).await?;
+    let admin = Person::create(
+      &mut context.pool(),
+      &PersonInsertForm::test_form(instance.id, "failed_import_admin"),
+    )
+    .await?;
+    let user_form = LocalUserInsertForm::builder()
+      .person_id(admin.id)
+      .password_encrypted("pass".to_string())
+      .admin(Some(true))
+      .build();
+    let local_user = LocalUser::create(&mut context.pool(), &user_form, vec![]).await?;
+    let admin_view = LocalUserView::read(&mut context.pool(), local_user.id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindLocalUser)?;
+
+    // Import failed because pictrs was unavailable
+    let page: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    let error = LemmyError::from(LemmyErrorType::PictrsResponseError("timeout".to_string()));
+    store_failed_import(&page, &error, &context).await;
+    let failed = FailedPostImport::read_from_apub_id(&mut context.pool(), &page.id.clone().into())
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindFailedPostImport)?;
+    let list = FailedPostImport::list(&mut context.pool(), None, None).await?;
+    assert_eq!(vec![failed.clone()], list);
+
+    let data = ReprocessFailedPostImport { id: failed.id };
+    let res =
+      reprocess_failed_post_import(Json(data), context.reset_request_count(), admin_view).await?;
+    assert_eq!("Post title", res.post_view.post.name);
+    assert_eq!(page.id.inner(), res.post_view.post.ap_id.inner());
+    assert_eq!(
+      None,
+      FailedPostImport::read(&mut context.pool(), failed.id).await?
+    );
+
+    Post::delete(&mut context.pool(), res.post_view.post.id).await?;
+    Person::delete(&mut context.pool(), admin.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+}
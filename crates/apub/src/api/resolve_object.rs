@@ -8,7 +8,7 @@ use diesel::NotFound;
 use lemmy_api_common::{
   context::LemmyContext,
   site::{ResolveObject, ResolveObjectResponse},
-  utils::check_private_instance,
+  utils::{check_private_instance, proxy_post_attachments},
 };
 use lemmy_db_schema::{source::local_site::LocalSite, utils::DbPool};
 use lemmy_db_views::structs::{CommentView, LocalUserView, PostView};
@@ -23,29 +23,45 @@ pub async fn resolve_object(
 ) -> LemmyResult<Json<ResolveObjectResponse>> {
   let local_site = LocalSite::read(&mut context.pool()).await?;
   check_private_instance(&local_user_view, &local_site)?;
+  Ok(Json(
+    resolve(data.q.clone(), local_user_view, &context).await?,
+  ))
+}
+
+/// Resolves a single search query to an object, only fetching remote objects for authenticated
+/// users.
+pub(crate) async fn resolve(
+  q: String,
+  local_user_view: Option<LocalUserView>,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<ResolveObjectResponse> {
   // If we get a valid personId back we can safely assume that the user is authenticated,
   // if there's no personId then the JWT was missing or invalid.
   let is_authenticated = local_user_view.is_some();
 
   let res = if is_authenticated {
     // user is fully authenticated; allow remote lookups as well.
-    search_query_to_object_id(data.q.clone(), &context).await
+    search_query_to_object_id(q, context).await
   } else {
     // user isn't authenticated only allow a local search.
-    search_query_to_object_id_local(&data.q, &context).await
+    search_query_to_object_id_local(&q, context).await
   }
   .with_lemmy_type(LemmyErrorType::CouldntFindObject)?;
 
-  convert_response(res, local_user_view, &mut context.pool())
+  let mut res = convert_response(res, local_user_view, &mut context.pool())
     .await
-    .with_lemmy_type(LemmyErrorType::CouldntFindObject)
+    .with_lemmy_type(LemmyErrorType::CouldntFindObject)?;
+  if let Some(post_view) = &mut res.post {
+    proxy_post_attachments(std::slice::from_mut(post_view), context).await?;
+  }
+  Ok(res)
 }
 
 async fn convert_response(
   object: SearchableObjects,
   local_user_view: Option<LocalUserView>,
   pool: &mut DbPool<'_>,
-) -> LemmyResult<Json<ResolveObjectResponse>> {
+) -> LemmyResult<ResolveObjectResponse> {
   use SearchableObjects::*;
   let removed_or_deleted;
   let mut res = ResolveObjectResponse::default();
@@ -91,6 +107,6 @@ async fn convert_response(
   if removed_or_deleted {
     Err(NotFound {}.into())
   } else {
-    Ok(Json(res))
+    Ok(res)
   }
 }
@@ -0,0 +1,135 @@
+use crate::api::resolve_object::resolve;
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{ResolveObjects, ResolveObjectsResponse, ResolvedObject},
+  utils::check_private_instance,
+};
+use lemmy_db_schema::source::local_site::LocalSite;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Maximum number of distinct objects which can be resolved with a single request. Each of them
+/// gets its own limit of outgoing HTTP requests, like a separate call to `/resolve_object`.
+const MAX_RESOLVE_OBJECTS: usize = 20;
+
+#[tracing::instrument(skip(context))]
+pub async fn resolve_objects(
+  data: Json<ResolveObjects>,
+  context: Data<LemmyContext>,
+  local_user_view: Option<LocalUserView>,
+) -> LemmyResult<Json<ResolveObjectsResponse>> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  check_private_instance(&local_user_view, &local_site)?;
+
+  let queries: BTreeSet<_> = data.q.iter().map(|q| q.trim().to_string()).collect();
+  if queries.len() > MAX_RESOLVE_OBJECTS {
+    Err(LemmyErrorType::TooManyItems)?;
+  }
+
+  // Resolve one after another, so that a failure of one item doesn't affect the others
+  let mut objects = BTreeMap::new();
+  for q in queries {
+    let item_context = context.reset_request_count();
+    let res = match resolve(q.clone(), local_user_view.clone(), &item_context).await {
+      Ok(object) => ResolvedObject {
+        object: Some(object),
+        error: None,
+      },
+      Err(e) => ResolvedObject {
+        object: None,
+        error: Some(e.error_type),
+      },
+    };
+    objects.insert(q, res);
+  }
+  Ok(Json(ResolveObjectsResponse { objects }))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    objects::{
+      community::tests::parse_lemmy_community,
+      person::tests::parse_lemmy_person,
+      post::ApubPost,
+    },
+    protocol::{objects::page::Page, tests::file_to_json_object},
+  };
+  use activitypub_federation::traits::Object;
+  use lemmy_db_schema::{
+    source::{
+      community::Community,
+      local_site::LocalSiteInsertForm,
+      person::Person,
+      post::Post,
+      site::Site,
+    },
+    traits::Crud,
+  };
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_resolve_objects() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+    let form = LocalSiteInsertForm::builder().site_id(site.id).build();
+    LocalSite::create(&mut context.pool(), &form).await?;
+    let json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    let post = ApubPost::from_json(json, &context).await?;
+
+    let post_id = post.ap_id.to_string();
+    let person_id = person.actor_id.to_string();
+    let missing_id = "https://enterprise.lemmy.ml/post/999999".to_string();
+    let invalid_id = "not an id".to_string();
+    let data = ResolveObjects {
+      q: vec![
+        post_id.clone(),
+        person_id.clone(),
+        missing_id.clone(),
+        invalid_id.clone(),
+        post_id.clone(),
+      ],
+    };
+    let res = resolve_objects(Json(data), context.reset_request_count(), None).await?;
+
+    // Duplicate ids are only resolved once
+    assert_eq!(4, res.objects.len());
+    let resolved_post = res.objects.get(&post_id).and_then(|o| o.object.as_ref());
+    assert_eq!(
+      Some(post.id),
+      resolved_post
+        .and_then(|o| o.post.as_ref())
+        .map(|p| p.post.id)
+    );
+    let resolved_person = res.objects.get(&person_id).and_then(|o| o.object.as_ref());
+    assert_eq!(
+      Some(person.id),
+      resolved_person
+        .and_then(|o| o.person.as_ref())
+        .map(|p| p.person.id)
+    );
+
+    // Failures are returned per item
+    for id in [missing_id, invalid_id] {
+      let resolved = res.objects.get(&id);
+      assert!(resolved.is_some_and(|o| o.object.is_none()));
+      assert_eq!(
+        Some(&LemmyErrorType::CouldntFindObject),
+        resolved.and_then(|o| o.error.as_ref())
+      );
+    }
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+}
@@ -4,7 +4,7 @@ use actix_web::web::{Json, Query};
 use lemmy_api_common::{
   context::LemmyContext,
   site::{Search, SearchResponse},
-  utils::{check_private_instance, is_admin},
+  utils::{check_private_instance, is_admin, proxy_post_attachments},
 };
 use lemmy_db_schema::{source::community::Community, utils::post_to_comment_sort_type, SearchType};
 use lemmy_db_views::{
@@ -201,6 +201,7 @@ pub async fn search(
       .await?;
     }
   };
+  proxy_post_attachments(&mut posts, &context).await?;
 
   // Return the jwt
   Ok(Json(SearchResponse {
@@ -94,3 +94,103 @@ impl Collection for ApubCommunityFeatured {
     Ok(ApubCommunityFeatured(()))
   }
 }
+
+#[cfg(test)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+  use super::*;
+  use crate::{
+    objects::{community::tests::parse_lemmy_community, person::tests::parse_lemmy_person},
+    protocol::{objects::page::Page, tests::file_to_json_object},
+  };
+  use activitypub_federation::fetch::object_id::ObjectId;
+  use chrono::Duration;
+  use lemmy_db_schema::{
+    source::{person::Person, site::Site},
+    traits::Crud,
+    SortType,
+  };
+  use lemmy_db_views::post_view::PostQuery;
+  use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_featured_order() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+
+    // The order of featured posts differs from the order in which they were published
+    let page: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    let ids = ["featured_b", "featured_c", "featured_a"]
+      .map(|name| format!("https://enterprise.lemmy.ml/post/{name}"));
+    let ordered_items = ids
+      .iter()
+      .enumerate()
+      .map(|(i, id)| {
+        let mut page = page.clone();
+        page.id = ObjectId::parse(id)?;
+        page.name = Some(id.to_string());
+        page.published = page.published.map(|p| p + Duration::hours(i as i64));
+        Ok(page)
+      })
+      .collect::<LemmyResult<Vec<_>>>()?;
+    let json = GroupFeatured {
+      r#type: OrderedCollectionType::OrderedCollection,
+      id: Url::parse("https://enterprise.lemmy.ml/c/tenforward/featured")?,
+      total_items: ordered_items.len() as i32,
+      ordered_items: ordered_items.clone(),
+    };
+
+    let url = Url::parse("https://enterprise.lemmy.ml/c/tenforward")?;
+    ApubCommunityFeatured::verify(&json, &url, &context).await?;
+    ApubCommunityFeatured::from_json(json, &community, &context).await?;
+
+    let featured = Post::list_featured_for_community(&mut context.pool(), community.id).await?;
+    let featured_ids: Vec<_> = featured.iter().map(|p| p.ap_id.to_string()).collect();
+    assert_eq!(ids.to_vec(), featured_ids);
+
+    // Featured posts are listed in this order, independently of the sort type
+    let posts = PostQuery {
+      community_id: Some(community.id),
+      sort: Some(SortType::New),
+      ..Default::default()
+    }
+    .list(&site, &mut context.pool())
+    .await?;
+    let listed_ids: Vec<_> = posts.iter().map(|p| p.post.ap_id.to_string()).collect();
+    assert_eq!(ids.to_vec(), listed_ids);
+
+    // The same order is federated to other instances
+    let collection = ApubCommunityFeatured::read_local(&community, &context).await?;
+    let federated_ids: Vec<_> = collection
+      .ordered_items
+      .iter()
+      .map(|p| p.id.to_string())
+      .collect();
+    assert_eq!(ids.to_vec(), federated_ids);
+
+    // Posts which are no longer featured lose their position
+    let json = GroupFeatured {
+      total_items: 2,
+      ordered_items: ordered_items[..2].to_vec(),
+      ..json
+    };
+    ApubCommunityFeatured::from_json(json, &community, &context).await?;
+    let unfeatured = Post::read(&mut context.pool(), featured[2].id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert!(!unfeatured.featured_community);
+    assert_eq!(0, unfeatured.featured_position);
+
+    for post in featured {
+      Post::delete(&mut context.pool(), post.id).await?;
+    }
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+}
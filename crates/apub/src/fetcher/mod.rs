@@ -11,6 +11,7 @@ use lemmy_db_views::structs::LocalUserView;
 use lemmy_utils::error::{LemmyError, LemmyResult};
 
 pub mod post_or_comment;
+pub(crate) mod replies;
 pub mod search;
 pub mod site_or_community_or_user;
 pub mod user_or_community;
@@ -0,0 +1,174 @@
+use crate::{
+  objects::comment::ApubComment,
+  protocol::{
+    objects::{
+      note::Note,
+      page::{Replies, RepliesPage},
+    },
+    IdOrNestedObject,
+  },
+};
+use activitypub_federation::{
+  config::Data,
+  fetch::{fetch_object_http, object_id::ObjectId},
+  protocol::verification::verify_domains_match,
+  traits::Object,
+};
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::utils::FETCH_LIMIT_MAX;
+use lemmy_utils::error::LemmyResult;
+use tracing::warn;
+use url::Url;
+
+/// Maximum number of replies which are fetched at once for a single post or comment.
+pub(crate) const MAX_FETCHED_REPLIES: usize = FETCH_LIMIT_MAX as usize;
+
+/// Fetches the direct replies of a post or comment from its `replies` collection. Replies of the
+/// fetched comments are not fetched, only their count is stored. This way opening a huge thread
+/// only loads the top level, and deeper levels are fetched once a client expands them.
+///
+/// `parent` is the id of the post or comment which the collection belongs to. At most
+/// `max_replies` are fetched, and replies which fail to fetch are skipped.
+pub(crate) async fn fetch_replies(
+  replies: Replies,
+  parent: &Url,
+  max_replies: usize,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<Vec<ApubComment>> {
+  let mut page = match (replies.first, replies.id) {
+    (Some(first), _) => Some(first),
+    (None, Some(id)) => {
+      verify_domains_match(&id, parent)?;
+      let replies: Replies = fetch_object_http(&id, context).await?.object;
+      replies.first
+    }
+    (None, None) => None,
+  };
+
+  let mut comments = vec![];
+  while let Some(RepliesPage { items, next }) = page.take() {
+    for item in items {
+      if comments.len() >= max_replies {
+        return Ok(comments);
+      }
+      let id = item.id().clone();
+      match fetch_reply(item, parent, context).await {
+        Ok(comment) => comments.push(comment),
+        Err(e) => warn!("Failed to fetch reply {id}: {e}"),
+      }
+    }
+    if let Some(next) = next {
+      match fetch_replies_page(&next, parent, context).await {
+        Ok(next_page) => page = Some(next_page),
+        Err(e) => warn!("Failed to fetch replies page {next}: {e}"),
+      }
+    }
+  }
+  Ok(comments)
+}
+
+async fn fetch_replies_page(
+  url: &Url,
+  parent: &Url,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<RepliesPage> {
+  verify_domains_match(url, parent)?;
+  Ok(fetch_object_http(url, context).await?.object)
+}
+
+/// Embedded replies from the same domain are used directly, others need to be fetched from their
+/// origin so that they can't be forged.
+async fn fetch_reply(
+  item: IdOrNestedObject<Note>,
+  parent: &Url,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<ApubComment> {
+  match item {
+    IdOrNestedObject::NestedObject(note)
+      if verify_domains_match(note.id.inner(), parent).is_ok() =>
+    {
+      ApubComment::verify(&note, parent, context).await?;
+      ApubComment::from_json(note, context).await
+    }
+    item => {
+      ObjectId::<ApubComment>::from(item.id().clone())
+        .dereference(context)
+        .await
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    objects::{
+      community::tests::parse_lemmy_community,
+      person::tests::parse_lemmy_person,
+      post::ApubPost,
+    },
+    protocol::{objects::page::Page, tests::file_to_json_object},
+  };
+  use lemmy_db_schema::{
+    source::{comment::Comment, community::Community, person::Person, post::Post, site::Site},
+    traits::Crud,
+  };
+  use lemmy_utils::error::LemmyErrorType;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  fn replies(items: Vec<Note>) -> Replies {
+    Replies {
+      id: None,
+      total_items: Some(items.len() as i64),
+      first: Some(RepliesPage {
+        items: items
+          .into_iter()
+          .map(IdOrNestedObject::NestedObject)
+          .collect(),
+        next: None,
+      }),
+    }
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_fetch_top_level_replies() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+    let page: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    let post = ApubPost::from_json(page, &context).await?;
+
+    // Top-level comment which includes its own reply
+    let mut child: Note = file_to_json_object("assets/lemmy/objects/note.json")?;
+    child.id = Url::parse("https://enterprise.lemmy.ml/comment/38742")?.into();
+    child.in_reply_to = Url::parse("https://enterprise.lemmy.ml/comment/38741")?.into();
+    let mut top_level: Note = file_to_json_object("assets/lemmy/objects/note.json")?;
+    top_level.replies = Some(replies(vec![child.clone()]));
+
+    let fetched = fetch_replies(
+      replies(vec![top_level]),
+      post.ap_id.inner(),
+      MAX_FETCHED_REPLIES,
+      &context,
+    )
+    .await?;
+    let comment = fetched.first().ok_or(LemmyErrorType::CouldntFindComment)?;
+    assert_eq!(1, fetched.len());
+    assert_eq!(post.id, comment.post_id);
+    assert_eq!(Some(1), comment.remote_child_count);
+    assert_eq!(0, context.request_count());
+
+    // The reply is only fetched when the comment is expanded
+    let child = Comment::read_from_apub_id(&mut context.pool(), child.id.into_inner()).await?;
+    assert!(child.is_none());
+
+    Comment::delete(&mut context.pool(), comment.id).await?;
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+}
@@ -41,11 +41,14 @@ impl Object for SiteOrCommunityOrUser {
   }
 
   #[tracing::instrument(skip_all)]
-  async fn read_from_id(
-    _object_id: Url,
-    _data: &Data<Self::DataType>,
-  ) -> LemmyResult<Option<Self>> {
-    unimplemented!();
+  async fn read_from_id(object_id: Url, data: &Data<Self::DataType>) -> LemmyResult<Option<Self>> {
+    let user_or_community = UserOrCommunity::read_from_id(object_id.clone(), data).await?;
+    Ok(match user_or_community {
+      Some(o) => Some(SiteOrCommunityOrUser::UserOrCommunity(o)),
+      None => ApubSite::read_from_id(object_id, data)
+        .await?
+        .map(SiteOrCommunityOrUser::Site),
+    })
   }
 
   #[tracing::instrument(skip_all)]
@@ -75,8 +78,15 @@ impl Object for SiteOrCommunityOrUser {
   }
 
   #[tracing::instrument(skip_all)]
-  async fn from_json(_apub: Self::Kind, _data: &Data<Self::DataType>) -> LemmyResult<Self> {
-    unimplemented!();
+  async fn from_json(apub: Self::Kind, data: &Data<Self::DataType>) -> LemmyResult<Self> {
+    Ok(match apub {
+      SiteOrPersonOrGroup::Instance(a) => {
+        SiteOrCommunityOrUser::Site(ApubSite::from_json(a, data).await?)
+      }
+      SiteOrPersonOrGroup::PersonOrGroup(a) => {
+        SiteOrCommunityOrUser::UserOrCommunity(UserOrCommunity::from_json(a, data).await?)
+      }
+    })
   }
 }
 
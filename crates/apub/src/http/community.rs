@@ -6,7 +6,12 @@ use crate::{
     community_moderators::ApubCommunityModerators,
     community_outbox::ApubCommunityOutbox,
   },
-  http::{check_community_public, create_apub_response, create_apub_tombstone_response},
+  http::{
+    check_community_public,
+    create_apub_response,
+    create_apub_tombstone_response,
+    with_received_body,
+  },
   objects::{community::ApubCommunity, person::ApubPerson},
 };
 use activitypub_federation::{
@@ -54,8 +59,11 @@ pub async fn community_inbox(
   body: Bytes,
   data: Data<LemmyContext>,
 ) -> LemmyResult<HttpResponse> {
-  receive_activity::<WithContext<GroupInboxActivities>, ApubPerson, LemmyContext>(
-    request, body, &data,
+  with_received_body(
+    body.clone(),
+    receive_activity::<WithContext<GroupInboxActivities>, ApubPerson, LemmyContext>(
+      request, body, &data,
+    ),
   )
   .await
 }
@@ -1,6 +1,6 @@
 use crate::{
   activity_lists::SharedInboxActivities,
-  fetcher::user_or_community::UserOrCommunity,
+  fetcher::site_or_community_or_user::SiteOrCommunityOrUser,
   protocol::objects::tombstone::Tombstone,
   FEDERATION_CONTEXT,
 };
@@ -20,7 +20,8 @@ use lemmy_db_schema::{
 };
 use lemmy_utils::error::{LemmyErrorType, LemmyResult};
 use serde::{Deserialize, Serialize};
-use std::{ops::Deref, time::Duration};
+use serde_json::Value;
+use std::{future::Future, ops::Deref, time::Duration};
 use tokio::time::timeout;
 use url::Url;
 
@@ -33,13 +34,46 @@ pub mod site;
 
 const INCOMING_ACTIVITY_TIMEOUT: Duration = Duration::from_secs(9);
 
+tokio::task_local! {
+  /// The body of the activity which is currently received, before it was deserialized.
+  static RECEIVED_BODY: Bytes;
+}
+
+/// Keeps the received body available while the activity is processed, see [received_json].
+pub(crate) async fn with_received_body<F: Future>(body: Bytes, f: F) -> F::Output {
+  RECEIVED_BODY.scope(body, f).await
+}
+
+/// Returns the received activity exactly as it was sent, if it contains the full object with the
+/// given id. Objects which were fetched separately, or are only referenced by id, aren't included.
+pub(crate) fn received_json(object_id: &Url) -> Option<String> {
+  fn contains_object(json: &Value, id: &str) -> bool {
+    match json {
+      Value::Object(map) => {
+        map.get("id").and_then(Value::as_str) == Some(id)
+          || map.values().any(|v| contains_object(v, id))
+      }
+      Value::Array(items) => items.iter().any(|v| contains_object(v, id)),
+      _ => false,
+    }
+  }
+  let body = RECEIVED_BODY.try_with(Clone::clone).ok()?;
+  let json = serde_json::from_slice::<Value>(&body).ok()?;
+  contains_object(&json, object_id.as_str()).then(|| String::from_utf8_lossy(&body).into_owned())
+}
+
 pub async fn shared_inbox(
   request: HttpRequest,
   body: Bytes,
   data: Data<LemmyContext>,
 ) -> LemmyResult<HttpResponse> {
-  let receive_fut =
-    receive_activity::<SharedInboxActivities, UserOrCommunity, LemmyContext>(request, body, &data);
+  // Besides users and communities, the instance actor can send anonymous reports
+  let receive_fut = with_received_body(
+    body.clone(),
+    receive_activity::<SharedInboxActivities, SiteOrCommunityOrUser, LemmyContext>(
+      request, body, &data,
+    ),
+  );
   // Set a timeout shorter than `REQWEST_TIMEOUT` for processing incoming activities. This is to
   // avoid taking a long time to process an incoming activity when a required data fetch times out.
   // In this case our own instance would timeout and be marked as dead by the sender. Better to
@@ -1,7 +1,7 @@
 use crate::{
   activity_lists::PersonInboxActivities,
   fetcher::user_or_community::UserOrCommunity,
-  http::{create_apub_response, create_apub_tombstone_response},
+  http::{create_apub_response, create_apub_tombstone_response, with_received_body},
   objects::person::ApubPerson,
   protocol::collections::empty_outbox::EmptyOutbox,
 };
@@ -50,8 +50,11 @@ pub async fn person_inbox(
   body: Bytes,
   data: Data<LemmyContext>,
 ) -> LemmyResult<HttpResponse> {
-  receive_activity::<WithContext<PersonInboxActivities>, UserOrCommunity, LemmyContext>(
-    request, body, &data,
+  with_received_body(
+    body.clone(),
+    receive_activity::<WithContext<PersonInboxActivities>, UserOrCommunity, LemmyContext>(
+      request, body, &data,
+    ),
   )
   .await
 }
@@ -4,7 +4,7 @@ use activitypub_federation::{
   error::Error as ActivityPubError,
 };
 use async_trait::async_trait;
-use lemmy_api_common::context::LemmyContext;
+use lemmy_api_common::{context::LemmyContext, utils::check_domain_federation};
 use lemmy_db_schema::{
   source::{activity::ReceivedActivity, instance::Instance, local_site::LocalSite},
   utils::{ActualDbPool, DbPool},
@@ -80,39 +80,14 @@ impl UrlVerifier for VerifyUrlData {
 /// - URL not being in the blocklist (if it is active)
 #[tracing::instrument(skip(local_site_data))]
 fn check_apub_id_valid(apub_id: &Url, local_site_data: &LocalSiteData) -> LemmyResult<()> {
-  let domain = apub_id
-    .domain()
-    .ok_or(LemmyErrorType::UrlWithoutDomain)?
-    .to_string();
-
-  if !local_site_data
-    .local_site
-    .as_ref()
-    .map(|l| l.federation_enabled)
-    .unwrap_or(true)
-  {
-    Err(LemmyErrorType::FederationDisabled)?
-  }
+  let domain = apub_id.domain().ok_or(LemmyErrorType::UrlWithoutDomain)?;
 
-  if local_site_data
-    .blocked_instances
-    .iter()
-    .any(|i| domain.to_lowercase().eq(&i.domain.to_lowercase()))
-  {
-    Err(LemmyErrorType::DomainBlocked(domain.clone()))?
-  }
-
-  // Only check this if there are instances in the allowlist
-  if !local_site_data.allowed_instances.is_empty()
-    && !local_site_data
-      .allowed_instances
-      .iter()
-      .any(|i| domain.to_lowercase().eq(&i.domain.to_lowercase()))
-  {
-    Err(LemmyErrorType::DomainNotInAllowList(domain))?
-  }
-
-  Ok(())
+  check_domain_federation(
+    domain,
+    local_site_data.local_site.as_ref(),
+    &local_site_data.allowed_instances,
+    &local_site_data.blocked_instances,
+  )
 }
 
 #[derive(Clone)]
@@ -210,6 +185,21 @@ pub(crate) async fn check_apub_id_valid_with_strictness(
 /// would be a waste of resources.
 #[tracing::instrument(skip(data))]
 async fn insert_received_activity(ap_id: &Url, data: &Data<LemmyContext>) -> LemmyResult<()> {
+  // Domains which were recently marked as alive, to avoid a database write for every activity
+  static MARKED_ALIVE: Lazy<Cache<String, ()>> = Lazy::new(|| {
+    Cache::builder()
+      .max_capacity(10_000)
+      .time_to_live(CACHE_DURATION_FEDERATION)
+      .build()
+  });
+
   ReceivedActivity::create(&mut data.pool(), &ap_id.clone().into()).await?;
+  // Resume delivery to the sending instance in case it was marked as dead
+  if let Some(domain) = ap_id.domain() {
+    if !MARKED_ALIVE.contains_key(domain) {
+      Instance::mark_alive(&mut data.pool(), domain).await?;
+      MARKED_ALIVE.insert(domain.to_string(), ()).await;
+    }
+  }
   Ok(())
 }
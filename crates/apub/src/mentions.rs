@@ -1,13 +1,19 @@
-use crate::objects::{comment::ApubComment, community::ApubCommunity, person::ApubPerson};
+use crate::objects::{comment::ApubComment, person::ApubPerson};
 use activitypub_federation::{
   config::Data,
-  fetch::{object_id::ObjectId, webfinger::webfinger_resolve_actor},
+  fetch::webfinger::webfinger_resolve_actor,
   kinds::link::MentionType,
   traits::Actor,
 };
 use lemmy_api_common::context::LemmyContext;
 use lemmy_db_schema::{
-  source::{comment::Comment, person::Person, post::Post},
+  source::{
+    comment::Comment,
+    community::Community,
+    local_site::LocalSite,
+    person::Person,
+    post::Post,
+  },
   traits::Crud,
   utils::DbPool,
 };
@@ -39,14 +45,14 @@ pub struct MentionsAndAddresses {
 /// This takes a comment, and builds a list of to_addresses, inboxes,
 /// and mention tags, so they know where to be sent to.
 /// Addresses are the persons / addresses that go in the cc field.
-#[tracing::instrument(skip(comment, community_id, context))]
+#[tracing::instrument(skip(comment, community, context))]
 pub async fn collect_non_local_mentions(
   comment: &ApubComment,
-  community_id: ObjectId<ApubCommunity>,
+  community: &Community,
   context: &Data<LemmyContext>,
 ) -> LemmyResult<MentionsAndAddresses> {
   let parent_creator = get_comment_parent_creator(&mut context.pool(), comment).await?;
-  let mut addressed_ccs: Vec<Url> = vec![community_id.into(), parent_creator.id()];
+  let mut addressed_ccs: Vec<Url> = vec![community.actor_id.clone().into(), parent_creator.id()];
 
   // Add the mention tag
   let parent_creator_tag = Mention {
@@ -84,13 +90,31 @@ pub async fn collect_non_local_mentions(
     }
   }
 
+  let max_recipients = LocalSite::read(&mut context.pool())
+    .await
+    .ok()
+    .and_then(|l| l.federation_max_recipients);
   let tags = tags.into_iter().map(MentionOrValue::Mention).collect();
   Ok(MentionsAndAddresses {
-    ccs: addressed_ccs,
+    ccs: limit_recipients(addressed_ccs, community, max_recipients),
     tags,
   })
 }
 
+/// If there are more than `max_recipients` persons in `ccs`, they are replaced by the followers
+/// collection of the community to keep the activity small. Mentioned persons are still listed in
+/// the tags, and the activity is delivered to their inboxes as before.
+fn limit_recipients(ccs: Vec<Url>, community: &Community, max_recipients: Option<i32>) -> Vec<Url> {
+  let community_id: Url = community.actor_id.clone().into();
+  let persons = ccs.iter().filter(|c| **c != community_id).count();
+  match (max_recipients, &community.followers_url) {
+    (Some(max), Some(followers_url)) if persons > usize::try_from(max).unwrap_or_default() => {
+      vec![community_id, followers_url.clone().into()]
+    }
+    _ => ccs,
+  }
+}
+
 /// Returns the apub ID of the person this comment is responding to. Meaning, in case this is a
 /// top-level comment, the creator of the post, otherwise the creator of the parent comment.
 #[tracing::instrument(skip(pool, comment))]
@@ -117,3 +141,42 @@ async fn get_comment_parent_creator(
       .into(),
   )
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::objects::community::tests::parse_lemmy_community;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_limit_recipients() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let community = parse_lemmy_community(&context).await?;
+    let community_id: Url = community.actor_id.clone().into();
+    let followers_url: Url = community
+      .followers_url
+      .clone()
+      .ok_or(LemmyErrorType::CouldntFindCommunity)?
+      .into();
+    let ccs = vec![
+      community_id.clone(),
+      Url::parse("https://enterprise.lemmy.ml/u/picard")?,
+      Url::parse("https://ds9.lemmy.ml/u/lemmy_alpha")?,
+    ];
+
+    // Recipients are listed individually up to the limit
+    assert_eq!(ccs, limit_recipients(ccs.clone(), &community, None));
+    assert_eq!(ccs, limit_recipients(ccs.clone(), &community, Some(2)));
+
+    // Beyond it the community followers are addressed instead
+    assert_eq!(
+      vec![community_id, followers_url],
+      limit_recipients(ccs, &community, Some(1))
+    );
+
+    Community::delete(&mut context.pool(), community.id).await?;
+    Ok(())
+  }
+}
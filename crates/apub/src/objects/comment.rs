@@ -113,7 +113,7 @@ impl Object for ApubComment {
       post.ap_id.into()
     };
     let language = LanguageTag::new_single(self.language_id, &mut context.pool()).await?;
-    let maa = collect_non_local_mentions(&self, community.actor_id.clone().into(), context).await?;
+    let maa = collect_non_local_mentions(&self, &community, context).await?;
 
     let note = Note {
       r#type: NoteType::Note,
@@ -131,6 +131,7 @@ impl Object for ApubComment {
       distinguished: Some(self.distinguished),
       language,
       audience: Some(community.actor_id.into()),
+      replies: None,
     };
 
     Ok(note)
@@ -192,8 +193,21 @@ impl Object for ApubComment {
       distinguished: note.distinguished,
       local: Some(false),
       language_id,
+      remote_child_count: note
+        .replies
+        .as_ref()
+        .and_then(|r| r.total_items)
+        .filter(|c| *c >= 0),
     };
-    let parent_comment_path = parent_comment.map(|t| t.0.path);
+    // Replies in very deep threads are attached to the deepest allowed ancestor instead
+    let max_depth = local_site
+      .as_ref()
+      .and_then(|l| usize::try_from(l.max_comment_fetch_depth).ok())
+      .map(|d| d.max(1));
+    let parent_comment_path = parent_comment.map(|t| match max_depth {
+      Some(max_depth) => t.ancestor_path(max_depth - 1),
+      None => t.0.path,
+    });
     let timestamp: DateTime<Utc> = note.updated.or(note.published).unwrap_or_else(naive_now);
     let comment = Comment::insert_apub(
       &mut context.pool(),
@@ -218,11 +232,17 @@ pub(crate) mod tests {
     },
     protocol::tests::file_to_json_object,
   };
+  use activitypub_federation::fetch::object_id::ObjectId;
   use assert_json_diff::assert_json_include;
   use html2md::parse_html;
-  use lemmy_db_schema::source::site::Site;
+  use lemmy_db_schema::source::{
+    local_site::{LocalSiteInsertForm, LocalSiteUpdateForm},
+    site::Site,
+  };
+  use lemmy_utils::CACHE_DURATION_API;
   use pretty_assertions::assert_eq;
   use serial_test::serial;
+  use tokio::time::sleep;
 
   async fn prepare_comment_test(
     url: &Url,
@@ -301,6 +321,62 @@ pub(crate) mod tests {
     Ok(())
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn test_max_comment_fetch_depth() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let url = Url::parse("https://enterprise.lemmy.ml/comment/38741")?;
+    let data = prepare_comment_test(&url, &context).await?;
+    let form = LocalSiteInsertForm::builder()
+      .site_id(data.3.id)
+      .max_comment_fetch_depth(Some(2))
+      .build();
+    LocalSite::create(&mut context.pool(), &form).await?;
+    // wait for the cached local site to expire
+    sleep(CACHE_DURATION_API).await;
+
+    // Thread which is three levels deep
+    let mut thread: Vec<Comment> = vec![];
+    for _ in 0..3 {
+      let form = CommentInsertForm::builder()
+        .creator_id(data.0.id)
+        .post_id(data.2.id)
+        .content("reply".to_string())
+        .build();
+      let parent_path = thread.last().map(|c| &c.path);
+      thread.push(Comment::create(&mut context.pool(), &form, parent_path).await?);
+    }
+
+    let mut json: Note = file_to_json_object("assets/lemmy/objects/note.json")?;
+    if let Some(deepest) = thread.last() {
+      json.in_reply_to = ObjectId::from(deepest.ap_id.clone());
+    }
+    ApubComment::verify(&json, &url, &context).await?;
+    let comment = ApubComment::from_json(json, &context).await?;
+
+    // The reply is attached to the comment at the maximum depth
+    assert_eq!(comment.parent_comment_id(), thread.first().map(|c| c.id));
+    assert_eq!(comment.path.0.split('.').count(), 3);
+
+    // Unknown parents aren't fetched once the fetch depth is reached
+    let form = LocalSiteUpdateForm {
+      max_comment_fetch_depth: Some(0),
+      ..Default::default()
+    };
+    LocalSite::update(&mut context.pool(), &form).await?;
+    sleep(CACHE_DURATION_API).await;
+    let mut json: Note = file_to_json_object("assets/lemmy/objects/note.json")?;
+    json.in_reply_to = ObjectId::parse("https://enterprise.lemmy.ml/comment/1")?;
+    let res = ApubComment::verify(&json, &url, &context).await;
+    assert_eq!(
+      Some(LemmyErrorType::CommentFetchDepthExceeded),
+      res.err().map(|e| e.error_type)
+    );
+
+    cleanup(data, &context).await?;
+    Ok(())
+  }
+
   #[tokio::test]
   #[serial]
   async fn test_html_to_markdown_sanitize() {
@@ -14,7 +14,7 @@ use activitypub_federation::{
   kinds::actor::GroupType,
   traits::{Actor, Object},
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Days, Utc};
 use lemmy_api_common::{
   context::LemmyContext,
   utils::{
@@ -151,6 +151,24 @@ impl Object for ApubCommunity {
     let icon = proxy_image_link_opt_apub(group.icon.map(|i| i.url), context).await?;
     let banner = proxy_image_link_opt_apub(group.image.map(|i| i.url), context).await?;
 
+    // Communities which are seen for the first time are quarantined. This is only set on insert,
+    // so that updates of the community don't extend the quarantine.
+    let quarantine_days = local_site
+      .as_ref()
+      .and_then(|l| l.federation_community_quarantine_days);
+    // Non-positive values disable the quarantine
+    let quarantined_until = match quarantine_days {
+      Some(days)
+        if days > 0
+          && Community::read_from_apub_id(&mut context.pool(), &group.id.clone().into())
+            .await?
+            .is_none() =>
+      {
+        Utc::now().checked_add_days(Days::new(days.unsigned_abs().into()))
+      }
+      _ => None,
+    };
+
     let form = CommunityInsertForm {
       name: group.preferred_username.clone(),
       title: group.name.unwrap_or(group.preferred_username.clone()),
@@ -172,6 +190,7 @@ impl Object for ApubCommunity {
       posting_restricted_to_mods: group.posting_restricted_to_mods,
       instance_id,
       featured_url: group.featured.clone().map(Into::into),
+      quarantined_until,
       ..Default::default()
     };
     let languages =
@@ -8,9 +8,18 @@ use activitypub_federation::{
 use anyhow::anyhow;
 use html2md::parse_html;
 use lemmy_api_common::context::LemmyContext;
-use lemmy_utils::error::LemmyResult;
+use lemmy_db_schema::PostSourceFormat;
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType, LemmyResult},
+  utils::markdown::markdown_remove_images,
+  CACHE_DURATION_FEDERATION,
+};
+use moka::future::Cache;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex, RegexBuilder};
 use serde::Deserialize;
-use std::fmt::Debug;
+use std::{fmt::Debug, sync::Arc};
+use url::Url;
 
 pub mod comment;
 pub mod community;
@@ -46,6 +55,125 @@ pub(crate) fn read_from_string_or_source_opt(
     .map(|content| read_from_string_or_source(content, media_type, source))
 }
 
+/// Returns the format in which received content was written, matching the conversion done by
+/// [read_from_string_or_source].
+pub(crate) fn read_source_format(
+  media_type: &Option<MediaTypeMarkdownOrHtml>,
+  source: &Option<Source>,
+) -> PostSourceFormat {
+  if source.is_some() || media_type == &Some(MediaTypeMarkdownOrHtml::Markdown) {
+    PostSourceFormat::Markdown
+  } else {
+    PostSourceFormat::ConvertedHtml
+  }
+}
+
+static HTML_IMAGE: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"(?i)<img\b[^>]*>").expect("compile html image regex"));
+static HTML_ATTRIBUTE: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(r#"(?i)\b(src|width|height)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))"#)
+    .expect("compile html attribute regex")
+});
+
+/// Removes likely tracking pixels from received HTML. These are images which are at most one
+/// pixel wide or high, and images from the given tracker domains.
+pub(crate) fn remove_tracking_pixels_html(html: &str, tracker_domains: &[String]) -> String {
+  HTML_IMAGE
+    .replace_all(html, |caps: &Captures| {
+      let tag = caps.get(0).map(|m| m.as_str()).unwrap_or_default();
+      let mut is_tracker = false;
+      for attr in HTML_ATTRIBUTE.captures_iter(tag) {
+        let name = attr.get(1).map(|n| n.as_str().to_lowercase());
+        let value = attr
+          .get(2)
+          .or(attr.get(3))
+          .or(attr.get(4))
+          .map(|v| v.as_str().trim())
+          .unwrap_or_default();
+        is_tracker |= match name.as_deref() {
+          Some("src") => Url::parse(value).is_ok_and(|u| is_tracker_url(&u, tracker_domains)),
+          _ => value
+            .trim_end_matches("px")
+            .parse::<u32>()
+            .is_ok_and(|size| size <= 1),
+        };
+      }
+      if is_tracker {
+        String::new()
+      } else {
+        tag.to_string()
+      }
+    })
+    .into_owned()
+}
+
+/// Removes images from the given tracker domains in received markdown. Markdown doesn't contain
+/// image sizes, so these can't be checked.
+pub(crate) fn remove_tracking_pixels_markdown(
+  markdown: &str,
+  tracker_domains: &[String],
+) -> String {
+  if tracker_domains.is_empty() {
+    return markdown.to_string();
+  }
+  markdown_remove_images(markdown, |u| is_tracker_url(u, tracker_domains))
+}
+
+/// Builds the regexes for [strip_body_patterns]. Patterns are matched in multi-line mode, so `^`
+/// and `$` refer to single lines. They are cached, so that they aren't rebuilt for every received
+/// post.
+pub(crate) async fn build_body_strip_regexes(patterns: &[String]) -> LemmyResult<Arc<Vec<Regex>>> {
+  static BODY_STRIP_REGEXES: Lazy<Cache<Vec<String>, Arc<Vec<Regex>>>> = Lazy::new(|| {
+    Cache::builder()
+      .max_capacity(1)
+      .time_to_live(CACHE_DURATION_FEDERATION)
+      .build()
+  });
+
+  Ok(
+    BODY_STRIP_REGEXES
+      .try_get_with::<_, LemmyError>(patterns.to_vec(), async {
+        let regexes = patterns
+          .iter()
+          .map(|p| {
+            RegexBuilder::new(p)
+              .multi_line(true)
+              .build()
+              .with_lemmy_type(LemmyErrorType::InvalidRegex)
+          })
+          .collect::<LemmyResult<Vec<_>>>()?;
+        Ok(Arc::new(regexes))
+      })
+      .await
+      .map_err(|e| anyhow!("Failed to build body strip patterns due to `{}`", e))?,
+  )
+}
+
+/// Removes text matching any of the given regexes from a received body, for example signatures
+/// which some platforms append to every post. Returns `None` if nothing remains of the body.
+pub(crate) fn strip_body_patterns(body: &str, regexes: &[Regex]) -> Option<String> {
+  if regexes.is_empty() {
+    return Some(body.to_string());
+  }
+  let stripped = regexes
+    .iter()
+    .fold(body.to_string(), |body, regex| {
+      regex.replace_all(&body, "").into_owned()
+    })
+    .trim_end()
+    .to_string();
+  Some(stripped).filter(|b| !b.is_empty())
+}
+
+fn is_tracker_url(url: &Url, tracker_domains: &[String]) -> bool {
+  url.domain().is_some_and(|domain| {
+    tracker_domains.iter().any(|tracker| {
+      let tracker = tracker.trim().to_lowercase();
+      domain == tracker || domain.ends_with(&format!(".{tracker}"))
+    })
+  })
+}
+
 /// When for example a Post is made in a remote community, the community will send it back,
 /// wrapped in Announce. If we simply receive this like any other federated object, overwrite the
 /// existing, local Post. In particular, it will set the field local = false, so that the object
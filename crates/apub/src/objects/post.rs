@@ -1,12 +1,25 @@
 use crate::{
   activities::{verify_is_public, verify_person_in_community},
   check_apub_id_valid_with_strictness,
+  fetcher::replies::{fetch_replies, MAX_FETCHED_REPLIES},
+  http::received_json,
   local_site_data_cached,
-  objects::{read_from_string_or_source_opt, verify_is_remote_object},
+  objects::{
+    build_body_strip_regexes,
+    community::ApubCommunity,
+    person::ApubPerson,
+    read_from_string_or_source_opt,
+    read_source_format,
+    remove_tracking_pixels_html,
+    remove_tracking_pixels_markdown,
+    strip_body_patterns,
+    verify_is_remote_object,
+  },
   protocol::{
     objects::{
-      page::{Attachment, AttributedTo, Hashtag, HashtagType, Page, PageType},
+      page::{Attachment, Hashtag, HashtagType, Page, PageBuilder, PageType},
       LanguageTag,
+      Timestamps,
     },
     ImageObject,
     InCommunity,
@@ -15,40 +28,77 @@ use crate::{
 };
 use activitypub_federation::{
   config::Data,
-  kinds::public,
-  protocol::{values::MediaTypeMarkdownOrHtml, verification::verify_domains_match},
+  protocol::verification::verify_domains_match,
   traits::Object,
 };
-use anyhow::anyhow;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
+use diesel::result::DatabaseErrorKind;
 use html2text::{from_read_with_decorator, render::text_renderer::TrivialDecorator};
 use lemmy_api_common::{
   context::LemmyContext,
-  request::generate_post_link_metadata,
-  utils::{get_url_blocklist, local_site_opt_to_slur_regex, process_markdown_opt},
+  request::{generate_post_link_metadata, is_media_type_allowed, is_media_type_mismatch},
+  utils::{
+    check_community_duplicate_title,
+    check_community_min_karma,
+    check_community_nsfw,
+    check_community_post_interval,
+    check_community_posting_window,
+    check_community_slurs,
+    get_url_blocklist,
+    is_mod_or_admin,
+    local_site_opt_to_slur_regex,
+    process_markdown_opt,
+  },
 };
 use lemmy_db_schema::{
+  impls::actor_language::UNDETERMINED_ID,
   source::{
+    comment::Comment,
     community::Community,
+    failed_post_import::{FailedPostImport, FailedPostImportForm},
+    images::{ImageDetails, ImageDetailsForm, RemoteImage},
     local_site::LocalSite,
-    person::Person,
+    person::{Person, PersonInsertForm},
     post::{Post, PostInsertForm, PostUpdateForm},
+    post_previous_version::PostPreviousVersion,
+    post_raw_json::{PostRawJson, PostRawJsonForm},
   },
   traits::Crud,
   utils::naive_now,
+  PostSourceFormat,
 };
 use lemmy_db_views_actor::structs::CommunityModeratorView;
 use lemmy_utils::{
   error::{LemmyError, LemmyErrorType, LemmyResult},
   spawn_try_task,
-  utils::{markdown::markdown_to_html, slurs::check_slurs_opt, validation::check_url_scheme},
+  utils::{
+    markdown::{markdown_check_for_blocked_urls, markdown_to_html},
+    slurs::{check_title_slurs, remove_slurs},
+    validation::{check_url_scheme, clean_url_params},
+  },
 };
-use std::ops::Deref;
+use std::{io::ErrorKind, ops::Deref};
 use stringreader::StringReader;
+use tracing::{info, warn};
 use url::Url;
 
 const MAX_TITLE_LENGTH: usize = 200;
 
+/// Used as title for posts which have neither name, summary nor content.
+const PLACEHOLDER_TITLE: &str = "Untitled post";
+
+/// Text of the link to the full post, appended to truncated content.
+const READ_MORE: &str = "Read more";
+
+/// Longer categories are most likely not meant for classification, and are dropped.
+const MAX_CATEGORY_LENGTH: usize = 100;
+
+/// Maximum size of received post json which is stored for debugging, in bytes.
+const MAX_RAW_JSON_LENGTH: usize = 64 * 1024;
+
+/// Maximum size of a failed import which is stored for reprocessing, in bytes.
+const MAX_FAILED_IMPORT_LENGTH: usize = 64 * 1024;
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct ApubPost(pub(crate) Post);
 
@@ -65,6 +115,15 @@ impl From<Post> for ApubPost {
   }
 }
 
+impl ApubPost {
+  fn timestamps(&self) -> Timestamps {
+    Timestamps {
+      published: Some(self.published),
+      updated: self.updated,
+    }
+  }
+}
+
 #[async_trait::async_trait]
 impl Object for ApubPost {
   type DataType = LemmyContext;
@@ -111,45 +170,92 @@ impl Object for ApubPost {
       .await?
       .ok_or(LemmyErrorType::CouldntFindCommunity)?;
     let language = LanguageTag::new_single(self.language_id, &mut context.pool()).await?;
+    let original_language = match self.original_language_id {
+      Some(id) => LanguageTag::new_single(id, &mut context.pool()).await?,
+      None => None,
+    };
+    let pinned_comment = match self.pinned_comment_id {
+      Some(id) => Comment::read(&mut context.pool(), id)
+        .await?
+        .map(|c| c.ap_id.into()),
+      None => None,
+    };
+    let content_max_length = LocalSite::read(&mut context.pool())
+      .await
+      .ok()
+      .and_then(|l| l.federation_content_max_length);
 
-    let attachment = self
-      .url
-      .clone()
-      .map(|url| {
-        Attachment::new(
-          url.into(),
-          self.url_content_type.clone(),
-          self.alt_text.clone(),
-        )
-      })
-      .into_iter()
-      .collect();
+    // Dimensions are known for images which were uploaded locally or received with the post
+    let image_details = match &self.url {
+      Some(url) => ImageDetails::read(&mut context.pool(), url).await?,
+      None => None,
+    };
+    let attachment = self.url.clone().map(|url| {
+      Attachment::new(
+        url.into(),
+        self.url_content_type.clone(),
+        self.alt_text.clone(),
+        self.url_nsfw,
+      )
+      .with_dimensions(image_details.map(|i| (i.width, i.height)))
+    });
     let hashtag = Hashtag {
       href: self.ap_id.clone().into(),
       name: format!("#{}", &community.name),
       kind: HashtagType::Hashtag,
     };
 
-    let page = Page {
-      kind: PageType::Page,
-      id: self.ap_id.clone().into(),
-      attributed_to: AttributedTo::Lemmy(creator.actor_id.into()),
-      to: vec![community.actor_id.clone().into(), public()],
-      cc: vec![],
-      name: Some(self.name.clone()),
-      content: self.body.as_ref().map(|b| markdown_to_html(b)),
-      media_type: Some(MediaTypeMarkdownOrHtml::Html),
-      source: self.body.clone().map(Source::new),
-      attachment,
-      image: self.thumbnail_url.clone().map(ImageObject::new),
-      sensitive: Some(self.nsfw),
-      language,
-      published: Some(self.published),
-      updated: self.updated,
-      audience: Some(community.actor_id.into()),
-      in_reply_to: None,
-      tag: vec![hashtag],
+    let url = if self.local {
+      Some(Url::parse(&format!(
+        "{}/post/{}",
+        context.settings().get_protocol_and_hostname(),
+        self.id
+      ))?)
+    } else {
+      self.canonical_url.clone().map(Into::into)
     };
+
+    // Preserved HTML is sent back out as it was received, unless it needs to be truncated
+    let content = self
+      .content_html
+      .clone()
+      .filter(|html| {
+        content_max_length
+          .and_then(|l| usize::try_from(l).ok())
+          .map_or(true, |l| html.chars().count() <= l)
+      })
+      .or_else(|| {
+        self
+          .body
+          .as_ref()
+          .map(|b| federated_content(b, self.ap_id.inner(), content_max_length))
+      });
+    // Converted HTML is not the original source, so it is only sent as content
+    let source = self
+      .body
+      .clone()
+      .filter(|_| self.source_format == PostSourceFormat::Markdown)
+      .map(Source::new);
+    let page = PageBuilder::new(self.ap_id.clone().into(), creator.actor_id.into())
+      .community(community.actor_id.into())
+      .url(url)
+      .name(self.name.clone())
+      .content(content, source)
+      .attachment(attachment)
+      .image(self.thumbnail_url.clone().map(ImageObject::new))
+      .sensitive(self.nsfw)
+      .language(language)
+      .original_language(original_language)
+      .crosspost_group(self.crosspost_group.clone().map(Into::into))
+      .pinned_comment(pinned_comment)
+      .timestamps(self.published, self.updated)
+      .tag(hashtag)
+      .category(self.category.clone())
+      .distinguished(self.distinguished)
+      .original_content(self.original_content)
+      .default_comment_sort(self.default_comment_sort)
+      .close_at(self.close_at)
+      .build()?;
     Ok(page)
   }
 
@@ -159,145 +265,821 @@ impl Object for ApubPost {
     expected_domain: &Url,
     context: &Data<Self::DataType>,
   ) -> LemmyResult<()> {
-    verify_domains_match(page.id.inner(), expected_domain)?;
-    verify_is_remote_object(&page.id, context)?;
+    let res = verify_page(page, expected_domain, context).await;
+    if let Err(e) = &res {
+      log_rejected_page(page.id.inner(), e);
+      store_raw_json(page.id.inner(), Err(e), context).await;
+    }
+    res
+  }
 
-    let community = page.community(context).await?;
-    check_apub_id_valid_with_strictness(page.id.inner(), community.local, context).await?;
-    verify_person_in_community(&page.creator()?, &community, context).await?;
+  #[tracing::instrument(skip_all)]
+  async fn from_json(page: Page, context: &Data<Self::DataType>) -> LemmyResult<ApubPost> {
+    let res = page_from_json(page.clone(), context).await;
+    store_raw_json(page.id.inner(), res.as_ref(), context).await;
+    if let Err(e) = &res {
+      log_rejected_page(page.id.inner(), e);
+      if is_temporary_error(e) {
+        store_failed_import(&page, e, context).await;
+      }
+    }
+    res
+  }
+}
+
+/// Errors which are not caused by the post itself, like timeouts or unreachable servers. The import
+/// may succeed if it is attempted again later.
+pub(crate) fn is_temporary_error(error: &LemmyError) -> bool {
+  if matches!(
+    error.error_type,
+    LemmyErrorType::PictrsResponseError(_) | LemmyErrorType::InboxTimeout
+  ) {
+    return true;
+  }
+  error.inner.chain().any(|cause| {
+    if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+      e.is_timeout() || e.is_connect()
+    } else if let Some(e) = cause.downcast_ref::<std::io::Error>() {
+      matches!(
+        e.kind(),
+        ErrorKind::TimedOut | ErrorKind::ConnectionRefused | ErrorKind::ConnectionReset
+      )
+    } else {
+      matches!(
+        cause.downcast_ref::<diesel::result::Error>(),
+        Some(diesel::result::Error::DatabaseError(
+          DatabaseErrorKind::ClosedConnection,
+          _
+        ))
+      )
+    }
+  })
+}
+
+/// Keeps a post which failed to import, so that admins can reprocess it later. Very large objects
+/// are skipped.
+pub(crate) async fn store_failed_import(page: &Page, error: &LemmyError, context: &LemmyContext) {
+  let data = match serde_json::to_string(page) {
+    Ok(data) if data.len() <= MAX_FAILED_IMPORT_LENGTH => data,
+    Ok(_) => return,
+    Err(e) => {
+      warn!(
+        "Failed to serialize failed import of {}: {e}",
+        page.id.inner()
+      );
+      return;
+    }
+  };
+  let form = FailedPostImportForm {
+    ap_id: page.id.clone().into(),
+    data,
+    error: format!("{}: {}", error.error_type, error.inner),
+    updated: None,
+  };
+  if let Err(e) = FailedPostImport::upsert(&mut context.pool(), &form).await {
+    warn!("Failed to store failed import of {}: {e}", page.id.inner());
+  }
+}
+
+/// Keeps the json of a received post for debugging, if enabled by the local site. The json is
+/// stored exactly as it was received, also if the post couldn't be imported. Very large objects,
+/// and posts which weren't received in an activity, are skipped.
+async fn store_raw_json(
+  ap_id: &Url,
+  result: Result<&ApubPost, &LemmyError>,
+  context: &LemmyContext,
+) {
+  let enabled = LocalSite::read(&mut context.pool())
+    .await
+    .is_ok_and(|l| l.federation_store_raw_json);
+  if !enabled {
+    return;
+  }
+  let Some(data) = received_json(ap_id).filter(|d| d.len() <= MAX_RAW_JSON_LENGTH) else {
+    return;
+  };
+  let form = PostRawJsonForm {
+    ap_id: ap_id.clone().into(),
+    post_id: result.ok().map(|p| p.id),
+    data,
+    error: result
+      .err()
+      .map(|e| format!("{}: {}", e.error_type, e.inner)),
+    published: naive_now(),
+  };
+  if let Err(e) = PostRawJson::upsert(&mut context.pool(), &form).await {
+    warn!("Failed to store raw json of {ap_id}: {e}");
+  }
+}
 
-    let local_site_data = local_site_data_cached(&mut context.pool()).await?;
-    let slur_regex = &local_site_opt_to_slur_regex(&local_site_data.local_site);
-    check_slurs_opt(&page.name, slur_regex)?;
+/// Name of the placeholder account for posts which are attributed only to a community.
+const GROUP_POST_CREATOR_NAME: &str = "group_posts";
 
+/// Returns the placeholder account for posts which are attributed only to a community. There is one
+/// per instance, and its actor id is the fragment of the instance root url. So it can't clash with
+/// the actor id of the community or of any real user.
+async fn group_post_creator(
+  community: &ApubCommunity,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<ApubPerson> {
+  let mut actor_id = community.actor_id.inner().clone();
+  actor_id.set_path("");
+  actor_id.set_query(None);
+  actor_id.set_fragment(Some(GROUP_POST_CREATOR_NAME));
+  let form = PersonInsertForm {
+    actor_id: Some(actor_id.into()),
+    local: Some(false),
+    bot_account: Some(true),
+    last_refreshed_at: Some(naive_now()),
+    ..PersonInsertForm::new(
+      GROUP_POST_CREATOR_NAME.to_string(),
+      community.public_key.clone(),
+      community.instance_id,
+    )
+  };
+  Ok(Person::upsert(&mut context.pool(), &form).await?.into())
+}
+
+/// Emits a structured event for a rejected incoming post, so that federation failures can be
+/// grouped by origin instance.
+fn log_rejected_page(id: &Url, error: &LemmyError) {
+  warn!(
+    origin = id.host_str().unwrap_or_default(),
+    error_type = %error.error_type,
+    object_id = %id,
+    "Rejected incoming post"
+  );
+}
+
+async fn verify_page(
+  page: &Page,
+  expected_domain: &Url,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  verify_domains_match(page.id.inner(), expected_domain)?;
+  verify_is_remote_object(&page.id, context)?;
+
+  let community = page.community(context).await?;
+  check_apub_id_valid_with_strictness(page.id.inner(), community.local, context).await?;
+  let local_site_data = local_site_data_cached(&mut context.pool()).await?;
+  let is_group_post = page.is_group_post(&community);
+  if is_group_post {
+    let accept_group_posts = local_site_data
+      .local_site
+      .as_ref()
+      .is_some_and(|l| l.federation_group_posts);
+    if !accept_group_posts {
+      Err(LemmyErrorType::PageDoesNotSpecifyCreator)?
+    }
+    verify_domains_match(community.actor_id.inner(), page.id.inner())?;
+  } else {
+    verify_person_in_community(&page.creator()?, &community, context).await?;
     verify_domains_match(page.creator()?.inner(), page.id.inner())?;
-    verify_is_public(&page.to, &page.cc)?;
-    Ok(())
   }
 
-  #[tracing::instrument(skip_all)]
-  async fn from_json(page: Page, context: &Data<Self::DataType>) -> LemmyResult<ApubPost> {
-    let creator = page.creator()?.dereference(context).await?;
-    let community = page.community(context).await?;
-    if community.posting_restricted_to_mods {
-      CommunityModeratorView::is_community_moderator(&mut context.pool(), community.id, creator.id)
+  let slur_regex = &local_site_opt_to_slur_regex(&local_site_data.local_site);
+  let normalize_titles = local_site_data
+    .local_site
+    .as_ref()
+    .is_some_and(|l| l.normalize_titles);
+  if let Some(name) = &page.name {
+    check_title_slurs(name, slur_regex, normalize_titles)?;
+  }
+
+  verify_is_public(&page.to, &page.cc)?;
+  Ok(())
+}
+
+async fn page_from_json(page: Page, context: &Data<LemmyContext>) -> LemmyResult<ApubPost> {
+  let community = page.community(context).await?;
+  // Only reachable if group posts are accepted, see [verify_page]
+  let creator = if page.is_group_post(&community) {
+    group_post_creator(&community, context).await?
+  } else {
+    page.creator()?.dereference(context).await?
+  };
+  // Posts can also arrive without verification, for example when they are fetched. Users which
+  // are banned locally may still be active on their home instance.
+  if creator.banned {
+    Err(LemmyErrorType::PersonIsBannedFromSite(
+      creator.actor_id.to_string(),
+    ))?
+  }
+  if community.posting_restricted_to_mods {
+    CommunityModeratorView::is_community_moderator(&mut context.pool(), community.id, creator.id)
+      .await?;
+  }
+  let local_site = LocalSite::read(&mut context.pool()).await.ok();
+  let accepted_types = local_site
+    .as_ref()
+    .map(|l| l.federation_page_types.as_slice())
+    .unwrap_or_default();
+  if !accepted_types.is_empty() && !accepted_types.contains(&page.kind.to_string()) {
+    Err(LemmyErrorType::PageTypeNotAccepted)?
+  }
+  let future_tolerance = local_site
+    .as_ref()
+    .map(|l| Duration::seconds(l.federation_future_tolerance.into()))
+    .unwrap_or_default();
+  let timestamps = page.timestamps().clamp_future(Utc::now(), future_tolerance)?;
+  let existing: Option<ApubPost> =
+    Post::read_from_apub_id(&mut context.pool(), page.id.inner().clone())
+      .await?
+      .map(Into::into);
+  let is_new = existing.is_none();
+  let previous_version = existing.as_deref().cloned();
+  let remote_comments = page
+    .replies
+    .as_ref()
+    .and_then(|r| r.total_items)
+    .filter(|c| *c >= 0);
+  match existing {
+    // Ignore outdated versions of a post which was already received. The comment count changes
+    // without the post being edited, so it is refreshed anyway.
+    Some(existing) if !timestamps.is_newer_than(&existing.timestamps()) => {
+      if remote_comments.is_none() || remote_comments == existing.remote_comments {
+        return Ok(existing);
+      }
+      let form = PostUpdateForm {
+        remote_comments: Some(remote_comments),
+        ..Default::default()
+      };
+      let post = Post::update(&mut context.pool(), existing.id, &form).await?;
+      return Ok(post.into());
+    }
+    Some(_) => {}
+    // Only new posts are restricted, edits are allowed at any time
+    None => {
+      check_community_posting_window(&community, creator.id, &mut context.pool()).await?;
+      check_community_min_karma(&community, creator.id, &mut context.pool()).await?;
+      let published = timestamps.published.unwrap_or_else(Utc::now);
+      check_community_post_interval(&community, creator.id, published, &mut context.pool())
         .await?;
     }
-    let mut name = page
-      .name
-      .clone()
-      .or_else(|| {
-        // Posts coming from Mastodon or similar platforms don't have a title. Instead we take the
-        // first line of the content and convert it from HTML to plaintext. We also remove mentions
-        // of the community name.
-        page
-          .content
-          .as_deref()
-          .map(StringReader::new)
-          .map(|c| from_read_with_decorator(c, MAX_TITLE_LENGTH, TrivialDecorator::new()))
-          .and_then(|c| {
-            c.lines().next().map(|s| {
-              s.replace(&format!("@{}", community.name), "")
-                .trim()
-                .to_string()
-            })
-          })
-      })
-      .ok_or_else(|| anyhow!("Object must have name or content"))?;
-    if name.chars().count() > MAX_TITLE_LENGTH {
-      name = name.chars().take(MAX_TITLE_LENGTH).collect();
+  }
+  // Only mods and admins can distinguish their posts. This is checked here, because posts can also
+  // be fetched without an activity. A post stays distinguished after the creator stops being a mod,
+  // as long as the flag isn't changed.
+  if let Some(distinguished) = page.distinguished {
+    if distinguished != previous_version.as_ref().is_some_and(|p| p.distinguished) {
+      is_mod_or_admin(&mut context.pool(), &creator, community.id).await?;
     }
+  }
+  let name = derive_post_title(&page, &community.name, local_site.as_ref());
 
-    let first_attachment = page.attachment.first();
-    let local_site = LocalSite::read(&mut context.pool()).await.ok();
+  // Drop attachments whose declared media type isn't allowed by the local site. Duplicates are
+  // removed first, so a rejected attachment can't come back as a copy with a different media type.
+  let attachments: Vec<Attachment> = page
+    .unique_attachments()
+    .into_iter()
+    .filter(|a| match (a.media_type(), &local_site) {
+      (Some(media_type), Some(local_site)) => {
+        let allowed = is_media_type_allowed(media_type, local_site);
+        if !allowed {
+          info!(
+            "Dropped attachment of type {media_type} from post {}",
+            page.id
+          );
+        }
+        allowed
+      }
+      _ => true,
+    })
+    .collect();
+  let first_attachment = attachments.first();
 
-    let url = if let Some(attachment) = first_attachment.cloned() {
-      Some(attachment.url())
-    } else if page.kind == PageType::Video {
-      // we cant display videos directly, so insert a link to external video page
-      Some(page.id.inner().clone())
-    } else {
-      None
+  let url = if let Some(attachment) = first_attachment.cloned() {
+    Some(attachment.url())
+  } else if page.kind == PageType::Video {
+    // we cant display videos directly, so insert a link to external video page
+    Some(page.id.inner().clone())
+  } else {
+    None
+  };
+
+  if let Some(url) = &url {
+    check_url_scheme(url)?;
+  }
+  // Remove tracking params like for local posts, so that crossposts from different instances are
+  // linked by their url
+  let url = url.map(|u| clean_url_params(&u));
+  // Only accept a canonical link on the same domain, so it can't point to a different site
+  let canonical_url = page
+    .url
+    .filter(|u| verify_domains_match(u, page.id.inner()).is_ok() && check_url_scheme(u).is_ok());
+
+  let alt_text = first_attachment.cloned().and_then(Attachment::alt_text);
+  let url_nsfw = first_attachment.and_then(Attachment::sensitive);
+  let declared_media_type = first_attachment
+    .and_then(Attachment::media_type)
+    .map(ToString::to_string);
+  if let (Some(url), Some((width, height))) =
+    (&url, first_attachment.and_then(Attachment::dimensions))
+  {
+    let form = ImageDetailsForm {
+      link: url.clone().into(),
+      width,
+      height,
+      content_type: declared_media_type
+        .clone()
+        .unwrap_or_else(|| "image/*".to_string()),
     };
+    ImageDetails::create_federated(&mut context.pool(), &form).await?;
+  }
+  // Allows serving the attachment to clients through the image proxy
+  if let Some(url) = url
+    .as_ref()
+    .filter(|_| local_site.as_ref().is_some_and(|l| l.proxy_post_attachments))
+  {
+    RemoteImage::register(&mut context.pool(), url.clone().into()).await?;
+  }
 
-    if let Some(url) = &url {
-      check_url_scheme(url)?;
+  let slur_regex = &local_site_opt_to_slur_regex(&local_site);
+  let url_blocklist = get_url_blocklist(context).await?;
+
+  let tracker_domains = local_site
+    .as_ref()
+    .map(|l| l.tracking_pixel_domains.as_slice())
+    .unwrap_or_default();
+  let content = page
+    .content
+    .as_deref()
+    .map(|c| remove_tracking_pixels_html(c, tracker_domains));
+  let strip_patterns = local_site
+    .as_ref()
+    .map(|l| l.federation_body_strip_patterns.as_slice())
+    .unwrap_or_default();
+  let strip_regexes = build_body_strip_regexes(strip_patterns).await?;
+  let body = read_from_string_or_source_opt(&content, &page.media_type, &page.source)
+    .map(|b| remove_tracking_pixels_markdown(&b, tracker_domains))
+    .and_then(|b| strip_body_patterns(&b, &strip_regexes));
+  let source_format = body
+    .as_ref()
+    .map(|_| read_source_format(&page.media_type, &page.source));
+  let preserve_html = local_site
+    .as_ref()
+    .is_some_and(|l| l.federation_preserve_html);
+  // Avoids lossy conversion of HTML to markdown and back for posts which are only passed through.
+  // The body is converted as usual, so that clients always get markdown.
+  let content_html = content
+    .filter(|_| preserve_html && source_format == Some(PostSourceFormat::ConvertedHtml))
+    .map(|html| ammonia::clean(&remove_slurs(&html, slur_regex)));
+  if let Some(html) = &content_html {
+    markdown_check_for_blocked_urls(html, &url_blocklist)?;
+  }
+  let body = process_markdown_opt(&body, slur_regex, &url_blocklist, context).await?;
+  // Without the attachment the post would be empty
+  let dropped_all_attachments = attachments.is_empty() && !page.attachment.is_empty();
+  if dropped_all_attachments && body.as_deref().unwrap_or_default().trim().is_empty() {
+    Err(LemmyErrorType::AttachmentTypeNotAllowed)?
+  }
+  // Later changes of the lock only come from moderators, otherwise the creator could unlock the
+  // post with an edit
+  let comments_enabled = page.comments_enabled.unwrap_or_else(|| {
+    local_site
+      .as_ref()
+      .map_or(true, |l| l.federation_comments_enabled_default)
+  });
+  let locked = Some(!comments_enabled).filter(|_| is_new);
+  // Undetermined language is sent without tag. It needs to be set explicitly, otherwise edits
+  // which remove the language would be ignored.
+  let language_id = LanguageTag::to_language_id_single(page.language, &mut context.pool())
+    .await?
+    .unwrap_or(UNDETERMINED_ID);
+  // Only meaningful for translations, so it must differ from the post language
+  let original_language_id =
+    LanguageTag::to_language_id_single(page.original_language, &mut context.pool())
+      .await?
+      .filter(|id| *id != language_id && *id != UNDETERMINED_ID);
+  // Otherwise any instance could add its posts to unrelated crossposts. The group is trusted if it
+  // belongs to the instance of the post, or if it is already used for the same link.
+  let crosspost_group = match (page.crosspost_group.clone(), &url) {
+    (Some(group), _) if group.domain() == page.id.inner().domain() => Some(group.into()),
+    (Some(group), Some(url)) => {
+      let group = group.into();
+      Post::is_crosspost_group_of_url(&mut context.pool(), &url.clone().into(), &group)
+        .await?
+        .then_some(group)
     }
+    _ => None,
+  };
 
-    let alt_text = first_attachment.cloned().and_then(Attachment::alt_text);
+  // Posts in quarantined communities stay hidden until an admin approves the community. Otherwise
+  // the removed status is left untouched, so that edits don't restore removed posts.
+  let quarantined = community
+    .quarantined_until
+    .filter(|until| *until > Utc::now())
+    .map(|_| true);
 
-    let slur_regex = &local_site_opt_to_slur_regex(&local_site);
-    let url_blocklist = get_url_blocklist(context).await?;
+  let form = PostInsertForm::builder()
+    .name(name)
+    .url(url.map(Into::into))
+    .body(body)
+    .alt_text(alt_text)
+    .url_nsfw(url_nsfw)
+    .creator_id(creator.id)
+    .community_id(community.id)
+    .published(timestamps.published)
+    // Some platforms set `updated` on every post, it should only be stored for actual edits
+    .updated(timestamps.updated.filter(|_| timestamps.is_edit()))
+    .deleted(Some(false))
+    .locked(locked)
+    // Posts in NSFW communities are always NSFW, even if the sender didn't mark them
+    .nsfw(Some(community.nsfw || page.sensitive.unwrap_or_default()))
+    .ap_id(Some(page.id.clone().into()))
+    .local(Some(false))
+    .language_id(Some(language_id))
+    .original_language_id(original_language_id)
+    .crosspost_group(crosspost_group.clone())
+    .remote_views(page.views.filter(|v| *v >= 0))
+    .remote_comments(remote_comments)
+    .canonical_url(canonical_url.map(Into::into))
+    .source_format(source_format)
+    .distinguished(page.distinguished)
+    .original_content(Some(page.original_content.unwrap_or_default()))
+    .default_comment_sort(page.default_comment_sort)
+    .close_at(page.close_at)
+    .content_html(content_html.clone())
+    .category(
+      page
+        .category
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty() && c.chars().count() <= MAX_CATEGORY_LENGTH)
+        .map(ToString::to_string),
+    )
+    .removed(quarantined)
+    .quarantined(quarantined)
+    .additional_audience(Some(
+      page
+        .additional_audience()
+        .into_iter()
+        .map(|a| a.clone().into())
+        .collect(),
+    ))
+    .build();
 
-    let body = read_from_string_or_source_opt(&page.content, &page.media_type, &page.source);
-    let body = process_markdown_opt(&body, slur_regex, &url_blocklist, context).await?;
-    let language_id =
-      LanguageTag::to_language_id_single(page.language, &mut context.pool()).await?;
+  let timestamp = timestamps.latest().unwrap_or_else(naive_now);
+  let post = Post::insert_apub(&mut context.pool(), timestamp, &form).await?;
+  if let Some(previous_version) = &previous_version {
+    PostPreviousVersion::store_if_changed(&mut context.pool(), previous_version, &post).await?;
+  }
+  // The pin is cleared by leaving out the field, so it can't be part of the upsert. Only comments
+  // of the same post can be pinned.
+  let pinned_comment_id = match &page.pinned_comment {
+    Some(pinned) => pinned
+      .dereference(context)
+      .await
+      .ok()
+      .filter(|c| c.post_id == post.id)
+      .map(|c| c.id),
+    None => None,
+  };
+  // The comment sort, close time, preserved HTML and crosspost group are also cleared by leaving
+  // out the field
+  let clear_fields = (page.default_comment_sort.is_none() && post.default_comment_sort.is_some())
+    || (page.close_at.is_none() && post.close_at.is_some())
+    || (content_html.is_none() && post.content_html.is_some())
+    || (crosspost_group.is_none() && post.crosspost_group.is_some());
+  let post = if pinned_comment_id != post.pinned_comment_id || clear_fields {
+    let form = PostUpdateForm {
+      pinned_comment_id: Some(pinned_comment_id),
+      default_comment_sort: Some(page.default_comment_sort),
+      close_at: Some(page.close_at),
+      content_html: Some(content_html),
+      crosspost_group: Some(crosspost_group),
+      ..Default::default()
+    };
+    Post::update(&mut context.pool(), post.id, &form).await?
+  } else {
+    post
+  };
+  let normalize_titles = local_site.as_ref().is_some_and(|l| l.normalize_titles);
+  let post = check_community_slurs(post, &community, normalize_titles, &mut context.pool()).await?;
+  let post = check_community_nsfw(post, &community, &mut context.pool()).await?;
+  // Otherwise every edit of a duplicate would be reported again
+  let post = if is_new {
+    check_community_duplicate_title(post, &community, normalize_titles, &mut context.pool()).await?
+  } else {
+    post
+  };
+  let post_ = post.clone();
+  let context_ = context.reset_request_count();
+  let has_attachment = first_attachment.is_some();
+  let local_site_ = local_site.clone();
 
-    let form = PostInsertForm::builder()
-      .name(name)
-      .url(url.map(Into::into))
-      .body(body)
-      .alt_text(alt_text)
-      .creator_id(creator.id)
-      .community_id(community.id)
-      .published(page.published.map(Into::into))
-      .updated(page.updated.map(Into::into))
-      .deleted(Some(false))
-      .nsfw(page.sensitive)
-      .ap_id(Some(page.id.clone().into()))
-      .local(Some(false))
-      .language_id(language_id)
-      .build();
+  // Generates a post thumbnail in background task, because some sites can be very slow to
+  // respond.
+  spawn_try_task(async move {
+    let post_id = post_.id;
+    generate_post_link_metadata(
+      post_,
+      None,
+      |_| None,
+      local_site,
+      context_.reset_request_count(),
+    )
+    .await?;
 
-    let timestamp = page.updated.or(page.published).unwrap_or_else(naive_now);
-    let post = Post::insert_apub(&mut context.pool(), timestamp, &form).await?;
-    let post_ = post.clone();
-    let context_ = context.reset_request_count();
+    // Drop attachments whose declared media type doesn't match the actual file. Attachments
+    // without declared media type can only be checked against the allowed types after fetching
+    // them.
+    let actual = Post::read(&mut context_.pool(), post_id)
+      .await?
+      .and_then(|p| p.url_content_type);
+    if let (true, Some(actual)) = (has_attachment, actual) {
+      let mismatch = declared_media_type
+        .as_deref()
+        .is_some_and(|declared| is_media_type_mismatch(declared, &actual));
+      let disallowed = local_site_
+        .as_ref()
+        .is_some_and(|l| !is_media_type_allowed(&actual, l));
+      if mismatch || disallowed {
+        info!("Dropped attachment of type {actual} from post {post_id:?}");
+        let form = PostUpdateForm {
+          url: Some(None),
+          url_content_type: Some(None),
+          thumbnail_url: Some(None),
+          ..Default::default()
+        };
+        Post::update(&mut context_.pool(), post_id, &form).await?;
+      }
+    }
+    Ok(())
+  });
 
-    // Generates a post thumbnail in background task, because some sites can be very slow to
-    // respond.
+  // Only the top level of the thread is fetched, deeper levels are fetched when a client expands
+  // them
+  if let (true, Some(replies)) = (is_new, page.replies) {
+    let ap_id = post.ap_id.clone();
+    let context_ = context.reset_request_count();
     spawn_try_task(async move {
-      generate_post_link_metadata(post_, None, |_| None, local_site, context_).await
+      fetch_replies(replies, ap_id.inner(), MAX_FETCHED_REPLIES, &context_).await?;
+      Ok(())
     });
+  }
 
-    Ok(post.into())
+  Ok(post.into())
+}
+
+/// Converts the post body to HTML for federation. Some platforms reject posts with very long
+/// content, so if the local site sets a maximum length, longer bodies are truncated and a link to
+/// the full post is appended. The complete markdown is still sent as `source`.
+fn federated_content(body: &str, ap_id: &Url, max_length: Option<i32>) -> String {
+  let max_length = max_length.and_then(|l| usize::try_from(l).ok());
+  match max_length {
+    Some(max_length) if body.chars().count() > max_length => {
+      let truncated: String = body.chars().take(max_length).collect();
+      let content = markdown_to_html(&format!("{}…", truncated.trim_end()));
+      format!("{content}<p><a href=\"{ap_id}\">{READ_MORE}</a></p>\n")
+    }
+    _ => markdown_to_html(body),
   }
 }
 
+/// Derives the title for a post. Posts coming from Mastodon or similar platforms don't have a
+/// `name`, so depending on local site settings we use the `summary`, or the first line of the
+/// content converted from HTML to plaintext (with mentions of the community name removed). If
+/// none of these are available, a placeholder is used.
+pub(crate) fn derive_post_title(
+  page: &Page,
+  community_name: &str,
+  local_site: Option<&LocalSite>,
+) -> String {
+  let (from_summary, content_length) = local_site
+    .map(|l| {
+      (
+        l.post_title_from_summary,
+        usize::try_from(l.post_title_content_length).unwrap_or(MAX_TITLE_LENGTH),
+      )
+    })
+    .unwrap_or((true, MAX_TITLE_LENGTH));
+  let content_length = content_length.clamp(1, MAX_TITLE_LENGTH);
+
+  let first_line = |text: &str| {
+    text
+      .lines()
+      .next()
+      .map(|s| {
+        s.replace(&format!("@{community_name}"), "")
+          .trim()
+          .to_string()
+      })
+      .filter(|s| !s.is_empty())
+  };
+  let summary = || {
+    page
+      .summary
+      .as_deref()
+      .filter(|_| from_summary)
+      .and_then(first_line)
+  };
+  let content = || {
+    page
+      .content
+      .as_deref()
+      .map(StringReader::new)
+      .map(|c| from_read_with_decorator(c, MAX_TITLE_LENGTH, TrivialDecorator::new()))
+      .and_then(|c| first_line(&c))
+      .map(|c| c.chars().take(content_length).collect())
+  };
+
+  let name = page
+    .localized_name()
+    .filter(|n| !n.trim().is_empty())
+    .map(ToString::to_string)
+    .or_else(summary)
+    .or_else(content)
+    .unwrap_or_else(|| PLACEHOLDER_TITLE.to_string());
+  name.chars().take(MAX_TITLE_LENGTH).collect()
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
   use crate::{
+    activities::community::announce::is_removed_post,
+    activity_lists::AnnouncableActivities,
+    fetcher::user_or_community::UserOrCommunity,
+    http::with_received_body,
     objects::{
       community::tests::parse_lemmy_community,
       person::{tests::parse_lemmy_person, ApubPerson},
     },
-    protocol::tests::file_to_json_object,
+    protocol::{
+      activities::{
+        community::announce::RawAnnouncableActivities,
+        create_or_update::page::CreateOrUpdatePage,
+      },
+      objects::page::Replies,
+      tests::file_to_json_object,
+    },
   };
-  use lemmy_db_schema::source::site::Site;
+  use activitypub_federation::{
+    fetch::object_id::ObjectId,
+    protocol::values::MediaTypeMarkdownOrHtml,
+  };
+  use chrono::{Days, Timelike, Utc};
+  use lemmy_db_schema::{
+    source::{
+      community::{CommunityInsertForm, CommunityUpdateForm},
+      images::{LocalImage, LocalImageForm},
+      instance::Instance,
+      language::Language,
+      local_site::{LocalSiteInsertForm, LocalSiteUpdateForm},
+      person::{PersonInsertForm, PersonUpdateForm},
+      site::Site,
+    },
+    utils::diesel_url_create,
+    CommentSortType,
+    DuplicateTitleAction,
+  };
+  use lemmy_db_views::{
+    post_view::PostQuery,
+    structs::{PostReportView, PostView},
+  };
+  use lemmy_api_common::utils::proxy_post_attachments;
+  use lemmy_db_views_moderator::structs::{ModRemovePostView, ModlogListParams};
+  use lemmy_utils::CACHE_DURATION_API;
   use pretty_assertions::assert_eq;
   use serial_test::serial;
+  use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+  };
+  use tokio::time::sleep;
+  use tracing::{
+    field::{Field, Visit},
+    Event,
+    Subscriber,
+  };
+  use tracing_subscriber::{layer::Context, prelude::*, Layer};
+
+  /// Collects the fields of all tracing events.
+  #[derive(Clone, Default)]
+  struct CapturedEvents(Arc<Mutex<Vec<BTreeMap<String, String>>>>);
+
+  impl<S: Subscriber> Layer<S> for CapturedEvents {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+      let mut fields = BTreeMap::new();
+      event.record(&mut FieldVisitor(&mut fields));
+      if let Ok(mut events) = self.0.lock() {
+        events.push(fields);
+      }
+    }
+  }
+
+  struct FieldVisitor<'a>(&'a mut BTreeMap<String, String>);
+
+  impl Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+      self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+      self
+        .0
+        .insert(field.name().to_string(), format!("{value:?}"));
+    }
+  }
 
   #[tokio::test]
   #[serial]
-  async fn test_parse_lemmy_post() -> LemmyResult<()> {
+  async fn test_post_in_nsfw_community() -> LemmyResult<()> {
     let context = LemmyContext::init_test_context().await;
     let (person, site) = parse_lemmy_person(&context).await?;
     let community = parse_lemmy_community(&context).await?;
+    let form = CommunityUpdateForm {
+      nsfw: Some(true),
+      ..Default::default()
+    };
+    Community::update(&mut context.pool(), community.id, &form).await?;
 
-    let json = file_to_json_object("assets/lemmy/objects/page.json")?;
-    let url = Url::parse("https://enterprise.lemmy.ml/post/55143")?;
-    ApubPost::verify(&json, &url, &context).await?;
+    let json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    assert_eq!(json.sensitive, Some(false));
     let post = ApubPost::from_json(json, &context).await?;
+    assert!(post.nsfw);
 
-    assert_eq!(post.ap_id, url.into());
-    assert_eq!(post.name, "Post title");
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_page_type_not_accepted() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+    let form = LocalSiteInsertForm::builder()
+      .site_id(site.id)
+      .federation_page_types(Some(vec!["Page".to_string(), "Article".to_string()]))
+      .build();
+    LocalSite::create(&mut context.pool(), &form).await?;
+    sleep(CACHE_DURATION_API).await;
+
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    json.kind = PageType::Video;
+    let res = ApubPost::from_json(json.clone(), &context).await;
+    assert!(res.is_err_and(|e| e.error_type == LemmyErrorType::PageTypeNotAccepted));
+
+    json.kind = PageType::Article;
+    let post = ApubPost::from_json(json, &context).await?;
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_attachment_type_not_allowed() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+    let form = LocalSiteInsertForm::builder()
+      .site_id(site.id)
+      .attachment_allowed_types(Some(vec!["image/*".to_string()]))
+      .build();
+    LocalSite::create(&mut context.pool(), &form).await?;
+    // wait for the cached local site to expire
+    sleep(CACHE_DURATION_API).await;
+
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    json.attachment = vec![Attachment::new(
+      Url::parse("https://example.com/video.mp4")?,
+      Some("video/mp4".to_string()),
+      None,
+      None,
+    )];
+
+    // Without the video there is nothing left to show
+    let mut empty = json.clone();
+    empty.content = None;
+    empty.source = None;
+    let res = ApubPost::from_json(empty, &context).await;
+    assert!(res.is_err_and(|e| e.error_type == LemmyErrorType::AttachmentTypeNotAllowed));
+
+    // The post is still stored with its text
+    let post = ApubPost::from_json(json.clone(), &context).await?;
+    assert_eq!(post.url, None);
     assert!(post.body.is_some());
-    assert_eq!(post.body.as_ref().map(std::string::String::len), Some(45));
-    assert!(!post.locked);
-    assert!(!post.featured_community);
-    assert_eq!(context.request_count(), 0);
+
+    // Links are not filtered
+    let link = Url::parse("https://example.com/article.html")?;
+    json.attachment = vec![Attachment::new(
+      link.clone(),
+      Some("text/html".to_string()),
+      None,
+      None,
+    )];
+    let post = ApubPost::from_json(json, &context).await?;
+    assert_eq!(post.url, Some(link.into()));
 
     Post::delete(&mut context.pool(), post.id).await?;
     Person::delete(&mut context.pool(), person.id).await?;
@@ -308,21 +1090,1480 @@ mod tests {
 
   #[tokio::test]
   #[serial]
-  async fn test_convert_mastodon_post_title() -> LemmyResult<()> {
+  async fn test_nsfw_post_in_sfw_community() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+    let form = CommunityUpdateForm {
+      allow_nsfw: Some(false),
+      ..Default::default()
+    };
+    Community::update(&mut context.pool(), community.id, &form).await?;
+
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    let post = ApubPost::from_json(json.clone(), &context).await?;
+    assert!(!post.removed);
+
+    // Edits which mark the post as sensitive cause removal
+    json.sensitive = Some(true);
+    json.updated = Some(naive_now());
+    let post = ApubPost::from_json(json.clone(), &context).await?;
+    assert!(post.nsfw);
+    assert!(post.removed);
+
+    // The removal is logged without moderator
+    let params = ModlogListParams {
+      community_id: None,
+      mod_person_id: None,
+      other_person_id: None,
+      post_id: Some(post.id),
+      comment_id: None,
+      page: None,
+      limit: None,
+      hide_modlog_names: false,
+    };
+    let modlog = ModRemovePostView::list(&mut context.pool(), params).await?;
+    assert_eq!(1, modlog.len());
+    let entry = modlog.first().ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert_eq!(None, entry.mod_remove_post.mod_person_id);
+    assert!(entry.mod_remove_post.removed);
+
+    // The removed post is not announced to the followers of the community
+    let mut update: CreateOrUpdatePage =
+      file_to_json_object("assets/lemmy/activities/create_or_update/update_page.json")?;
+    update.object = json;
+    let activity = AnnouncableActivities::CreateOrUpdatePost(update);
+    assert!(is_removed_post(&activity, &context).await);
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_duplicate_title_reported() -> LemmyResult<()> {
     let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
     let community = parse_lemmy_community(&context).await?;
+    let form = CommunityUpdateForm {
+      duplicate_title_hours: Some(24),
+      duplicate_title_action: Some(DuplicateTitleAction::Report),
+      ..Default::default()
+    };
+    // The community has no mods, the report is still created
+    let community = Community::update(&mut context.pool(), community.id, &form).await?;
 
-    let json = file_to_json_object("assets/mastodon/objects/person.json")?;
-    let person = ApubPerson::from_json(json, &context).await?;
+    let json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    let form = PostInsertForm::builder()
+      .name(format!(
+        "  {}  ",
+        json.name.clone().unwrap_or_default().to_uppercase()
+      ))
+      .creator_id(person.id)
+      .community_id(community.id)
+      .published(json.published.map(|p| p - chrono::Duration::hours(1)))
+      .build();
+    let original = Post::create(&mut context.pool(), &form).await?;
 
-    let json = file_to_json_object("assets/mastodon/objects/page.json")?;
     let post = ApubPost::from_json(json, &context).await?;
+    assert!(!post.removed);
+    let reports =
+      PostReportView::get_report_count(&mut context.pool(), person.id, true, Some(community.id))
+        .await?;
+    assert_eq!(1, reports);
 
-    assert_eq!(post.name, "Variable never resetting at refresh");
+    Post::delete(&mut context.pool(), post.id).await?;
+    Post::delete(&mut context.pool(), original.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_post_in_quarantined_community() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let form = LocalSiteInsertForm::builder()
+      .site_id(site.id)
+      .federation_community_quarantine_days(Some(7))
+      .build();
+    LocalSite::create(&mut context.pool(), &form).await?;
+    sleep(CACHE_DURATION_API).await;
+
+    // Newly federated communities are quarantined
+    let community = parse_lemmy_community(&context).await?;
+    assert!(community.quarantined_until.is_some_and(|t| t > Utc::now()));
+
+    let json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    let post = ApubPost::from_json(json.clone(), &context).await?;
+    assert!(post.removed);
+    assert!(post.quarantined);
+    // Posts which were removed for other reasons aren't restored
+    let form = PostInsertForm::builder()
+      .name("removed post".to_string())
+      .creator_id(person.id)
+      .community_id(community.id)
+      .removed(Some(true))
+      .build();
+    let removed_post = Post::create(&mut context.pool(), &form).await?;
+
+    // Approving the quarantine restores posts, later posts aren't removed anymore
+    let form = CommunityUpdateForm {
+      quarantined_until: Some(None),
+      ..Default::default()
+    };
+    Community::update(&mut context.pool(), community.id, &form).await?;
+    assert_eq!(
+      1,
+      Post::restore_quarantined(&mut context.pool(), community.id).await?
+    );
+    let post = ApubPost::from_json(json, &context).await?;
+    assert!(!post.removed);
+    assert!(!post.quarantined);
+    let removed_post = Post::read(&mut context.pool(), removed_post.id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert!(removed_post.removed);
 
+    Post::delete(&mut context.pool(), removed_post.id).await?;
     Post::delete(&mut context.pool(), post.id).await?;
     Person::delete(&mut context.pool(), person.id).await?;
     Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    // Don't quarantine communities of the following tests
+    sleep(CACHE_DURATION_API).await;
     Ok(())
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_remove_tracking_pixels() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let form = LocalSiteInsertForm::builder()
+      .site_id(site.id)
+      .tracking_pixel_domains(Some(vec!["tracker.tld".to_string()]))
+      .build();
+    LocalSite::create(&mut context.pool(), &form).await?;
+    sleep(CACHE_DURATION_API).await;
+    let community = parse_lemmy_community(&context).await?;
+
+    // Tiny images and images from tracker domains are removed from html
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    json.source = None;
+    json.media_type = Some(MediaTypeMarkdownOrHtml::Html);
+    json.content = Some(
+      r#"<p>Hello</p><img src="https://example.com/cat.png" alt="cat"><img src="https://example.com/pixel.gif" width="1" height="1"><img src='https://cdn.tracker.tld/open.png'>"#
+        .to_string(),
+    );
+    let post = ApubPost::from_json(json.clone(), &context).await?;
+    let body = post.body.clone().unwrap_or_default();
+    assert!(body.contains("https://example.com/cat.png"));
+    assert!(!body.contains("pixel.gif"));
+    assert!(!body.contains("tracker.tld"));
+
+    // Markdown is only checked for tracker domains
+    json.source = Some(Source::new(
+      "Hello ![](https://tracker.tld/open.png) ![cat](https://example.com/cat.png)".to_string(),
+    ));
+    json.updated = Some(Utc::now());
+    let post = ApubPost::from_json(json, &context).await?;
+    assert_eq!(
+      Some("Hello  ![cat](https://example.com/cat.png)".to_string()),
+      post.body
+    );
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    sleep(CACHE_DURATION_API).await;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_strip_body_patterns() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let form = LocalSiteInsertForm::builder()
+      .site_id(site.id)
+      .federation_body_strip_patterns(Some(vec![
+        "^Sent from my .*$".to_string(),
+        r"^-- \n(?s:.*)".to_string(),
+      ]))
+      .build();
+    LocalSite::create(&mut context.pool(), &form).await?;
+    sleep(CACHE_DURATION_API).await;
+    let community = parse_lemmy_community(&context).await?;
+
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    json.source = Some(Source::new(
+      "Meaningful body\n\nSent from my phone\n\n-- \nMy signature\nhttps://example.com".to_string(),
+    ));
+    let post = ApubPost::from_json(json.clone(), &context).await?;
+    assert_eq!(Some("Meaningful body".to_string()), post.body);
+
+    // A body which consists only of stripped text is removed entirely
+    json.source = Some(Source::new("Sent from my phone".to_string()));
+    json.updated = Some(naive_now());
+    let post = ApubPost::from_json(json, &context).await?;
+    assert_eq!(None, post.body);
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    sleep(CACHE_DURATION_API).await;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_source_format() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+
+    // Lemmy sends the markdown source
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    let post = ApubPost::from_json(json.clone(), &context).await?;
+    assert_eq!(PostSourceFormat::Markdown, post.source_format);
+    let page = post.into_json(&context).await?;
+    assert!(page.source.is_some());
+
+    // Content which is only available as HTML gets flagged as converted
+    json.source = None;
+    json.updated = Some(naive_now());
+    let post = ApubPost::from_json(json.clone(), &context).await?;
+    assert_eq!(PostSourceFormat::ConvertedHtml, post.source_format);
+    let post_view = PostView::read(&mut context.pool(), post.id, None, false)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert_eq!(
+      PostSourceFormat::ConvertedHtml,
+      post_view.post.source_format
+    );
+    let page = post.clone().into_json(&context).await?;
+    assert!(page.source.is_none());
+
+    // Markdown in the content field, as sent by Peertube
+    json.media_type = Some(MediaTypeMarkdownOrHtml::Markdown);
+    json.updated = Some(naive_now());
+    let post = ApubPost::from_json(json, &context).await?;
+    assert_eq!(PostSourceFormat::Markdown, post.source_format);
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_preserve_html() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+    let form = LocalSiteInsertForm::builder()
+      .site_id(site.id)
+      .federation_preserve_html(Some(true))
+      .build();
+    LocalSite::create(&mut context.pool(), &form).await?;
+    sleep(CACHE_DURATION_API).await;
+
+    // Markup without markdown equivalent would get lost when converting. Unsafe markup is removed.
+    let html = "<p>Some html with <sup>markup</sup></p>";
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    json.source = None;
+    json.content = Some(format!("{html}<script>alert('xss')</script>"));
+    let post = ApubPost::from_json(json.clone(), &context).await?;
+    assert_eq!(PostSourceFormat::ConvertedHtml, post.source_format);
+    assert_eq!(Some(html.to_string()), post.content_html);
+    let body = post.body.clone().unwrap_or_default();
+    assert!(body.contains("Some html"));
+
+    // The stored HTML is federated back out unchanged
+    let page = post.clone().into_json(&context).await?;
+    assert_eq!(Some(html.to_string()), page.content);
+    assert!(page.source.is_none());
+
+    // Unless it is too long, then the body is truncated as usual
+    let form = LocalSiteUpdateForm {
+      federation_content_max_length: Some(Some(5)),
+      ..Default::default()
+    };
+    LocalSite::update(&mut context.pool(), &form).await?;
+    sleep(CACHE_DURATION_API).await;
+    let page = post.clone().into_json(&context).await?;
+    assert!(page.content.is_some_and(|c| c.contains(READ_MORE)));
+
+    // Posts with markdown source are handled as usual
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    json.updated = Some(naive_now());
+    let post = ApubPost::from_json(json, &context).await?;
+    assert_eq!(PostSourceFormat::Markdown, post.source_format);
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    sleep(CACHE_DURATION_API).await;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_comments_enabled_default() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+    let form = LocalSiteInsertForm::builder()
+      .site_id(site.id)
+      .federation_comments_enabled_default(Some(false))
+      .build();
+    LocalSite::create(&mut context.pool(), &form).await?;
+    sleep(CACHE_DURATION_API).await;
+
+    // Without the field the post is locked
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    assert_eq!(None, json.comments_enabled);
+    let post = ApubPost::from_json(json.clone(), &context).await?;
+    assert!(post.locked);
+    Post::delete(&mut context.pool(), post.id).await?;
+
+    // An explicit value is always used
+    json.comments_enabled = Some(true);
+    let post = ApubPost::from_json(json, &context).await?;
+    assert!(!post.locked);
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    sleep(CACHE_DURATION_API).await;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_creator_banned_locally() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+    let form = PersonUpdateForm {
+      banned: Some(true),
+      ..Default::default()
+    };
+    Person::update(&mut context.pool(), person.id, &form).await?;
+
+    // The remote user is only banned here, so the page itself is valid
+    let json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    let res = ApubPost::from_json(json.clone(), &context).await;
+    assert!(res.is_err_and(|e| matches!(e.error_type, LemmyErrorType::PersonIsBannedFromSite(_))));
+    let post = Post::read_from_apub_id(&mut context.pool(), json.id.inner().clone()).await?;
+    assert!(post.is_none());
+
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_language_edit() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    let post = ApubPost::from_json(json.clone(), &context).await?;
+    let french = Language::read_id_from_code(&mut context.pool(), Some("fr")).await?;
+    assert_eq!(Some(post.language_id), french);
+
+    // Changed language is federated with the edit
+    let german = Language::read_id_from_code(&mut context.pool(), Some("de"))
+      .await?
+      .ok_or(LemmyErrorType::LanguageNotAllowed)?;
+    let form = PostUpdateForm {
+      language_id: Some(german),
+      updated: Some(Some(naive_now())),
+      ..Default::default()
+    };
+    let post: ApubPost = Post::update(&mut context.pool(), post.id, &form)
+      .await?
+      .into();
+    let page = post.clone().into_json(&context).await?;
+    assert_eq!(
+      Some("de".to_string()),
+      page.language.as_ref().map(|l| l.identifier.clone())
+    );
+
+    // Remote edits which remove the language are applied
+    json.language = None;
+    json.updated = Some(naive_now());
+    let post = ApubPost::from_json(json, &context).await?;
+    assert_eq!(UNDETERMINED_ID, post.language_id);
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_original_language() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+    let german = Language::read_id_from_code(&mut context.pool(), Some("de")).await?;
+
+    // The post is in french, translated from german
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    json.original_language = Some(LanguageTag {
+      identifier: "de".to_string(),
+      name: "Deutsch".to_string(),
+    });
+    let post = ApubPost::from_json(json, &context).await?;
+    assert_eq!(german, post.original_language_id);
+
+    let page = post.clone().into_json(&context).await?;
+    assert_eq!(
+      Some("de".to_string()),
+      page.original_language.as_ref().map(|l| l.identifier.clone())
+    );
+    let serialized = serde_json::to_value(&page)?;
+    assert_eq!(
+      Some("de"),
+      serialized["originalLanguage"]["identifier"].as_str()
+    );
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  /// Page from the test community which is attributed only to the community itself.
+  fn group_page(community: &ApubCommunity) -> LemmyResult<Page> {
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    json.attributed_to = serde_json::from_value(serde_json::json!({
+      "type": "Collection",
+      "items": [{"type": "Group", "id": community.actor_id}]
+    }))?;
+    Ok(json)
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_group_post_rejected() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+
+    let json = group_page(&community)?;
+    let url = json.id.inner().clone();
+    let res = ApubPost::verify(&json, &url, &context).await;
+    assert!(res.is_err_and(|e| e.error_type == LemmyErrorType::PageDoesNotSpecifyCreator));
+
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_group_post_accepted() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+    let form = LocalSiteInsertForm::builder()
+      .site_id(site.id)
+      .federation_group_posts(Some(true))
+      .build();
+    LocalSite::create(&mut context.pool(), &form).await?;
+    sleep(CACHE_DURATION_API).await;
+
+    let json = group_page(&community)?;
+    let url = json.id.inner().clone();
+    ApubPost::verify(&json, &url, &context).await?;
+    let post = ApubPost::from_json(json, &context).await?;
+
+    // The post is attributed to a placeholder account of the community instance, which doesn't
+    // take over the actor id of the community
+    let creator = Person::read(&mut context.pool(), post.creator_id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPerson)?;
+    assert_ne!(community.actor_id, creator.actor_id);
+    assert_eq!(
+      community.actor_id.inner().domain(),
+      creator.actor_id.inner().domain()
+    );
+    assert_eq!(community.instance_id, creator.instance_id);
+    assert!(creator.bot_account);
+    assert!(!creator.local);
+    let actor = UserOrCommunity::read_from_id(community.actor_id.inner().clone(), &context).await?;
+    assert!(matches!(actor, Some(UserOrCommunity::Community(_))));
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), creator.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_crosspost_group() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+    let group = Url::parse("https://enterprise.lemmy.ml/post/1")?;
+
+    // Two posts with different links, which belong to the same crosspost group of their instance
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    json.crosspost_group = Some(group.clone());
+    json.attachment = vec![Attachment::new(
+      Url::parse("https://example.com/article")?,
+      None,
+      None,
+      None,
+    )];
+    let post = ApubPost::from_json(json.clone(), &context).await?;
+    json.id = ObjectId::parse("https://enterprise.lemmy.ml/post/55144")?;
+    json.attachment = vec![Attachment::new(
+      Url::parse("https://example.com/article_mirror")?,
+      None,
+      None,
+      None,
+    )];
+    let other_post = ApubPost::from_json(json.clone(), &context).await?;
+    assert_eq!(Some(group.clone().into()), post.crosspost_group);
+    assert_eq!(post.crosspost_group, other_post.crosspost_group);
+
+    // Another instance can only use the group for the same link
+    json.id = ObjectId::parse("https://lemmy.example/post/1")?;
+    json.attachment = vec![Attachment::new(
+      Url::parse("https://example.com/article")?,
+      None,
+      None,
+      None,
+    )];
+    let same_link = ApubPost::from_json(json.clone(), &context).await?;
+    assert_eq!(post.crosspost_group, same_link.crosspost_group);
+    json.id = ObjectId::parse("https://lemmy.example/post/2")?;
+    json.attachment = vec![Attachment::new(
+      Url::parse("https://example.com/unrelated")?,
+      None,
+      None,
+      None,
+    )];
+    let unrelated = ApubPost::from_json(json, &context).await?;
+    assert_eq!(None, unrelated.crosspost_group);
+
+    // Both are linked as crossposts
+    let cross_posts = PostQuery {
+      crosspost_group: Some(group.clone().into()),
+      ..Default::default()
+    }
+    .list(&site, &mut context.pool())
+    .await?;
+    assert_eq!(cross_posts.len(), 3);
+    assert!(cross_posts.iter().any(|p| p.post.id == post.id));
+    assert!(cross_posts.iter().any(|p| p.post.id == other_post.id));
+    assert!(cross_posts.iter().any(|p| p.post.id == same_link.id));
+
+    // And the group is sent along
+    let page = post.clone().into_json(&context).await?;
+    assert_eq!(Some(group), page.crosspost_group);
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Post::delete(&mut context.pool(), other_post.id).await?;
+    Post::delete(&mut context.pool(), same_link.id).await?;
+    Post::delete(&mut context.pool(), unrelated.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_duplicate_attachments() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+
+    let url = Url::parse("https://enterprise.lemmy.ml/pictrs/image/eOtYb9iEiB.png")?;
+    let other_url = Url::parse("https://enterprise.lemmy.ml/pictrs/image/other.png")?;
+    let mut duplicate_url = url.clone();
+    duplicate_url.set_fragment(Some("copy"));
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    json.attachment = [&url, &duplicate_url, &other_url, &url]
+      .into_iter()
+      .map(|u| Attachment::new(u.clone(), Some("image/png".to_string()), None, None))
+      .collect();
+
+    // Only the first occurrence of each url is kept, in the original order
+    let unique: Vec<Url> = json
+      .unique_attachments()
+      .into_iter()
+      .map(Attachment::url)
+      .collect();
+    assert_eq!(vec![url.clone(), other_url], unique);
+
+    let post = ApubPost::from_json(json, &context).await?;
+    assert_eq!(Some(url.into()), post.url);
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_alt_text_edit() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+
+    let url = Url::parse("https://enterprise.lemmy.ml/pictrs/image/eOtYb9iEiB.png")?;
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    json.attachment = vec![Attachment::new(
+      url.clone(),
+      Some("image/png".to_string()),
+      Some("old alt text".to_string()),
+      None,
+    )];
+    let post = ApubPost::from_json(json.clone(), &context).await?;
+    assert_eq!(Some("old alt text".to_string()), post.alt_text);
+
+    // An edit which only changes the alt text is applied, and leaves the rest of the post as is
+    json.attachment = vec![Attachment::new(
+      url,
+      Some("image/png".to_string()),
+      Some("new alt text".to_string()),
+      None,
+    )];
+    json.updated = Some(naive_now());
+    let edited = ApubPost::from_json(json, &context).await?;
+    assert_eq!(post.id, edited.id);
+    assert_eq!(Some("new alt text".to_string()), edited.alt_text);
+    assert_eq!(post.name, edited.name);
+    assert_eq!(post.body, edited.body);
+    assert_eq!(post.url, edited.url);
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_outdated_update() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    let published = json.published.ok_or(LemmyErrorType::CouldntFindPost)?;
+    json.updated = Some(published);
+    let post = ApubPost::from_json(json.clone(), &context).await?;
+    // Not an actual edit
+    assert_eq!(post.updated, None);
+
+    // Edits are applied
+    let edit_time = published + Days::new(2);
+    json.name = Some("Edited title".to_string());
+    json.updated = Some(edit_time);
+    let post = ApubPost::from_json(json.clone(), &context).await?;
+    assert_eq!(post.name, "Edited title");
+    assert_eq!(post.updated, Some(edit_time));
+
+    // An older version received later is ignored
+    json.name = Some("Outdated title".to_string());
+    json.updated = Some(published + Days::new(1));
+    let post = ApubPost::from_json(json, &context).await?;
+    assert_eq!(post.name, "Edited title");
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_posting_window() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+
+    // Posts outside of the posting window are rejected
+    let hour = i16::try_from(Utc::now().hour())?;
+    let form = CommunityUpdateForm {
+      posting_window_start: Some((hour + 1) % 24),
+      posting_window_end: Some((hour + 2) % 24),
+      ..Default::default()
+    };
+    Community::update(&mut context.pool(), community.id, &form).await?;
+    let json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    let res = ApubPost::from_json(json.clone(), &context).await;
+    assert!(res.is_err_and(|e| e.error_type == LemmyErrorType::PostingClosed));
+
+    // Inside the window they are accepted
+    let form = CommunityUpdateForm {
+      posting_window_start: Some(hour),
+      posting_window_end: Some((hour + 1) % 24),
+      ..Default::default()
+    };
+    Community::update(&mut context.pool(), community.id, &form).await?;
+    let post = ApubPost::from_json(json, &context).await?;
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_original_content() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+
+    // Pages without the flag are not original content
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    assert_eq!(None, json.original_content);
+    let post = ApubPost::from_json(json.clone(), &context).await?;
+    assert!(!post.original_content);
+    Post::delete(&mut context.pool(), post.id).await?;
+
+    json.original_content = Some(true);
+    let post = ApubPost::from_json(json, &context).await?;
+    assert!(post.original_content);
+
+    // The flag survives a round trip through the serialized page
+    let page = post.clone().into_json(&context).await?;
+    let serialized = serde_json::to_value(&page)?;
+    assert_eq!(
+      Some(true),
+      serialized
+        .get("originalContent")
+        .and_then(serde_json::Value::as_bool)
+    );
+    let page: Page = serde_json::from_value(serialized)?;
+    assert_eq!(Some(true), page.original_content);
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_default_comment_sort() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    json.default_comment_sort = Some(CommentSortType::New);
+    let post = ApubPost::from_json(json.clone(), &context).await?;
+    assert_eq!(Some(CommentSortType::New), post.default_comment_sort);
+
+    // The sort survives a round trip through the serialized page
+    let page = post.clone().into_json(&context).await?;
+    let mut serialized = serde_json::to_value(&page)?;
+    assert_eq!(
+      Some("New"),
+      serialized
+        .get("defaultCommentSort")
+        .and_then(serde_json::Value::as_str)
+    );
+    let page: Page = serde_json::from_value(serialized.clone())?;
+    assert_eq!(Some(CommentSortType::New), page.default_comment_sort);
+
+    // Unknown sorts are ignored
+    if let Some(sort) = serialized.get_mut("defaultCommentSort") {
+      *sort = serde_json::json!("Random");
+    }
+    let page: Page = serde_json::from_value(serialized)?;
+    assert_eq!(None, page.default_comment_sort);
+
+    // An edit without the field clears the sort
+    json.default_comment_sort = None;
+    json.updated = Some(Utc::now());
+    let post = ApubPost::from_json(json, &context).await?;
+    assert_eq!(None, post.default_comment_sort);
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_category() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    json.category = Some(" Science/Astronomy ".to_string());
+    let post = ApubPost::from_json(json, &context).await?;
+    assert_eq!(Some("Science/Astronomy".to_string()), post.category);
+
+    // Unknown categories are federated unchanged
+    let page = post.clone().into_json(&context).await?;
+    assert_eq!(Some("Science/Astronomy".to_string()), page.category);
+    let serialized = serde_json::to_value(&page)?;
+    assert_eq!(
+      Some("Science/Astronomy"),
+      serialized.get("category").and_then(|c| c.as_str())
+    );
+
+    // Categories which aren't a string are ignored
+    let mut value = serde_json::to_value(&page)?;
+    if let Some(category) = value.get_mut("category") {
+      *category = serde_json::json!({"type": "Category"});
+    }
+    let page: Page = serde_json::from_value(value)?;
+    assert_eq!(None, page.category);
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_remote_views() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    json.views = Some(1234);
+    json.replies = Some(Replies {
+      id: None,
+      total_items: Some(56),
+      first: None,
+    });
+    let post = ApubPost::from_json(json.clone(), &context).await?;
+    assert_eq!(post.remote_views, Some(1234));
+    assert_eq!(post.remote_comments, Some(56));
+
+    // The comment count is refreshed even if the post wasn't edited
+    json.replies = Some(Replies {
+      id: None,
+      total_items: Some(60),
+      first: None,
+    });
+    let post = ApubPost::from_json(json, &context).await?;
+    assert_eq!(post.remote_comments, Some(60));
+
+    let post_view = PostView::read(&mut context.pool(), post.id, None, false)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert_eq!(post_view.post.remote_views, Some(1234));
+
+    // Remote view counts are not federated further
+    let page = post.clone().into_json(&context).await?;
+    assert_eq!(page.views, None);
+    assert!(page.replies.is_none());
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_additional_audience() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+
+    // The post belongs to the primary audience, the others are stored with it
+    let json: Page = file_to_json_object("assets/lemmy/objects/page_multiple_audience.json")?;
+    let post = ApubPost::from_json(json, &context).await?;
+    assert_eq!(community.id, post.community_id);
+    let additional: Vec<_> = post
+      .additional_audience
+      .iter()
+      .map(ToString::to_string)
+      .collect();
+    assert_eq!(
+      vec![
+        "https://voyager.lemmy.ml/c/astrometrics",
+        "https://ds9.lemmy.ml/c/main"
+      ],
+      additional
+    );
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_canonical_url() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+
+    // A short link on the same domain is stored
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    let url = Url::parse("https://enterprise.lemmy.ml/p/55143")?;
+    json.url = Some(url.clone());
+    let post = ApubPost::from_json(json.clone(), &context).await?;
+    assert_eq!(Some(url.clone().into()), post.canonical_url);
+    assert_eq!(Some(url), post.clone().into_json(&context).await?.url);
+
+    // Links to other domains are ignored
+    Post::delete(&mut context.pool(), post.id).await?;
+    json.url = Some(Url::parse("https://example.com/p/55143")?);
+    let post = ApubPost::from_json(json, &context).await?;
+    assert_eq!(None, post.canonical_url);
+
+    // Local posts are sent with a link to the post on this instance
+    let local_post = Post::update(
+      &mut context.pool(),
+      post.id,
+      &PostUpdateForm {
+        local: Some(true),
+        ..Default::default()
+      },
+    )
+    .await?;
+    let page = ApubPost(local_post).into_json(&context).await?;
+    let expected = format!(
+      "{}/post/{}",
+      context.settings().get_protocol_and_hostname(),
+      post.id
+    );
+    assert_eq!(Some(expected), page.url.map(|u| u.to_string()));
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_image_dimensions() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+
+    // Dimensions of a federated image are stored
+    let json: Page = file_to_json_object("assets/lemmy/objects/page_image_dimensions.json")?;
+    let post = ApubPost::from_json(json, &context).await?;
+    let url = post.url.clone().ok_or(LemmyErrorType::CouldntFindPost)?;
+    let details = ImageDetails::read(&mut context.pool(), &url).await?;
+    assert_eq!(Some((1920, 1080)), details.map(|d| (d.width, d.height)));
+
+    // Images uploaded to the local pictrs have their dimensions stored on upload, and these are
+    // sent along with the post
+    let local_url = Url::parse(&format!(
+      "{}/pictrs/image/portrait.png",
+      context.settings().get_protocol_and_hostname()
+    ))?;
+    let form = LocalImageForm {
+      local_user_id: None,
+      pictrs_alias: "portrait.png".to_string(),
+      pictrs_delete_token: "token".to_string(),
+    };
+    let details_form = ImageDetailsForm {
+      link: local_url.clone().into(),
+      width: 600,
+      height: 800,
+      content_type: "image/png".to_string(),
+    };
+    LocalImage::create(&mut context.pool(), &form, &details_form).await?;
+    let form = PostUpdateForm {
+      url: Some(Some(local_url.into())),
+      url_content_type: Some(Some("image/png".to_string())),
+      ..Default::default()
+    };
+    let local_post = Post::update(&mut context.pool(), post.id, &form).await?;
+    let page = ApubPost(local_post).into_json(&context).await?;
+    let dimensions: Vec<_> = page.attachment.iter().map(Attachment::dimensions).collect();
+    assert_eq!(vec![Some((600, 800))], dimensions);
+
+    LocalImage::delete_by_alias(&mut context.pool(), "portrait.png").await?;
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_parse_lemmy_post() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+
+    let json = file_to_json_object("assets/lemmy/objects/page.json")?;
+    let url = Url::parse("https://enterprise.lemmy.ml/post/55143")?;
+    ApubPost::verify(&json, &url, &context).await?;
+    let post = ApubPost::from_json(json, &context).await?;
+
+    assert_eq!(post.ap_id, url.into());
+    assert_eq!(post.name, "Post title");
+    assert!(post.body.is_some());
+    assert_eq!(post.body.as_ref().map(std::string::String::len), Some(45));
+    assert!(!post.locked);
+    assert!(!post.featured_community);
+    assert_eq!(post.remote_views, None);
+    assert_eq!(context.request_count(), 0);
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_convert_mastodon_post_title() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let community = parse_lemmy_community(&context).await?;
+
+    let json = file_to_json_object("assets/mastodon/objects/person.json")?;
+    let person = ApubPerson::from_json(json, &context).await?;
+
+    let json = file_to_json_object("assets/mastodon/objects/page.json")?;
+    let post = ApubPost::from_json(json, &context).await?;
+
+    assert_eq!(post.name, "Variable never resetting at refresh");
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Ok(())
+  }
+
+  #[test]
+  fn test_derive_post_title() -> LemmyResult<()> {
+    let mut page: Page = file_to_json_object("assets/mastodon/objects/page.json")?;
+    let local_site = LocalSite {
+      post_title_from_summary: true,
+      post_title_content_length: 20,
+      ..Default::default()
+    };
+
+    // Without summary, the first characters of the content are used
+    assert_eq!(
+      derive_post_title(&page, "tenforward", Some(&local_site)),
+      "Variable never reset"
+    );
+
+    // Summary takes precedence over content
+    page.summary = Some("Question about variables".to_string());
+    assert_eq!(
+      derive_post_title(&page, "tenforward", Some(&local_site)),
+      "Question about variables"
+    );
+
+    // Unless it is disabled in the site settings
+    let no_summary = LocalSite {
+      post_title_from_summary: false,
+      ..local_site.clone()
+    };
+    assert_eq!(
+      derive_post_title(&page, "tenforward", Some(&no_summary)),
+      "Variable never reset"
+    );
+
+    // Explicit name always wins
+    page.name = Some("Explicit title".to_string());
+    assert_eq!(
+      derive_post_title(&page, "tenforward", Some(&local_site)),
+      "Explicit title"
+    );
+
+    // Placeholder if there is nothing else
+    page.name = None;
+    page.summary = None;
+    page.content = None;
+    assert_eq!(
+      derive_post_title(&page, "tenforward", Some(&local_site)),
+      PLACEHOLDER_TITLE
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_federated_content() -> LemmyResult<()> {
+    let ap_id = Url::parse("https://lemmy-alpha/post/1")?;
+    let body = "This is a very long post body";
+
+    // Unchanged without limit, or if the body is short enough
+    assert_eq!(
+      federated_content(body, &ap_id, None),
+      markdown_to_html(body)
+    );
+    assert_eq!(
+      federated_content(body, &ap_id, Some(100)),
+      markdown_to_html(body)
+    );
+
+    // Long content is truncated and links to the canonical post
+    let content = federated_content(body, &ap_id, Some(14));
+    assert_eq!(
+      content,
+      "<p>This is a very…</p>\n<p><a href=\"https://lemmy-alpha/post/1\">Read more</a></p>\n"
+    );
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_log_rejected_page() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let captured = CapturedEvents::default();
+    let _guard = tracing_subscriber::registry()
+      .with(captured.clone())
+      .set_default();
+
+    // Page is hosted on a different domain than expected
+    let json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    let url = Url::parse("https://lemmy-alpha/post/1")?;
+    assert!(ApubPost::verify(&json, &url, &context).await.is_err());
+
+    let events = captured.0.lock().map(|e| e.clone()).unwrap_or_default();
+    let event = events
+      .iter()
+      .find(|e| e.get("message").map(String::as_str) == Some("Rejected incoming post"));
+    assert_eq!(
+      event.and_then(|e| e.get("origin")).map(String::as_str),
+      Some("enterprise.lemmy.ml")
+    );
+    assert_eq!(
+      event.and_then(|e| e.get("object_id")).map(String::as_str),
+      Some("https://enterprise.lemmy.ml/post/55143")
+    );
+    assert!(event.and_then(|e| e.get("error_type")).is_some());
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_federated_crosspost() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    let url = Url::parse("https://example.com/article?utm_source=lemmy&id=1")?;
+    json.attachment = vec![Attachment::new(url, None, None, None)];
+    let post = ApubPost::from_json(json, &context).await?;
+    // Tracking params are removed
+    assert_eq!(
+      post.url.as_ref().map(|u| u.inner().as_str()),
+      Some("https://example.com/article?id=1")
+    );
+
+    // Another instance has a post with the same link
+    let instance =
+      Instance::read_or_create(&mut context.pool(), "my_domain.tld".to_string()).await?;
+    let other_person = Person::create(
+      &mut context.pool(),
+      &PersonInsertForm::test_form(instance.id, "crosspost_person"),
+    )
+    .await?;
+    let community_form = CommunityInsertForm::builder()
+      .name("crosspost_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let other_community = Community::create(&mut context.pool(), &community_form).await?;
+    let post_form = PostInsertForm::builder()
+      .name("Crosspost".to_string())
+      .url(diesel_url_create(Some(
+        "https://example.com/article?id=1&utm_medium=social",
+      ))?)
+      .creator_id(other_person.id)
+      .community_id(other_community.id)
+      .build();
+    let other_post = Post::create(&mut context.pool(), &post_form).await?;
+
+    // Both posts are found as crossposts of each other
+    let cross_posts = PostQuery {
+      url_search: post.url.as_ref().map(|u| u.inner().to_string()),
+      ..Default::default()
+    }
+    .list(&site, &mut context.pool())
+    .await?;
+    assert_eq!(cross_posts.len(), 3);
+    assert!(cross_posts.iter().any(|p| p.post.id == post.id));
+    assert!(cross_posts.iter().any(|p| p.post.id == other_post.id));
+    assert!(cross_posts.iter().any(|p| p.post.id == same_link.id));
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Instance::delete(&mut context.pool(), instance.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_store_raw_json() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+    let form = LocalSiteInsertForm::builder()
+      .site_id(site.id)
+      .federation_store_raw_json(Some(true))
+      .federation_page_types(Some(vec!["Page".to_string()]))
+      .build();
+    LocalSite::create(&mut context.pool(), &form).await?;
+    sleep(CACHE_DURATION_API).await;
+
+    // The json is stored exactly as it was received
+    let raw = std::fs::read_to_string("assets/lemmy/objects/page.json")?;
+    let json: Page = serde_json::from_str(&raw)?;
+    let post = with_received_body(
+      raw.clone().into(),
+      ApubPost::from_json(json.clone(), &context),
+    )
+    .await?;
+    let raw_json = PostRawJson::read(&mut context.pool(), post.id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert_eq!(raw, raw_json.data);
+    assert_eq!(None, raw_json.error);
+
+    // Also if the post is rejected
+    let mut rejected = json;
+    rejected.id = ObjectId::parse("https://enterprise.lemmy.ml/post/55145")?;
+    rejected.kind = PageType::Article;
+    let raw = serde_json::to_string(&rejected)?;
+    let res = with_received_body(
+      raw.clone().into(),
+      ApubPost::from_json(rejected.clone(), &context),
+    )
+    .await;
+    assert!(res.is_err());
+    let raw_json = PostRawJson::read_from_apub_id(&mut context.pool(), &rejected.id.into())
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert_eq!(raw, raw_json.data);
+    assert_eq!(None, raw_json.post_id);
+    assert!(raw_json
+      .error
+      .is_some_and(|e| e.contains(&LemmyErrorType::PageTypeNotAccepted.to_string())));
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_future_published() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+    let form = LocalSiteInsertForm::builder()
+      .site_id(site.id)
+      .federation_future_tolerance(Some(300))
+      .build();
+    LocalSite::create(&mut context.pool(), &form).await?;
+    sleep(CACHE_DURATION_API).await;
+
+    // Posts from the far future are rejected
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    json.published = Some(Utc::now() + Duration::days(1));
+    json.updated = None;
+    let res = ApubPost::from_json(json.clone(), &context).await;
+    assert!(res.is_err_and(|e| e.error_type == LemmyErrorType::PublishedInFuture));
+
+    // Slight clock skew is tolerated, the post is stored with the time of receipt
+    json.published = Some(Utc::now() + Duration::minutes(2));
+    let post = ApubPost::from_json(json, &context).await?;
+    assert!(post.published <= Utc::now());
+    assert!(post.published > Utc::now() - Duration::minutes(1));
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_community_from_audience_only() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+
+    // The community is only in audience, not in to or cc
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    json.to = vec![Url::parse("https://www.w3.org/ns/activitystreams#Public")?];
+    json.cc = vec![];
+    let post = ApubPost::from_json(json, &context).await?;
+    assert_eq!(community.id, post.community_id);
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_fallback_community() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let instance =
+      Instance::read_or_create(&mut context.pool(), "my_domain.tld".to_string()).await?;
+    let community_form = CommunityInsertForm::builder()
+      .name("uncategorized".to_string())
+      .title("Uncategorized".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .local(Some(true))
+      .build();
+    let fallback = Community::create(&mut context.pool(), &community_form).await?;
+
+    // Without any community the post is rejected
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    json.to = vec![];
+    json.cc = vec![];
+    json.audience = vec![];
+    let res = ApubPost::from_json(json.clone(), &context).await;
+    assert!(res.is_err_and(|e| e.error_type == LemmyErrorType::CouldntFindCommunity));
+
+    // With a fallback community configured, the post ends up there
+    let form = LocalSiteInsertForm::builder()
+      .site_id(site.id)
+      .federation_fallback_community_id(Some(fallback.id))
+      .build();
+    LocalSite::create(&mut context.pool(), &form).await?;
+    sleep(CACHE_DURATION_API).await;
+    let post = ApubPost::from_json(json, &context).await?;
+    assert_eq!(fallback.id, post.community_id);
+
+    // It isn't announced to the followers of the fallback community, unless it was sent there
+    let fallback: ApubCommunity = fallback.into();
+    let mut create = serde_json::json!({
+      "id": "https://enterprise.lemmy.ml/activities/create/1",
+      "actor": "https://enterprise.lemmy.ml/u/picard",
+      "type": "Create",
+      "to": ["https://www.w3.org/ns/activitystreams#Public"],
+      "cc": [],
+    });
+    let activity: RawAnnouncableActivities = serde_json::from_value(create.clone())?;
+    assert!(activity.is_misdirected(&fallback, &context).await);
+    create["cc"] = serde_json::json!([fallback.actor_id]);
+    let activity: RawAnnouncableActivities = serde_json::from_value(create)?;
+    assert!(!activity.is_misdirected(&fallback, &context).await);
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Community::delete(&mut context.pool(), fallback.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_proxy_post_attachments() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+    let form = LocalSiteInsertForm::builder()
+      .site_id(site.id)
+      .proxy_post_attachments(Some(true))
+      .build();
+    LocalSite::create(&mut context.pool(), &form).await?;
+    sleep(CACHE_DURATION_API).await;
+
+    let json: Page = file_to_json_object("assets/lemmy/objects/page.json")?;
+    let post = ApubPost::from_json(json, &context).await?;
+    let original = post.url.clone().ok_or(LemmyErrorType::CouldntFindPost)?;
+
+    // Clients get the proxied url
+    let mut post_view = PostView::read(&mut context.pool(), post.id, None, false)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    // Normally set when fetching the link metadata
+    post_view.post.url_content_type = Some("image/png".to_string());
+    proxy_post_attachments(std::slice::from_mut(&mut post_view), &context).await?;
+    let proxied = post_view.post.url.ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert!(proxied.as_str().starts_with(&format!(
+      "{}/api/v3/image_proxy?url=",
+      context.settings().get_protocol_and_hostname()
+    )));
+
+    // Federation uses the original url
+    let page = post.clone().into_json(&context).await?;
+    let attachment_url = page.attachment.first().cloned().map(Attachment::url);
+    assert_eq!(Some(original.into()), attachment_url);
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[test]
+  fn test_is_temporary_error() {
+    let pictrs = LemmyError::from(LemmyErrorType::PictrsResponseError("timeout".to_string()));
+    assert!(is_temporary_error(&pictrs));
+    let timeout = LemmyError::from(std::io::Error::from(ErrorKind::TimedOut));
+    assert!(is_temporary_error(&timeout));
+
+    // Other unknown errors are caused by the post itself, and would fail again
+    let invalid = LemmyError::from(anyhow::anyhow!("invalid post"));
+    assert!(!is_temporary_error(&invalid));
+    let not_found = LemmyError::from(diesel::result::Error::NotFound);
+    assert!(!is_temporary_error(&not_found));
+  }
 }
@@ -1,6 +1,7 @@
 use crate::{
   activities::verify_community_matches,
-  objects::{community::ApubCommunity, person::ApubPerson, post::ApubPost},
+  fetcher::user_or_community::UserOrCommunity,
+  objects::{community::ApubCommunity, post::ApubPost},
   protocol::InCommunity,
 };
 use activitypub_federation::{
@@ -24,7 +25,7 @@ pub enum LockType {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LockPage {
-  pub(crate) actor: ObjectId<ApubPerson>,
+  pub(crate) actor: ObjectId<UserOrCommunity>,
   #[serde(deserialize_with = "deserialize_one_or_many")]
   pub(crate) to: Vec<Url>,
   pub(crate) object: ObjectId<ApubPost>,
@@ -39,7 +40,7 @@ pub struct LockPage {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UndoLockPage {
-  pub(crate) actor: ObjectId<ApubPerson>,
+  pub(crate) actor: ObjectId<UserOrCommunity>,
   #[serde(deserialize_with = "deserialize_one_or_many")]
   pub(crate) to: Vec<Url>,
   pub(crate) object: LockPage,
@@ -2,6 +2,7 @@ pub mod announce;
 pub mod collection_add;
 pub mod collection_remove;
 pub mod lock_page;
+pub mod move_page;
 pub mod report;
 pub mod update;
 
@@ -13,6 +14,7 @@ mod tests {
       collection_add::CollectionAdd,
       collection_remove::CollectionRemove,
       lock_page::{LockPage, UndoLockPage},
+      move_page::MovePage,
       report::Report,
       update::UpdateCommunity,
     },
@@ -39,6 +41,8 @@ mod tests {
     test_parse_lemmy_item::<LockPage>("assets/lemmy/activities/community/lock_page.json")?;
     test_parse_lemmy_item::<UndoLockPage>("assets/lemmy/activities/community/undo_lock_page.json")?;
 
+    test_parse_lemmy_item::<MovePage>("assets/lemmy/activities/community/move_page.json")?;
+
     test_parse_lemmy_item::<UpdateCommunity>(
       "assets/lemmy/activities/community/update_community.json",
     )?;
@@ -0,0 +1,49 @@
+use crate::{
+  activities::verify_community_matches,
+  objects::{community::ApubCommunity, person::ApubPerson, post::ApubPost},
+  protocol::InCommunity,
+};
+use activitypub_federation::{
+  config::Data,
+  fetch::object_id::ObjectId,
+  kinds::activity::MoveType,
+  protocol::helpers::deserialize_one_or_many,
+};
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::{source::community::Community, traits::Crud};
+use lemmy_utils::{error::LemmyResult, LemmyErrorType};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Moves a post from the `origin` community to the `target` community.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MovePage {
+  pub(crate) actor: ObjectId<ApubPerson>,
+  #[serde(deserialize_with = "deserialize_one_or_many")]
+  pub(crate) to: Vec<Url>,
+  pub(crate) object: ObjectId<ApubPost>,
+  #[serde(deserialize_with = "deserialize_one_or_many")]
+  pub(crate) cc: Vec<Url>,
+  pub(crate) origin: ObjectId<ApubCommunity>,
+  pub(crate) target: ObjectId<ApubCommunity>,
+  #[serde(rename = "type")]
+  pub(crate) kind: MoveType,
+  pub(crate) id: Url,
+  pub(crate) audience: Option<ObjectId<ApubCommunity>>,
+}
+
+#[async_trait::async_trait]
+impl InCommunity for MovePage {
+  async fn community(&self, context: &Data<LemmyContext>) -> LemmyResult<ApubCommunity> {
+    let post = self.object.dereference(context).await?;
+    let community = Community::read(&mut context.pool(), post.community_id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindCommunity)?;
+    verify_community_matches(&self.origin, community.actor_id.clone())?;
+    if let Some(audience) = &self.audience {
+      verify_community_matches(audience, community.actor_id.clone())?;
+    }
+    Ok(community.into())
+  }
+}
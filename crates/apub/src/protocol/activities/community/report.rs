@@ -1,7 +1,7 @@
 use crate::{
   activities::verify_community_matches,
-  fetcher::post_or_comment::PostOrComment,
-  objects::{community::ApubCommunity, person::ApubPerson},
+  fetcher::{post_or_comment::PostOrComment, site_or_community_or_user::SiteOrCommunityOrUser},
+  objects::community::ApubCommunity,
   protocol::InCommunity,
 };
 use activitypub_federation::{
@@ -18,7 +18,8 @@ use url::Url;
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Report {
-  pub(crate) actor: ObjectId<ApubPerson>,
+  /// The reporting person, or the instance actor for anonymous reports
+  pub(crate) actor: ObjectId<SiteOrCommunityOrUser>,
   #[serde(deserialize_with = "deserialize_one")]
   pub(crate) to: [ObjectId<ApubCommunity>; 1],
   pub(crate) object: ReportObject,
@@ -0,0 +1,16 @@
+use crate::objects::person::ApubPerson;
+use activitypub_federation::{fetch::object_id::ObjectId, kinds::activity::ReadType};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Sent by some platforms when a user has seen an object. Lemmy doesn't send these.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Read {
+  pub(crate) actor: ObjectId<ApubPerson>,
+  /// Not dereferenced, as only local posts are handled
+  pub(crate) object: Url,
+  #[serde(rename = "type")]
+  pub(crate) kind: ReadType,
+  pub(crate) id: Url,
+}
@@ -1,10 +1,11 @@
+use chrono::{DateTime, Duration, Utc};
 use lemmy_db_schema::{
   impls::actor_language::UNDETERMINED_ID,
   newtypes::LanguageId,
   source::language::Language,
   utils::DbPool,
 };
-use lemmy_utils::error::LemmyResult;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
 use serde::{Deserialize, Serialize};
 use url::Url;
 
@@ -22,6 +23,51 @@ pub struct Endpoints {
   pub shared_inbox: Url,
 }
 
+/// Creation and edit time of a federated object.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Timestamps {
+  pub(crate) published: Option<DateTime<Utc>>,
+  pub(crate) updated: Option<DateTime<Utc>>,
+}
+
+impl Timestamps {
+  /// Whether the object was changed after it was published.
+  pub(crate) fn is_edit(&self) -> bool {
+    match (self.published, self.updated) {
+      (Some(published), Some(updated)) => updated > published,
+      (None, Some(_)) => true,
+      _ => false,
+    }
+  }
+
+  /// Time of the latest version.
+  pub(crate) fn latest(&self) -> Option<DateTime<Utc>> {
+    self.updated.max(self.published)
+  }
+
+  /// Whether this is a more recent version than `other`. If either timestamp is missing this can't
+  /// be determined, so it is treated as newer.
+  pub(crate) fn is_newer_than(&self, other: &Timestamps) -> bool {
+    match (self.latest(), other.latest()) {
+      (Some(latest), Some(other_latest)) => latest > other_latest,
+      _ => true,
+    }
+  }
+
+  /// Timestamps slightly in the future, for example because of clock skew on the sending instance,
+  /// are replaced with `now`. Returns an error if they are further in the future than `tolerance`.
+  pub(crate) fn clamp_future(self, now: DateTime<Utc>, tolerance: Duration) -> LemmyResult<Self> {
+    let clamp = |timestamp: Option<DateTime<Utc>>| match timestamp {
+      Some(t) if t > now + tolerance => Err(LemmyErrorType::PublishedInFuture),
+      t => Ok(t.map(|t| t.min(now))),
+    };
+    Ok(Timestamps {
+      published: clamp(self.published)?,
+      updated: clamp(self.updated)?,
+    })
+  }
+}
+
 /// As specified in https://schema.org/Language
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -104,9 +150,11 @@ mod tests {
       page::Page,
       person::Person,
       tombstone::Tombstone,
+      Timestamps,
     },
     tests::{test_json, test_parse_lemmy_item},
   };
+  use chrono::{Days, Utc};
   use lemmy_utils::error::LemmyResult;
 
   #[test]
@@ -144,6 +192,13 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  fn test_not_parsing_question() {
+    // Polls are not supported, so their option labels can never be stored
+    assert!(test_json::<Page>("assets/mastodon/objects/question.json").is_err());
+    assert!(test_json::<Note>("assets/mastodon/objects/question.json").is_err());
+  }
+
   #[test]
   fn test_parse_objects_lotide() -> LemmyResult<()> {
     test_json::<Group>("assets/lotide/objects/group.json")?;
@@ -215,4 +270,41 @@ mod tests {
     test_json::<Note>("assets/wordpress/objects/note.json")?;
     Ok(())
   }
+
+  #[test]
+  fn test_timestamps() {
+    let published = Utc::now() - Days::new(1);
+    let created = Timestamps {
+      published: Some(published),
+      updated: None,
+    };
+    assert!(!created.is_edit());
+    assert_eq!(Some(published), created.latest());
+
+    // Some platforms set updated to the same time as published
+    let same = Timestamps {
+      updated: Some(published),
+      ..created
+    };
+    assert!(!same.is_edit());
+    assert!(!same.is_newer_than(&created));
+
+    let edited = Timestamps {
+      updated: Some(Utc::now()),
+      ..created
+    };
+    assert!(edited.is_edit());
+    assert!(edited.is_newer_than(&created));
+    assert!(!created.is_newer_than(&edited));
+    assert!(!edited.is_newer_than(&edited));
+
+    // Without any timestamps the order is unknown
+    let unknown = Timestamps {
+      published: None,
+      updated: None,
+    };
+    assert!(!unknown.is_edit());
+    assert!(unknown.is_newer_than(&edited));
+    assert!(edited.is_newer_than(&unknown));
+  }
 }
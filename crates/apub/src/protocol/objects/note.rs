@@ -3,7 +3,12 @@ use crate::{
   fetcher::post_or_comment::PostOrComment,
   mentions::MentionOrValue,
   objects::{comment::ApubComment, community::ApubCommunity, person::ApubPerson, post::ApubPost},
-  protocol::{objects::LanguageTag, InCommunity, Source},
+  protocol::{
+    objects::{page::Replies, LanguageTag},
+    Id,
+    InCommunity,
+    Source,
+  },
 };
 use activitypub_federation::{
   config::Data,
@@ -17,10 +22,13 @@ use activitypub_federation::{
 use chrono::{DateTime, Utc};
 use lemmy_api_common::context::LemmyContext;
 use lemmy_db_schema::{
-  source::{community::Community, post::Post},
+  source::{community::Community, local_site::LocalSite, post::Post},
   traits::Crud,
 };
-use lemmy_utils::{error::LemmyResult, LemmyErrorType};
+use lemmy_utils::{
+  error::{LemmyErrorExt2, LemmyResult},
+  LemmyErrorType,
+};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 use std::ops::Deref;
@@ -51,6 +59,10 @@ pub struct Note {
   pub(crate) distinguished: Option<bool>,
   pub(crate) language: Option<LanguageTag>,
   pub(crate) audience: Option<ObjectId<ApubCommunity>>,
+  /// Collection of direct replies. Lemmy doesn't send it, but other platforms may include the
+  /// number of replies, and a link to fetch them when the comment is expanded.
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub(crate) replies: Option<Replies>,
 }
 
 impl Note {
@@ -58,8 +70,20 @@ impl Note {
     &self,
     context: &Data<LemmyContext>,
   ) -> LemmyResult<(ApubPost, Option<ApubComment>)> {
-    // Fetch parent comment chain in a box, otherwise it can cause a stack overflow.
-    let parent = Box::pin(self.in_reply_to.dereference(context).await?);
+    // Parents are fetched recursively, so only use locally known parents once the number of
+    // fetches reaches the maximum comment depth.
+    let parent = if parent_fetch_limit_reached(context).await {
+      Box::pin(
+        self
+          .in_reply_to
+          .dereference_local(context)
+          .await
+          .with_lemmy_type(LemmyErrorType::CommentFetchDepthExceeded)?,
+      )
+    } else {
+      // Fetch parent comment chain in a box, otherwise it can cause a stack overflow.
+      Box::pin(self.in_reply_to.dereference(context).await?)
+    };
     match parent.deref() {
       PostOrComment::Post(p) => Ok((p.clone(), None)),
       PostOrComment::Comment(c) => {
@@ -73,6 +97,19 @@ impl Note {
   }
 }
 
+/// The request count grows with each fetched parent, so it bounds the depth of the fetched chain.
+async fn parent_fetch_limit_reached(context: &Data<LemmyContext>) -> bool {
+  LocalSite::read(&mut context.pool()).await.is_ok_and(|l| {
+    u32::try_from(l.max_comment_fetch_depth).is_ok_and(|max| context.request_count() >= max)
+  })
+}
+
+impl Id for Note {
+  fn object_id(&self) -> &Url {
+    self.id.inner()
+  }
+}
+
 #[async_trait::async_trait]
 impl InCommunity for Note {
   async fn community(&self, context: &Data<LemmyContext>) -> LemmyResult<ApubCommunity> {
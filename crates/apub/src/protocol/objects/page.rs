@@ -1,8 +1,19 @@
 use crate::{
   activities::verify_community_matches,
   fetcher::user_or_community::{PersonOrGroupType, UserOrCommunity},
-  objects::{community::ApubCommunity, person::ApubPerson, post::ApubPost},
-  protocol::{objects::LanguageTag, ImageObject, InCommunity, Source},
+  objects::{
+    comment::ApubComment,
+    community::ApubCommunity,
+    person::ApubPerson,
+    post::{is_temporary_error, ApubPost},
+  },
+  protocol::{
+    objects::{note::Note, LanguageTag, Timestamps},
+    IdOrNestedObject,
+    ImageObject,
+    InCommunity,
+    Source,
+  },
 };
 use activitypub_federation::{
   config::Data,
@@ -10,6 +21,7 @@ use activitypub_federation::{
   kinds::{
     link::LinkType,
     object::{DocumentType, ImageType},
+    public,
   },
   protocol::{
     helpers::{deserialize_one_or_many, deserialize_skip_error},
@@ -20,12 +32,20 @@ use activitypub_federation::{
 use chrono::{DateTime, Utc};
 use itertools::Itertools;
 use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::{
+  source::{community::Community, local_site::LocalSite},
+  traits::Crud,
+  CommentSortType,
+};
 use lemmy_utils::error::{LemmyError, LemmyErrorType, LemmyResult};
-use serde::{de::Error, Deserialize, Deserializer, Serialize};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 use serde_with::skip_serializing_none;
+use std::collections::{BTreeMap, HashSet};
+use strum_macros::Display;
+use tracing::debug;
 use url::Url;
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Display, Deserialize, Serialize, PartialEq, Eq)]
 pub enum PageType {
   Page,
   Article,
@@ -41,6 +61,10 @@ pub struct Page {
   #[serde(rename = "type")]
   pub(crate) kind: PageType,
   pub(crate) id: ObjectId<ApubPost>,
+  /// Link for sharing the post, which may be shorter than its id. Peertube sends a list of video
+  /// files here instead, which is ignored.
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub(crate) url: Option<Url>,
   pub(crate) attributed_to: AttributedTo,
   #[serde(deserialize_with = "deserialize_one_or_many", default)]
   pub(crate) to: Vec<Url>,
@@ -49,6 +73,10 @@ pub struct Page {
   pub(crate) in_reply_to: Option<String>,
 
   pub(crate) name: Option<String>,
+  /// Alternative titles keyed by language code. The one matching `language` is used as title.
+  pub(crate) name_map: Option<BTreeMap<String, String>>,
+  /// Used by microblog platforms as content warning. We use it as title for posts without `name`.
+  pub(crate) summary: Option<String>,
   #[serde(deserialize_with = "deserialize_one_or_many", default)]
   pub(crate) cc: Vec<Url>,
   pub(crate) content: Option<String>,
@@ -64,9 +92,80 @@ pub struct Page {
   pub(crate) published: Option<DateTime<Utc>>,
   pub(crate) updated: Option<DateTime<Utc>>,
   pub(crate) language: Option<LanguageTag>,
-  pub(crate) audience: Option<ObjectId<ApubCommunity>>,
+  /// Lemmy only sends a single community here, but other platforms may address a post to multiple
+  /// audiences. See [Page::primary_audience], the others are stored in `post.additional_audience`.
+  #[serde(
+    deserialize_with = "deserialize_one_or_many",
+    serialize_with = "serialize_audience",
+    skip_serializing_if = "Vec::is_empty",
+    default
+  )]
+  pub(crate) audience: Vec<ObjectId<ApubCommunity>>,
   #[serde(deserialize_with = "deserialize_skip_error", default)]
   pub(crate) tag: Vec<Hashtag>,
+  /// View count sent by some platforms like Peertube. It is never sent by Lemmy.
+  #[serde(alias = "ephemeral:views")]
+  pub(crate) views: Option<i64>,
+  /// Collection of comments. Lemmy doesn't send it, but other platforms may include the number
+  /// of comments which is used until the comments themselves are fetched.
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub(crate) replies: Option<Replies>,
+  /// Topical classification used by some platforms. Only a single string value is supported.
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub(crate) category: Option<String>,
+  /// Sent by Peertube and Mobilizon. Lemmy doesn't send it, because posts are locked by moderators
+  /// with a separate activity. Only used when a post is first received, see
+  /// [LocalSite::federation_comments_enabled_default].
+  pub(crate) comments_enabled: Option<bool>,
+  /// Lemmy extension, set by moderators to highlight their own post. Changes are only accepted
+  /// from community moderators, like locking.
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub(crate) distinguished: Option<bool>,
+  /// Lemmy extension, set if the post was translated from this language into `language`.
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub(crate) original_language: Option<LanguageTag>,
+  /// Lemmy extension, shared by crossposts of the same link so that they can be grouped across
+  /// instances.
+  pub(crate) crosspost_group: Option<Url>,
+  /// Lemmy extension, comment which the post creator or a moderator pinned to the top. Only
+  /// comments of the same post are accepted.
+  pub(crate) pinned_comment: Option<ObjectId<ApubComment>>,
+  /// Lemmy extension, set by the creator if the post is original content. Missing means false.
+  pub(crate) original_content: Option<bool>,
+  /// Lemmy extension, comment sort which the creator chose for the post. Unknown values are
+  /// ignored.
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub(crate) default_comment_sort: Option<CommentSortType>,
+  /// Lemmy extension, time after which the post is locked. Each instance locks the post itself,
+  /// so that this also works if the lock activity is lost.
+  pub(crate) close_at: Option<DateTime<Utc>>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Replies {
+  pub(crate) id: Option<Url>,
+  pub(crate) total_items: Option<i64>,
+  /// Only an embedded first page is supported. If it is missing, the collection is fetched from
+  /// its id.
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub(crate) first: Option<RepliesPage>,
+}
+
+/// Page of a replies collection. Mastodon only lists replies by the author on the first page, so
+/// `next` needs to be followed to get the others.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RepliesPage {
+  #[serde(
+    alias = "orderedItems",
+    deserialize_with = "deserialize_skip_error",
+    default
+  )]
+  pub(crate) items: Vec<IdOrNestedObject<Note>>,
+  pub(crate) next: Option<Url>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -75,6 +174,8 @@ pub(crate) struct Link {
   href: Url,
   media_type: Option<String>,
   r#type: LinkType,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  sensitive: Option<bool>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -83,8 +184,18 @@ pub(crate) struct Image {
   #[serde(rename = "type")]
   kind: ImageType,
   url: Url,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  media_type: Option<String>,
   /// Used for alt_text
   name: Option<String>,
+  /// Marks this image as spoiler, independently of other attachments
+  #[serde(skip_serializing_if = "Option::is_none")]
+  sensitive: Option<bool>,
+  /// Dimensions in pixels, so that clients can reserve space before the image is loaded
+  #[serde(skip_serializing_if = "Option::is_none")]
+  width: Option<i32>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  height: Option<i32>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -95,6 +206,8 @@ pub(crate) struct Document {
   url: Url,
   /// Used for alt_text
   name: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  sensitive: Option<bool>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -117,6 +230,45 @@ impl Attachment {
     }
   }
 
+  /// The media type which the sender declared for this attachment.
+  pub(crate) fn media_type(&self) -> Option<&str> {
+    match self {
+      Attachment::Link(l) => l.media_type.as_deref(),
+      Attachment::Image(i) => i.media_type.as_deref(),
+      Attachment::Document(_) => None,
+    }
+  }
+
+  /// Whether this attachment is marked as sensitive. If not present, the flag of the post applies.
+  pub(crate) fn sensitive(&self) -> Option<bool> {
+    match self {
+      Attachment::Link(l) => l.sensitive,
+      Attachment::Image(i) => i.sensitive,
+      Attachment::Document(d) => d.sensitive,
+    }
+  }
+
+  /// Width and height of an image attachment in pixels, if both are known.
+  pub(crate) fn dimensions(&self) -> Option<(i32, i32)> {
+    match self {
+      Attachment::Image(Image {
+        width: Some(width),
+        height: Some(height),
+        ..
+      }) if *width > 0 && *height > 0 => Some((*width, *height)),
+      _ => None,
+    }
+  }
+
+  /// Sets the dimensions of an image attachment. Has no effect for other attachment types.
+  pub(crate) fn with_dimensions(mut self, dimensions: Option<(i32, i32)>) -> Attachment {
+    if let (Attachment::Image(i), Some((width, height))) = (&mut self, dimensions) {
+      i.width = Some(width);
+      i.height = Some(height);
+    }
+    self
+  }
+
   pub(crate) fn alt_text(self) -> Option<String> {
     match self {
       Attachment::Image(i) => i.name,
@@ -131,6 +283,8 @@ impl Attachment {
 pub(crate) enum AttributedTo {
   Lemmy(ObjectId<ApubPerson>),
   Peertube([AttributedToPeertube; 2]),
+  /// Used by some platforms for posts of organizational accounts.
+  Collection(AttributedToCollection),
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -141,6 +295,29 @@ pub(crate) struct AttributedToPeertube {
   pub id: ObjectId<UserOrCommunity>,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AttributedToCollection {
+  #[serde(alias = "orderedItems")]
+  pub items: Vec<AttributedToPeertube>,
+}
+
+impl AttributedTo {
+  /// Returns the first actor of the given type. Always empty for Lemmy, which only specifies the
+  /// creator.
+  fn find(&self, kind: PersonOrGroupType) -> Option<Url> {
+    let actors: &[AttributedToPeertube] = match self {
+      AttributedTo::Lemmy(_) => &[],
+      AttributedTo::Peertube(p) => p,
+      AttributedTo::Collection(c) => &c.items,
+    };
+    actors
+      .iter()
+      .find(|a| a.kind == kind)
+      .map(|a| a.id.clone().into_inner())
+  }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Hashtag {
   pub(crate) href: Url,
@@ -158,30 +335,106 @@ impl Page {
   pub(crate) fn creator(&self) -> LemmyResult<ObjectId<ApubPerson>> {
     match &self.attributed_to {
       AttributedTo::Lemmy(l) => Ok(l.clone()),
-      AttributedTo::Peertube(p) => p
-        .iter()
-        .find(|a| a.kind == PersonOrGroupType::Person)
-        .map(|a| ObjectId::<ApubPerson>::from(a.id.clone().into_inner()))
+      a => a
+        .find(PersonOrGroupType::Person)
+        .map(ObjectId::from)
         .ok_or_else(|| LemmyErrorType::PageDoesNotSpecifyCreator.into()),
     }
   }
+
+  /// Returns true if the page is attributed to the given community, but not to any person. Some
+  /// platforms do this for posts which are published by the group itself.
+  pub(crate) fn is_group_post(&self, community: &ApubCommunity) -> bool {
+    let group = match &self.attributed_to {
+      AttributedTo::Lemmy(l) => Some(l.inner().clone()),
+      a if a.find(PersonOrGroupType::Person).is_none() => a.find(PersonOrGroupType::Group),
+      _ => None,
+    };
+    group.is_some_and(|g| &g == community.actor_id.inner())
+  }
+
+  /// Returns the title from `nameMap` which matches the post language. Falls back to `name`, or
+  /// to any title from `nameMap` if `name` is missing.
+  pub(crate) fn localized_name(&self) -> Option<&str> {
+    let name_map = self.name_map.as_ref();
+    self
+      .language
+      .as_ref()
+      .and_then(|l| name_map?.get(&l.identifier))
+      .or(self.name.as_ref())
+      .or_else(|| name_map?.values().next())
+      .map(String::as_str)
+  }
+
+  pub(crate) fn timestamps(&self) -> Timestamps {
+    Timestamps {
+      published: self.published,
+      updated: self.updated,
+    }
+  }
+
+  /// Attachments without duplicates, as some platforms repeat the same image. Urls are compared
+  /// without fragment, and the first occurrence is kept.
+  pub(crate) fn unique_attachments(&self) -> Vec<Attachment> {
+    let mut seen = HashSet::new();
+    self
+      .attachment
+      .iter()
+      .filter(|a| {
+        let mut url = (*a).clone().url();
+        url.set_fragment(None);
+        seen.insert(url)
+      })
+      .cloned()
+      .collect()
+  }
+
+  /// The audience which the post belongs to. If there are multiple, the first one which is also
+  /// addressed in `to` or `cc` is preferred.
+  pub(crate) fn primary_audience(&self) -> Option<&ObjectId<ApubCommunity>> {
+    self
+      .audience
+      .iter()
+      .find(|a| self.to.contains(a.inner()) || self.cc.contains(a.inner()))
+      .or(self.audience.first())
+  }
+
+  /// All audiences except the primary one.
+  pub(crate) fn additional_audience(&self) -> Vec<&ObjectId<ApubCommunity>> {
+    let primary = self.primary_audience();
+    self
+      .audience
+      .iter()
+      .filter(|a| Some(*a) != primary)
+      .collect()
+  }
 }
 
 impl Attachment {
   /// Creates new attachment for a given link and mime type.
-  pub(crate) fn new(url: Url, media_type: Option<String>, alt_text: Option<String>) -> Attachment {
+  pub(crate) fn new(
+    url: Url,
+    media_type: Option<String>,
+    alt_text: Option<String>,
+    sensitive: Option<bool>,
+  ) -> Attachment {
     let is_image = media_type.clone().unwrap_or_default().starts_with("image");
     if is_image {
       Attachment::Image(Image {
         kind: Default::default(),
         url,
+        media_type: None,
         name: alt_text,
+        sensitive,
+        width: None,
+        height: None,
       })
     } else {
       Attachment::Link(Link {
         href: url,
         media_type,
         r#type: Default::default(),
+        sensitive,
       })
     }
   }
@@ -210,41 +463,96 @@ impl ActivityHandler for Page {
 #[async_trait::async_trait]
 impl InCommunity for Page {
   async fn community(&self, context: &Data<LemmyContext>) -> LemmyResult<ApubCommunity> {
-    if let Some(audience) = &self.audience {
-      return audience.dereference(context).await;
+    match self.addressed_community(context).await {
+      Err(e) if e.error_type == LemmyErrorType::CouldntFindCommunity => {
+        fallback_community(context).await?.ok_or(e)
+      }
+      res => res,
+    }
+  }
+}
+
+impl Page {
+  async fn addressed_community(&self, context: &Data<LemmyContext>) -> LemmyResult<ApubCommunity> {
+    // Only if the community is surely unknown, the post may end up in the fallback community.
+    // Temporary errors are returned instead, so that the post can be received again later.
+    let mut temporary_error = None;
+    if let Some(audience) = self.primary_audience() {
+      // If the audience can't be fetched, try the addressed communities instead
+      match audience.dereference(context).await {
+        Ok(community) => return Ok(community),
+        Err(e) => {
+          debug!(
+            "Failed to fetch audience {audience} of post {}: {e}",
+            self.id
+          );
+          temporary_error = Some(e).filter(is_temporary_error);
+        }
+      }
     }
 
-    let community = match &self.attributed_to {
-      AttributedTo::Lemmy(_) => {
+    // Collections don't necessarily include a group, then the community is taken from the
+    // addressing like for Lemmy
+    let group = self.attributed_to.find(PersonOrGroupType::Group);
+    let community = match (&self.attributed_to, group) {
+      (_, Some(group)) => {
+        ObjectId::<ApubCommunity>::from(group)
+          .dereference(context)
+          .await?
+      }
+      (AttributedTo::Peertube(_), None) => Err(LemmyErrorType::CouldntFindCommunity)?,
+      (AttributedTo::Lemmy(_) | AttributedTo::Collection(_), None) => {
         let mut iter = self.to.iter().merge(self.cc.iter());
         loop {
           if let Some(cid) = iter.next() {
             let cid = ObjectId::from(cid.clone());
-            if let Ok(c) = cid.dereference(context).await {
-              break c;
+            match cid.dereference(context).await {
+              Ok(c) => break c,
+              Err(e) if is_temporary_error(&e) => temporary_error = Some(e),
+              Err(_) => {}
             }
           } else {
-            Err(LemmyErrorType::CouldntFindCommunity)?;
+            Err(temporary_error.unwrap_or_else(|| LemmyErrorType::CouldntFindCommunity.into()))?;
           }
         }
       }
-      AttributedTo::Peertube(p) => {
-        p.iter()
-          .find(|a| a.kind == PersonOrGroupType::Group)
-          .map(|a| ObjectId::<ApubCommunity>::from(a.id.clone().into_inner()))
-          .ok_or(LemmyErrorType::CouldntFindCommunity)?
-          .dereference(context)
-          .await?
-      }
     };
 
-    if let Some(audience) = &self.audience {
+    if let Some(audience) = self.primary_audience() {
       verify_community_matches(audience, community.actor_id.clone())?;
     }
     Ok(community)
   }
 }
 
+/// The local community which receives posts whose community can't be found, if configured by the
+/// admin.
+async fn fallback_community(context: &Data<LemmyContext>) -> LemmyResult<Option<ApubCommunity>> {
+  let Some(community_id) = LocalSite::read(&mut context.pool())
+    .await
+    .ok()
+    .and_then(|l| l.federation_fallback_community_id)
+  else {
+    return Ok(None);
+  };
+  let community = Community::read(&mut context.pool(), community_id).await?;
+  Ok(
+    community
+      .filter(|c| c.local && !c.deleted && !c.removed)
+      .map(Into::into),
+  )
+}
+
+/// Checks if the community is the fallback community of the local site.
+pub(crate) async fn is_fallback_community(
+  community: &ApubCommunity,
+  context: &Data<LemmyContext>,
+) -> bool {
+  LocalSite::read(&mut context.pool())
+    .await
+    .is_ok_and(|l| l.federation_fallback_community_id == Some(community.id))
+}
+
 /// Only allows deserialization if the field is missing or null. If it is present, throws an error.
 pub fn deserialize_not_present<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
 where
@@ -257,12 +565,456 @@ where
   }
 }
 
+/// Serializes a single audience as plain value for compatibility, and multiple ones as array.
+fn serialize_audience<S>(
+  audience: &[ObjectId<ApubCommunity>],
+  serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+  S: Serializer,
+{
+  match audience {
+    [single] => single.serialize(serializer),
+    _ => audience.serialize(serializer),
+  }
+}
+
+/// Builds outgoing [Page] objects, and checks that they can be parsed by other instances.
+pub(crate) struct PageBuilder {
+  page: Page,
+}
+
+impl PageBuilder {
+  pub(crate) fn new(id: ObjectId<ApubPost>, creator: ObjectId<ApubPerson>) -> Self {
+    PageBuilder {
+      page: Page {
+        kind: PageType::Page,
+        id,
+        url: None,
+        attributed_to: AttributedTo::Lemmy(creator),
+        to: vec![],
+        in_reply_to: None,
+        name: None,
+        name_map: None,
+        summary: None,
+        cc: vec![],
+        content: None,
+        media_type: Some(MediaTypeMarkdownOrHtml::Html),
+        source: None,
+        attachment: vec![],
+        image: None,
+        sensitive: None,
+        published: None,
+        updated: None,
+        language: None,
+        audience: vec![],
+        tag: vec![],
+        views: None,
+        replies: None,
+        category: None,
+        comments_enabled: None,
+        distinguished: None,
+        original_language: None,
+        crosspost_group: None,
+        pinned_comment: None,
+        original_content: None,
+        default_comment_sort: None,
+        close_at: None,
+      },
+    }
+  }
+
+  /// Addresses the page publicly to the given community.
+  pub(crate) fn community(mut self, community: ObjectId<ApubCommunity>) -> Self {
+    self.page.to = vec![community.clone().into(), public()];
+    self.page.audience = vec![community];
+    self
+  }
+
+  pub(crate) fn url(mut self, url: Option<Url>) -> Self {
+    self.page.url = url;
+    self
+  }
+
+  pub(crate) fn name(mut self, name: String) -> Self {
+    self.page.name = Some(name);
+    self
+  }
+
+  /// HTML content, and optionally the markdown from which it was rendered.
+  pub(crate) fn content(mut self, content: Option<String>, source: Option<Source>) -> Self {
+    self.page.content = content;
+    self.page.source = source;
+    self
+  }
+
+  pub(crate) fn attachment(mut self, attachment: Option<Attachment>) -> Self {
+    self.page.attachment = attachment.into_iter().collect();
+    self
+  }
+
+  pub(crate) fn image(mut self, image: Option<ImageObject>) -> Self {
+    self.page.image = image;
+    self
+  }
+
+  pub(crate) fn sensitive(mut self, sensitive: bool) -> Self {
+    self.page.sensitive = Some(sensitive);
+    self
+  }
+
+  pub(crate) fn language(mut self, language: Option<LanguageTag>) -> Self {
+    self.page.language = language;
+    self
+  }
+
+  pub(crate) fn original_language(mut self, original_language: Option<LanguageTag>) -> Self {
+    self.page.original_language = original_language;
+    self
+  }
+
+  pub(crate) fn timestamps(
+    mut self,
+    published: DateTime<Utc>,
+    updated: Option<DateTime<Utc>>,
+  ) -> Self {
+    self.page.published = Some(published);
+    self.page.updated = updated;
+    self
+  }
+
+  pub(crate) fn category(mut self, category: Option<String>) -> Self {
+    self.page.category = category;
+    self
+  }
+
+  pub(crate) fn crosspost_group(mut self, crosspost_group: Option<Url>) -> Self {
+    self.page.crosspost_group = crosspost_group;
+    self
+  }
+
+  pub(crate) fn pinned_comment(mut self, pinned_comment: Option<ObjectId<ApubComment>>) -> Self {
+    self.page.pinned_comment = pinned_comment;
+    self
+  }
+
+  pub(crate) fn original_content(mut self, original_content: bool) -> Self {
+    self.page.original_content = Some(original_content);
+    self
+  }
+
+  pub(crate) fn default_comment_sort(
+    mut self,
+    default_comment_sort: Option<CommentSortType>,
+  ) -> Self {
+    self.page.default_comment_sort = default_comment_sort;
+    self
+  }
+
+  pub(crate) fn close_at(mut self, close_at: Option<DateTime<Utc>>) -> Self {
+    self.page.close_at = close_at;
+    self
+  }
+
+  pub(crate) fn distinguished(mut self, distinguished: bool) -> Self {
+    self.page.distinguished = Some(distinguished);
+    self
+  }
+
+  pub(crate) fn tag(mut self, tag: Hashtag) -> Self {
+    self.page.tag.push(tag);
+    self
+  }
+
+  pub(crate) fn build(self) -> LemmyResult<Page> {
+    let page = self.page;
+    let invalid = |reason: &str| Err(LemmyErrorType::InvalidOutgoingPage(reason.to_string()));
+    if page.audience.is_empty() {
+      invalid("not addressed to a community")?
+    }
+    let has_name = page.name.as_deref().is_some_and(|n| !n.trim().is_empty());
+    if !has_name && page.content.is_none() && page.attachment.is_empty() {
+      invalid("needs a name, content or attachment")?
+    }
+    if page.source.is_some() && page.content.is_none() {
+      invalid("source without content")?
+    }
+    Ok(page)
+  }
+}
+
 #[cfg(test)]
 mod tests {
-  use crate::protocol::{objects::page::Page, tests::test_parse_lemmy_item};
+  use crate::protocol::{
+    objects::{
+      page::{Attachment, AttributedTo, Page, PageBuilder},
+      LanguageTag,
+    },
+    tests::{test_json, test_parse_lemmy_item},
+    Source,
+  };
+  use chrono::Utc;
+  use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+  use pretty_assertions::assert_eq;
+  use serde_json::{json, Value};
+  use std::collections::{BTreeMap, HashMap};
+  use url::Url;
+
+  fn page_builder() -> LemmyResult<PageBuilder> {
+    Ok(PageBuilder::new(
+      Url::parse("https://enterprise.lemmy.ml/post/1")?.into(),
+      Url::parse("https://enterprise.lemmy.ml/u/picard")?.into(),
+    ))
+  }
+
+  #[test]
+  fn test_page_builder() -> LemmyResult<()> {
+    let community = Url::parse("https://enterprise.lemmy.ml/c/main")?;
+    let page = page_builder()?
+      .community(community.clone().into())
+      .name("Title".to_string())
+      .content(
+        Some("<p>body</p>".to_string()),
+        Some(Source::new("body".to_string())),
+      )
+      .sensitive(false)
+      .timestamps(Utc::now(), None)
+      .build()?;
+    assert_eq!(Some(&community), page.to.first());
+    assert_eq!(page.audience, vec![community.into()]);
+    assert_eq!(
+      Some("body"),
+      page.source.as_ref().map(|s| s.content.as_str())
+    );
+
+    // A post with only a link is valid
+    let attachment = Attachment::new(Url::parse("https://example.com/")?, None, None, None);
+    let page = page_builder()?
+      .community(Url::parse("https://enterprise.lemmy.ml/c/main")?.into())
+      .attachment(Some(attachment))
+      .build()?;
+    assert_eq!(1, page.attachment.len());
+    Ok(())
+  }
+
+  #[test]
+  fn test_page_builder_invalid() -> LemmyResult<()> {
+    let community = Url::parse("https://enterprise.lemmy.ml/c/main")?;
+    let invalid = |reason: &str| LemmyErrorType::InvalidOutgoingPage(reason.to_string());
+
+    let res = page_builder()?.name("Title".to_string()).build();
+    assert!(res.is_err_and(|e| e.error_type == invalid("not addressed to a community")));
+
+    let res = page_builder()?
+      .community(community.clone().into())
+      .name(" ".to_string())
+      .build();
+    assert!(res.is_err_and(|e| e.error_type == invalid("needs a name, content or attachment")));
+
+    let res = page_builder()?
+      .community(community.into())
+      .name("Title".to_string())
+      .content(None, Some(Source::new("body".to_string())))
+      .build();
+    assert!(res.is_err_and(|e| e.error_type == invalid("source without content")));
+    Ok(())
+  }
 
   #[test]
   fn test_not_parsing_note_as_page() {
     assert!(test_parse_lemmy_item::<Page>("assets/lemmy/objects/note.json").is_err());
   }
+
+  #[test]
+  fn test_parse_attributed_to_string() -> LemmyResult<()> {
+    let page = test_parse_lemmy_item::<Page>("assets/lemmy/objects/page.json")?;
+    assert!(matches!(page.attributed_to, AttributedTo::Lemmy(_)));
+    assert_eq!(
+      "https://enterprise.lemmy.ml/u/picard",
+      page.creator()?.inner().as_str()
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_attributed_to_collection() -> LemmyResult<()> {
+    let page = test_parse_lemmy_item::<Page>("assets/lemmy/objects/page.json")?;
+    let mut json = serde_json::to_value(&page)?;
+    json["attributedTo"] = json!({
+      "type": "Collection",
+      "items": [
+        {"type": "Group", "id": "https://enterprise.lemmy.ml/c/tenforward"},
+        {"type": "Person", "id": "https://enterprise.lemmy.ml/u/picard"}
+      ]
+    });
+    let parsed: Page = serde_json::from_value(json.clone())?;
+    assert!(matches!(parsed.attributed_to, AttributedTo::Collection(_)));
+    assert_eq!(
+      "https://enterprise.lemmy.ml/u/picard",
+      parsed.creator()?.inner().as_str()
+    );
+
+    // Without a person there is no creator
+    json["attributedTo"] = json!({
+      "type": "OrderedCollection",
+      "orderedItems": [{"type": "Group", "id": "https://enterprise.lemmy.ml/c/tenforward"}]
+    });
+    let parsed: Page = serde_json::from_value(json)?;
+    assert!(parsed
+      .creator()
+      .is_err_and(|e| e.error_type == LemmyErrorType::PageDoesNotSpecifyCreator));
+    Ok(())
+  }
+
+  #[test]
+  fn test_url_round_trip() -> LemmyResult<()> {
+    let mut page = test_parse_lemmy_item::<Page>("assets/lemmy/objects/page.json")?;
+    assert_eq!(None, page.url);
+    let url = Url::parse("https://enterprise.lemmy.ml/p/55143")?;
+    page.url = Some(url.clone());
+
+    // The url is sent in addition to the id, and parsed back unchanged
+    let mut json: HashMap<String, Value> = serde_json::from_value(serde_json::to_value(&page)?)?;
+    assert_eq!(Some(&Value::String(url.to_string())), json.get("url"));
+    assert_eq!(
+      Some(&Value::String(page.id.inner().to_string())),
+      json.get("id")
+    );
+    let parsed: Page = serde_json::from_value(serde_json::to_value(&json)?)?;
+    assert_eq!(Some(url), parsed.url);
+
+    // A list of links as sent by Peertube is ignored
+    json.insert(
+      "url".to_string(),
+      json!([{"type": "Link", "href": "https://example.com/video.mp4"}]),
+    );
+    let parsed: Page = serde_json::from_value(serde_json::to_value(&json)?)?;
+    assert_eq!(None, parsed.url);
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_name_map() -> LemmyResult<()> {
+    let mut page = test_parse_lemmy_item::<Page>("assets/lemmy/objects/page_name_map.json")?;
+    assert_eq!(Some(2), page.name_map.as_ref().map(BTreeMap::len));
+    assert_eq!(Some("Deutscher Titel"), page.localized_name());
+
+    // Without a matching entry in nameMap, the default name is used
+    page.language = Some(LanguageTag {
+      identifier: "fr".to_string(),
+      name: "Français".to_string(),
+    });
+    assert_eq!(Some("English title"), page.localized_name());
+
+    page.name = None;
+    assert_eq!(Some("Deutscher Titel"), page.localized_name());
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_sensitive_attachment() -> LemmyResult<()> {
+    let page =
+      test_parse_lemmy_item::<Page>("assets/lemmy/objects/page_sensitive_attachment.json")?;
+    let sensitive: Vec<_> = page.attachment.iter().map(Attachment::sensitive).collect();
+    assert_eq!(vec![Some(true), None], sensitive);
+    assert_eq!(Some(false), page.sensitive);
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_image_dimensions() -> LemmyResult<()> {
+    let page = test_parse_lemmy_item::<Page>("assets/lemmy/objects/page_image_dimensions.json")?;
+    let dimensions: Vec<_> = page.attachment.iter().map(Attachment::dimensions).collect();
+    assert_eq!(vec![Some((1920, 1080))], dimensions);
+
+    // Attachments without dimensions are unchanged
+    let page = test_parse_lemmy_item::<Page>("assets/lemmy/objects/page.json")?;
+    let dimensions: Vec<_> = page.attachment.iter().map(Attachment::dimensions).collect();
+    assert_eq!(vec![None], dimensions);
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_multiple_audience() -> LemmyResult<()> {
+    let page = test_parse_lemmy_item::<Page>("assets/lemmy/objects/page_multiple_audience.json")?;
+    assert_eq!(3, page.audience.len());
+    // The first audience which is also addressed in `to` or `cc` is used
+    assert_eq!(
+      Some("https://enterprise.lemmy.ml/c/tenforward"),
+      page.primary_audience().map(|a| a.inner().as_str())
+    );
+    let additional: Vec<_> = page
+      .additional_audience()
+      .iter()
+      .map(|a| a.inner().as_str())
+      .collect();
+    assert_eq!(
+      vec![
+        "https://voyager.lemmy.ml/c/astrometrics",
+        "https://ds9.lemmy.ml/c/main"
+      ],
+      additional
+    );
+
+    // A single audience is still parsed and serialized as plain value
+    let page = test_parse_lemmy_item::<Page>("assets/lemmy/objects/page.json")?;
+    assert_eq!(1, page.audience.len());
+    let json = serde_json::to_value(&page)?;
+    assert!(json
+      .get("audience")
+      .is_some_and(serde_json::Value::is_string));
+    assert!(page.additional_audience().is_empty());
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_remote_views() -> LemmyResult<()> {
+    let page = test_json::<Page>("assets/peertube/objects/video.json")?;
+    assert_eq!(Some(83), page.inner().views);
+
+    let json = r#"{
+      "type": "Page",
+      "id": "https://example.com/post/1",
+      "attributedTo": "https://example.com/u/alice",
+      "ephemeral:views": 12
+    }"#;
+    let page: Page = serde_json::from_str(json)?;
+    assert_eq!(Some(12), page.views);
+
+    let page = test_parse_lemmy_item::<Page>("assets/lemmy/objects/page.json")?;
+    assert_eq!(None, page.views);
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_replies_total_items() -> LemmyResult<()> {
+    let json = r#"{
+      "type": "Page",
+      "id": "https://example.com/post/1",
+      "attributedTo": "https://example.com/u/alice",
+      "replies": {
+        "id": "https://example.com/post/1/replies",
+        "type": "Collection",
+        "totalItems": 42
+      }
+    }"#;
+    let page: Page = serde_json::from_str(json)?;
+    assert_eq!(Some(42), page.replies.and_then(|r| r.total_items));
+
+    // Replies collection without count, or only given as url
+    let page = test_json::<Page>("assets/mastodon/objects/page.json")?;
+    assert_eq!(
+      None,
+      page.inner().replies.as_ref().and_then(|r| r.total_items)
+    );
+    let json = r#"{
+      "type": "Page",
+      "id": "https://example.com/post/1",
+      "attributedTo": "https://example.com/u/alice",
+      "replies": "https://example.com/post/1/replies"
+    }"#;
+    let page: Page = serde_json::from_str(json)?;
+    assert!(page.replies.is_none());
+    Ok(())
+  }
 }
@@ -32,13 +32,62 @@ CREATE FUNCTION r.creator_id_from_comment_aggregates (agg comment_aggregates)
             comment.id = agg.comment_id LIMIT 1
 );
 
+-- Votes of users from an instance are multiplied by the instance's vote weight. Votes of shadow voters
+-- are not counted at all.
+CREATE FUNCTION r.vote_weight (person_id int)
+    RETURNS int
+    LANGUAGE sql
+    STABLE PARALLEL SAFE RETURN coalesce((
+        SELECT
+            CASE WHEN person.shadow_vote THEN
+                0
+            ELSE
+                instance.vote_weight
+            END
+        FROM person
+        INNER JOIN instance ON instance.id = person.instance_id
+    WHERE
+        person.id = vote_weight.person_id), 1);
+
 CREATE PROCEDURE r.post_or_comment (table_name text)
 LANGUAGE plpgsql
 AS $a$
 BEGIN
-    EXECUTE replace($b$
+    EXECUTE replace(replace(replace($b$
         -- When a thing gets a vote, update its aggregates and its creator's aggregates
         CALL r.create_triggers ('thing_like', $$
+            BEGIN
+                WITH diff AS (
+                    SELECT
+                        (thing_like).thing_id, coalesce(sum(count_diff * r.vote_weight ((thing_like).person_id)) FILTER (WHERE (thing_like).score = 1), 0) AS upvotes, coalesce(sum(count_diff * r.vote_weight ((thing_like).person_id)) FILTER (WHERE (thing_like).score != 1), 0) AS downvotes FROM select_old_and_new_rows AS old_and_new_rows GROUP BY (thing_like).thing_id),
+                queued_votes_ctes thing_diff AS ( UPDATE
+                        thing_aggregates AS a
+                    SET
+                        score = a.score + diff.upvotes - diff.downvotes, upvotes = a.upvotes + diff.upvotes, downvotes = a.downvotes + diff.downvotes, controversy_rank = r.controversy_rank ((a.upvotes + diff.upvotes)::numeric, (a.downvotes + diff.downvotes)::numeric)
+                    FROM diff
+            WHERE
+                a.thing_id = diff.thing_id
+                    AND (diff.upvotes, diff.downvotes) != (0, 0) immediate_votes_filter
+                RETURNING
+                    r.creator_id_from_thing_aggregates (a.*) AS creator_id, diff.upvotes - diff.downvotes AS score)
+            UPDATE
+                person_aggregates AS a
+            SET
+                thing_score = a.thing_score + diff.score FROM (
+                    SELECT
+                        creator_id, sum(score) AS score FROM thing_diff GROUP BY creator_id) AS diff
+                WHERE
+                    a.person_id = diff.creator_id
+                    AND diff.score != 0;
+                RETURN NULL;
+            END;
+    $$);
+        -- When the vote weight of an instance or a single person changes, apply the difference to their
+        -- votes. Shadow voters are only affected by changes to themselves.
+        CREATE FUNCTION r.thing_score_from_vote_weight (changed_instance_id int, changed_person_id int, weight_diff int)
+            RETURNS void
+            LANGUAGE plpgsql
+            AS $$
             BEGIN
                 WITH thing_diff AS ( UPDATE
                         thing_aggregates AS a
@@ -46,7 +95,12 @@ BEGIN
                         score = a.score + diff.upvotes - diff.downvotes, upvotes = a.upvotes + diff.upvotes, downvotes = a.downvotes + diff.downvotes, controversy_rank = r.controversy_rank ((a.upvotes + diff.upvotes)::numeric, (a.downvotes + diff.downvotes)::numeric)
                     FROM (
                         SELECT
-                            (thing_like).thing_id, coalesce(sum(count_diff) FILTER (WHERE (thing_like).score = 1), 0) AS upvotes, coalesce(sum(count_diff) FILTER (WHERE (thing_like).score != 1), 0) AS downvotes FROM select_old_and_new_rows AS old_and_new_rows GROUP BY (thing_like).thing_id) AS diff
+                            thing_like.thing_id, weight_diff * count(*) FILTER (WHERE thing_like.score = 1) AS upvotes, weight_diff * count(*) FILTER (WHERE thing_like.score != 1) AS downvotes FROM thing_like
+                    INNER JOIN person ON person.id = thing_like.person_id
+                WHERE
+                    person.id = changed_person_id
+                    OR (person.instance_id = changed_instance_id
+                        AND NOT person.shadow_vote) GROUP BY thing_like.thing_id) AS diff
             WHERE
                 a.thing_id = diff.thing_id
                     AND (diff.upvotes, diff.downvotes) != (0, 0)
@@ -61,12 +115,43 @@ BEGIN
                 WHERE
                     a.person_id = diff.creator_id
                     AND diff.score != 0;
-                RETURN NULL;
             END;
-    $$);
+    $$;
     $b$,
     'thing',
-    table_name);
+    table_name),
+    -- Votes of batched posts are queued instead, and added to the aggregates by r.flush_post_vote_queue. The
+    -- rows are locked so that batching can't be disabled before the queued votes are committed. If it gets
+    -- enabled concurrently, the vote is still added directly.
+    'queued_votes_ctes', CASE WHEN table_name = 'post' THEN
+        $c$ batched AS MATERIALIZED (
+            SELECT
+                post_id
+            FROM
+                post_vote_batching
+            WHERE
+                post_id IN (
+                    SELECT
+                        post_id
+                    FROM
+                        diff)
+                FOR SHARE),
+        queued AS (
+INSERT INTO post_vote_queue (post_id, upvotes, downvotes)
+    SELECT
+        diff.post_id, diff.upvotes, diff.downvotes
+    FROM
+        diff
+        INNER JOIN batched USING (post_id)
+    WHERE (diff.upvotes, diff.downvotes) != (0, 0)), $c$
+    ELSE
+        ''
+    END),
+    'immediate_votes_filter', CASE WHEN table_name = 'post' THEN
+        'AND a.post_id NOT IN (SELECT post_id FROM batched)'
+    ELSE
+        ''
+    END);
 END;
 $a$;
 
@@ -74,6 +159,123 @@ CALL r.post_or_comment ('post');
 
 CALL r.post_or_comment ('comment');
 
+-- Adds the queued votes of batched posts to the aggregates, and returns the number of flushed queue entries.
+CREATE FUNCTION r.flush_post_vote_queue ()
+    RETURNS int
+    LANGUAGE plpgsql
+    AS $$
+DECLARE
+    flushed int;
+BEGIN
+    WITH queued AS (
+        DELETE FROM post_vote_queue
+        RETURNING
+            post_id,
+            upvotes,
+            downvotes
+),
+diff AS (
+    SELECT
+        post_id,
+        sum(upvotes) AS upvotes,
+        sum(downvotes) AS downvotes,
+        count(*) AS votes
+    FROM
+        queued
+    GROUP BY
+        post_id
+),
+post_diff AS (
+    UPDATE
+        post_aggregates AS a
+    SET
+        score = a.score + diff.upvotes - diff.downvotes,
+        upvotes = a.upvotes + diff.upvotes,
+        downvotes = a.downvotes + diff.downvotes,
+        controversy_rank = r.controversy_rank ((a.upvotes + diff.upvotes)::numeric, (a.downvotes + diff.downvotes)::numeric)
+    FROM
+        diff
+    WHERE
+        a.post_id = diff.post_id
+    RETURNING
+        a.creator_id,
+        diff.upvotes - diff.downvotes AS score
+),
+person_diff AS (
+    UPDATE
+        person_aggregates AS a
+    SET
+        post_score = a.post_score + diff.score
+    FROM (
+        SELECT
+            creator_id,
+            sum(score) AS score
+        FROM
+            post_diff
+        GROUP BY
+            creator_id) AS diff
+    WHERE
+        a.person_id = diff.creator_id
+        AND diff.score != 0)
+SELECT
+    coalesce(sum(votes), 0) INTO flushed
+FROM
+    diff;
+    RETURN flushed;
+END;
+$$;
+
+CREATE FUNCTION r.score_from_instance_vote_weight ()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    PERFORM
+        r.comment_score_from_vote_weight (NEW.id, NULL, NEW.vote_weight - OLD.vote_weight);
+    PERFORM
+        r.post_score_from_vote_weight (NEW.id, NULL, NEW.vote_weight - OLD.vote_weight);
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER vote_weight
+    AFTER UPDATE OF vote_weight ON instance
+    FOR EACH ROW
+    WHEN (OLD.vote_weight != NEW.vote_weight)
+    EXECUTE FUNCTION r.score_from_instance_vote_weight ();
+
+-- Remove the votes of a person from scores when they become a shadow voter, and add them back afterwards
+CREATE FUNCTION r.score_from_shadow_vote ()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+    AS $$
+DECLARE
+    weight_diff int := (
+        SELECT
+            CASE WHEN NEW.shadow_vote THEN
+                - vote_weight
+            ELSE
+                vote_weight
+            END
+        FROM
+            instance
+        WHERE
+            id = NEW.instance_id);
+BEGIN
+    PERFORM
+        r.comment_score_from_vote_weight (NULL, NEW.id, weight_diff);
+    PERFORM
+        r.post_score_from_vote_weight (NULL, NEW.id, weight_diff);
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER shadow_vote
+    AFTER UPDATE OF shadow_vote ON person
+    FOR EACH ROW
+    WHEN (OLD.shadow_vote != NEW.shadow_vote)
+    EXECUTE FUNCTION r.score_from_shadow_vote ();
+
 -- Create triggers that update counts in parent aggregates
 CREATE FUNCTION r.parent_comment_ids (path ltree)
     RETURNS SETOF int
@@ -319,7 +521,8 @@ END;
 
 $$);
 
--- For community_aggregates.comments, don't include comments of deleted or removed posts
+-- For community_aggregates.comments, don't include comments of deleted or removed posts. When a
+-- post is moved to another community, its comments are moved along with it.
 CREATE FUNCTION r.update_comment_count_from_post ()
     RETURNS TRIGGER
     LANGUAGE plpgsql
@@ -331,20 +534,34 @@ BEGIN
         comments = a.comments + diff.comments
     FROM (
         SELECT
-            old_post.community_id,
-            sum((
-                CASE WHEN r.is_counted (new_post.*) THEN
-                    1
-                ELSE
-                    -1
-                END) * post_aggregates.comments) AS comments
-        FROM
-            new_post
-            INNER JOIN old_post ON new_post.id = old_post.id
-                AND (r.is_counted (new_post.*) != r.is_counted (old_post.*))
+            changed_post.community_id,
+            sum(changed_post.comments) AS comments
+        FROM (
+            SELECT
+                old_post.community_id,
+                - post_aggregates.comments AS comments
+            FROM
+                new_post
+                INNER JOIN old_post ON new_post.id = old_post.id
+                INNER JOIN post_aggregates ON post_aggregates.post_id = new_post.id
+            WHERE
+                r.is_counted (old_post.*)
+                AND (r.is_counted (new_post.*) != r.is_counted (old_post.*)
+                    OR new_post.community_id != old_post.community_id)
+            UNION ALL
+            SELECT
+                new_post.community_id,
+                post_aggregates.comments
+            FROM
+                new_post
+                INNER JOIN old_post ON new_post.id = old_post.id
                 INNER JOIN post_aggregates ON post_aggregates.post_id = new_post.id
-            GROUP BY
-                old_post.community_id) AS diff
+            WHERE
+                r.is_counted (new_post.*)
+                AND (r.is_counted (new_post.*) != r.is_counted (old_post.*)
+                    OR new_post.community_id != old_post.community_id)) AS changed_post
+    GROUP BY
+        changed_post.community_id) AS diff
 WHERE
     a.community_id = diff.community_id
         AND diff.comments != 0;
@@ -448,7 +665,7 @@ CREATE FUNCTION r.post_aggregates_from_post ()
     LANGUAGE plpgsql
     AS $$
 BEGIN
-    INSERT INTO post_aggregates (post_id, published, newest_comment_time, newest_comment_time_necro, community_id, creator_id, instance_id, featured_community, featured_local)
+    INSERT INTO post_aggregates (post_id, published, newest_comment_time, newest_comment_time_necro, community_id, creator_id, instance_id, featured_community, featured_local, featured_position)
     SELECT
         new_post.id,
         new_post.published,
@@ -458,7 +675,8 @@ BEGIN
         new_post.creator_id,
         community.instance_id,
         new_post.featured_community,
-        new_post.featured_local
+        new_post.featured_local,
+        new_post.featured_position
     FROM
         new_post
         INNER JOIN community ON community.id = new_post.community_id;
@@ -480,13 +698,21 @@ BEGIN
         post_aggregates
     SET
         featured_community = new_post.featured_community,
-        featured_local = new_post.featured_local
+        featured_local = new_post.featured_local,
+        featured_position = new_post.featured_position,
+        community_id = new_post.community_id,
+        instance_id = community.instance_id
     FROM
         new_post
         INNER JOIN old_post ON old_post.id = new_post.id
             AND (old_post.featured_community,
-                old_post.featured_local) != (new_post.featured_community,
-                new_post.featured_local)
+                old_post.featured_local,
+                old_post.featured_position,
+                old_post.community_id) != (new_post.featured_community,
+                new_post.featured_local,
+                new_post.featured_position,
+                new_post.community_id)
+        INNER JOIN community ON community.id = new_post.community_id
     WHERE
         post_aggregates.post_id = new_post.id;
     RETURN NULL;
@@ -552,6 +778,25 @@ CREATE TRIGGER delete_follow
     FOR EACH ROW
     EXECUTE FUNCTION r.delete_follow_before_person ();
 
+-- Votes are weighted by the instance of the voter, which can't be read anymore after the person is deleted
+CREATE FUNCTION r.delete_likes_before_person ()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    DELETE FROM comment_like AS c
+    WHERE c.person_id = OLD.id;
+    DELETE FROM post_like AS p
+    WHERE p.person_id = OLD.id;
+    RETURN OLD;
+END;
+$$;
+
+CREATE TRIGGER delete_likes
+    BEFORE DELETE ON person
+    FOR EACH ROW
+    EXECUTE FUNCTION r.delete_likes_before_person ();
+
 -- Triggers that change values before insert or update
 CREATE FUNCTION r.comment_change_values ()
     RETURNS TRIGGER
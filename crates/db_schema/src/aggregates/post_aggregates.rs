@@ -1,15 +1,31 @@
 use crate::{
   aggregates::structs::PostAggregates,
   diesel::OptionalExtension,
-  newtypes::PostId,
-  schema::{community_aggregates, post, post_aggregates},
+  newtypes::{PersonId, PostId},
+  schema::{
+    comment,
+    community_aggregates,
+    person_aggregates,
+    post,
+    post_aggregates,
+    post_like,
+    post_vote_batching,
+    post_vote_queue,
+  },
   utils::{
-    functions::{hot_rank, scaled_rank},
+    functions::{controversy_rank, flush_post_vote_queue, hot_rank, scaled_rank, vote_weight},
     get_conn,
+    now,
     DbPool,
   },
 };
-use diesel::{result::Error, ExpressionMethods, JoinOnDsl, QueryDsl};
+use diesel::{
+  dsl::{insert_into, not, sum, IntervalDsl},
+  result::Error,
+  ExpressionMethods,
+  JoinOnDsl,
+  QueryDsl,
+};
 use diesel_async::RunQueryDsl;
 
 impl PostAggregates {
@@ -51,6 +67,120 @@ impl PostAggregates {
       .get_result::<Self>(conn)
       .await
   }
+
+  /// Recounts the votes and comments of a post from the `post_like` and `comment` tables, and
+  /// overwrites the aggregates with the result. This repairs aggregates which have drifted from the
+  /// actual votes, and running it again without new votes doesn't change anything. Votes which are
+  /// still in the batching queue are left out, because flushing the queue adds them later. The
+  /// post score of the creator is corrected by the same difference.
+  pub async fn recompute(pool: &mut DbPool<'_>, post_id: PostId) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let weighted_votes = |score: i16| {
+            post_like::table
+              .filter(post_like::post_id.eq(post_id))
+              .filter(post_like::score.eq(score))
+              .select(sum(vote_weight(post_like::person_id)))
+          };
+          let upvotes = weighted_votes(1)
+            .first::<Option<i64>>(conn)
+            .await?
+            .unwrap_or_default();
+          let downvotes = weighted_votes(-1)
+            .first::<Option<i64>>(conn)
+            .await?
+            .unwrap_or_default();
+          let (queued_upvotes, queued_downvotes) = post_vote_queue::table
+            .filter(post_vote_queue::post_id.eq(post_id))
+            .select((post_vote_queue::upvotes, post_vote_queue::downvotes))
+            .load::<(i64, i64)>(conn)
+            .await?
+            .into_iter()
+            .fold((0, 0), |(up, down), (queued_up, queued_down)| {
+              (up + queued_up, down + queued_down)
+            });
+          let upvotes = upvotes - queued_upvotes;
+          let downvotes = downvotes - queued_downvotes;
+          let comments = comment::table
+            .filter(comment::post_id.eq(post_id))
+            .filter(comment::deleted.eq(false))
+            .filter(comment::removed.eq(false))
+            .count()
+            .get_result::<i64>(conn)
+            .await?;
+          let (creator_id, previous_score) = post_aggregates::table
+            .find(post_id)
+            .select((post_aggregates::creator_id, post_aggregates::score))
+            .first::<(PersonId, i64)>(conn)
+            .await?;
+          let score = upvotes - downvotes;
+
+          diesel::update(post_aggregates::table.find(post_id))
+            .set((
+              post_aggregates::upvotes.eq(upvotes),
+              post_aggregates::downvotes.eq(downvotes),
+              post_aggregates::score.eq(score),
+              post_aggregates::controversy_rank.eq(controversy_rank(upvotes, downvotes, score)),
+              post_aggregates::comments.eq(comments),
+            ))
+            .execute(conn)
+            .await?;
+          diesel::update(person_aggregates::table.find(creator_id))
+            .set(
+              person_aggregates::post_score
+                .eq(person_aggregates::post_score + (score - previous_score)),
+            )
+            .execute(conn)
+            .await?;
+          Self::update_ranks(&mut conn.into(), post_id).await
+        }) as _
+      })
+      .await
+  }
+
+  /// Switches posts from the last day with at least `threshold` votes to batched vote updates, and
+  /// all other posts back to immediate updates. Without threshold, batching is disabled for all
+  /// posts.
+  pub async fn update_vote_batching(
+    pool: &mut DbPool<'_>,
+    threshold: Option<i32>,
+  ) -> Result<(), Error> {
+    let conn = &mut get_conn(pool).await?;
+    let Some(threshold) = threshold else {
+      diesel::delete(post_vote_batching::table)
+        .execute(conn)
+        .await?;
+      return Ok(());
+    };
+    let hot_posts = post_aggregates::table
+      .filter(post_aggregates::published.gt(now() - 1.days()))
+      .filter((post_aggregates::upvotes + post_aggregates::downvotes).ge(i64::from(threshold)))
+      .select(post_aggregates::post_id);
+    diesel::delete(
+      post_vote_batching::table.filter(not(post_vote_batching::post_id.eq_any(hot_posts))),
+    )
+    .execute(conn)
+    .await?;
+    insert_into(post_vote_batching::table)
+      .values(hot_posts)
+      .into_columns(post_vote_batching::post_id)
+      .on_conflict_do_nothing()
+      .execute(conn)
+      .await?;
+    Ok(())
+  }
+
+  /// Adds the queued votes of batched posts to the aggregates. Returns the number of flushed queue
+  /// entries.
+  pub async fn flush_vote_queue(pool: &mut DbPool<'_>) -> Result<i32, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::select(flush_post_vote_queue())
+      .get_result(conn)
+      .await
+  }
 }
 
 #[cfg(test)]
@@ -59,17 +189,21 @@ impl PostAggregates {
 mod tests {
 
   use crate::{
-    aggregates::post_aggregates::PostAggregates,
+    aggregates::{post_aggregates::PostAggregates, structs::PersonAggregates},
+    schema::{person_aggregates, post_aggregates},
     source::{
       comment::{Comment, CommentInsertForm, CommentUpdateForm},
       community::{Community, CommunityInsertForm},
-      instance::Instance,
-      person::{Person, PersonInsertForm},
+      instance::{Instance, InstanceForm},
+      person::{Person, PersonInsertForm, PersonUpdateForm},
       post::{Post, PostInsertForm, PostLike, PostLikeForm},
     },
     traits::{Crud, Likeable},
-    utils::build_db_pool_for_tests,
+    utils::{build_db_pool_for_tests, get_conn},
   };
+  use diesel::{ExpressionMethods, QueryDsl};
+  use diesel_async::RunQueryDsl;
+  use futures_util::future::try_join_all;
   use pretty_assertions::assert_eq;
   use serial_test::serial;
 
@@ -326,4 +460,393 @@ mod tests {
       .unwrap();
     Instance::delete(pool, inserted_instance.id).await.unwrap();
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_vote_weight() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let voter_instance = Instance::read_or_create(pool, "vote_weight.tld".to_string())
+      .await
+      .unwrap();
+    assert_eq!(1, voter_instance.vote_weight);
+
+    let new_person = PersonInsertForm::test_form(inserted_instance.id, "thommy_vote_weight");
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+    let voter_form = PersonInsertForm::test_form(voter_instance.id, "jerry_vote_weight");
+    let voter = Person::create(pool, &voter_form).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("TIL_vote_weight".into())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let new_post = PostInsertForm::builder()
+      .name("A test post".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+
+    let set_vote_weight = |vote_weight| {
+      InstanceForm::builder()
+        .domain(voter_instance.domain.clone())
+        .vote_weight(Some(vote_weight))
+        .build()
+    };
+
+    // Votes from an instance with zero weight are ignored
+    Instance::update(pool, voter_instance.id, set_vote_weight(0))
+      .await
+      .unwrap();
+    let post_like = PostLikeForm {
+      post_id: inserted_post.id,
+      person_id: voter.id,
+      score: 1,
+    };
+    PostLike::like(pool, &post_like).await.unwrap();
+    let local_like = PostLikeForm {
+      post_id: inserted_post.id,
+      person_id: inserted_person.id,
+      score: 1,
+    };
+    PostLike::like(pool, &local_like).await.unwrap();
+
+    let zero_weight = PostAggregates::read(pool, inserted_post.id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(1, zero_weight.score);
+    assert_eq!(1, zero_weight.upvotes);
+    assert_eq!(0, zero_weight.downvotes);
+
+    // Changing the weight applies to existing votes
+    Instance::update(pool, voter_instance.id, set_vote_weight(2))
+      .await
+      .unwrap();
+    let double_weight = PostAggregates::read(pool, inserted_post.id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(3, double_weight.score);
+    assert_eq!(3, double_weight.upvotes);
+
+    Instance::update(pool, voter_instance.id, set_vote_weight(0))
+      .await
+      .unwrap();
+    let reset_weight = PostAggregates::read(pool, inserted_post.id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(1, reset_weight.score);
+    assert_eq!(1, reset_weight.upvotes);
+
+    // Removing an ignored vote doesn't change the score
+    PostLike::remove(pool, voter.id, inserted_post.id)
+      .await
+      .unwrap();
+    let after_remove = PostAggregates::read(pool, inserted_post.id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(1, after_remove.score);
+    assert_eq!(1, after_remove.upvotes);
+
+    Instance::delete(pool, voter_instance.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_shadow_vote() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::test_form(inserted_instance.id, "thommy_shadow_vote");
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+    let voter_form = PersonInsertForm::test_form(inserted_instance.id, "jerry_shadow_vote");
+    let voter = Person::create(pool, &voter_form).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("TIL_shadow_vote".into())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let new_post = PostInsertForm::builder()
+      .name("A test post".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+
+    let set_shadow_vote = |shadow_vote| PersonUpdateForm {
+      shadow_vote: Some(shadow_vote),
+      ..Default::default()
+    };
+
+    // Votes of a shadow voter are stored, but not counted
+    Person::update(pool, voter.id, &set_shadow_vote(true))
+      .await
+      .unwrap();
+    let post_like = PostLikeForm {
+      post_id: inserted_post.id,
+      person_id: voter.id,
+      score: 1,
+    };
+    PostLike::like(pool, &post_like).await.unwrap();
+    let shadow = PostAggregates::read(pool, inserted_post.id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(0, shadow.score);
+    assert_eq!(0, shadow.upvotes);
+
+    // The flag also applies to existing votes
+    Person::update(pool, voter.id, &set_shadow_vote(false))
+      .await
+      .unwrap();
+    let counted = PostAggregates::read(pool, inserted_post.id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(1, counted.score);
+    assert_eq!(1, counted.upvotes);
+
+    Person::update(pool, voter.id, &set_shadow_vote(true))
+      .await
+      .unwrap();
+    let hidden = PostAggregates::read(pool, inserted_post.id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(0, hidden.score);
+    assert_eq!(0, hidden.upvotes);
+
+    // Changes to the instance vote weight don't affect the shadow voter
+    let form = InstanceForm::builder()
+      .domain(inserted_instance.domain.clone())
+      .vote_weight(Some(2))
+      .build();
+    Instance::update(pool, inserted_instance.id, form)
+      .await
+      .unwrap();
+    let weighted = PostAggregates::read(pool, inserted_post.id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(0, weighted.score);
+
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_batched_votes() {
+    let actual_pool = &build_db_pool_for_tests().await;
+    let pool = &mut actual_pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let new_person = PersonInsertForm::test_form(inserted_instance.id, "thommy_batched_votes");
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+    let mut voters = vec![];
+    for i in 0..10 {
+      let form = PersonInsertForm::test_form(inserted_instance.id, &format!("voter_batched_{i}"));
+      voters.push(Person::create(pool, &form).await.unwrap());
+    }
+
+    let new_community = CommunityInsertForm::builder()
+      .name("TIL_batched_votes".into())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+    let new_post = PostInsertForm::builder()
+      .name("A viral post".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+    let like = |person_id| PostLikeForm {
+      post_id: inserted_post.id,
+      person_id,
+      score: 1,
+    };
+
+    // Posts below the threshold are updated immediately
+    PostLike::like(pool, &like(voters[0].id)).await.unwrap();
+    PostAggregates::update_vote_batching(pool, Some(2))
+      .await
+      .unwrap();
+    PostLike::like(pool, &like(voters[1].id)).await.unwrap();
+    let immediate = PostAggregates::read(pool, inserted_post.id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(2, immediate.score);
+
+    // Once the post is hot, concurrent votes are queued
+    PostAggregates::update_vote_batching(pool, Some(2))
+      .await
+      .unwrap();
+    let votes = voters[2..]
+      .iter()
+      .map(|voter| async move { PostLike::like(&mut actual_pool.into(), &like(voter.id)).await });
+    try_join_all(votes).await.unwrap();
+    let queued = PostAggregates::read(pool, inserted_post.id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(2, queued.score);
+
+    // Flushing the queue makes the aggregates converge
+    assert_eq!(8, PostAggregates::flush_vote_queue(pool).await.unwrap());
+    let flushed = PostAggregates::read(pool, inserted_post.id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(10, flushed.score);
+    assert_eq!(10, flushed.upvotes);
+    let creator = PersonAggregates::read(pool, inserted_person.id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(10, creator.post_score);
+
+    // After disabling batching, votes are counted immediately again
+    PostAggregates::update_vote_batching(pool, None)
+      .await
+      .unwrap();
+    PostLike::remove(pool, voters[0].id, inserted_post.id)
+      .await
+      .unwrap();
+    let immediate = PostAggregates::read(pool, inserted_post.id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(9, immediate.score);
+    assert_eq!(0, PostAggregates::flush_vote_queue(pool).await.unwrap());
+
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_recompute() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let new_person = PersonInsertForm::test_form(inserted_instance.id, "thommy_recompute");
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+    let voter_form = PersonInsertForm::test_form(inserted_instance.id, "jerry_recompute");
+    let voter = Person::create(pool, &voter_form).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("TIL_recompute".into())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+    let new_post = PostInsertForm::builder()
+      .name("A test post".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+
+    let comment_form = CommentInsertForm::builder()
+      .content("A test comment".into())
+      .creator_id(inserted_person.id)
+      .post_id(inserted_post.id)
+      .build();
+    Comment::create(pool, &comment_form, None).await.unwrap();
+    let deleted_comment = Comment::create(pool, &comment_form, None).await.unwrap();
+    Comment::update(
+      pool,
+      deleted_comment.id,
+      &CommentUpdateForm {
+        deleted: Some(true),
+        ..Default::default()
+      },
+    )
+    .await
+    .unwrap();
+    let like = |person_id, score| PostLikeForm {
+      post_id: inserted_post.id,
+      person_id,
+      score,
+    };
+    PostLike::like(pool, &like(inserted_person.id, 1))
+      .await
+      .unwrap();
+    PostLike::like(pool, &like(voter.id, -1)).await.unwrap();
+
+    // Make the aggregates drift from the actual votes and comments
+    {
+      let conn = &mut get_conn(pool).await.unwrap();
+      diesel::update(post_aggregates::table.find(inserted_post.id))
+        .set((
+          post_aggregates::score.eq(50),
+          post_aggregates::upvotes.eq(60),
+          post_aggregates::downvotes.eq(10),
+          post_aggregates::controversy_rank.eq(0.0),
+          post_aggregates::comments.eq(7),
+        ))
+        .execute(conn)
+        .await
+        .unwrap();
+      diesel::update(person_aggregates::table.find(inserted_person.id))
+        .set(person_aggregates::post_score.eq(person_aggregates::post_score + 50))
+        .execute(conn)
+        .await
+        .unwrap();
+    }
+
+    let recomputed = PostAggregates::recompute(pool, inserted_post.id)
+      .await
+      .unwrap();
+    assert_eq!(0, recomputed.score);
+    assert_eq!(1, recomputed.upvotes);
+    assert_eq!(1, recomputed.downvotes);
+    assert_eq!(1, recomputed.comments);
+    assert_eq!(2.0, recomputed.controversy_rank);
+    let creator = PersonAggregates::read(pool, inserted_person.id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(0, creator.post_score);
+
+    // Running it again changes nothing
+    let again = PostAggregates::recompute(pool, inserted_post.id)
+      .await
+      .unwrap();
+    assert_eq!(recomputed, again);
+    let creator_again = PersonAggregates::read(pool, inserted_person.id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(creator, creator_again);
+
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
 }
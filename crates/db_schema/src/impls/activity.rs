@@ -2,7 +2,7 @@ use crate::{
   diesel::OptionalExtension,
   newtypes::{ActivityId, DbUrl},
   source::activity::{ReceivedActivity, SentActivity, SentActivityForm},
-  utils::{get_conn, DbPool},
+  utils::{get_conn, limit_and_offset, DbPool},
 };
 use diesel::{
   dsl::insert_into,
@@ -39,6 +39,25 @@ impl SentActivity {
     let conn = &mut get_conn(pool).await?;
     sent_activity.find(object_id).first(conn).await.optional()
   }
+
+  /// Lists activities sent by the given actor, newest first.
+  pub async fn list_by_actor(
+    pool: &mut DbPool<'_>,
+    actor_apub_id_: &DbUrl,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::sent_activity::dsl::{actor_apub_id, id, sent_activity};
+    let conn = &mut get_conn(pool).await?;
+    let (limit, offset) = limit_and_offset(page, limit)?;
+    sent_activity
+      .filter(actor_apub_id.eq(actor_apub_id_))
+      .order_by(id.desc())
+      .limit(limit)
+      .offset(offset)
+      .load(conn)
+      .await
+  }
 }
 
 impl ReceivedActivity {
@@ -70,7 +89,7 @@ impl ReceivedActivity {
 mod tests {
 
   use super::*;
-  use crate::{source::activity::ActorType, utils::build_db_pool_for_tests};
+  use crate::{schema::sent_activity, source::activity::ActorType, utils::build_db_pool_for_tests};
   use pretty_assertions::assert_eq;
   use serde_json::json;
   use serial_test::serial;
@@ -127,4 +146,60 @@ mod tests {
     assert_eq!(res.data, data);
     assert_eq!(res.sensitive, sensitive);
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn sent_activity_list_by_actor() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let actor: DbUrl = Url::parse("http://example.com/u/list_actor_1")
+      .unwrap()
+      .into();
+    let other_actor: DbUrl = Url::parse("http://example.com/u/list_actor_2")
+      .unwrap()
+      .into();
+
+    let form = |ap_id: &str, actor: &DbUrl| SentActivityForm {
+      ap_id: Url::parse(ap_id).unwrap().into(),
+      data: json!({}),
+      sensitive: false,
+      actor_apub_id: actor.clone(),
+      actor_type: ActorType::Person,
+      send_all_instances: false,
+      send_community_followers_of: None,
+      send_inboxes: vec![],
+    };
+    let first = SentActivity::create(pool, form("http://example.com/activity/701", &actor))
+      .await
+      .unwrap();
+    let second = SentActivity::create(pool, form("http://example.com/activity/702", &actor))
+      .await
+      .unwrap();
+    SentActivity::create(pool, form("http://example.com/activity/703", &other_actor))
+      .await
+      .unwrap();
+
+    // Only the actor's own activities are returned, newest first
+    let res = SentActivity::list_by_actor(pool, &actor, None, None)
+      .await
+      .unwrap();
+    assert_eq!(
+      vec![second.ap_id.clone(), first.ap_id],
+      res.into_iter().map(|a| a.ap_id).collect::<Vec<_>>()
+    );
+
+    let res = SentActivity::list_by_actor(pool, &actor, Some(1), Some(1))
+      .await
+      .unwrap();
+    assert_eq!(1, res.len());
+    assert_eq!(second.ap_id, res[0].ap_id);
+
+    let conn = &mut get_conn(pool).await.unwrap();
+    diesel::delete(
+      sent_activity::table.filter(sent_activity::actor_apub_id.eq_any([actor, other_actor])),
+    )
+    .execute(conn)
+    .await
+    .unwrap();
+  }
 }
@@ -231,9 +231,7 @@ impl CommunityLanguage {
     for_instance_id: InstanceId,
   ) -> Result<(), Error> {
     use crate::schema::{
-      community::dsl as c,
-      community_language::dsl as cl,
-      site_language::dsl as sl,
+      community::dsl as c, community_language::dsl as cl, site_language::dsl as sl,
     };
     let community_languages: Vec<LanguageId> = cl::community_language
       .left_outer_join(sl::site_language.on(cl::language_id.eq(sl::language_id)))
@@ -1,6 +1,6 @@
 use crate::{
   diesel::{DecoratableTarget, OptionalExtension},
-  newtypes::{CommentId, DbUrl, PersonId},
+  newtypes::{CommentId, DbUrl, PersonId, PostId},
   schema::comment,
   source::comment::{
     Comment,
@@ -52,6 +52,19 @@ impl Comment {
       .await
   }
 
+  /// All comments of the given post, ordered so that parents come before their replies.
+  pub async fn list_for_post(
+    pool: &mut DbPool<'_>,
+    for_post_id: PostId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    comment::table
+      .filter(comment::post_id.eq(for_post_id))
+      .order_by(comment::path)
+      .load::<Self>(conn)
+      .await
+  }
+
   pub async fn create(
     pool: &mut DbPool<'_>,
     comment_form: &CommentInsertForm,
@@ -99,6 +112,13 @@ impl Comment {
       .optional()
   }
 
+  /// Path of this comment's ancestor at the given depth, where `0` is the post itself. Returns the
+  /// path of this comment if it isn't nested as deeply.
+  pub fn ancestor_path(&self, depth: usize) -> Ltree {
+    let path = self.path.0.split('.').take(depth + 1).collect::<Vec<_>>();
+    Ltree(path.join("."))
+  }
+
   pub fn parent_comment_id(&self) -> Option<CommentId> {
     let mut ltree_split: Vec<&str> = self.path.0.split('.').collect();
     ltree_split.remove(0); // The first is always 0
@@ -282,6 +302,7 @@ mod tests {
       .into(),
       distinguished: false,
       local: true,
+      remote_child_count: None,
       language_id: LanguageId::default(),
     };
 
@@ -1,7 +1,7 @@
 use crate::{
   diesel::{DecoratableTarget, OptionalExtension},
-  newtypes::{CommunityId, DbUrl, PersonId},
-  schema::{community, community_follower, instance},
+  newtypes::{CommunityId, DbUrl, InstanceId, PersonId},
+  schema::{community, community_aggregates, community_follower, instance},
   source::{
     actor_language::CommunityLanguage,
     community::{
@@ -25,7 +25,7 @@ use crate::{
   },
   SubscribedType,
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Timelike, Utc};
 use diesel::{
   deserialize,
   dsl,
@@ -112,6 +112,18 @@ pub enum CollectionType {
 }
 
 impl Community {
+  /// Whether the posting window of the community allows posts at the given time.
+  pub fn is_posting_open(&self, now: DateTime<Utc>) -> bool {
+    let (start, end) = (self.posting_window_start, self.posting_window_end);
+    let hour = i16::try_from(now.hour()).unwrap_or_default();
+    if start <= end {
+      start == end || (start..end).contains(&hour)
+    } else {
+      // Window which extends past midnight
+      hour >= start || hour < end
+    }
+  }
+
   pub async fn insert_apub(
     pool: &mut DbPool<'_>,
     timestamp: DateTime<Utc>,
@@ -172,6 +184,28 @@ impl Community {
     }
   }
 
+  /// Remote communities of the given instance which have local followers, with the most followed
+  /// communities first.
+  pub async fn list_followed_for_instance(
+    pool: &mut DbPool<'_>,
+    for_instance_id: InstanceId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community::table
+      .inner_join(community_aggregates::table)
+      .filter(community::instance_id.eq(for_instance_id))
+      .filter(community::local.eq(false))
+      .filter(community::deleted.eq(false))
+      .filter(community::removed.eq(false))
+      .filter(exists(
+        community_follower::table.filter(community_follower::community_id.eq(community::id)),
+      ))
+      .order_by(community_aggregates::subscribers_local.desc())
+      .select(community::all_columns)
+      .load(conn)
+      .await
+  }
+
   pub async fn set_featured_posts(
     community_id: CommunityId,
     posts: Vec<Post>,
@@ -182,15 +216,27 @@ impl Community {
     for p in &posts {
       debug_assert!(p.community_id == community_id);
     }
-    // Mark the given posts as featured and all other posts as not featured.
+    // Mark the given posts as featured and all other posts as not featured. Positions are reset
+    // here and assigned below.
     let post_ids = posts.iter().map(|p| p.id);
     update(post::table)
       .filter(post::dsl::community_id.eq(community_id))
       // This filter is just for performance
       .filter(post::dsl::featured_community.or(post::dsl::id.eq_any(post_ids.clone())))
-      .set(post::dsl::featured_community.eq(post::dsl::id.eq_any(post_ids)))
+      .set((
+        post::dsl::featured_community.eq(post::dsl::id.eq_any(post_ids)),
+        post::dsl::featured_position.eq(0),
+      ))
       .execute(conn)
       .await?;
+    // Posts are given in the order in which they should be shown, so the first one gets the
+    // highest position.
+    for (position, p) in posts.iter().rev().enumerate() {
+      update(post::table.find(p.id))
+        .set(post::dsl::featured_position.eq(position as i32 + 1))
+        .execute(conn)
+        .await?;
+    }
     Ok(())
   }
 }
@@ -420,6 +466,7 @@ mod tests {
     traits::{Bannable, Crud, Followable, Joinable},
     utils::build_db_pool_for_tests,
     CommunityVisibility,
+    DuplicateTitleAction,
   };
   use pretty_assertions::assert_eq;
   use serial_test::serial;
@@ -473,6 +520,17 @@ mod tests {
       posting_restricted_to_mods: false,
       instance_id: inserted_instance.id,
       visibility: CommunityVisibility::Public,
+      posting_window_start: 0,
+      posting_window_end: 0,
+      federation_paused: false,
+      federation_incoming_paused: false,
+      slur_filter_regex: None,
+      quarantined_until: None,
+      allow_nsfw: true,
+      duplicate_title_hours: 0,
+      duplicate_title_action: DuplicateTitleAction::Report,
+      min_post_karma: 0,
+      post_interval_secs: 0,
     };
 
     let community_follower_form = CommunityFollowerForm {
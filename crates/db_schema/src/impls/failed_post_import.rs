@@ -0,0 +1,89 @@
+use crate::{
+  newtypes::{DbUrl, FailedPostImportId},
+  schema::failed_post_import,
+  source::failed_post_import::{FailedPostImport, FailedPostImportForm},
+  utils::{functions::coalesce, get_conn, limit_and_offset, naive_now, now, DbPool},
+};
+use diesel::{
+  dsl::IntervalDsl,
+  insert_into,
+  result::Error,
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+impl FailedPostImport {
+  /// Store a failed import. If the same post already failed before, its data and error are
+  /// replaced.
+  pub async fn upsert(pool: &mut DbPool<'_>, form: &FailedPostImportForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let update_form = FailedPostImportForm {
+      updated: Some(naive_now()),
+      ..form.clone()
+    };
+    insert_into(failed_post_import::table)
+      .values(form)
+      .on_conflict(failed_post_import::ap_id)
+      .do_update()
+      .set(&update_form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn read(pool: &mut DbPool<'_>, id: FailedPostImportId) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    failed_post_import::table
+      .find(id)
+      .first(conn)
+      .await
+      .optional()
+  }
+
+  pub async fn read_from_apub_id(
+    pool: &mut DbPool<'_>,
+    ap_id: &DbUrl,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    failed_post_import::table
+      .filter(failed_post_import::ap_id.eq(ap_id))
+      .first(conn)
+      .await
+      .optional()
+  }
+
+  /// Lists failed imports, newest first.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let (limit, offset) = limit_and_offset(page, limit)?;
+    failed_post_import::table
+      .order_by(failed_post_import::id.desc())
+      .limit(limit)
+      .offset(offset)
+      .load(conn)
+      .await
+  }
+
+  pub async fn delete(pool: &mut DbPool<'_>, id: FailedPostImportId) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(failed_post_import::table.find(id))
+      .execute(conn)
+      .await
+  }
+
+  /// Deletes failed imports which were not attempted again for a month. By then the post is most
+  /// likely outdated, or the problem was not temporary after all.
+  pub async fn delete_old(pool: &mut DbPool<'_>) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(failed_post_import::table.filter(
+      coalesce(failed_post_import::updated, failed_post_import::published).lt(now() - 30.days()),
+    ))
+    .execute(conn)
+    .await
+  }
+}
@@ -18,6 +18,7 @@ use diesel::{
   select,
   ExpressionMethods,
   NotFound,
+  OptionalExtension,
   QueryDsl,
 };
 use diesel_async::{AsyncPgConnection, RunQueryDsl};
@@ -83,6 +84,26 @@ impl RemoteImage {
       .await
   }
 
+  /// Allows a federated image to be proxied, without storing any details about it.
+  pub async fn register(pool: &mut DbPool<'_>, link: DbUrl) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(remote_image::table)
+      .values(RemoteImageForm { link })
+      .on_conflict_do_nothing()
+      .execute(conn)
+      .await
+  }
+
+  /// Returns the given links which can be proxied.
+  pub async fn filter_valid(pool: &mut DbPool<'_>, links: Vec<DbUrl>) -> Result<Vec<DbUrl>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    remote_image::table
+      .filter(remote_image::link.eq_any(links))
+      .select(remote_image::link)
+      .load(conn)
+      .await
+  }
+
   pub async fn validate(pool: &mut DbPool<'_>, link_: DbUrl) -> Result<(), Error> {
     let conn = &mut get_conn(pool).await?;
 
@@ -100,6 +121,21 @@ impl RemoteImage {
 }
 
 impl ImageDetails {
+  pub async fn read(pool: &mut DbPool<'_>, link: &DbUrl) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    image_details::table.find(link).first(conn).await.optional()
+  }
+
+  /// Stores image details which were received over federation. Existing details are kept, as those
+  /// were determined by pictrs and are more reliable.
+  pub async fn create_federated(
+    pool: &mut DbPool<'_>,
+    form: &ImageDetailsForm,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    Self::create(conn, form).await
+  }
+
   pub(crate) async fn create(
     conn: &mut AsyncPgConnection,
     form: &ImageDetailsForm,
@@ -67,6 +67,15 @@ impl Instance {
       }
     }
   }
+  pub async fn read(pool: &mut DbPool<'_>, instance_id: InstanceId) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    instance::table
+      .find(instance_id)
+      .first(conn)
+      .await
+      .optional()
+  }
+
   pub async fn update(
     pool: &mut DbPool<'_>,
     instance_id: InstanceId,
@@ -79,6 +88,31 @@ impl Instance {
       .await
   }
 
+  /// Mark the instance with the given domain as alive if it is currently considered dead. This
+  /// also resets the failure count of its federation queue, so that delivery resumes immediately.
+  pub async fn mark_alive(pool: &mut DbPool<'_>, domain: &str) -> Result<(), Error> {
+    let conn = &mut get_conn(pool).await?;
+    let dead_instance_id = diesel::update(
+      instance::table
+        .filter(instance::domain.eq(domain))
+        .filter(coalesce(instance::updated, instance::published).lt(now() - 3.days())),
+    )
+    .set(instance::updated.eq(naive_now()))
+    .returning(instance::id)
+    .get_result::<InstanceId>(conn)
+    .await
+    .optional()?;
+    if let Some(instance_id) = dead_instance_id {
+      diesel::update(
+        federation_queue_state::table.filter(federation_queue_state::instance_id.eq(instance_id)),
+      )
+      .set(federation_queue_state::fail_count.eq(0))
+      .execute(conn)
+      .await?;
+    }
+    Ok(())
+  }
+
   pub async fn delete(pool: &mut DbPool<'_>, instance_id: InstanceId) -> Result<usize, Error> {
     let conn = &mut get_conn(pool).await?;
     diesel::delete(instance::table.find(instance_id))
@@ -152,7 +152,6 @@ impl LocalUser {
     use crate::schema::{
       comment,
       comment_saved,
-      community,
       community_block,
       community_follower,
       instance,
@@ -550,7 +550,7 @@ mod tests {
 
     // remove post
     let mod_remove_post_form = ModRemovePostForm {
-      mod_person_id: inserted_mod.id,
+      mod_person_id: Some(inserted_mod.id),
       post_id: inserted_post.id,
       reason: None,
       removed: None,
@@ -565,7 +565,7 @@ mod tests {
     let expected_mod_remove_post = ModRemovePost {
       id: inserted_mod_remove_post.id,
       post_id: inserted_post.id,
-      mod_person_id: inserted_mod.id,
+      mod_person_id: Some(inserted_mod.id),
       reason: None,
       removed: true,
       when_: inserted_mod_remove_post.when_,
@@ -574,7 +574,7 @@ mod tests {
     // lock post
 
     let mod_lock_post_form = ModLockPostForm {
-      mod_person_id: inserted_mod.id,
+      mod_person_id: Some(inserted_mod.id),
       post_id: inserted_post.id,
       locked: None,
     };
@@ -588,7 +588,7 @@ mod tests {
     let expected_mod_lock_post = ModLockPost {
       id: inserted_mod_lock_post.id,
       post_id: inserted_post.id,
-      mod_person_id: inserted_mod.id,
+      mod_person_id: Some(inserted_mod.id),
       locked: true,
       when_: inserted_mod_lock_post.when_,
     };
@@ -263,6 +263,7 @@ mod tests {
       bio: None,
       local: true,
       bot_account: false,
+      shadow_vote: false,
       private_key: None,
       public_key: "pubkey".to_owned(),
       last_refreshed_at: inserted_person.published,
@@ -1,24 +1,39 @@
 use crate::{
   diesel::OptionalExtension,
-  newtypes::{CommunityId, DbUrl, PersonId, PostId},
-  schema::{post, post_hide, post_like, post_read, post_saved},
-  source::post::{
-    Post,
-    PostHide,
-    PostHideForm,
-    PostInsertForm,
-    PostLike,
-    PostLikeForm,
-    PostRead,
-    PostReadForm,
-    PostSaved,
-    PostSavedForm,
-    PostUpdateForm,
+  newtypes::{CommunityId, DbUrl, InstanceId, PersonId, PostId},
+  schema::{
+    community,
+    community_moderator,
+    mod_remove_post,
+    person,
+    post,
+    post_hide,
+    post_like,
+    post_previous_version,
+    post_read,
+    post_saved,
+  },
+  source::{
+    community::Community,
+    post::{
+      Post,
+      PostHide,
+      PostHideForm,
+      PostInsertForm,
+      PostLike,
+      PostLikeForm,
+      PostRead,
+      PostReadForm,
+      PostSaved,
+      PostSavedForm,
+      PostUpdateForm,
+    },
   },
   traits::{Crud, Likeable, Saveable},
   utils::{
-    functions::coalesce,
+    functions::{btrim, coalesce, lower, regexp_replace, vote_weight},
     get_conn,
+    limit_and_offset,
     naive_now,
     DbPool,
     DELETED_REPLACEMENT_TEXT,
@@ -26,19 +41,27 @@ use crate::{
     SITEMAP_DAYS,
     SITEMAP_LIMIT,
   },
+  PostFrequencyInterval,
 };
 use ::url::Url;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Duration, DurationRound, Utc};
 use diesel::{
-  dsl::insert_into,
+  dsl::{count, count_star, exists, insert_into, max, not, select, sql, sum},
   result::Error,
+  sql_types::{BigInt, Bool, Integer, Timestamptz},
+  BoolExpressionMethods,
   DecoratableTarget,
   ExpressionMethods,
   QueryDsl,
   TextExpressionMethods,
 };
 use diesel_async::RunQueryDsl;
-use std::collections::HashSet;
+use lemmy_utils::utils::confusables::normalize_title;
+use std::collections::{HashMap, HashSet};
+
+/// Maximum number of recent titles which are compared in normalized form by
+/// [Post::find_duplicate_title].
+const NORMALIZED_TITLE_SCAN_LIMIT: i64 = 1000;
 
 #[async_trait]
 impl Crud for Post {
@@ -94,12 +117,28 @@ impl Post {
       .filter(post::deleted.eq(false))
       .filter(post::removed.eq(false))
       .filter(post::featured_community.eq(true))
+      .then_order_by(post::featured_position.desc())
       .then_order_by(post::published.desc())
       .limit(FETCH_LIMIT_MAX)
       .load::<Self>(conn)
       .await
   }
 
+  /// Position for a newly featured post in the community, so that it is shown above the others.
+  pub async fn next_featured_position(
+    pool: &mut DbPool<'_>,
+    the_community_id: CommunityId,
+  ) -> Result<i32, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let position: Option<i32> = post::table
+      .filter(post::community_id.eq(the_community_id))
+      .filter(post::featured_community.eq(true))
+      .select(max(post::featured_position))
+      .first(conn)
+      .await?;
+    Ok(position.unwrap_or_default() + 1)
+  }
+
   pub async fn list_for_sitemap(
     pool: &mut DbPool<'_>,
   ) -> Result<Vec<(DbUrl, chrono::DateTime<Utc>)>, Error> {
@@ -124,6 +163,16 @@ impl Post {
   ) -> Result<Vec<Self>, Error> {
     let conn = &mut get_conn(pool).await?;
 
+    // Otherwise the deleted content would remain available as previous version
+    let creator_posts = post::table
+      .filter(post::creator_id.eq(for_creator_id))
+      .select(post::id);
+    diesel::delete(
+      post_previous_version::table.filter(post_previous_version::post_id.eq_any(creator_posts)),
+    )
+    .execute(conn)
+    .await?;
+
     diesel::update(post::table.filter(post::creator_id.eq(for_creator_id)))
       .set((
         post::name.eq(DELETED_REPLACEMENT_TEXT),
@@ -157,6 +206,238 @@ impl Post {
       .await
   }
 
+  /// Restores the posts of a community which were removed because it was in quarantine. Posts
+  /// which were also removed with a mod action stay removed.
+  pub async fn restore_quarantined(
+    pool: &mut DbPool<'_>,
+    for_community_id: CommunityId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let restored = diesel::update(post::table)
+      .filter(post::community_id.eq(for_community_id))
+      .filter(post::quarantined.eq(true))
+      .filter(not(exists(
+        mod_remove_post::table.filter(mod_remove_post::post_id.eq(post::id)),
+      )))
+      .set((
+        post::removed.eq(false),
+        post::quarantined.eq(false),
+        post::updated.eq(naive_now()),
+      ))
+      .execute(conn)
+      .await?;
+    // The remaining posts were removed by a mod
+    diesel::update(post::table)
+      .filter(post::community_id.eq(for_community_id))
+      .filter(post::quarantined.eq(true))
+      .set(post::quarantined.eq(false))
+      .execute(conn)
+      .await?;
+    Ok(restored)
+  }
+
+  /// Finds an older post in the same community whose title only differs from the given post in
+  /// case and whitespace, and which was published after `since`. With `normalize_titles`, titles
+  /// are compared after [normalize_title], which can't be done in SQL. In that case only the
+  /// `NORMALIZED_TITLE_SCAN_LIMIT` most recent titles in the window are checked.
+  pub async fn find_duplicate_title(
+    pool: &mut DbPool<'_>,
+    new_post: &Post,
+    since: DateTime<Utc>,
+    normalize_titles: bool,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let collapse_whitespace = |title: &str| title.split_whitespace().collect::<Vec<_>>().join(" ");
+    let query = post::table
+      .filter(post::community_id.eq(new_post.community_id))
+      .filter(post::id.ne(new_post.id))
+      .filter(post::published.ge(since))
+      .filter(post::published.le(new_post.published))
+      .filter(post::deleted.eq(false))
+      .filter(post::removed.eq(false))
+      .order_by(post::published.desc());
+
+    if normalize_titles {
+      let normalized = collapse_whitespace(&normalize_title(&new_post.name));
+      let duplicate_id = query
+        .select((post::id, post::name))
+        .limit(NORMALIZED_TITLE_SCAN_LIMIT)
+        .load::<(PostId, String)>(conn)
+        .await?
+        .into_iter()
+        .find(|(_, name)| collapse_whitespace(&normalize_title(name)) == normalized)
+        .map(|(id, _)| id);
+      return match duplicate_id {
+        Some(id) => post::table.find(id).first(conn).await.optional(),
+        None => Ok(None),
+      };
+    }
+
+    // Both titles go through the same SQL expression, so they are compared the same way
+    let new_title = lower(btrim(regexp_replace(
+      new_post.name.clone(),
+      r"\s+",
+      " ",
+      "g",
+    )));
+    query
+      .filter(lower(btrim(regexp_replace(post::name, r"\s+", " ", "g"))).eq(new_title))
+      .first(conn)
+      .await
+      .optional()
+  }
+
+  /// Returns when the user last posted in the community, including deleted and removed posts.
+  pub async fn latest_published_in_community(
+    pool: &mut DbPool<'_>,
+    community_id: CommunityId,
+    creator_id: PersonId,
+  ) -> Result<Option<DateTime<Utc>>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post::table
+      .filter(post::community_id.eq(community_id))
+      .filter(post::creator_id.eq(creator_id))
+      .select(post::published)
+      .order_by(post::published.desc())
+      .first(conn)
+      .await
+      .optional()
+  }
+
+  /// Communities in which the user has posts, with the number of posts in each, most posts first.
+  /// Deleted and removed posts are not counted. If `moderator_id` is given, only communities
+  /// moderated by that person are returned.
+  pub async fn communities_of_creator(
+    pool: &mut DbPool<'_>,
+    creator_id: PersonId,
+    moderator_id: Option<PersonId>,
+  ) -> Result<Vec<(Community, i64)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mut query = post::table
+      .inner_join(community::table)
+      .filter(post::creator_id.eq(creator_id))
+      .filter(post::deleted.eq(false))
+      .filter(post::removed.eq(false))
+      .group_by(community::id)
+      .select((community::all_columns, count(post::id)))
+      .into_boxed();
+    if let Some(moderator_id) = moderator_id {
+      let moderated = community_moderator::table
+        .filter(community_moderator::person_id.eq(moderator_id))
+        .select(community_moderator::community_id);
+      query = query.filter(community::id.eq_any(moderated));
+    }
+    query
+      .order_by((count(post::id).desc(), community::id))
+      .load(conn)
+      .await
+  }
+
+  /// Locks all posts whose close date has passed, and returns them. The close date is cleared, so
+  /// that posts which are unlocked later stay unlocked. Posts which were already locked before
+  /// their close date are not returned.
+  pub async fn lock_closed(pool: &mut DbPool<'_>) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(
+      post::table
+        .filter(post::close_at.le(naive_now()))
+        .filter(post::locked.eq(true)),
+    )
+    .set(post::close_at.eq(None::<DateTime<Utc>>))
+    .execute(conn)
+    .await?;
+    diesel::update(
+      post::table
+        .filter(post::close_at.le(naive_now()))
+        .filter(post::locked.eq(false)),
+    )
+    .set((
+      post::locked.eq(true),
+      post::close_at.eq(None::<DateTime<Utc>>),
+    ))
+    .get_results::<Self>(conn)
+    .await
+  }
+
+  /// Number of posts in a community per day or week, for the buckets which overlap the time range.
+  /// Buckets without posts are included with a count of zero. Deleted and removed posts are
+  /// counted too, as this is about the posting rate.
+  pub async fn frequency_in_community(
+    pool: &mut DbPool<'_>,
+    for_community_id: CommunityId,
+    interval: PostFrequencyInterval,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+  ) -> Result<Vec<(DateTime<Utc>, i64)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let field = match interval {
+      PostFrequencyInterval::Day => "day",
+      PostFrequencyInterval::Week => "week",
+    };
+    // Diesel can't group by a function call on a column, so the bucket is a literal. The field
+    // name comes from the enum above and not from user input.
+    let bucket = sql::<Timestamptz>(&format!("date_trunc('{field}', post.published, 'UTC')"));
+    let counts = post::table
+      .filter(post::community_id.eq(for_community_id))
+      .filter(post::published.ge(start))
+      .filter(post::published.lt(end))
+      .group_by(bucket.clone())
+      .select((bucket, count(post::id)))
+      .load::<(DateTime<Utc>, i64)>(conn)
+      .await?
+      .into_iter()
+      .collect::<HashMap<_, _>>();
+
+    let step = match interval {
+      PostFrequencyInterval::Day => Duration::days(1),
+      PostFrequencyInterval::Week => Duration::weeks(1),
+    };
+    let mut bucket_start = start.duration_trunc(Duration::days(1)).unwrap_or(start);
+    if interval == PostFrequencyInterval::Week {
+      bucket_start -= Duration::days(bucket_start.weekday().num_days_from_monday().into());
+    }
+    let mut buckets = vec![];
+    while bucket_start < end {
+      let post_count = counts.get(&bucket_start).copied().unwrap_or_default();
+      buckets.push((bucket_start, post_count));
+      bucket_start += step;
+    }
+    Ok(buckets)
+  }
+
+  /// Returns the crosspost group for a new post with the given url. This is the group of the first
+  /// post with the same url, or its `ap_id` if it doesn't have a group yet.
+  pub async fn crosspost_group_for_url(
+    pool: &mut DbPool<'_>,
+    url: &DbUrl,
+  ) -> Result<Option<DbUrl>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post::table
+      .filter(post::url.eq(url))
+      .filter(post::deleted.eq(false))
+      .filter(post::removed.eq(false))
+      .order_by(post::published.asc())
+      .select((post::crosspost_group, post::ap_id))
+      .first::<(Option<DbUrl>, DbUrl)>(conn)
+      .await
+      .optional()
+      .map(|first| first.map(|(group, ap_id)| group.unwrap_or(ap_id)))
+  }
+
+  /// Checks if the crosspost group is already used by a post with the given url.
+  pub async fn is_crosspost_group_of_url(
+    pool: &mut DbPool<'_>,
+    url: &DbUrl,
+    group: &DbUrl,
+  ) -> Result<bool, Error> {
+    let conn = &mut get_conn(pool).await?;
+    select(exists(post::table.filter(post::url.eq(url)).filter(
+      post::crosspost_group.eq(group).or(post::ap_id.eq(group)),
+    )))
+    .get_result(conn)
+    .await
+  }
+
   pub fn is_post_creator(person_id: PersonId, post_creator_id: PersonId) -> bool {
     person_id == post_creator_id
   }
@@ -270,6 +551,88 @@ impl Likeable for PostLike {
   }
 }
 
+impl PostLike {
+  /// Returns the weighted upvotes and downvotes of a post which were cast in the given time window.
+  /// Votes are weighted the same way as in the post aggregates. Changed votes keep the time of the
+  /// first vote, and removed votes are not counted.
+  pub async fn score_delta(
+    pool: &mut DbPool<'_>,
+    for_post_id: PostId,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+  ) -> Result<(i64, i64), Error> {
+    let conn = &mut get_conn(pool).await?;
+    let weighted_votes = |score: i16| {
+      post_like::table
+        .filter(post_like::post_id.eq(for_post_id))
+        .filter(post_like::published.ge(start))
+        .filter(post_like::published.lt(end))
+        .filter(post_like::score.eq(score))
+        .select(sum(vote_weight(post_like::person_id)))
+    };
+    let upvotes = weighted_votes(1)
+      .first::<Option<i64>>(conn)
+      .await?
+      .unwrap_or_default();
+    let downvotes = weighted_votes(-1)
+      .first::<Option<i64>>(conn)
+      .await?
+      .unwrap_or_default();
+    Ok((upvotes, downvotes))
+  }
+
+  /// Returns posts where users of the given instance cast at least `min_percent` of all
+  /// downvotes, together with the downvotes from that instance and the total downvotes. Only
+  /// downvotes cast after `since` are counted, and posts with less than `min_downvotes` downvotes
+  /// are ignored. The posts with most downvotes from the instance come first.
+  #[allow(clippy::too_many_arguments)]
+  pub async fn downvoted_by_instance(
+    pool: &mut DbPool<'_>,
+    for_instance_id: InstanceId,
+    for_community_id: Option<CommunityId>,
+    min_percent: i64,
+    min_downvotes: i64,
+    since: DateTime<Utc>,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<(Post, i64, i64)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let (limit, offset) = limit_and_offset(page, limit)?;
+    let instance_downvotes = || {
+      sql::<BigInt>("count(*) FILTER (WHERE person.instance_id = ")
+        .bind::<Integer, _>(for_instance_id)
+        .sql(")")
+    };
+    let mut query = post_like::table
+      .inner_join(person::table)
+      .inner_join(post::table)
+      .filter(post_like::score.eq(-1))
+      .filter(post_like::published.ge(since))
+      .group_by(post::id)
+      .having(
+        count_star().ge(min_downvotes).and(
+          sql::<Bool>("100 * count(*) FILTER (WHERE person.instance_id = ")
+            .bind::<Integer, _>(for_instance_id)
+            .sql(") >= ")
+            .bind::<BigInt, _>(min_percent)
+            .sql(" * count(*)"),
+        ),
+      )
+      .select((post::all_columns, instance_downvotes(), count_star()))
+      .into_boxed();
+    if let Some(for_community_id) = for_community_id {
+      query = query.filter(post::community_id.eq(for_community_id));
+    }
+    query
+      .order_by(instance_downvotes().desc())
+      .then_order_by(post::id.desc())
+      .limit(limit)
+      .offset(offset)
+      .load::<(Post, i64, i64)>(conn)
+      .await
+  }
+}
+
 #[async_trait]
 impl Saveable for PostSaved {
   type Form = PostSavedForm;
@@ -291,6 +654,56 @@ impl Saveable for PostSaved {
   }
 }
 
+impl PostSaved {
+  pub async fn save_many(
+    pool: &mut DbPool<'_>,
+    post_ids: HashSet<PostId>,
+    person_id: PersonId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+
+    let forms = post_ids
+      .into_iter()
+      .map(|post_id| PostSavedForm { post_id, person_id })
+      .collect::<Vec<PostSavedForm>>();
+    insert_into(post_saved::table)
+      .values(forms)
+      .on_conflict_do_nothing()
+      .execute(conn)
+      .await
+  }
+
+  pub async fn unsave_many(
+    pool: &mut DbPool<'_>,
+    post_id_: HashSet<PostId>,
+    person_id_: PersonId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+
+    diesel::delete(
+      post_saved::table
+        .filter(post_saved::post_id.eq_any(post_id_))
+        .filter(post_saved::person_id.eq(person_id_)),
+    )
+    .execute(conn)
+    .await
+  }
+
+  /// Ids of all posts saved by the user, most recently saved first.
+  pub async fn list_post_ids(
+    pool: &mut DbPool<'_>,
+    person_id: PersonId,
+  ) -> Result<Vec<PostId>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post_saved::table
+      .filter(post_saved::person_id.eq(person_id))
+      .order_by(post_saved::published.desc())
+      .select(post_saved::post_id)
+      .load(conn)
+      .await
+  }
+}
+
 impl PostRead {
   pub async fn mark_as_read(
     pool: &mut DbPool<'_>,
@@ -369,8 +782,9 @@ impl PostHide {
 mod tests {
 
   use crate::{
+    schema::{person, post_like},
     source::{
-      community::{Community, CommunityInsertForm},
+      community::{Community, CommunityInsertForm, CommunityModerator, CommunityModeratorForm},
       instance::Instance,
       person::{Person, PersonInsertForm},
       post::{
@@ -384,9 +798,14 @@ mod tests {
         PostUpdateForm,
       },
     },
-    traits::{Crud, Likeable, Saveable},
-    utils::build_db_pool_for_tests,
+    traits::{Crud, Joinable, Likeable, Saveable},
+    utils::{build_db_pool_for_tests, get_conn},
+    PostFrequencyInterval,
+    PostSourceFormat,
   };
+  use chrono::{Duration, TimeZone, Utc};
+  use diesel::{result::Error, ExpressionMethods, QueryDsl};
+  use diesel_async::RunQueryDsl;
   use pretty_assertions::assert_eq;
   use serial_test::serial;
   use std::collections::HashSet;
@@ -436,6 +855,24 @@ mod tests {
       url: None,
       body: None,
       alt_text: None,
+      url_nsfw: None,
+      remote_views: None,
+      remote_comments: None,
+      featured_position: 0,
+      canonical_url: None,
+      source_format: PostSourceFormat::Markdown,
+      category: None,
+      distinguished: false,
+      updated_by: None,
+      original_language_id: None,
+      crosspost_group: None,
+      pinned_comment_id: None,
+      original_content: false,
+      default_comment_sort: None,
+      close_at: None,
+      content_html: None,
+      quarantined: false,
+      additional_audience: vec![],
       creator_id: inserted_person.id,
       community_id: inserted_community.id,
       published: inserted_post.published,
@@ -538,4 +975,436 @@ mod tests {
     assert_eq!(expected_post_like, inserted_post_like);
     assert_eq!(expected_post_saved, inserted_post_saved);
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_score_delta() -> Result<(), Error> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let mut persons = vec![];
+    for name in ["delta_1", "delta_2", "delta_3", "delta_4"] {
+      let form = PersonInsertForm::test_form(instance.id, name);
+      persons.push(Person::create(pool, &form).await?);
+    }
+    let community_form = CommunityInsertForm::builder()
+      .name("test_score_delta".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+    let post_form = PostInsertForm::builder()
+      .name("A test post".into())
+      .creator_id(persons[0].id)
+      .community_id(community.id)
+      .build();
+    let post = Post::create(pool, &post_form).await?;
+
+    for (person, score) in persons.iter().zip([1, 1, -1, -1]) {
+      let form = PostLikeForm {
+        post_id: post.id,
+        person_id: person.id,
+        score,
+      };
+      PostLike::like(pool, &form).await?;
+    }
+    // The first vote was cast before the window, votes of shadow voters are not counted
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(post_like::table.find((persons[0].id, post.id)))
+      .set(post_like::published.eq(Utc::now() - Duration::hours(2)))
+      .execute(conn)
+      .await?;
+    diesel::update(person::table.find(persons[3].id))
+      .set(person::shadow_vote.eq(true))
+      .execute(conn)
+      .await?;
+
+    let now = Utc::now();
+    let delta = PostLike::score_delta(pool, post.id, now - Duration::hours(1), now).await?;
+    assert_eq!((1, 1), delta);
+    let delta = PostLike::score_delta(pool, post.id, now - Duration::hours(3), now).await?;
+    assert_eq!((2, 1), delta);
+    let delta = PostLike::score_delta(
+      pool,
+      post.id,
+      now - Duration::hours(3),
+      now - Duration::hours(1),
+    )
+    .await?;
+    assert_eq!((1, 0), delta);
+
+    Post::delete(pool, post.id).await?;
+    Community::delete(pool, community.id).await?;
+    for person in persons {
+      Person::delete(pool, person.id).await?;
+    }
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_save_many() -> Result<(), Error> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person = Person::create(pool, &PersonInsertForm::test_form(instance.id, "saver")).await?;
+    let community_form = CommunityInsertForm::builder()
+      .name("test_save_many".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+    let mut post_ids = vec![];
+    for name in ["first", "second", "third"] {
+      let form = PostInsertForm::builder()
+        .name(name.into())
+        .creator_id(person.id)
+        .community_id(community.id)
+        .build();
+      post_ids.push(Post::create(pool, &form).await?.id);
+    }
+
+    let all = post_ids.iter().copied().collect::<HashSet<_>>();
+    assert_eq!(3, PostSaved::save_many(pool, all.clone(), person.id).await?);
+    // Saving again is ignored
+    assert_eq!(0, PostSaved::save_many(pool, all.clone(), person.id).await?);
+    let listed = PostSaved::list_post_ids(pool, person.id).await?;
+    assert_eq!(all, listed.into_iter().collect());
+
+    let unsaved = post_ids.iter().copied().take(2).collect::<HashSet<_>>();
+    assert_eq!(2, PostSaved::unsave_many(pool, unsaved, person.id).await?);
+    let listed = PostSaved::list_post_ids(pool, person.id).await?;
+    assert_eq!(post_ids.last(), listed.first());
+    assert_eq!(1, listed.len());
+
+    Community::delete(pool, community.id).await?;
+    Person::delete(pool, person.id).await?;
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+  #[tokio::test]
+  #[serial]
+  async fn test_lock_closed() -> Result<(), Error> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person = Person::create(pool, &PersonInsertForm::test_form(instance.id, "closer")).await?;
+    let community_form = CommunityInsertForm::builder()
+      .name("test_lock_closed".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+    let now = Utc::now();
+    let mut posts = vec![];
+    for (close_at, locked) in [
+      (now - Duration::minutes(1), false),
+      (now + Duration::hours(1), false),
+      (now - Duration::minutes(1), true),
+    ] {
+      let form = PostInsertForm::builder()
+        .name("A contest".into())
+        .creator_id(person.id)
+        .community_id(community.id)
+        .close_at(Some(close_at))
+        .locked(Some(locked))
+        .build();
+      posts.push(Post::create(pool, &form).await?);
+    }
+
+    // Only the unlocked post whose close time has passed is locked
+    let locked: Vec<_> = Post::lock_closed(pool)
+      .await?
+      .into_iter()
+      .map(|p| p.id)
+      .collect();
+    assert_eq!(vec![posts[0].id], locked);
+    let open = Post::read(pool, posts[1].id).await?;
+    assert!(open.is_some_and(|p| !p.locked));
+    let already_locked = Post::read(pool, posts[2].id).await?;
+    assert!(already_locked.is_some_and(|p| p.locked && p.close_at.is_none()));
+
+    // The close time is cleared, so that the post stays unlocked if a mod unlocks it
+    let closed = Post::read(pool, posts[0].id).await?;
+    assert!(closed.is_some_and(|p| p.locked && p.close_at.is_none()));
+    let form = PostUpdateForm {
+      locked: Some(false),
+      ..Default::default()
+    };
+    Post::update(pool, posts[0].id, &form).await?;
+    assert!(Post::lock_closed(pool).await?.is_empty());
+
+    Community::delete(pool, community.id).await?;
+    Person::delete(pool, person.id).await?;
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_frequency_in_community() -> Result<(), Error> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person =
+      Person::create(pool, &PersonInsertForm::test_form(instance.id, "frequent")).await?;
+    let community_form = CommunityInsertForm::builder()
+      .name("test_post_frequency".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+
+    // 2024-07-01 is a Monday
+    let monday = Utc
+      .with_ymd_and_hms(2024, 7, 1, 0, 0, 0)
+      .single()
+      .ok_or(Error::NotFound)?;
+    for hours in [10, 20, 56, 204] {
+      let form = PostInsertForm::builder()
+        .name("A test post".into())
+        .creator_id(person.id)
+        .community_id(community.id)
+        .published(Some(monday + Duration::hours(hours)))
+        .build();
+      Post::create(pool, &form).await?;
+    }
+
+    let days = Post::frequency_in_community(
+      pool,
+      community.id,
+      PostFrequencyInterval::Day,
+      monday + Duration::hours(6),
+      monday + Duration::days(3),
+    )
+    .await?;
+    let expected = vec![
+      (monday, 2),
+      (monday + Duration::days(1), 0),
+      (monday + Duration::days(2), 1),
+    ];
+    assert_eq!(expected, days);
+
+    // The first bucket starts before the range, posts before the range aren't counted
+    let weeks = Post::frequency_in_community(
+      pool,
+      community.id,
+      PostFrequencyInterval::Week,
+      monday + Duration::days(1),
+      monday + Duration::days(10),
+    )
+    .await?;
+    assert_eq!(vec![(monday, 1), (monday + Duration::weeks(1), 1)], weeks);
+
+    Community::delete(pool, community.id).await?;
+    Person::delete(pool, person.id).await?;
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_communities_of_creator() -> Result<(), Error> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person = Person::create(pool, &PersonInsertForm::test_form(instance.id, "poster")).await?;
+    let mut communities = vec![];
+    for name in ["test_posts_first", "test_posts_second"] {
+      let form = CommunityInsertForm::builder()
+        .name(name.to_string())
+        .title("nada".to_owned())
+        .public_key("pubkey".to_string())
+        .instance_id(instance.id)
+        .build();
+      communities.push(Community::create(pool, &form).await?);
+    }
+    let posts_per_community = communities.iter().zip([1_i64, 3]);
+    for (community, count) in posts_per_community.clone() {
+      for _ in 0..count {
+        let form = PostInsertForm::builder()
+          .name("A test post".into())
+          .creator_id(person.id)
+          .community_id(community.id)
+          .build();
+        Post::create(pool, &form).await?;
+      }
+    }
+
+    // Deleted and removed posts aren't counted
+    for (deleted, removed) in [(true, false), (false, true)] {
+      let form = PostInsertForm::builder()
+        .name("A test post".into())
+        .creator_id(person.id)
+        .community_id(communities[0].id)
+        .deleted(Some(deleted))
+        .removed(Some(removed))
+        .build();
+      Post::create(pool, &form).await?;
+    }
+
+    // The community with most posts comes first
+    let listed = Post::communities_of_creator(pool, person.id, None)
+      .await?
+      .into_iter()
+      .map(|(community, count)| (community.id, count))
+      .collect::<Vec<_>>();
+    let expected = posts_per_community
+      .rev()
+      .map(|(community, count)| (community.id, count))
+      .collect::<Vec<_>>();
+    assert_eq!(expected, listed);
+
+    // Moderators only see the communities which they moderate
+    let moderator = Person::create(pool, &PersonInsertForm::test_form(instance.id, "mod")).await?;
+    let form = CommunityModeratorForm {
+      community_id: communities[0].id,
+      person_id: moderator.id,
+    };
+    CommunityModerator::join(pool, &form).await?;
+    let listed = Post::communities_of_creator(pool, person.id, Some(moderator.id))
+      .await?
+      .into_iter()
+      .map(|(community, count)| (community.id, count))
+      .collect::<Vec<_>>();
+    assert_eq!(vec![(communities[0].id, 1)], listed);
+
+    for community in communities {
+      Community::delete(pool, community.id).await?;
+    }
+    Person::delete(pool, moderator.id).await?;
+    Person::delete(pool, person.id).await?;
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_downvoted_by_instance() -> Result<(), Error> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let other_instance = Instance::read_or_create(pool, "other_domain.tld".to_string()).await?;
+    let mut local_persons = vec![];
+    for name in ["local_voter_1", "local_voter_2", "local_voter_3"] {
+      let form = PersonInsertForm::test_form(instance.id, name);
+      local_persons.push(Person::create(pool, &form).await?);
+    }
+    let mut remote_persons = vec![];
+    for name in ["remote_voter_1", "remote_voter_2", "remote_voter_3"] {
+      let form = PersonInsertForm::test_form(other_instance.id, name);
+      remote_persons.push(Person::create(pool, &form).await?);
+    }
+    let community_form = CommunityInsertForm::builder()
+      .name("test_downvoted_by_instance".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+    let mut posts = vec![];
+    for _ in 0..2 {
+      let post_form = PostInsertForm::builder()
+        .name("A test post".into())
+        .creator_id(local_persons[0].id)
+        .community_id(community.id)
+        .build();
+      posts.push(Post::create(pool, &post_form).await?);
+    }
+
+    // The first post is downvoted mostly from the other instance, the second one mostly locally
+    let votes = [
+      (&posts[0], &remote_persons[0], -1),
+      (&posts[0], &remote_persons[1], -1),
+      (&posts[0], &remote_persons[2], -1),
+      (&posts[0], &local_persons[1], -1),
+      (&posts[0], &local_persons[2], 1),
+      (&posts[1], &remote_persons[0], -1),
+      (&posts[1], &local_persons[0], -1),
+      (&posts[1], &local_persons[1], -1),
+      (&posts[1], &local_persons[2], -1),
+    ];
+    for (post, person, score) in votes {
+      let form = PostLikeForm {
+        post_id: post.id,
+        person_id: person.id,
+        score,
+      };
+      PostLike::like(pool, &form).await?;
+    }
+
+    let since = Utc::now() - Duration::days(1);
+    let downvoted =
+      PostLike::downvoted_by_instance(pool, other_instance.id, None, 50, 2, since, None, None)
+        .await?;
+    let downvoted = downvoted
+      .into_iter()
+      .map(|(post, instance_downvotes, total_downvotes)| {
+        (post.id, instance_downvotes, total_downvotes)
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(vec![(posts[0].id, 3, 4)], downvoted);
+
+    // Both posts are listed with a low enough threshold, but not with too few downvotes
+    let downvoted = PostLike::downvoted_by_instance(
+      pool,
+      other_instance.id,
+      Some(community.id),
+      25,
+      2,
+      since,
+      None,
+      None,
+    )
+    .await?;
+    assert_eq!(2, downvoted.len());
+    let downvoted =
+      PostLike::downvoted_by_instance(pool, other_instance.id, None, 25, 5, since, None, None)
+        .await?;
+    assert!(downvoted.is_empty());
+
+    // Pages contain the requested number of posts
+    let page = PostLike::downvoted_by_instance(
+      pool,
+      other_instance.id,
+      None,
+      25,
+      2,
+      since,
+      Some(2),
+      Some(1),
+    )
+    .await?;
+    assert_eq!(Some(posts[1].id), page.first().map(|(post, ..)| post.id));
+
+    // Downvotes from before the time window aren't counted
+    let downvoted =
+      PostLike::downvoted_by_instance(pool, other_instance.id, None, 25, 2, Utc::now(), None, None)
+        .await?;
+    assert!(downvoted.is_empty());
+
+    // The local instance did not cast most downvotes on either post
+    let downvoted =
+      PostLike::downvoted_by_instance(pool, instance.id, None, 80, 2, since, None, None).await?;
+    assert!(downvoted.is_empty());
+
+    Community::delete(pool, community.id).await?;
+    for person in local_persons.into_iter().chain(remote_persons) {
+      Person::delete(pool, person.id).await?;
+    }
+    Instance::delete(pool, instance.id).await?;
+    Instance::delete(pool, other_instance.id).await?;
+    Ok(())
+  }
 }
@@ -0,0 +1,124 @@
+use crate::{
+  newtypes::PostId,
+  schema::post_pending_federation,
+  source::post_pending_federation::PostPendingFederation,
+  utils::{get_conn, DbPool},
+};
+use chrono::Utc;
+use diesel::{
+  delete,
+  dsl::{exists, select},
+  insert_into,
+  result::Error,
+  ExpressionMethods, QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+impl PostPendingFederation {
+  /// Hold back the Create activity of a post until the given time.
+  pub async fn create(pool: &mut DbPool<'_>, form: &PostPendingFederation) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(post_pending_federation::table)
+      .values(form)
+      .on_conflict(post_pending_federation::post_id)
+      .do_update()
+      .set(post_pending_federation::send_at.eq(form.send_at))
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// Returns true if the Create activity of the post was not sent yet. Activities which depend on
+  /// the post need to be held back as well.
+  pub async fn is_pending(pool: &mut DbPool<'_>, post_id: PostId) -> Result<bool, Error> {
+    let conn = &mut get_conn(pool).await?;
+    select(exists(post_pending_federation::table.find(post_id)))
+      .get_result(conn)
+      .await
+  }
+
+  /// Removes all entries whose grace period is over, and returns them so that the activities
+  /// can be sent.
+  pub async fn take_due(pool: &mut DbPool<'_>) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    delete(post_pending_federation::table.filter(post_pending_federation::send_at.le(Utc::now())))
+      .get_results::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+      post_pending_federation::PostPendingFederation,
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use chrono::{Duration, Utc};
+  use diesel::result::Error;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_take_due() -> Result<(), Error> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person = Person::create(pool, &PersonInsertForm::test_form(instance.id, "pending")).await?;
+    let community_form = CommunityInsertForm::builder()
+      .name("test_pending_federation".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+    let post_form = PostInsertForm::builder()
+      .name("A test post".into())
+      .creator_id(person.id)
+      .community_id(community.id)
+      .build();
+    let post = Post::create(pool, &post_form).await?;
+    let post_form = PostInsertForm::builder()
+      .name("Another test post".into())
+      .creator_id(person.id)
+      .community_id(community.id)
+      .build();
+    let other_post = Post::create(pool, &post_form).await?;
+
+    let due = PostPendingFederation {
+      post_id: post.id,
+      send_at: Utc::now() - Duration::seconds(1),
+    };
+    PostPendingFederation::create(pool, &due).await?;
+    let later = PostPendingFederation {
+      post_id: other_post.id,
+      send_at: Utc::now() + Duration::minutes(5),
+    };
+    PostPendingFederation::create(pool, &later).await?;
+    assert!(PostPendingFederation::is_pending(pool, post.id).await?);
+
+    // Only the entry whose grace period is over is returned, and it is removed
+    let taken = PostPendingFederation::take_due(pool).await?;
+    assert_eq!(
+      vec![post.id],
+      taken.iter().map(|p| p.post_id).collect::<Vec<_>>()
+    );
+    assert!(!PostPendingFederation::is_pending(pool, post.id).await?);
+    assert!(PostPendingFederation::is_pending(pool, other_post.id).await?);
+    assert!(PostPendingFederation::take_due(pool).await?.is_empty());
+
+    Community::delete(pool, community.id).await?;
+    Person::delete(pool, person.id).await?;
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+}
@@ -0,0 +1,139 @@
+use crate::{
+  newtypes::PostId,
+  schema::post_previous_version,
+  source::{
+    post::Post,
+    post_previous_version::{PostPreviousVersion, PostPreviousVersionForm},
+  },
+  utils::{get_conn, DbPool},
+};
+use chrono::Utc;
+use diesel::{insert_into, result::Error, ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl PostPreviousVersion {
+  /// Stores title and body of the post before an edit, if the edit changed any of them. Only the
+  /// version before the last such edit is kept.
+  pub async fn store_if_changed(
+    pool: &mut DbPool<'_>,
+    before: &Post,
+    after: &Post,
+  ) -> Result<Option<Self>, Error> {
+    if before.name == after.name && before.body == after.body {
+      return Ok(None);
+    }
+    let conn = &mut get_conn(pool).await?;
+    let form = PostPreviousVersionForm {
+      post_id: before.id,
+      name: before.name.clone(),
+      body: before.body.clone(),
+      published: Utc::now(),
+    };
+    insert_into(post_previous_version::table)
+      .values(&form)
+      .on_conflict(post_previous_version::post_id)
+      .do_update()
+      .set((
+        post_previous_version::name.eq(&form.name),
+        post_previous_version::body.eq(&form.body),
+        post_previous_version::published.eq(form.published),
+      ))
+      .get_result::<Self>(conn)
+      .await
+      .map(Some)
+  }
+
+  pub async fn read(pool: &mut DbPool<'_>, post_id: PostId) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post_previous_version::table
+      .find(post_id)
+      .first(conn)
+      .await
+      .optional()
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm, PostUpdateForm},
+      post_previous_version::PostPreviousVersion,
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use diesel::result::Error;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_store_if_changed() -> Result<(), Error> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person = Person::create(pool, &PersonInsertForm::test_form(instance.id, "editor")).await?;
+    let community_form = CommunityInsertForm::builder()
+      .name("test_previous_version".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+    let post_form = PostInsertForm::builder()
+      .name("Original title".into())
+      .body(Some("Original body".into()))
+      .creator_id(person.id)
+      .community_id(community.id)
+      .build();
+    let post = Post::create(pool, &post_form).await?;
+
+    // Edits which don't touch title or body are not stored
+    let form = PostUpdateForm {
+      nsfw: Some(true),
+      ..Default::default()
+    };
+    let updated = Post::update(pool, post.id, &form).await?;
+    assert_eq!(
+      None,
+      PostPreviousVersion::store_if_changed(pool, &post, &updated).await?
+    );
+    assert_eq!(None, PostPreviousVersion::read(pool, post.id).await?);
+
+    let form = PostUpdateForm {
+      body: Some(Some("Edited body".into())),
+      ..Default::default()
+    };
+    let edited = Post::update(pool, post.id, &form).await?;
+    PostPreviousVersion::store_if_changed(pool, &updated, &edited).await?;
+    let previous = PostPreviousVersion::read(pool, post.id).await?.unwrap();
+    assert_eq!("Original title", previous.name);
+    assert_eq!(Some("Original body".to_string()), previous.body);
+
+    // Only the version before the last edit is kept
+    let form = PostUpdateForm {
+      name: Some("Edited title".into()),
+      body: Some(None),
+      ..Default::default()
+    };
+    let edited_again = Post::update(pool, post.id, &form).await?;
+    PostPreviousVersion::store_if_changed(pool, &edited, &edited_again).await?;
+    let previous = PostPreviousVersion::read(pool, post.id).await?.unwrap();
+    assert_eq!("Original title", previous.name);
+    assert_eq!(Some("Edited body".to_string()), previous.body);
+    assert_eq!("Edited title", edited_again.name);
+    assert_eq!(None, edited_again.body);
+
+    Community::delete(pool, community.id).await?;
+    Person::delete(pool, person.id).await?;
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+}
@@ -0,0 +1,49 @@
+use crate::{
+  newtypes::{DbUrl, PostId},
+  schema::post_raw_json,
+  source::post_raw_json::{PostRawJson, PostRawJsonForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{insert_into, result::Error, ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl PostRawJson {
+  /// Store the raw json of a post. If it was already stored before, it is replaced. This also
+  /// clears the error of a previous failed import.
+  pub async fn upsert(pool: &mut DbPool<'_>, form: &PostRawJsonForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(post_raw_json::table)
+      .values(form)
+      .on_conflict(post_raw_json::ap_id)
+      .do_update()
+      .set((
+        post_raw_json::post_id.eq(form.post_id),
+        post_raw_json::data.eq(&form.data),
+        post_raw_json::error.eq(&form.error),
+        post_raw_json::published.eq(form.published),
+      ))
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn read(pool: &mut DbPool<'_>, post_id: PostId) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post_raw_json::table
+      .filter(post_raw_json::post_id.eq(post_id))
+      .first(conn)
+      .await
+      .optional()
+  }
+
+  pub async fn read_from_apub_id(
+    pool: &mut DbPool<'_>,
+    ap_id: &DbUrl,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post_raw_json::table
+      .find(ap_id)
+      .first(conn)
+      .await
+      .optional()
+  }
+}
@@ -121,7 +121,7 @@ mod tests {
 
     let report_form = PostReportForm {
       post_id: post.id,
-      creator_id: person.id,
+      creator_id: Some(person.id),
       reason: "my reason".to_string(),
       ..Default::default()
     };
@@ -0,0 +1,118 @@
+use crate::{
+  newtypes::PostId,
+  schema::post_thumbnail_retry,
+  source::post_thumbnail_retry::PostThumbnailRetry,
+  utils::{get_conn, DbPool},
+};
+use chrono::Utc;
+use diesel::{delete, insert_into, result::Error, ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl PostThumbnailRetry {
+  /// Schedule thumbnail generation for the post. An earlier entry for the post is replaced.
+  pub async fn create(pool: &mut DbPool<'_>, form: &PostThumbnailRetry) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(post_thumbnail_retry::table)
+      .values(form)
+      .on_conflict(post_thumbnail_retry::post_id)
+      .do_update()
+      .set((
+        post_thumbnail_retry::image_url.eq(&form.image_url),
+        post_thumbnail_retry::retry_at.eq(form.retry_at),
+      ))
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn read(pool: &mut DbPool<'_>, post_id: PostId) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post_thumbnail_retry::table
+      .find(post_id)
+      .first(conn)
+      .await
+      .optional()
+  }
+
+  /// Removes all entries which are due, and returns them so that the thumbnails can be generated.
+  pub async fn take_due(pool: &mut DbPool<'_>) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    delete(post_thumbnail_retry::table.filter(post_thumbnail_retry::retry_at.le(Utc::now())))
+      .get_results::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+      post_thumbnail_retry::PostThumbnailRetry,
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use chrono::{Duration, Utc};
+  use diesel::result::Error;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_take_due() -> Result<(), Error> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person = Person::create(pool, &PersonInsertForm::test_form(instance.id, "retry")).await?;
+    let community_form = CommunityInsertForm::builder()
+      .name("test_thumbnail_retry".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+    let post_form = PostInsertForm::builder()
+      .name("A test post".into())
+      .creator_id(person.id)
+      .community_id(community.id)
+      .build();
+    let post = Post::create(pool, &post_form).await?;
+    let post_form = PostInsertForm::builder()
+      .name("Another test post".into())
+      .creator_id(person.id)
+      .community_id(community.id)
+      .build();
+    let other_post = Post::create(pool, &post_form).await?;
+
+    let image_url = Url::parse("https://example.com/image.png").unwrap();
+    let due = PostThumbnailRetry {
+      post_id: post.id,
+      image_url: image_url.clone().into(),
+      retry_at: Utc::now() - Duration::seconds(1),
+    };
+    PostThumbnailRetry::create(pool, &due).await?;
+    let later = PostThumbnailRetry {
+      post_id: other_post.id,
+      image_url: image_url.into(),
+      retry_at: Utc::now() + Duration::minutes(5),
+    };
+    PostThumbnailRetry::create(pool, &later).await?;
+
+    // Only the due entry is returned, and it is removed
+    let taken = PostThumbnailRetry::take_due(pool).await?;
+    assert_eq!(vec![due], taken);
+    assert!(PostThumbnailRetry::take_due(pool).await?.is_empty());
+
+    Community::delete(pool, community.id).await?;
+    Person::delete(pool, person.id).await?;
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+}
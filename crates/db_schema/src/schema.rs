@@ -5,10 +5,18 @@ pub mod sql_types {
     #[diesel(postgres_type(name = "actor_type_enum"))]
     pub struct ActorTypeEnum;
 
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "comment_sort_type_enum"))]
+    pub struct CommentSortTypeEnum;
+
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "community_visibility"))]
     pub struct CommunityVisibility;
 
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "duplicate_title_action_enum"))]
+    pub struct DuplicateTitleActionEnum;
+
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "listing_type_enum"))]
     pub struct ListingTypeEnum;
@@ -21,6 +29,10 @@ pub mod sql_types {
     #[diesel(postgres_type(name = "post_listing_mode_enum"))]
     pub struct PostListingModeEnum;
 
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "post_source_format_enum"))]
+    pub struct PostSourceFormatEnum;
+
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "registration_mode_enum"))]
     pub struct RegistrationModeEnum;
@@ -95,6 +107,7 @@ diesel::table! {
         path -> Ltree,
         distinguished -> Bool,
         language_id -> Int4,
+        remote_child_count -> Nullable<Int8>,
     }
 }
 
@@ -134,7 +147,7 @@ diesel::table! {
 diesel::table! {
     comment_report (id) {
         id -> Int4,
-        creator_id -> Int4,
+        creator_id -> Nullable<Int4>,
         comment_id -> Int4,
         original_comment_text -> Text,
         reason -> Text,
@@ -156,6 +169,7 @@ diesel::table! {
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::CommunityVisibility;
+    use super::sql_types::DuplicateTitleActionEnum;
 
     community (id) {
         id -> Int4,
@@ -191,6 +205,17 @@ diesel::table! {
         #[max_length = 255]
         featured_url -> Nullable<Varchar>,
         visibility -> CommunityVisibility,
+        posting_window_start -> Int2,
+        posting_window_end -> Int2,
+        federation_paused -> Bool,
+        federation_incoming_paused -> Bool,
+        slur_filter_regex -> Nullable<Text>,
+        quarantined_until -> Nullable<Timestamptz>,
+        allow_nsfw -> Bool,
+        duplicate_title_hours -> Int4,
+        duplicate_title_action -> DuplicateTitleActionEnum,
+        min_post_karma -> Int4,
+        post_interval_secs -> Int4,
     }
 }
 
@@ -283,6 +308,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    failed_post_import (id) {
+        id -> Int4,
+        ap_id -> Text,
+        data -> Text,
+        error -> Text,
+        published -> Timestamptz,
+        updated -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     federation_allowlist (instance_id) {
         instance_id -> Int4,
@@ -329,6 +365,7 @@ diesel::table! {
         software -> Nullable<Varchar>,
         #[max_length = 255]
         version -> Nullable<Varchar>,
+        vote_weight -> Int4,
     }
 }
 
@@ -393,6 +430,40 @@ diesel::table! {
         federation_signed_fetch -> Bool,
         default_post_listing_mode -> PostListingModeEnum,
         default_sort_type -> SortTypeEnum,
+        post_title_from_summary -> Bool,
+        post_title_content_length -> Int4,
+        anonymize_votes -> Bool,
+        thumbnail_max_size -> Nullable<Int4>,
+        thumbnail_format -> Nullable<Text>,
+        federation_edit_grace_period -> Int4,
+        vote_cooldown_hours -> Int4,
+        federation_content_max_length -> Nullable<Int4>,
+        max_comment_fetch_depth -> Int4,
+        attachment_allowed_types -> Array<Text>,
+        attachment_blocked_types -> Array<Text>,
+        federation_dead_instance_threshold -> Nullable<Int4>,
+        vote_max_post_age_days -> Nullable<Int4>,
+        federation_backfill_request_limit -> Nullable<Int4>,
+        federation_community_quarantine_days -> Nullable<Int4>,
+        federation_user_agent -> Nullable<Text>,
+        tracking_pixel_domains -> Array<Text>,
+        vote_batching_threshold -> Nullable<Int4>,
+        federation_reports -> Bool,
+        federation_reports_anonymous -> Bool,
+        federation_store_raw_json -> Bool,
+        federation_fallback_community_id -> Nullable<Int4>,
+        proxy_post_attachments -> Bool,
+        federation_max_recipients -> Nullable<Int4>,
+        federation_future_tolerance -> Int4,
+        normalize_titles -> Bool,
+        federation_group_posts -> Bool,
+        thumbnail_min_size -> Nullable<Int4>,
+        federation_vote_switch_undo -> Bool,
+        federation_body_strip_patterns -> Array<Text>,
+        federation_page_types -> Array<Text>,
+        federation_preserve_html -> Bool,
+        federation_comments_enabled_default -> Bool,
+        backfill_max_comments_per_post -> Nullable<Int4>,
     }
 }
 
@@ -563,7 +634,7 @@ diesel::table! {
 diesel::table! {
     mod_lock_post (id) {
         id -> Int4,
-        mod_person_id -> Int4,
+        mod_person_id -> Nullable<Int4>,
         post_id -> Int4,
         locked -> Bool,
         when_ -> Timestamptz,
@@ -595,7 +666,7 @@ diesel::table! {
 diesel::table! {
     mod_remove_post (id) {
         id -> Int4,
-        mod_person_id -> Int4,
+        mod_person_id -> Nullable<Int4>,
         post_id -> Int4,
         reason -> Nullable<Text>,
         removed -> Bool,
@@ -650,6 +721,7 @@ diesel::table! {
         bot_account -> Bool,
         ban_expires -> Nullable<Timestamptz>,
         instance_id -> Int4,
+        shadow_vote -> Bool,
     }
 }
 
@@ -707,6 +779,10 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::PostSourceFormatEnum;
+    use super::sql_types::CommentSortTypeEnum;
+
     post (id) {
         id -> Int4,
         #[max_length = 200]
@@ -734,6 +810,24 @@ diesel::table! {
         featured_local -> Bool,
         url_content_type -> Nullable<Text>,
         alt_text -> Nullable<Text>,
+        url_nsfw -> Nullable<Bool>,
+        remote_views -> Nullable<Int8>,
+        remote_comments -> Nullable<Int8>,
+        featured_position -> Int4,
+        canonical_url -> Nullable<Text>,
+        source_format -> PostSourceFormatEnum,
+        category -> Nullable<Text>,
+        distinguished -> Bool,
+        updated_by -> Nullable<Int4>,
+        original_language_id -> Nullable<Int4>,
+        crosspost_group -> Nullable<Text>,
+        pinned_comment_id -> Nullable<Int4>,
+        original_content -> Bool,
+        default_comment_sort -> Nullable<CommentSortTypeEnum>,
+        close_at -> Nullable<Timestamptz>,
+        content_html -> Nullable<Text>,
+        quarantined -> Bool,
+        additional_audience -> Array<Text>,
     }
 }
 
@@ -756,6 +850,7 @@ diesel::table! {
         controversy_rank -> Float8,
         instance_id -> Int4,
         scaled_rank -> Float8,
+        featured_position -> Int4,
     }
 }
 
@@ -776,6 +871,33 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    post_pending_federation (post_id) {
+        post_id -> Int4,
+        send_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    post_previous_version (post_id) {
+        post_id -> Int4,
+        #[max_length = 200]
+        name -> Varchar,
+        body -> Nullable<Text>,
+        published -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    post_raw_json (ap_id) {
+        ap_id -> Text,
+        post_id -> Nullable<Int4>,
+        data -> Text,
+        error -> Nullable<Text>,
+        published -> Timestamptz,
+    }
+}
+
 diesel::table! {
     post_read (person_id, post_id) {
         post_id -> Int4,
@@ -787,7 +909,7 @@ diesel::table! {
 diesel::table! {
     post_report (id) {
         id -> Int4,
-        creator_id -> Int4,
+        creator_id -> Nullable<Int4>,
         post_id -> Int4,
         #[max_length = 200]
         original_post_name -> Varchar,
@@ -809,6 +931,29 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    post_thumbnail_retry (post_id) {
+        post_id -> Int4,
+        image_url -> Text,
+        retry_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    post_vote_batching (post_id) {
+        post_id -> Int4,
+    }
+}
+
+diesel::table! {
+    post_vote_queue (id) {
+        id -> Int4,
+        post_id -> Int4,
+        upvotes -> Int8,
+        downvotes -> Int8,
+    }
+}
+
 diesel::table! {
     private_message (id) {
         id -> Int4,
@@ -983,6 +1128,7 @@ diesel::joinable!(federation_queue_state -> instance (instance_id));
 diesel::joinable!(instance_block -> instance (instance_id));
 diesel::joinable!(instance_block -> person (person_id));
 diesel::joinable!(local_image -> local_user (local_user_id));
+diesel::joinable!(local_site -> community (federation_fallback_community_id));
 diesel::joinable!(local_site -> site (site_id));
 diesel::joinable!(local_site_rate_limit -> local_site (local_site_id));
 diesel::joinable!(local_user -> person (person_id));
@@ -1024,11 +1170,16 @@ diesel::joinable!(post_hide -> person (person_id));
 diesel::joinable!(post_hide -> post (post_id));
 diesel::joinable!(post_like -> person (person_id));
 diesel::joinable!(post_like -> post (post_id));
+diesel::joinable!(post_pending_federation -> post (post_id));
+diesel::joinable!(post_previous_version -> post (post_id));
+diesel::joinable!(post_raw_json -> post (post_id));
 diesel::joinable!(post_read -> person (person_id));
 diesel::joinable!(post_read -> post (post_id));
 diesel::joinable!(post_report -> post (post_id));
 diesel::joinable!(post_saved -> person (person_id));
 diesel::joinable!(post_saved -> post (post_id));
+diesel::joinable!(post_thumbnail_retry -> post (post_id));
+diesel::joinable!(post_vote_batching -> post (post_id));
 diesel::joinable!(private_message_report -> private_message (private_message_id));
 diesel::joinable!(registration_application -> local_user (local_user_id));
 diesel::joinable!(registration_application -> person (admin_id));
@@ -1060,6 +1211,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     custom_emoji,
     custom_emoji_keyword,
     email_verification,
+    failed_post_import,
     federation_allowlist,
     federation_blocklist,
     federation_queue_state,
@@ -1098,9 +1250,15 @@ diesel::allow_tables_to_appear_in_same_query!(
     post_aggregates,
     post_hide,
     post_like,
+    post_pending_federation,
+    post_previous_version,
+    post_raw_json,
     post_read,
     post_report,
     post_saved,
+    post_thumbnail_retry,
+    post_vote_batching,
+    post_vote_queue,
     private_message,
     private_message_report,
     received_activity,
@@ -49,6 +49,10 @@ pub struct Comment {
   /// Whether the comment has been distinguished(speaking officially) by a mod.
   pub distinguished: bool,
   pub language_id: LanguageId,
+  /// Number of direct replies as reported by the remote instance. Replies are only fetched when
+  /// the comment is expanded, so until then this can be shown instead of the local count. This is
+  /// untrusted data.
+  pub remote_child_count: Option<i64>,
 }
 
 #[derive(Debug, Clone, TypedBuilder)]
@@ -70,6 +74,7 @@ pub struct CommentInsertForm {
   pub local: Option<bool>,
   pub distinguished: Option<bool>,
   pub language_id: Option<LanguageId>,
+  pub remote_child_count: Option<i64>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -85,6 +90,7 @@ pub struct CommentUpdateForm {
   pub local: Option<bool>,
   pub distinguished: Option<bool>,
   pub language_id: Option<LanguageId>,
+  pub remote_child_count: Option<Option<i64>>,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -20,7 +20,8 @@ use ts_rs::TS;
 /// A comment report.
 pub struct CommentReport {
   pub id: CommentReportId,
-  pub creator_id: PersonId,
+  /// Not set for anonymous reports from other instances.
+  pub creator_id: Option<PersonId>,
   pub comment_id: CommentId,
   pub original_comment_text: String,
   pub reason: String,
@@ -34,7 +35,7 @@ pub struct CommentReport {
 #[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
 #[cfg_attr(feature = "full", diesel(table_name = comment_report))]
 pub struct CommentReportForm {
-  pub creator_id: PersonId,
+  pub creator_id: Option<PersonId>,
   pub comment_id: CommentId,
   pub original_comment_text: String,
   pub reason: String,
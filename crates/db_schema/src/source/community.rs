@@ -5,6 +5,7 @@ use crate::{
   sensitive::SensitiveString,
   source::placeholder_apub_url,
   CommunityVisibility,
+  DuplicateTitleAction,
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -69,6 +70,34 @@ pub struct Community {
   #[serde(skip)]
   pub featured_url: Option<DbUrl>,
   pub visibility: CommunityVisibility,
+  /// Hour (UTC) from which posting is allowed each day. If it equals `posting_window_end`, posting
+  /// is allowed at any time.
+  pub posting_window_start: i16,
+  /// Hour (UTC) until which posting is allowed each day. May be lower than `posting_window_start`
+  /// for windows which extend past midnight.
+  pub posting_window_end: i16,
+  /// While set, no activities from this community are sent to other instances.
+  pub federation_paused: bool,
+  /// While set, activities from other instances in this community are rejected.
+  pub federation_incoming_paused: bool,
+  /// Additional slur filter for posts in this community, applied on top of the site filter.
+  pub slur_filter_regex: Option<String>,
+  /// Posts which are received until this time are stored as removed, unless an admin approves the
+  /// community before.
+  pub quarantined_until: Option<DateTime<Utc>>,
+  /// If false, posts marked as NSFW are removed from this community.
+  pub allow_nsfw: bool,
+  /// New posts are checked for titles which were used in this community during the given number
+  /// of hours. Zero disables the check.
+  pub duplicate_title_hours: i32,
+  /// What happens to posts with a duplicate title.
+  pub duplicate_title_action: DuplicateTitleAction,
+  /// Users whose karma, the sum of their post and comment score, is below this can't post in the
+  /// community. Zero disables the check.
+  pub min_post_karma: i32,
+  /// Minimum time in seconds between two posts of the same user in the community. Zero disables
+  /// the check.
+  pub post_interval_secs: i32,
 }
 
 #[derive(Debug, Clone, TypedBuilder, Default)]
@@ -103,6 +132,17 @@ pub struct CommunityInsertForm {
   #[builder(!default)]
   pub instance_id: InstanceId,
   pub visibility: Option<CommunityVisibility>,
+  pub posting_window_start: Option<i16>,
+  pub posting_window_end: Option<i16>,
+  pub federation_paused: Option<bool>,
+  pub federation_incoming_paused: Option<bool>,
+  pub slur_filter_regex: Option<String>,
+  pub quarantined_until: Option<DateTime<Utc>>,
+  pub allow_nsfw: Option<bool>,
+  pub duplicate_title_hours: Option<i32>,
+  pub duplicate_title_action: Option<DuplicateTitleAction>,
+  pub min_post_karma: Option<i32>,
+  pub post_interval_secs: Option<i32>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -131,6 +171,17 @@ pub struct CommunityUpdateForm {
   pub hidden: Option<bool>,
   pub posting_restricted_to_mods: Option<bool>,
   pub visibility: Option<CommunityVisibility>,
+  pub posting_window_start: Option<i16>,
+  pub posting_window_end: Option<i16>,
+  pub federation_paused: Option<bool>,
+  pub federation_incoming_paused: Option<bool>,
+  pub slur_filter_regex: Option<Option<String>>,
+  pub quarantined_until: Option<Option<DateTime<Utc>>>,
+  pub allow_nsfw: Option<bool>,
+  pub duplicate_title_hours: Option<i32>,
+  pub duplicate_title_action: Option<DuplicateTitleAction>,
+  pub min_post_karma: Option<i32>,
+  pub post_interval_secs: Option<i32>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
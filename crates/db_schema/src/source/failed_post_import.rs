@@ -0,0 +1,39 @@
+use crate::newtypes::{DbUrl, FailedPostImportId};
+#[cfg(feature = "full")]
+use crate::schema::failed_post_import;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = failed_post_import))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An incoming post which couldn't be imported because of a temporary problem. It can be
+/// reprocessed by an admin.
+pub struct FailedPostImport {
+  pub id: FailedPostImportId,
+  #[cfg_attr(feature = "full", ts(type = "string"))]
+  /// The federated activity id / ap_id of the post.
+  pub ap_id: DbUrl,
+  /// The received `Page` object as json.
+  pub data: String,
+  /// The error of the last import attempt.
+  pub error: String,
+  pub published: DateTime<Utc>,
+  pub updated: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = failed_post_import))]
+pub struct FailedPostImportForm {
+  pub ap_id: DbUrl,
+  pub data: String,
+  pub error: String,
+  pub updated: Option<DateTime<Utc>>,
+}
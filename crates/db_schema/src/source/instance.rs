@@ -23,6 +23,8 @@ pub struct Instance {
   pub updated: Option<DateTime<Utc>>,
   pub software: Option<String>,
   pub version: Option<String>,
+  /// Multiplier for votes from users of this instance. Zero means their votes are ignored.
+  pub vote_weight: i32,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -35,4 +37,5 @@ pub struct InstanceForm {
   pub software: Option<String>,
   pub version: Option<String>,
   pub updated: Option<DateTime<Utc>>,
+  pub vote_weight: Option<i32>,
 }
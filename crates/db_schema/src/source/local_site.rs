@@ -1,7 +1,7 @@
 #[cfg(feature = "full")]
 use crate::schema::local_site;
 use crate::{
-  newtypes::{LocalSiteId, SiteId},
+  newtypes::{CommunityId, LocalSiteId, SiteId},
   ListingType,
   PostListingMode,
   RegistrationMode,
@@ -70,6 +70,104 @@ pub struct LocalSite {
   pub default_post_listing_mode: PostListingMode,
   /// Default value for [LocalUser.post_listing_mode]
   pub default_sort_type: SortType,
+  /// Whether to use the summary of a federated microblog post as its title.
+  pub post_title_from_summary: bool,
+  /// Number of content characters used as title for federated posts without name or summary.
+  pub post_title_content_length: i32,
+  /// Whether individual votes are hidden from everyone except admins.
+  pub anonymize_votes: bool,
+  /// Maximum width or height of generated post thumbnails in pixels. Unset keeps the original
+  /// size.
+  pub thumbnail_max_size: Option<i32>,
+  /// Image format of generated post thumbnails (for example `webp` or `avif`).
+  pub thumbnail_format: Option<String>,
+  /// Seconds after creating a post during which edits are not federated separately. The post is
+  /// federated once this period is over, including any edits.
+  pub federation_edit_grace_period: i32,
+  /// Number of hours after signup during which new accounts are not allowed to vote.
+  pub vote_cooldown_hours: i32,
+  /// If set, the content of outgoing posts is truncated to this many characters, with a link to
+  /// the full post. The full text is still federated as source.
+  pub federation_content_max_length: Option<i32>,
+  /// Maximum nesting depth of federated comments. Replies in deeper threads are attached to the
+  /// ancestor at this depth, and unknown parents aren't fetched beyond this depth.
+  pub max_comment_fetch_depth: i32,
+  /// Media types of federated media attachments which are accepted, like `image/png` or
+  /// `image/*`. If empty, all types which aren't blocked are accepted. Links are always accepted.
+  pub attachment_allowed_types: Vec<String>,
+  /// Media types of federated attachments which are rejected, like `video/*`.
+  pub attachment_blocked_types: Vec<String>,
+  /// Number of consecutive failed deliveries after which an instance is marked as dead. Disabled
+  /// if not set.
+  pub federation_dead_instance_threshold: Option<i32>,
+  /// Votes on posts older than this many days are rejected, except from mods and admins.
+  pub vote_max_post_age_days: Option<i32>,
+  /// Maximum number of HTTP requests for backfilling posts from an instance after it is unblocked.
+  /// Defaults to the fetch limit of a single object.
+  pub federation_backfill_request_limit: Option<i32>,
+  /// Posts from newly federated communities are stored as removed for this many days, until an
+  /// admin approves the community.
+  pub federation_community_quarantine_days: Option<i32>,
+  /// Custom User-Agent for outgoing HTTP requests. The default contains the Lemmy version and the
+  /// instance domain. Changes take effect after a restart.
+  pub federation_user_agent: Option<String>,
+  /// Images from these domains and their subdomains are removed from federated posts, as they
+  /// are likely used for tracking.
+  pub tracking_pixel_domains: Vec<String>,
+  /// Votes on posts from the last day which have at least this many votes are added to the
+  /// aggregates in batches every few seconds, to avoid lock contention on viral posts. Empty or
+  /// zero disables batching.
+  pub vote_batching_threshold: Option<i32>,
+  /// Whether reports of remote content are sent to the instance it comes from, so that its mods
+  /// can handle them.
+  pub federation_reports: bool,
+  /// Send federated reports from the instance actor instead of the reporter.
+  pub federation_reports_anonymous: bool,
+  /// Store the raw json of received posts for a few days, so that admins can debug federation
+  /// issues.
+  pub federation_store_raw_json: bool,
+  /// Local community which receives federated posts whose community can't be found, instead of
+  /// rejecting them.
+  pub federation_fallback_community_id: Option<CommunityId>,
+  /// Serve remote images of posts to clients through the image proxy, so that remote hosts can't
+  /// see the IP addresses of users.
+  pub proxy_post_attachments: bool,
+  /// Outgoing comments which mention more than this many users are addressed to the followers
+  /// collection of the community instead of listing each recipient in `cc`. Disabled if not set.
+  pub federation_max_recipients: Option<i32>,
+  /// Seconds by which the timestamps of federated posts may be in the future, for example because
+  /// of clock skew on the sending instance. They are replaced with the time of receipt. Posts
+  /// which are further in the future are rejected.
+  pub federation_future_tolerance: i32,
+  /// Post titles are additionally checked against slur filters and duplicate titles in a
+  /// normalized form, with compatibility characters and lookalike letters from other scripts
+  /// replaced by their basic latin equivalent. The original title is stored unchanged.
+  pub normalize_titles: bool,
+  /// Federated posts which are attributed to a community, but not to any person, are accepted
+  /// and attributed to a placeholder bot account of the community's instance. Otherwise they are
+  /// rejected.
+  pub federation_group_posts: bool,
+  /// Minimum width and height in pixels of an opengraph image to be preferred as post thumbnail.
+  /// If no image is large enough, the largest one is used. Unset always uses the first image.
+  pub thumbnail_min_size: Option<i32>,
+  /// Changed votes are federated as an Undo of the previous vote followed by the new vote, for
+  /// remote software which doesn't replace existing votes. Otherwise only the new vote is sent.
+  pub federation_vote_switch_undo: bool,
+  /// Text matching these regular expressions is removed from the body of federated posts, for
+  /// example signatures which some platforms append to every post.
+  pub federation_body_strip_patterns: Vec<String>,
+  /// Object types of federated posts which are accepted, like `Page` or `Video`. If empty, all
+  /// types are accepted.
+  pub federation_page_types: Vec<String>,
+  /// Keep the sanitized HTML of federated posts which don't include a markdown source, in addition
+  /// to the body converted to markdown. The HTML is then federated back out as received.
+  pub federation_preserve_html: bool,
+  /// Whether comments are enabled on imported posts which don't specify it with `commentsEnabled`.
+  /// Set to false to lock such posts, for example on archive instances.
+  pub federation_comments_enabled_default: bool,
+  /// Maximum number of comments which are fetched for each post when backfilling a community. The
+  /// remaining comments are only fetched when requested. Defaults to the limit for a single fetch.
+  pub backfill_max_comments_per_post: Option<i32>,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -101,6 +199,40 @@ pub struct LocalSiteInsertForm {
   pub federation_signed_fetch: Option<bool>,
   pub default_post_listing_mode: Option<PostListingMode>,
   pub default_sort_type: Option<SortType>,
+  pub post_title_from_summary: Option<bool>,
+  pub post_title_content_length: Option<i32>,
+  pub anonymize_votes: Option<bool>,
+  pub thumbnail_max_size: Option<i32>,
+  pub thumbnail_format: Option<String>,
+  pub federation_edit_grace_period: Option<i32>,
+  pub vote_cooldown_hours: Option<i32>,
+  pub federation_content_max_length: Option<i32>,
+  pub max_comment_fetch_depth: Option<i32>,
+  pub attachment_allowed_types: Option<Vec<String>>,
+  pub attachment_blocked_types: Option<Vec<String>>,
+  pub federation_dead_instance_threshold: Option<i32>,
+  pub vote_max_post_age_days: Option<i32>,
+  pub federation_backfill_request_limit: Option<i32>,
+  pub federation_community_quarantine_days: Option<i32>,
+  pub federation_user_agent: Option<String>,
+  pub tracking_pixel_domains: Option<Vec<String>>,
+  pub vote_batching_threshold: Option<i32>,
+  pub federation_reports: Option<bool>,
+  pub federation_reports_anonymous: Option<bool>,
+  pub federation_store_raw_json: Option<bool>,
+  pub federation_fallback_community_id: Option<CommunityId>,
+  pub proxy_post_attachments: Option<bool>,
+  pub federation_max_recipients: Option<i32>,
+  pub federation_future_tolerance: Option<i32>,
+  pub normalize_titles: Option<bool>,
+  pub federation_group_posts: Option<bool>,
+  pub thumbnail_min_size: Option<i32>,
+  pub federation_vote_switch_undo: Option<bool>,
+  pub federation_body_strip_patterns: Option<Vec<String>>,
+  pub federation_page_types: Option<Vec<String>>,
+  pub federation_preserve_html: Option<bool>,
+  pub federation_comments_enabled_default: Option<bool>,
+  pub backfill_max_comments_per_post: Option<i32>,
 }
 
 #[derive(Clone, Default)]
@@ -130,4 +262,38 @@ pub struct LocalSiteUpdateForm {
   pub federation_signed_fetch: Option<bool>,
   pub default_post_listing_mode: Option<PostListingMode>,
   pub default_sort_type: Option<SortType>,
+  pub post_title_from_summary: Option<bool>,
+  pub post_title_content_length: Option<i32>,
+  pub anonymize_votes: Option<bool>,
+  pub thumbnail_max_size: Option<Option<i32>>,
+  pub thumbnail_format: Option<Option<String>>,
+  pub federation_edit_grace_period: Option<i32>,
+  pub vote_cooldown_hours: Option<i32>,
+  pub federation_content_max_length: Option<Option<i32>>,
+  pub max_comment_fetch_depth: Option<i32>,
+  pub attachment_allowed_types: Option<Vec<String>>,
+  pub attachment_blocked_types: Option<Vec<String>>,
+  pub federation_dead_instance_threshold: Option<Option<i32>>,
+  pub vote_max_post_age_days: Option<Option<i32>>,
+  pub federation_backfill_request_limit: Option<Option<i32>>,
+  pub federation_community_quarantine_days: Option<Option<i32>>,
+  pub federation_user_agent: Option<Option<String>>,
+  pub tracking_pixel_domains: Option<Vec<String>>,
+  pub vote_batching_threshold: Option<Option<i32>>,
+  pub federation_reports: Option<bool>,
+  pub federation_reports_anonymous: Option<bool>,
+  pub federation_store_raw_json: Option<bool>,
+  pub federation_fallback_community_id: Option<Option<CommunityId>>,
+  pub proxy_post_attachments: Option<bool>,
+  pub federation_max_recipients: Option<Option<i32>>,
+  pub federation_future_tolerance: Option<i32>,
+  pub normalize_titles: Option<bool>,
+  pub federation_group_posts: Option<bool>,
+  pub thumbnail_min_size: Option<Option<i32>>,
+  pub federation_vote_switch_undo: Option<bool>,
+  pub federation_body_strip_patterns: Option<Vec<String>>,
+  pub federation_page_types: Option<Vec<String>>,
+  pub federation_preserve_html: Option<bool>,
+  pub federation_comments_enabled_default: Option<bool>,
+  pub backfill_max_comments_per_post: Option<Option<i32>>,
 }
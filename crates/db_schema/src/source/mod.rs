@@ -13,6 +13,7 @@ pub mod community_block;
 pub mod custom_emoji;
 pub mod custom_emoji_keyword;
 pub mod email_verification;
+pub mod failed_post_import;
 pub mod federation_allowlist;
 pub mod federation_blocklist;
 pub mod federation_queue_state;
@@ -32,7 +33,11 @@ pub mod person;
 pub mod person_block;
 pub mod person_mention;
 pub mod post;
+pub mod post_pending_federation;
+pub mod post_previous_version;
+pub mod post_raw_json;
 pub mod post_report;
+pub mod post_thumbnail_retry;
 pub mod private_message;
 pub mod private_message_report;
 pub mod registration_application;
@@ -32,7 +32,8 @@ use ts_rs::TS;
 /// When a moderator removes a post.
 pub struct ModRemovePost {
   pub id: i32,
-  pub mod_person_id: PersonId,
+  /// Not set for automatic removals, eg by the community slur filter.
+  pub mod_person_id: Option<PersonId>,
   pub post_id: PostId,
   pub reason: Option<String>,
   pub removed: bool,
@@ -42,7 +43,7 @@ pub struct ModRemovePost {
 #[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
 #[cfg_attr(feature = "full", diesel(table_name = mod_remove_post))]
 pub struct ModRemovePostForm {
-  pub mod_person_id: PersonId,
+  pub mod_person_id: Option<PersonId>,
   pub post_id: PostId,
   pub reason: Option<String>,
   pub removed: Option<bool>,
@@ -56,7 +57,8 @@ pub struct ModRemovePostForm {
 /// When a moderator locks a post (prevents new comments being made).
 pub struct ModLockPost {
   pub id: i32,
-  pub mod_person_id: PersonId,
+  /// Not set for automatic locks, eg after the close time of the post.
+  pub mod_person_id: Option<PersonId>,
   pub post_id: PostId,
   pub locked: bool,
   pub when_: DateTime<Utc>,
@@ -65,7 +67,7 @@ pub struct ModLockPost {
 #[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
 #[cfg_attr(feature = "full", diesel(table_name = mod_lock_post))]
 pub struct ModLockPostForm {
-  pub mod_person_id: PersonId,
+  pub mod_person_id: Option<PersonId>,
   pub post_id: PostId,
   pub locked: Option<bool>,
 }
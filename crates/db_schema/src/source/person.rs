@@ -57,6 +57,10 @@ pub struct Person {
   /// When their ban, if it exists, expires, if at all.
   pub ban_expires: Option<DateTime<Utc>>,
   pub instance_id: InstanceId,
+  /// Votes of shadow voters are stored but don't count towards scores and aren't federated. This
+  /// is hidden from the API so that suspected vote bots don't notice it.
+  #[serde(skip)]
+  pub shadow_vote: bool,
 }
 
 #[derive(Clone, derive_new::new)]
@@ -123,6 +127,7 @@ pub struct PersonUpdateForm {
   pub matrix_user_id: Option<Option<String>>,
   pub bot_account: Option<bool>,
   pub ban_expires: Option<Option<DateTime<Utc>>>,
+  pub shadow_vote: Option<bool>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -1,6 +1,10 @@
-use crate::newtypes::{CommunityId, DbUrl, LanguageId, PersonId, PostId};
 #[cfg(feature = "full")]
 use crate::schema::{post, post_hide, post_like, post_read, post_saved};
+use crate::{
+  newtypes::{CommentId, CommunityId, DbUrl, LanguageId, PersonId, PostId},
+  CommentSortType,
+  PostSourceFormat,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
@@ -58,6 +62,52 @@ pub struct Post {
   pub url_content_type: Option<String>,
   /// An optional alt_text, usable for image posts.
   pub alt_text: Option<String>,
+  /// Whether the url is NSFW, eg a federated image marked as spoiler. If unset, `nsfw` applies.
+  pub url_nsfw: Option<bool>,
+  /// View count as reported by the remote instance. This is untrusted data which can be set to
+  /// any value by the sender, so it must not be used for ranking.
+  pub remote_views: Option<i64>,
+  /// Comment count as reported by the remote instance. Comments are fetched individually, so
+  /// until then this can be shown instead of the much lower local count. Like `remote_views` this
+  /// is untrusted data.
+  pub remote_comments: Option<i64>,
+  /// Order of posts which are featured in the community, with highest value shown first.
+  pub featured_position: i32,
+  #[cfg_attr(feature = "full", ts(type = "string"))]
+  /// Canonical link to the post for sharing, as announced by the remote instance. For local posts
+  /// this is unset.
+  pub canonical_url: Option<DbUrl>,
+  /// Whether the body was written in markdown, or converted from HTML of a remote post.
+  pub source_format: PostSourceFormat,
+  /// Topical classification of the post, as sent by the remote platform. The value is opaque and
+  /// federated unchanged.
+  pub category: Option<String>,
+  /// Whether the post is distinguished by a moderator (IE speak as moderator).
+  pub distinguished: bool,
+  /// Moderator who made the last edit of the post. Unset if it was edited by the creator.
+  pub updated_by: Option<PersonId>,
+  /// Language in which the post was originally written, if it was translated to `language_id`.
+  pub original_language_id: Option<LanguageId>,
+  /// Federated identifier shared by crossposts of the same link, so that they can also be grouped
+  /// on other instances. This is the `ap_id` of the first post in the group.
+  pub crosspost_group: Option<DbUrl>,
+  /// Comment which the creator or a mod pinned to the top of the post.
+  pub pinned_comment_id: Option<CommentId>,
+  /// Whether the creator marked the post as original content (OC).
+  pub original_content: bool,
+  /// Comment sort which the creator chose for the post. Clients should use it unless the user
+  /// selects a different sort. If unset, the usual default applies.
+  pub default_comment_sort: Option<CommentSortType>,
+  /// Time after which the post is locked automatically, eg for contests.
+  pub close_at: Option<DateTime<Utc>>,
+  /// Sanitized HTML content of a remote post as it was received, if the site preserves remote
+  /// HTML. The body contains the same content converted to markdown.
+  pub content_html: Option<String>,
+  /// Whether the post is removed because its community is in quarantine.
+  pub quarantined: bool,
+  /// Further communities which a remote post was addressed to. The post only appears in its own
+  /// community, these are stored for information.
+  pub additional_audience: Vec<DbUrl>,
 }
 
 #[derive(Debug, Clone, TypedBuilder)]
@@ -90,6 +140,23 @@ pub struct PostInsertForm {
   pub featured_local: Option<bool>,
   pub url_content_type: Option<String>,
   pub alt_text: Option<String>,
+  pub url_nsfw: Option<bool>,
+  pub remote_views: Option<i64>,
+  pub remote_comments: Option<i64>,
+  pub canonical_url: Option<DbUrl>,
+  pub source_format: Option<PostSourceFormat>,
+  pub category: Option<String>,
+  pub distinguished: Option<bool>,
+  pub updated_by: Option<PersonId>,
+  pub original_language_id: Option<LanguageId>,
+  pub crosspost_group: Option<DbUrl>,
+  pub pinned_comment_id: Option<CommentId>,
+  pub original_content: Option<bool>,
+  pub default_comment_sort: Option<CommentSortType>,
+  pub close_at: Option<DateTime<Utc>>,
+  pub content_html: Option<String>,
+  pub quarantined: Option<bool>,
+  pub additional_audience: Option<Vec<DbUrl>>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -116,6 +183,25 @@ pub struct PostUpdateForm {
   pub featured_local: Option<bool>,
   pub url_content_type: Option<Option<String>>,
   pub alt_text: Option<Option<String>>,
+  pub url_nsfw: Option<Option<bool>>,
+  pub community_id: Option<CommunityId>,
+  pub remote_views: Option<Option<i64>>,
+  pub remote_comments: Option<Option<i64>>,
+  pub featured_position: Option<i32>,
+  pub canonical_url: Option<Option<DbUrl>>,
+  pub source_format: Option<PostSourceFormat>,
+  pub category: Option<Option<String>>,
+  pub distinguished: Option<bool>,
+  pub updated_by: Option<Option<PersonId>>,
+  pub original_language_id: Option<Option<LanguageId>>,
+  pub crosspost_group: Option<Option<DbUrl>>,
+  pub pinned_comment_id: Option<Option<CommentId>>,
+  pub original_content: Option<bool>,
+  pub default_comment_sort: Option<Option<CommentSortType>>,
+  pub close_at: Option<Option<DateTime<Utc>>>,
+  pub content_html: Option<Option<String>>,
+  pub quarantined: Option<bool>,
+  pub additional_audience: Option<Vec<DbUrl>>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -0,0 +1,20 @@
+use crate::newtypes::PostId;
+#[cfg(feature = "full")]
+use crate::schema::post_pending_federation;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(
+  feature = "full",
+  derive(Queryable, Selectable, Identifiable, Insertable)
+)]
+#[cfg_attr(feature = "full", diesel(table_name = post_pending_federation))]
+#[cfg_attr(feature = "full", diesel(primary_key(post_id)))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+/// A local post whose Create activity is held back until the end of the edit grace period.
+pub struct PostPendingFederation {
+  pub post_id: PostId,
+  /// When the Create activity should be sent.
+  pub send_at: DateTime<Utc>,
+}
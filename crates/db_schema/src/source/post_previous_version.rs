@@ -0,0 +1,34 @@
+use crate::newtypes::PostId;
+#[cfg(feature = "full")]
+use crate::schema::post_previous_version;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = post_previous_version))]
+#[cfg_attr(feature = "full", diesel(primary_key(post_id)))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Title and body of a post before its last edit.
+pub struct PostPreviousVersion {
+  pub post_id: PostId,
+  pub name: String,
+  pub body: Option<String>,
+  /// When the version was replaced by the edit.
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = post_previous_version))]
+pub struct PostPreviousVersionForm {
+  pub post_id: PostId,
+  pub name: String,
+  pub body: Option<String>,
+  pub published: DateTime<Utc>,
+}
@@ -0,0 +1,40 @@
+use crate::newtypes::{DbUrl, PostId};
+#[cfg(feature = "full")]
+use crate::schema::post_raw_json;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = post_raw_json))]
+#[cfg_attr(feature = "full", diesel(primary_key(ap_id)))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The raw json of a received post, stored for debugging federation issues.
+pub struct PostRawJson {
+  #[cfg_attr(feature = "full", ts(type = "string"))]
+  /// The federated activity id / ap_id of the post.
+  pub ap_id: DbUrl,
+  /// Empty if the post couldn't be imported.
+  pub post_id: Option<PostId>,
+  /// The received json, exactly as it was sent. This is the activity which contained the post.
+  pub data: String,
+  /// The reason why the post couldn't be imported.
+  pub error: Option<String>,
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = post_raw_json))]
+pub struct PostRawJsonForm {
+  pub ap_id: DbUrl,
+  pub post_id: Option<PostId>,
+  pub data: String,
+  pub error: Option<String>,
+  pub published: DateTime<Utc>,
+}
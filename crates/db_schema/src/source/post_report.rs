@@ -20,7 +20,8 @@ use ts_rs::TS;
 /// A post report.
 pub struct PostReport {
   pub id: PostReportId,
-  pub creator_id: PersonId,
+  /// Not set for automatic reports, eg for duplicate titles.
+  pub creator_id: Option<PersonId>,
   pub post_id: PostId,
   /// The original post title.
   pub original_post_name: String,
@@ -39,7 +40,7 @@ pub struct PostReport {
 #[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
 #[cfg_attr(feature = "full", diesel(table_name = post_report))]
 pub struct PostReportForm {
-  pub creator_id: PersonId,
+  pub creator_id: Option<PersonId>,
   pub post_id: PostId,
   pub original_post_name: String,
   pub original_post_url: Option<DbUrl>,
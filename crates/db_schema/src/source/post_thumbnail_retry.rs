@@ -0,0 +1,22 @@
+use crate::newtypes::{DbUrl, PostId};
+#[cfg(feature = "full")]
+use crate::schema::post_thumbnail_retry;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(
+  feature = "full",
+  derive(Queryable, Selectable, Identifiable, Insertable)
+)]
+#[cfg_attr(feature = "full", diesel(table_name = post_thumbnail_retry))]
+#[cfg_attr(feature = "full", diesel(primary_key(post_id)))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+/// A post whose thumbnail couldn't be generated because pictrs was unavailable.
+pub struct PostThumbnailRetry {
+  pub post_id: PostId,
+  /// The image to generate the thumbnail from.
+  pub image_url: DbUrl,
+  /// When thumbnail generation should be tried again.
+  pub retry_at: DateTime<Utc>,
+}
@@ -488,7 +488,7 @@ static EMAIL_REGEX: Lazy<Regex> = Lazy::new(|| {
 });
 
 pub mod functions {
-  use diesel::sql_types::{BigInt, Bool, Text, Timestamptz};
+  use diesel::sql_types::{BigInt, Bool, Integer, Text, Timestamptz};
 
   sql_function! {
     #[sql_name = "r.hot_rank"]
@@ -505,10 +505,24 @@ pub mod functions {
     fn controversy_rank(upvotes: BigInt, downvotes: BigInt, score: BigInt) -> Double;
   }
 
+  sql_function! {
+    #[sql_name = "r.vote_weight"]
+    fn vote_weight(person_id: Integer) -> Integer;
+  }
+
+  sql_function! {
+    #[sql_name = "r.flush_post_vote_queue"]
+    fn flush_post_vote_queue() -> Integer;
+  }
+
   sql_function!(fn reverse_timestamp_sort(time: Timestamptz) -> BigInt);
 
   sql_function!(fn lower(x: Text) -> Text);
 
+  sql_function!(fn btrim(x: Text) -> Text);
+
+  sql_function!(fn regexp_replace(source: Text, pattern: Text, replacement: Text, flags: Text) -> Text);
+
   // really this function is variadic, this just adds the two-argument version
   sql_function!(fn coalesce<T: diesel::sql_types::SqlType + diesel::sql_types::SingleValue>(x: diesel::sql_types::Nullable<T>, y: T) -> T);
 
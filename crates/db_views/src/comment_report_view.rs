@@ -40,7 +40,7 @@ fn queries<'a>() -> Queries<
       .inner_join(comment::table)
       .inner_join(post::table.on(comment::post_id.eq(post::id)))
       .inner_join(community::table.on(post::community_id.eq(community::id)))
-      .inner_join(person::table.on(comment_report::creator_id.eq(person::id)))
+      .left_join(person::table.on(comment_report::creator_id.eq(person::id.nullable())))
       .inner_join(aliases::person1.on(comment::creator_id.eq(aliases::person1.field(person::id))))
       .inner_join(
         comment_aggregates::table.on(comment_report::comment_id.eq(comment_aggregates::comment_id)),
@@ -112,7 +112,7 @@ fn queries<'a>() -> Queries<
         comment::all_columns,
         post::all_columns,
         community::all_columns,
-        person::all_columns,
+        person::all_columns.nullable(),
         aliases::person1.fields(person::all_columns),
         comment_aggregates::all_columns,
         community_person_ban::community_id.nullable().is_not_null(),
@@ -282,6 +282,7 @@ mod tests {
     traits::{Crud, Joinable, Reportable},
     utils::{build_db_pool_for_tests, RANK_DEFAULT},
     CommunityVisibility,
+    DuplicateTitleAction,
     SubscribedType,
   };
   use pretty_assertions::assert_eq;
@@ -361,7 +362,7 @@ mod tests {
 
     // sara reports
     let sara_report_form = CommentReportForm {
-      creator_id: inserted_sara.id,
+      creator_id: Some(inserted_sara.id),
       comment_id: inserted_comment.id,
       original_comment_text: "this was it at time of creation".into(),
       reason: "from sara".into(),
@@ -373,7 +374,7 @@ mod tests {
 
     // jessica reports
     let jessica_report_form = CommentReportForm {
-      creator_id: inserted_jessica.id,
+      creator_id: Some(inserted_jessica.id),
       comment_id: inserted_comment.id,
       original_comment_text: "this was it at time of creation".into(),
       reason: "from jessica".into(),
@@ -428,8 +429,19 @@ mod tests {
         featured_url: inserted_community.featured_url,
         instance_id: inserted_instance.id,
         visibility: CommunityVisibility::Public,
+        posting_window_start: 0,
+        posting_window_end: 0,
+        federation_paused: false,
+        federation_incoming_paused: false,
+        slur_filter_regex: None,
+        quarantined_until: None,
+        allow_nsfw: true,
+        duplicate_title_hours: 0,
+        duplicate_title_action: DuplicateTitleAction::Report,
+        min_post_karma: 0,
+        post_interval_secs: 0,
       },
-      creator: Person {
+      creator: Some(Person {
         id: inserted_jessica.id,
         name: inserted_jessica.name,
         display_name: None,
@@ -440,6 +452,7 @@ mod tests {
         banned: false,
         deleted: false,
         bot_account: false,
+        shadow_vote: false,
         bio: None,
         banner: None,
         updated: None,
@@ -451,7 +464,7 @@ mod tests {
         private_key: inserted_jessica.private_key,
         public_key: inserted_jessica.public_key,
         last_refreshed_at: inserted_jessica.last_refreshed_at,
-      },
+      }),
       comment_creator: Person {
         id: inserted_timmy.id,
         name: inserted_timmy.name.clone(),
@@ -463,6 +476,7 @@ mod tests {
         banned: false,
         deleted: false,
         bot_account: false,
+        shadow_vote: false,
         bio: None,
         banner: None,
         updated: None,
@@ -494,7 +508,7 @@ mod tests {
 
     let mut expected_sara_report_view = expected_jessica_report_view.clone();
     expected_sara_report_view.comment_report = inserted_sara_report;
-    expected_sara_report_view.creator = Person {
+    expected_sara_report_view.creator = Some(Person {
       id: inserted_sara.id,
       name: inserted_sara.name,
       display_name: None,
@@ -505,6 +519,7 @@ mod tests {
       banned: false,
       deleted: false,
       bot_account: false,
+      shadow_vote: false,
       bio: None,
       banner: None,
       updated: None,
@@ -516,7 +531,7 @@ mod tests {
       private_key: inserted_sara.private_key,
       public_key: inserted_sara.public_key,
       last_refreshed_at: inserted_sara.last_refreshed_at,
-    };
+    });
 
     // Do a batch read of timmys reports
     let reports = CommentReportQuery::default()
@@ -571,6 +586,7 @@ mod tests {
       banned: false,
       deleted: false,
       bot_account: false,
+      shadow_vote: false,
       bio: None,
       banner: None,
       updated: None,
@@ -165,6 +165,7 @@ fn queries<'a>() -> Queries<
         is_local_user_banned_from_community_selection,
         creator_is_moderator,
         creator_is_admin,
+        comment::creator_id.eq(post::creator_id),
         subscribed_type_selection,
         comment_saved::person_id.nullable().is_not_null(),
         is_creator_blocked_selection,
@@ -462,6 +463,8 @@ mod tests {
     traits::{Bannable, Blockable, Crud, Joinable, Likeable, Saveable},
     utils::{build_db_pool_for_tests, RANK_DEFAULT},
     CommunityVisibility,
+    DuplicateTitleAction,
+    PostSourceFormat,
     SubscribedType,
   };
   use lemmy_utils::{error::LemmyResult, LemmyErrorType};
@@ -951,6 +954,30 @@ mod tests {
     cleanup(data, pool).await
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn test_creator_is_op() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await?;
+
+    let comments = CommentQuery {
+      sort: (Some(CommentSortType::Old)),
+      ..Default::default()
+    }
+    .list(pool)
+    .await?;
+
+    // Timmy created the post
+    assert_eq!(comments[0].creator.name, "timmy");
+    assert!(comments[0].creator_is_op);
+
+    assert_eq!(comments[1].creator.name, "sara");
+    assert!(!comments[1].creator_is_op);
+
+    cleanup(data, pool).await
+  }
+
   #[tokio::test]
   #[serial]
   async fn test_saved_order() -> LemmyResult<()> {
@@ -1020,6 +1047,7 @@ mod tests {
       banned_from_community: false,
       creator_is_moderator: false,
       creator_is_admin: true,
+      creator_is_op: true,
       my_vote: None,
       subscribed: SubscribedType::NotSubscribed,
       saved: false,
@@ -1037,6 +1065,7 @@ mod tests {
         local: true,
         distinguished: false,
         path: data.inserted_comment_0.clone().path,
+        remote_child_count: None,
         language_id: LanguageId(37),
       },
       creator: Person {
@@ -1050,6 +1079,7 @@ mod tests {
         banned: false,
         deleted: false,
         bot_account: false,
+        shadow_vote: false,
         bio: None,
         banner: None,
         updated: None,
@@ -1069,6 +1099,24 @@ mod tests {
         url: None,
         body: None,
         alt_text: None,
+        url_nsfw: None,
+        remote_views: None,
+        remote_comments: None,
+        featured_position: 0,
+        canonical_url: None,
+        source_format: PostSourceFormat::Markdown,
+        category: None,
+        distinguished: false,
+        updated_by: None,
+        original_language_id: None,
+        crosspost_group: None,
+        pinned_comment_id: None,
+        original_content: false,
+        default_comment_sort: None,
+        close_at: None,
+        content_html: None,
+        quarantined: false,
+        additional_audience: vec![],
         published: data.inserted_post.published,
         updated: None,
         community_id: data.inserted_community.id,
@@ -1113,6 +1161,17 @@ mod tests {
         moderators_url: data.inserted_community.moderators_url.clone(),
         featured_url: data.inserted_community.featured_url.clone(),
         visibility: CommunityVisibility::Public,
+        posting_window_start: 0,
+        posting_window_end: 0,
+        federation_paused: false,
+        federation_incoming_paused: false,
+        slur_filter_regex: None,
+        quarantined_until: None,
+        allow_nsfw: true,
+        duplicate_title_hours: 0,
+        duplicate_title_action: DuplicateTitleAction::Report,
+        min_post_karma: 0,
+        post_interval_secs: 0,
       },
       counts: CommentAggregates {
         comment_id: data.inserted_comment_0.id,
@@ -49,7 +49,7 @@ fn queries<'a>() -> Queries<
     query
       .inner_join(post::table)
       .inner_join(community::table.on(post::community_id.eq(community::id)))
-      .inner_join(person::table.on(post_report::creator_id.eq(person::id)))
+      .left_join(person::table.on(post_report::creator_id.eq(person::id.nullable())))
       .inner_join(aliases::person1.on(post::creator_id.eq(aliases::person1.field(person::id))))
       .left_join(
         community_person_ban::table.on(
@@ -135,7 +135,7 @@ fn queries<'a>() -> Queries<
         post_report::all_columns,
         post::all_columns,
         community::all_columns,
-        person::all_columns,
+        person::all_columns.nullable(),
         aliases::person1.fields(person::all_columns),
         community_person_ban::community_id.nullable().is_not_null(),
         aliases::community_moderator1
@@ -375,7 +375,7 @@ mod tests {
 
     // sara reports
     let sara_report_form = PostReportForm {
-      creator_id: inserted_sara.id,
+      creator_id: Some(inserted_sara.id),
       post_id: inserted_post.id,
       original_post_name: "Orig post".into(),
       original_post_url: None,
@@ -395,7 +395,7 @@ mod tests {
 
     // jessica reports
     let jessica_report_form = PostReportForm {
-      creator_id: inserted_jessica.id,
+      creator_id: Some(inserted_jessica.id),
       post_id: inserted_post_2.id,
       original_post_name: "Orig post".into(),
       original_post_url: None,
@@ -419,7 +419,10 @@ mod tests {
     );
     assert_eq!(read_jessica_report_view.post, inserted_post_2);
     assert_eq!(read_jessica_report_view.community.id, inserted_community.id);
-    assert_eq!(read_jessica_report_view.creator.id, inserted_jessica.id);
+    assert_eq!(
+      read_jessica_report_view.creator.as_ref().map(|c| c.id),
+      Some(inserted_jessica.id)
+    );
     assert_eq!(read_jessica_report_view.post_creator.id, inserted_timmy.id);
     assert_eq!(read_jessica_report_view.my_vote, None);
     assert_eq!(read_jessica_report_view.resolver, None);
@@ -430,8 +433,14 @@ mod tests {
       .await
       .unwrap();
 
-    assert_eq!(reports[1].creator.id, inserted_sara.id);
-    assert_eq!(reports[0].creator.id, inserted_jessica.id);
+    assert_eq!(
+      reports[1].creator.as_ref().map(|c| c.id),
+      Some(inserted_sara.id)
+    );
+    assert_eq!(
+      reports[0].creator.as_ref().map(|c| c.id),
+      Some(inserted_jessica.id)
+    );
 
     // Make sure the counts are correct
     let report_count = PostReportView::get_report_count(pool, inserted_timmy.id, false, None)
@@ -472,7 +481,10 @@ mod tests {
     .await
     .unwrap();
     assert_length!(1, reports_after_resolve);
-    assert_eq!(reports_after_resolve[0].creator.id, inserted_sara.id);
+    assert_eq!(
+      reports_after_resolve[0].creator.as_ref().map(|c| c.id),
+      Some(inserted_sara.id)
+    );
 
     // Make sure the counts are correct
     let report_count_after_resolved =
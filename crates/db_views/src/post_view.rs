@@ -15,13 +15,14 @@ use diesel::{
   OptionalExtension,
   PgTextExpressionMethods,
   QueryDsl,
+  TextExpressionMethods,
 };
 use diesel_async::RunQueryDsl;
 use i_love_jesus::PaginatedQueryBuilder;
 use lemmy_db_schema::{
   aggregates::structs::{post_aggregates_keys as key, PostAggregates},
   impls::local_user::LocalUserOptionHelper,
-  newtypes::{CommunityId, LocalUserId, PersonId, PostId},
+  newtypes::{CommunityId, DbUrl, LocalUserId, PersonId, PostId},
   schema::{
     community,
     community_block,
@@ -338,6 +339,14 @@ fn queries<'a>() -> Queries<
       query = query.filter(post_aggregates::creator_id.eq(creator_id));
     }
 
+    if let Some(origin_domain) = &options.origin_domain {
+      let origin_domain = origin_domain
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+      query = query.filter(post::ap_id.ilike(format!("http%://{origin_domain}/%")));
+    }
+
     if let Some(listing_type) = options.listing_type {
       if let Some(person_id) = options.local_user.person_id() {
         let is_subscribed = exists(
@@ -385,6 +394,27 @@ fn queries<'a>() -> Queries<
       query = query.filter(post::url.eq(url_search));
     }
 
+    if let Some(crosspost_group) = &options.crosspost_group {
+      query = query.filter(
+        post::crosspost_group
+          .eq(crosspost_group.clone())
+          .or(post::ap_id.eq(crosspost_group.clone())),
+      );
+    }
+
+    if let Some(media_type) = &options.media_type {
+      query = match media_type.strip_suffix('*') {
+        Some(prefix) => {
+          let prefix = prefix
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+          query.filter(post::url_content_type.like(format!("{prefix}%")))
+        }
+        None => query.filter(post::url_content_type.eq(media_type.clone())),
+      };
+    }
+
     if let Some(search_term) = &options.search_term {
       let searcher = fuzzy_search(search_term);
       query = query
@@ -501,7 +531,9 @@ fn queries<'a>() -> Queries<
     query = if options.community_id.is_none() || options.community_id_just_for_prefetch {
       query.then_desc(key::featured_local)
     } else {
-      query.then_desc(key::featured_community)
+      query
+        .then_desc(key::featured_community)
+        .then_desc(key::featured_position)
     };
 
     let time = |interval| post_aggregates::published.gt(now() - interval);
@@ -608,12 +640,19 @@ pub struct PostQuery<'a> {
   pub sort: Option<SortType>,
   pub creator_id: Option<PersonId>,
   pub community_id: Option<CommunityId>,
+  /// Only include posts which originated on the instance with this domain, based on their ap_id.
+  pub origin_domain: Option<String>,
   // if true, the query should be handled as if community_id was not given except adding the
   // literal filter
   pub community_id_just_for_prefetch: bool,
   pub local_user: Option<&'a LocalUser>,
   pub search_term: Option<String>,
   pub url_search: Option<String>,
+  /// Only include posts of this crosspost group, including the post which started it.
+  pub crosspost_group: Option<DbUrl>,
+  /// Only include posts whose attachment has this media type. A type like `video/*` matches all
+  /// subtypes.
+  pub media_type: Option<String>,
   pub saved_only: Option<bool>,
   pub liked_only: Option<bool>,
   pub disliked_only: Option<bool>,
@@ -756,16 +795,19 @@ mod tests {
       instance::Instance,
       instance_block::{InstanceBlock, InstanceBlockForm},
       language::Language,
+      local_site::{LocalSite, LocalSiteInsertForm},
       local_user::{LocalUser, LocalUserInsertForm, LocalUserUpdateForm},
       local_user_vote_display_mode::LocalUserVoteDisplayMode,
       person::{Person, PersonInsertForm},
       person_block::{PersonBlock, PersonBlockForm},
       post::{Post, PostHide, PostInsertForm, PostLike, PostLikeForm, PostRead, PostUpdateForm},
-      site::Site,
+      site::{Site, SiteInsertForm},
     },
     traits::{Bannable, Blockable, Crud, Joinable, Likeable},
     utils::{build_db_pool, build_db_pool_for_tests, DbPool, RANK_DEFAULT},
     CommunityVisibility,
+    DuplicateTitleAction,
+    PostSourceFormat,
     SortType,
     SubscribedType,
   };
@@ -1023,6 +1065,62 @@ mod tests {
     cleanup(data, pool).await
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_my_vote_requires_auth() -> LemmyResult<()> {
+    let pool = &build_db_pool().await?;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await?;
+
+    // Users still see their own votes when individual votes are anonymized
+    let site_form = SiteInsertForm::builder()
+      .name("test site".to_string())
+      .instance_id(data.inserted_instance.id)
+      .build();
+    let site = Site::create(pool, &site_form).await?;
+    let local_site_form = LocalSiteInsertForm::builder()
+      .site_id(site.id)
+      .anonymize_votes(Some(true))
+      .build();
+    LocalSite::create(pool, &local_site_form).await?;
+
+    let post_like_form = PostLikeForm {
+      post_id: data.inserted_post.id,
+      person_id: data.local_user_view.person.id,
+      score: 1,
+    };
+    PostLike::like(pool, &post_like_form).await?;
+
+    let authenticated = PostView::read(
+      pool,
+      data.inserted_post.id,
+      Some(&data.local_user_view.local_user),
+      false,
+    )
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert_eq!(Some(1), authenticated.my_vote);
+
+    // Other users and anonymous requests never see the vote
+    let other_user = PostView::read(
+      pool,
+      data.inserted_post.id,
+      Some(&data.blocked_local_user_view.local_user),
+      false,
+    )
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert_eq!(None, other_user.my_vote);
+    let anonymous = PostView::read(pool, data.inserted_post.id, None, false)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert_eq!(None, anonymous.my_vote);
+
+    Site::delete(pool, site.id).await?;
+    LocalSite::delete(pool).await?;
+    cleanup(data, pool).await
+  }
+
   #[tokio::test]
   #[serial]
   async fn post_listing_block_community() -> LemmyResult<()> {
@@ -1394,6 +1492,102 @@ mod tests {
     cleanup(data, pool).await
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_origin_domain() -> LemmyResult<()> {
+    const POST_FROM_OTHER_INSTANCE: &str = "post from other instance";
+
+    let pool = &build_db_pool().await?;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await?;
+
+    let other_instance = Instance::read_or_create(pool, "other_domain.tld".to_string()).await?;
+    let post_form = PostInsertForm::builder()
+      .name(POST_FROM_OTHER_INSTANCE.to_string())
+      .creator_id(data.local_user_view.person.id)
+      .community_id(data.inserted_community.id)
+      .ap_id(Some(Url::parse("https://other_domain.tld/post/1")?.into()))
+      .local(Some(false))
+      .build();
+    Post::create(pool, &post_form).await?;
+
+    // only the post with ap_id on the other instance is returned
+    let post_listings = PostQuery {
+      origin_domain: Some(other_instance.domain.clone()),
+      ..data.default_post_query()
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert_eq!(vec![POST_FROM_OTHER_INSTANCE], names(&post_listings));
+
+    // posts created locally have their ap_id on the local domain
+    let post_listings = PostQuery {
+      origin_domain: Some("lemmy-alpha".to_string()),
+      ..data.default_post_query()
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert_eq!(vec![POST_BY_BOT, POST], names(&post_listings));
+
+    // wildcards in the domain are matched literally
+    let post_listings = PostQuery {
+      origin_domain: Some("%".to_string()),
+      ..data.default_post_query()
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert!(post_listings.is_empty());
+
+    Instance::delete(pool, other_instance.id).await?;
+    cleanup(data, pool).await
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_media_type() -> LemmyResult<()> {
+    let pool = &build_db_pool().await?;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await?;
+
+    let set_content_type = |content_type: &str| PostUpdateForm {
+      url_content_type: Some(Some(content_type.to_string())),
+      ..Default::default()
+    };
+    Post::update(pool, data.inserted_post.id, &set_content_type("image/png")).await?;
+    Post::update(
+      pool,
+      data.inserted_bot_post.id,
+      &set_content_type("application/pdf"),
+    )
+    .await?;
+    async fn list(
+      data: &Data,
+      media_type: &str,
+      pool: &mut DbPool<'_>,
+    ) -> LemmyResult<Vec<PostView>> {
+      let posts = PostQuery {
+        community_id: Some(data.inserted_community.id),
+        media_type: Some(media_type.to_string()),
+        ..data.default_post_query()
+      }
+      .list(&data.site, pool)
+      .await?;
+      Ok(posts)
+    }
+
+    assert_eq!(vec![POST], names(&list(&data, "image/*", pool).await?));
+    assert_eq!(vec![POST], names(&list(&data, "image/png", pool).await?));
+    assert_eq!(
+      vec![POST_BY_BOT],
+      names(&list(&data, "application/pdf", pool).await?)
+    );
+    assert!(list(&data, "image/jpeg", pool).await?.is_empty());
+    // Wildcard characters of LIKE are matched literally
+    assert!(list(&data, "%/*", pool).await?.is_empty());
+
+    cleanup(data, pool).await
+  }
+
   #[tokio::test]
   #[serial]
   async fn pagination_includes_each_post_once() -> LemmyResult<()> {
@@ -1665,6 +1859,24 @@ mod tests {
         url: None,
         body: None,
         alt_text: None,
+        url_nsfw: None,
+        remote_views: None,
+        remote_comments: None,
+        featured_position: 0,
+        canonical_url: None,
+        source_format: PostSourceFormat::Markdown,
+        category: None,
+        distinguished: false,
+        updated_by: None,
+        original_language_id: None,
+        crosspost_group: None,
+        pinned_comment_id: None,
+        original_content: false,
+        default_comment_sort: None,
+        close_at: None,
+        content_html: None,
+        quarantined: false,
+        additional_audience: vec![],
         published: inserted_post.published,
         updated: None,
         community_id: inserted_community.id,
@@ -1694,6 +1906,7 @@ mod tests {
         actor_id: inserted_person.actor_id.clone(),
         local: true,
         bot_account: false,
+        shadow_vote: false,
         banned: false,
         deleted: false,
         bio: None,
@@ -1739,6 +1952,17 @@ mod tests {
         moderators_url: inserted_community.moderators_url.clone(),
         featured_url: inserted_community.featured_url.clone(),
         visibility: CommunityVisibility::Public,
+        posting_window_start: 0,
+        posting_window_end: 0,
+        federation_paused: false,
+        federation_incoming_paused: false,
+        slur_filter_regex: None,
+        quarantined_until: None,
+        allow_nsfw: true,
+        duplicate_title_hours: 0,
+        duplicate_title_action: DuplicateTitleAction::Report,
+        min_post_karma: 0,
+        post_interval_secs: 0,
       },
       counts: PostAggregates {
         post_id: inserted_post.id,
@@ -1755,6 +1979,7 @@ mod tests {
         hot_rank_active: RANK_DEFAULT,
         controversy_rank: 0.0,
         scaled_rank: RANK_DEFAULT,
+        featured_position: 0,
         community_id: inserted_post.community_id,
         creator_id: inserted_post.creator_id,
         instance_id: data.inserted_instance.id,
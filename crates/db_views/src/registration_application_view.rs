@@ -276,6 +276,7 @@ mod tests {
         ban_expires: None,
         deleted: false,
         bot_account: false,
+        shadow_vote: false,
         bio: None,
         banner: None,
         updated: None,
@@ -355,6 +356,7 @@ mod tests {
       ban_expires: None,
       deleted: false,
       bot_account: false,
+      shadow_vote: false,
       bio: None,
       banner: None,
       updated: None,
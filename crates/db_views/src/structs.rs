@@ -39,7 +39,8 @@ pub struct CommentReportView {
   pub comment: Comment,
   pub post: Post,
   pub community: Community,
-  pub creator: Person,
+  /// Not set for anonymous reports from other instances.
+  pub creator: Option<Person>,
   pub comment_creator: Person,
   pub counts: CommentAggregates,
   pub creator_banned_from_community: bool,
@@ -68,6 +69,8 @@ pub struct CommentView {
   pub banned_from_community: bool,
   pub creator_is_moderator: bool,
   pub creator_is_admin: bool,
+  /// Whether the comment was written by the creator of the post.
+  pub creator_is_op: bool,
   pub subscribed: SubscribedType,
   pub saved: bool,
   pub creator_blocked: bool,
@@ -96,7 +99,8 @@ pub struct PostReportView {
   pub post_report: PostReport,
   pub post: Post,
   pub community: Community,
-  pub creator: Person,
+  /// Not set for automatic reports, eg for duplicate titles.
+  pub creator: Option<Person>,
   pub post_creator: Person,
   pub creator_banned_from_community: bool,
   pub creator_is_moderator: bool,
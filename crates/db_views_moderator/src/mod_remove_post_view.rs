@@ -25,7 +25,7 @@ impl ModRemovePostView {
     let show_mod_names_expr = show_mod_names.as_sql::<diesel::sql_types::Bool>();
 
     let admin_names_join = mod_remove_post::mod_person_id
-      .eq(person::id)
+      .eq(person::id.nullable())
       .and(show_mod_names_expr.or(person::id.eq(admin_person_id_join)));
     let mut query = mod_remove_post::table
       .left_join(person::table.on(admin_names_join))
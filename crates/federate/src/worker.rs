@@ -21,6 +21,7 @@ use lemmy_db_schema::{
     activity::SentActivity,
     federation_queue_state::FederationQueueState,
     instance::{Instance, InstanceForm},
+    local_site::LocalSite,
     site::Site,
   },
   utils::naive_now,
@@ -253,6 +254,7 @@ impl InstanceWorker {
           "{}: retrying {:?} attempt {} with delay {retry_delay:.2?}. ({e})",
           self.instance.domain, activity.id, self.state.fail_count
         );
+        self.check_dead_threshold().await?;
         self.save_and_send_state().await?;
         tokio::select! {
           () = sleep(retry_delay) => {},
@@ -278,6 +280,37 @@ impl InstanceWorker {
     Ok(())
   }
 
+  /// Mark the instance as dead once the number of consecutive failures reaches the configured
+  /// threshold. The send manager then stops this worker, and delivery resumes once an activity is
+  /// received from the instance.
+  async fn check_dead_threshold(&mut self) -> Result<()> {
+    let local_site = LocalSite::read(&mut self.context.pool())
+      .await
+      .map_err(|e| anyhow::anyhow!("err reading local site: {e:?}"))?;
+    // Non-positive values are rejected by the site settings, but would mark every instance as dead
+    let Some(threshold) = local_site
+      .federation_dead_instance_threshold
+      .filter(|t| *t > 0)
+    else {
+      return Ok(());
+    };
+    if self.state.fail_count < threshold {
+      return Ok(());
+    }
+    warn!(
+      "{}: marking instance as dead after {} failed attempts",
+      self.instance.domain, self.state.fail_count
+    );
+    let dead_since = DateTime::from_timestamp(0, 0);
+    self.instance.updated = dead_since;
+    let form = InstanceForm::builder()
+      .domain(self.instance.domain.clone())
+      .updated(dead_since)
+      .build();
+    Instance::update(&mut self.context.pool(), self.instance.id, form).await?;
+    Ok(())
+  }
+
   /// get inbox urls of sending the given activity to the given instance
   /// most often this will return 0 values (if instance doesn't care about the activity)
   /// or 1 value (the shared inbox)
@@ -366,3 +399,80 @@ impl InstanceWorker {
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use lemmy_db_schema::{
+    source::{local_site::LocalSiteInsertForm, site::SiteInsertForm},
+    traits::Crud,
+  };
+  use lemmy_utils::{error::LemmyResult, LemmyErrorType};
+  use serial_test::serial;
+  use tokio::sync::mpsc::unbounded_channel;
+
+  async fn is_dead(instance_id: InstanceId, context: &Data<LemmyContext>) -> LemmyResult<bool> {
+    let instance = Instance::read(&mut context.pool(), instance_id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindInstance)?;
+    Ok(instance.updated.unwrap_or(instance.published) < Utc::now() - Days::new(3))
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_mark_dead_after_failures() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let context = context.reset_request_count();
+    let local_instance =
+      Instance::read_or_create(&mut context.pool(), "local.com".to_string()).await?;
+    let site_form = SiteInsertForm::builder()
+      .name("test site".to_string())
+      .instance_id(local_instance.id)
+      .build();
+    let site = Site::create(&mut context.pool(), &site_form).await?;
+    let local_site_form = LocalSiteInsertForm::builder()
+      .site_id(site.id)
+      .federation_dead_instance_threshold(Some(3))
+      .build();
+    LocalSite::create(&mut context.pool(), &local_site_form).await?;
+
+    let instance = Instance::read_or_create(&mut context.pool(), "dead.com".to_string()).await?;
+    let (stats_sender, _stats_receiver) = unbounded_channel();
+    let mut worker = InstanceWorker {
+      instance: instance.clone(),
+      site_loaded: false,
+      site: None,
+      followed_communities: HashMap::new(),
+      stop: CancellationToken::new(),
+      context: context.reset_request_count(),
+      stats_sender,
+      last_full_communities_fetch: Utc.timestamp_nanos(0),
+      last_incremental_communities_fetch: Utc.timestamp_nanos(0),
+      state: FederationQueueState::load(&mut context.pool(), instance.id).await?,
+      last_state_insert: Utc.timestamp_nanos(0),
+    };
+
+    // A short streak of failures doesn't affect the instance
+    for _ in 0..2 {
+      worker.state.fail_count += 1;
+      worker.check_dead_threshold().await?;
+    }
+    assert!(!is_dead(instance.id, &context).await?);
+
+    // Reaching the threshold marks it as dead
+    worker.state.fail_count += 1;
+    worker.check_dead_threshold().await?;
+    worker.save_and_send_state().await?;
+    assert!(is_dead(instance.id, &context).await?);
+
+    // An incoming activity revives it and resets the failure count
+    Instance::mark_alive(&mut context.pool(), &instance.domain).await?;
+    assert!(!is_dead(instance.id, &context).await?);
+    let state = FederationQueueState::load(&mut context.pool(), instance.id).await?;
+    assert_eq!(0, state.fail_count);
+
+    Site::delete(&mut context.pool(), site.id).await?;
+    Instance::delete_all(&mut context.pool()).await?;
+    Ok(())
+  }
+}
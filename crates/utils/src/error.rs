@@ -53,6 +53,7 @@ pub enum LemmyErrorType {
   CouldntFindCommentReply,
   CouldntFindPrivateMessage,
   CouldntFindActivity,
+  CouldntFindInstance,
   PersonIsBlocked,
   CommunityIsBlocked,
   InstanceIsBlocked,
@@ -176,6 +177,39 @@ pub enum LemmyErrorType {
   CantBlockLocalInstance,
   UrlWithoutDomain,
   InboxTimeout,
+  InvalidThumbnailSize,
+  InvalidThumbnailFormat,
+  AccountTooNewToVote,
+  PostingClosed,
+  InvalidPostingWindow,
+  CommunityFederationPaused,
+  AttachmentTypeNotAllowed,
+  PostTooOldToVote,
+  CouldntFindFailedPostImport,
+  InvalidTimeWindow,
+  InvalidUserAgent,
+  CantBackfillLocalCommunity,
+  InvalidOutgoingPage(String),
+  InvalidDuplicateTitleWindow,
+  CantFetchRepliesOfLocalComment,
+  InsufficientKarma,
+  CouldntFindPostRawJson,
+  PostingTooFast,
+  PublishedInFuture,
+  CouldntFindNodeInfo,
+  DownloadTooLarge,
+  PageTypeNotAccepted,
+  InvalidPageType,
+  InvalidDownvotePercent,
+  InvalidDownvoteDays,
+  CommentFetchDepthExceeded,
+  InvalidVoteWeight,
+  InvalidBackfillMaxComments,
+  InvalidVoteMaxPostAge,
+  InvalidCommunityQuarantineDays,
+  InvalidFederationFutureTolerance,
+  InvalidFederationDeadInstanceThreshold,
+  PostAlreadyInCommunity,
   Unknown(String),
 }
 
@@ -49,6 +49,10 @@ pub struct Settings {
   /// The number of activitypub federation retry workers that can be in-flight concurrently
   #[default(0)]
   pub retry_count: usize,
+  /// Maximum size in bytes of remote files which are downloaded to generate link previews and
+  /// thumbnails. Larger downloads are aborted, and the post is stored without thumbnail.
+  #[default(20_971_520)]
+  pub max_download_size: usize,
   // Prometheus configuration.
   #[default(None)]
   #[doku(example = "Some(Default::default())")]
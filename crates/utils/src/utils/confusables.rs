@@ -0,0 +1,110 @@
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// Letters from other scripts which look like basic latin letters, mapped to their lowercase
+/// latin equivalent. This is a subset of the Unicode confusables list, restricted to characters
+/// which are commonly used to evade filters.
+const CONFUSABLES: [(char, char); 60] = [
+  // Cyrillic
+  ('а', 'a'),
+  ('в', 'b'),
+  ('с', 'c'),
+  ('ԁ', 'd'),
+  ('е', 'e'),
+  ('һ', 'h'),
+  ('і', 'i'),
+  ('ј', 'j'),
+  ('к', 'k'),
+  ('ӏ', 'l'),
+  ('м', 'm'),
+  ('н', 'h'),
+  ('о', 'o'),
+  ('р', 'p'),
+  ('ԛ', 'q'),
+  ('г', 'r'),
+  ('ѕ', 's'),
+  ('т', 't'),
+  ('ѵ', 'v'),
+  ('ԝ', 'w'),
+  ('х', 'x'),
+  ('у', 'y'),
+  ('А', 'a'),
+  ('В', 'b'),
+  ('С', 'c'),
+  ('Е', 'e'),
+  ('Н', 'h'),
+  ('І', 'i'),
+  ('Ј', 'j'),
+  ('К', 'k'),
+  ('М', 'm'),
+  ('О', 'o'),
+  ('Р', 'p'),
+  ('Ѕ', 's'),
+  ('Т', 't'),
+  ('Х', 'x'),
+  ('У', 'y'),
+  // Greek
+  ('α', 'a'),
+  ('ε', 'e'),
+  ('ι', 'i'),
+  ('κ', 'k'),
+  ('ν', 'v'),
+  ('ο', 'o'),
+  ('ρ', 'p'),
+  ('τ', 't'),
+  ('υ', 'u'),
+  ('χ', 'x'),
+  ('Α', 'a'),
+  ('Β', 'b'),
+  ('Ε', 'e'),
+  ('Ζ', 'z'),
+  ('Η', 'h'),
+  ('Ι', 'i'),
+  ('Κ', 'k'),
+  ('Μ', 'm'),
+  ('Ν', 'n'),
+  ('Ο', 'o'),
+  ('Ρ', 'p'),
+  ('Τ', 't'),
+  ('Χ', 'x'),
+];
+
+/// Characters without width which can be placed inside of words to split them.
+const INVISIBLE: [char; 6] = [
+  '\u{00AD}', '\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}', '\u{FEFF}',
+];
+
+/// Converts a title into a form for filter matching and duplicate detection. Compatibility
+/// characters like fullwidth or mathematical letters are decomposed (NFKD), diacritics and
+/// invisible characters are removed, lookalike letters are replaced by their latin equivalent and
+/// everything is lowercased. The result is not meant for display.
+pub fn normalize_title(title: &str) -> String {
+  title
+    .nfkd()
+    .filter(|c| !is_combining_mark(*c) && !INVISIBLE.contains(c))
+    .map(|c| {
+      CONFUSABLES
+        .iter()
+        .find(|(confusable, _)| *confusable == c)
+        .map_or(c, |(_, latin)| *latin)
+    })
+    .flat_map(char::to_lowercase)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::utils::confusables::normalize_title;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_normalize_title() {
+    assert_eq!("hello world", normalize_title("Hello World"));
+    // Cyrillic ie and o
+    assert_eq!("hello world", normalize_title("H\u{0435}ll\u{043E} World"));
+    // Fullwidth and mathematical letters
+    assert_eq!("hello", normalize_title("ＨＥＬＬＯ"));
+    assert_eq!("hello", normalize_title("𝐡𝐞𝐥𝐥𝐨"));
+    // Diacritics and zero width spaces
+    assert_eq!("hello", normalize_title("hé\u{200B}llö"));
+  }
+}
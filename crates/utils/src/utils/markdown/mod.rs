@@ -99,6 +99,27 @@ pub fn markdown_rewrite_image_links(mut src: String) -> (String, Vec<Url>) {
   (src, links)
 }
 
+/// Removes images from markdown if `remove` returns true for their url. Images with invalid urls
+/// are kept.
+pub fn markdown_remove_images(src: &str, remove: impl Fn(&Url) -> bool) -> String {
+  let ast = MARKDOWN_PARSER.parse(src);
+  let mut images_offsets = vec![];
+  ast.walk(|node, _depth| {
+    if let Some(image) = node.cast::<Image>() {
+      if Url::parse(&image.url).is_ok_and(|url| remove(&url)) {
+        images_offsets.push(node.srcmap.expect("srcmap is none").get_byte_offsets());
+      }
+    }
+  });
+
+  let mut src = src.to_string();
+  // Go through the collected images in reverse order, so that offsets remain valid
+  while let Some((start, end)) = images_offsets.pop() {
+    src.replace_range(start..end, "");
+  }
+  src
+}
+
 pub fn markdown_check_for_blocked_urls(text: &str, blocklist: &RegexSet) -> LemmyResult<()> {
   if blocklist.is_match(text) {
     Err(LemmyErrorType::BlockedUrl)?
@@ -245,6 +266,13 @@ mod tests {
     });
   }
 
+  #[test]
+  fn test_markdown_remove_images() {
+    let input = "![pixel](https://tracker.com/p.gif) text ![cat](https://example.com/cat.png)";
+    let result = markdown_remove_images(input, |url| url.domain() == Some("tracker.com"));
+    assert_eq!(result, " text ![cat](https://example.com/cat.png)");
+  }
+
   #[test]
   fn test_url_blocking() {
     let set = RegexSet::new(vec![r"(https://)?example\.com/?"]).unwrap();
@@ -1,4 +1,7 @@
-use crate::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+use crate::{
+  error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
+  utils::confusables::normalize_title,
+};
 use regex::{Regex, RegexBuilder};
 
 pub fn remove_slurs(test: &str, slur_regex: &Option<Regex>) -> String {
@@ -54,6 +57,20 @@ pub fn check_slurs_opt(text: &Option<String>, slur_regex: &Option<Regex>) -> Lem
   }
 }
 
+/// Checks a post title for slurs. With `normalize`, the title is additionally checked in its
+/// normalized form, so that lookalike characters can't be used to get around the filter.
+pub fn check_title_slurs(
+  title: &str,
+  slur_regex: &Option<Regex>,
+  normalize: bool,
+) -> LemmyResult<()> {
+  check_slurs(title, slur_regex)?;
+  if normalize {
+    check_slurs(&normalize_title(title), slur_regex)?;
+  }
+  Ok(())
+}
+
 pub(crate) fn slurs_vec_to_str(slurs: &[&str]) -> String {
   let start = "No slurs - ";
   let combined = &slurs.join(", ");
@@ -65,7 +82,7 @@ pub(crate) fn slurs_vec_to_str(slurs: &[&str]) -> String {
 #[allow(clippy::indexing_slicing)]
 mod test {
 
-  use crate::utils::slurs::{remove_slurs, slur_check, slurs_vec_to_str};
+  use crate::utils::slurs::{check_title_slurs, remove_slurs, slur_check, slurs_vec_to_str};
   use pretty_assertions::assert_eq;
   use regex::RegexBuilder;
 
@@ -98,6 +115,22 @@ mod test {
     }
   }
 
+  #[test]
+  fn test_title_slurs_homoglyph() {
+    let slur_regex = Some(
+      RegexBuilder::new(r"\bspam\b")
+        .case_insensitive(true)
+        .build()
+        .unwrap(),
+    );
+    // Cyrillic a and fullwidth m
+    let title = "Buy sp\u{0430}\u{FF4D} now";
+    assert!(check_title_slurs(title, &slur_regex, false).is_ok());
+    assert!(check_title_slurs(title, &slur_regex, true).is_err());
+    assert!(check_title_slurs("Buy spam now", &slur_regex, false).is_err());
+    assert!(check_title_slurs("Buy ham now", &slur_regex, true).is_ok());
+  }
+
   // These helped with testing
   // #[test]
   // fn test_send_email() {
@@ -25,6 +25,11 @@ const ALT_TEXT_MAX_LENGTH: usize = 1500;
 const SITE_NAME_MAX_LENGTH: usize = 20;
 const SITE_NAME_MIN_LENGTH: usize = 1;
 const SITE_DESCRIPTION_MAX_LENGTH: usize = 150;
+const THUMBNAIL_MAX_SIZE: i32 = 4096;
+const ALLOWED_THUMBNAIL_FORMATS: [&str; 4] = ["avif", "jpg", "png", "webp"];
+const USER_AGENT_MAX_LENGTH: usize = 256;
+const MAX_DUPLICATE_TITLE_HOURS: i32 = 24 * 365;
+const MAX_VOTE_WEIGHT: i32 = 100;
 //Invisible unicode characters, taken from https://invisible-characters.com/
 const FORBIDDEN_DISPLAY_CHARS: [char; 53] = [
   '\u{0009}',
@@ -202,6 +207,101 @@ pub fn site_description_length_check(description: &str) -> LemmyResult<()> {
   )
 }
 
+/// Checks the configured sizes and format of generated thumbnails. An empty format clears the
+/// setting, so it is always accepted.
+pub fn thumbnail_settings_check(
+  max_size: Option<i32>,
+  min_size: Option<i32>,
+  format: Option<&str>,
+) -> LemmyResult<()> {
+  let is_invalid_size =
+    |size: Option<i32>| size.is_some_and(|s| !(1..=THUMBNAIL_MAX_SIZE).contains(&s));
+  if is_invalid_size(max_size) || is_invalid_size(min_size) {
+    Err(LemmyErrorType::InvalidThumbnailSize)?
+  }
+  if format.is_some_and(|f| !f.is_empty() && !ALLOWED_THUMBNAIL_FORMATS.contains(&f)) {
+    Err(LemmyErrorType::InvalidThumbnailFormat)?
+  }
+  Ok(())
+}
+
+/// Checks that a custom User-Agent can be sent as HTTP header. An empty value clears the setting,
+/// so it is always accepted.
+pub fn user_agent_check(user_agent: Option<&str>) -> LemmyResult<()> {
+  if let Some(user_agent) = user_agent {
+    let is_valid = user_agent.len() <= USER_AGENT_MAX_LENGTH
+      && user_agent.chars().all(|c| c == ' ' || c.is_ascii_graphic());
+    if !is_valid {
+      Err(LemmyErrorType::InvalidUserAgent)?
+    }
+  }
+  Ok(())
+}
+
+/// Checks that all patterns for stripping federated post bodies are valid regular expressions.
+pub fn body_strip_patterns_check(patterns: Option<&[String]>) -> LemmyResult<()> {
+  for pattern in patterns.unwrap_or_default() {
+    RegexBuilder::new(pattern)
+      .multi_line(true)
+      .build()
+      .with_lemmy_type(LemmyErrorType::InvalidRegex)?;
+  }
+  Ok(())
+}
+
+/// Checks that the accepted federated post types are known. Must match `PageType` in the apub
+/// crate.
+pub fn page_types_check(page_types: Option<&[String]>) -> LemmyResult<()> {
+  const PAGE_TYPES: [&str; 5] = ["Page", "Article", "Note", "Video", "Event"];
+  for page_type in page_types.unwrap_or_default() {
+    if !PAGE_TYPES.contains(&page_type.as_str()) {
+      Err(LemmyErrorType::InvalidPageType)?
+    }
+  }
+  Ok(())
+}
+
+/// Checks that the maximum number of comments fetched per post during backfill isn't negative.
+pub fn backfill_max_comments_check(max_comments: Option<i32>) -> LemmyResult<()> {
+  if max_comments.is_some_and(|m| m < 0) {
+    Err(LemmyErrorType::InvalidBackfillMaxComments)?
+  }
+  Ok(())
+}
+
+/// Checks that the maximum post age for votes is at least one day and at most 100 years.
+pub fn vote_max_post_age_check(max_age_days: Option<i32>) -> LemmyResult<()> {
+  if max_age_days.is_some_and(|d| !(1..=36500).contains(&d)) {
+    Err(LemmyErrorType::InvalidVoteMaxPostAge)?
+  }
+  Ok(())
+}
+
+/// Checks that the quarantine of new communities lasts at most 100 years. Non-positive values
+/// disable it.
+pub fn community_quarantine_days_check(days: Option<i32>) -> LemmyResult<()> {
+  if days.is_some_and(|d| d > 36500) {
+    Err(LemmyErrorType::InvalidCommunityQuarantineDays)?
+  }
+  Ok(())
+}
+
+/// Checks that federated timestamps are allowed to be at most one day in the future.
+pub fn federation_future_tolerance_check(seconds: Option<i32>) -> LemmyResult<()> {
+  if seconds.is_some_and(|s| !(0..=86400).contains(&s)) {
+    Err(LemmyErrorType::InvalidFederationFutureTolerance)?
+  }
+  Ok(())
+}
+
+/// Checks that at least one failed delivery is needed to mark an instance as dead.
+pub fn federation_dead_instance_threshold_check(threshold: Option<i32>) -> LemmyResult<()> {
+  if threshold.is_some_and(|t| t <= 0) {
+    Err(LemmyErrorType::InvalidFederationDeadInstanceThreshold)?
+  }
+  Ok(())
+}
+
 /// Check minimum and maximum length of input string. If the string is too short or too long, the
 /// corresponding error is returned.
 ///
@@ -226,6 +326,30 @@ fn min_length_check(item: &str, min_length: usize, min_msg: LemmyErrorType) -> L
   }
 }
 
+/// Checks that the hour for a community posting window is valid.
+pub fn is_valid_posting_window_hour(hour: Option<i16>) -> LemmyResult<()> {
+  if hour.is_some_and(|h| !(0..24).contains(&h)) {
+    Err(LemmyErrorType::InvalidPostingWindow)?
+  }
+  Ok(())
+}
+
+/// Checks that the window for duplicate title detection is at most a year. Zero disables it.
+pub fn is_valid_duplicate_title_hours(hours: Option<i32>) -> LemmyResult<()> {
+  if hours.is_some_and(|h| !(0..=MAX_DUPLICATE_TITLE_HOURS).contains(&h)) {
+    Err(LemmyErrorType::InvalidDuplicateTitleWindow)?
+  }
+  Ok(())
+}
+
+/// Checks that the vote weight of an instance is between zero and the maximum.
+pub fn is_valid_vote_weight(weight: i32) -> LemmyResult<()> {
+  if !(0..=MAX_VOTE_WEIGHT).contains(&weight) {
+    Err(LemmyErrorType::InvalidVoteWeight)?
+  }
+  Ok(())
+}
+
 /// Attempts to build a regex and check it for common errors before inserting into the DB.
 pub fn build_and_check_regex(regex_str_opt: &Option<&str>) -> LemmyResult<Option<Regex>> {
   regex_str_opt.map_or_else(
@@ -260,10 +384,12 @@ pub fn clean_url_params(url: &Url) -> Url {
   let mut url_out = url.clone();
   if let Some(query) = url.query() {
     let new_query = query
-      .split_inclusive('&')
+      .split('&')
       .filter(|q| !CLEAN_URL_PARAMS_REGEX.is_match(q))
-      .collect::<String>();
-    url_out.set_query(Some(&new_query));
+      .collect::<Vec<_>>()
+      .join("&");
+    // Drop the empty query entirely, so that the url is the same as without any params
+    url_out.set_query(Some(new_query.as_str()).filter(|q| !q.is_empty()));
   }
   url_out
 }
@@ -347,20 +473,34 @@ mod tests {
   use crate::{
     error::{LemmyErrorType, LemmyResult},
     utils::validation::{
+      backfill_max_comments_check,
+      body_strip_patterns_check,
       build_and_check_regex,
       check_site_visibility_valid,
       check_url_scheme,
       check_urls_are_valid,
       clean_url_params,
+      community_quarantine_days_check,
+      federation_dead_instance_threshold_check,
+      federation_future_tolerance_check,
       is_url_blocked,
       is_valid_actor_name,
       is_valid_bio_field,
       is_valid_display_name,
+      is_valid_duplicate_title_hours,
       is_valid_matrix_id,
       is_valid_post_title,
+      is_valid_posting_window_hour,
+      is_valid_vote_weight,
+      page_types_check,
       site_description_length_check,
       site_name_length_check,
+      thumbnail_settings_check,
+      user_agent_check,
+      vote_max_post_age_check,
       BIO_MAX_LENGTH,
+      MAX_DUPLICATE_TITLE_HOURS,
+      MAX_VOTE_WEIGHT,
       SITE_DESCRIPTION_MAX_LENGTH,
       SITE_NAME_MAX_LENGTH,
     },
@@ -379,6 +519,15 @@ mod tests {
     let cleaned = clean_url_params(&url);
     assert_eq!(url.to_string(), cleaned.to_string());
 
+    // No separator is left behind when tracking params come last
+    let url = Url::parse("https://example.com/path/123?id=123&utm_medium=social")?;
+    let cleaned = clean_url_params(&url);
+    assert_eq!("https://example.com/path/123?id=123", cleaned.to_string());
+
+    let url = Url::parse("https://example.com/path/123?utm_source=lemmy")?;
+    let cleaned = clean_url_params(&url);
+    assert_eq!("https://example.com/path/123", cleaned.to_string());
+
     Ok(())
   }
 
@@ -445,6 +594,33 @@ mod tests {
     assert!(is_valid_post_title("\n \n \n \n    		").is_err()); // tabs/spaces/newlines
   }
 
+  #[test]
+  fn test_valid_posting_window_hour() {
+    assert!(is_valid_posting_window_hour(None).is_ok());
+    assert!(is_valid_posting_window_hour(Some(0)).is_ok());
+    assert!(is_valid_posting_window_hour(Some(23)).is_ok());
+    assert!(is_valid_posting_window_hour(Some(24)).is_err());
+    assert!(is_valid_posting_window_hour(Some(-1)).is_err());
+  }
+
+  #[test]
+  fn test_valid_vote_weight() {
+    assert!(is_valid_vote_weight(0).is_ok());
+    assert!(is_valid_vote_weight(1).is_ok());
+    assert!(is_valid_vote_weight(MAX_VOTE_WEIGHT).is_ok());
+    assert!(is_valid_vote_weight(MAX_VOTE_WEIGHT + 1).is_err());
+    assert!(is_valid_vote_weight(-1).is_err());
+  }
+
+  #[test]
+  fn test_valid_duplicate_title_hours() {
+    assert!(is_valid_duplicate_title_hours(None).is_ok());
+    assert!(is_valid_duplicate_title_hours(Some(0)).is_ok());
+    assert!(is_valid_duplicate_title_hours(Some(24)).is_ok());
+    assert!(is_valid_duplicate_title_hours(Some(MAX_DUPLICATE_TITLE_HOURS + 1)).is_err());
+    assert!(is_valid_duplicate_title_hours(Some(-1)).is_err());
+  }
+
   #[test]
   fn test_valid_matrix_id() {
     assert!(is_valid_matrix_id("@dess:matrix.org").is_ok());
@@ -533,6 +709,89 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_thumbnail_settings_check() {
+    assert!(thumbnail_settings_check(None, None, None).is_ok());
+    assert!(thumbnail_settings_check(Some(256), Some(200), Some("webp")).is_ok());
+    assert!(thumbnail_settings_check(Some(512), None, Some("")).is_ok());
+    assert!(thumbnail_settings_check(Some(0), None, None)
+      .is_err_and(|e| e.error_type.eq(&LemmyErrorType::InvalidThumbnailSize)));
+    assert!(thumbnail_settings_check(None, Some(-1), None)
+      .is_err_and(|e| e.error_type.eq(&LemmyErrorType::InvalidThumbnailSize)));
+    assert!(thumbnail_settings_check(None, None, Some("gif"))
+      .is_err_and(|e| e.error_type.eq(&LemmyErrorType::InvalidThumbnailFormat)));
+  }
+
+  #[test]
+  fn test_body_strip_patterns_check() {
+    assert!(body_strip_patterns_check(None).is_ok());
+    assert!(body_strip_patterns_check(Some(&["^Sent from my .*$".to_string()])).is_ok());
+    assert!(body_strip_patterns_check(Some(&["(unclosed".to_string()]))
+      .is_err_and(|e| e.error_type.eq(&LemmyErrorType::InvalidRegex)));
+  }
+
+  #[test]
+  fn test_page_types_check() {
+    assert!(page_types_check(None).is_ok());
+    assert!(page_types_check(Some(&["Page".to_string(), "Video".to_string()])).is_ok());
+    assert!(page_types_check(Some(&["Story".to_string()]))
+      .is_err_and(|e| e.error_type.eq(&LemmyErrorType::InvalidPageType)));
+  }
+
+  #[test]
+  fn test_backfill_max_comments_check() {
+    assert!(backfill_max_comments_check(None).is_ok());
+    assert!(backfill_max_comments_check(Some(0)).is_ok());
+    assert!(backfill_max_comments_check(Some(100)).is_ok());
+    assert!(backfill_max_comments_check(Some(-1)).is_err());
+  }
+
+  #[test]
+  fn test_vote_max_post_age_check() {
+    assert!(vote_max_post_age_check(None).is_ok());
+    assert!(vote_max_post_age_check(Some(1)).is_ok());
+    assert!(vote_max_post_age_check(Some(36500)).is_ok());
+    assert!(vote_max_post_age_check(Some(0)).is_err());
+    assert!(vote_max_post_age_check(Some(-1)).is_err());
+    assert!(vote_max_post_age_check(Some(i32::MAX)).is_err());
+  }
+
+  #[test]
+  fn test_community_quarantine_days_check() {
+    assert!(community_quarantine_days_check(None).is_ok());
+    assert!(community_quarantine_days_check(Some(0)).is_ok());
+    assert!(community_quarantine_days_check(Some(36500)).is_ok());
+    assert!(community_quarantine_days_check(Some(i32::MAX)).is_err());
+  }
+
+  #[test]
+  fn test_federation_future_tolerance_check() {
+    assert!(federation_future_tolerance_check(None).is_ok());
+    assert!(federation_future_tolerance_check(Some(0)).is_ok());
+    assert!(federation_future_tolerance_check(Some(86400)).is_ok());
+    assert!(federation_future_tolerance_check(Some(-1)).is_err());
+    assert!(federation_future_tolerance_check(Some(86401)).is_err());
+  }
+
+  #[test]
+  fn test_federation_dead_instance_threshold_check() {
+    assert!(federation_dead_instance_threshold_check(None).is_ok());
+    assert!(federation_dead_instance_threshold_check(Some(1)).is_ok());
+    assert!(federation_dead_instance_threshold_check(Some(0)).is_err());
+    assert!(federation_dead_instance_threshold_check(Some(-5)).is_err());
+  }
+
+  #[test]
+  fn test_user_agent_check() {
+    assert!(user_agent_check(None).is_ok());
+    assert!(user_agent_check(Some("")).is_ok());
+    assert!(user_agent_check(Some("Lemmy (+https://example.com)")).is_ok());
+    assert!(user_agent_check(Some("Lemmy\r\nX-Injected: 1"))
+      .is_err_and(|e| e.error_type.eq(&LemmyErrorType::InvalidUserAgent)));
+    assert!(user_agent_check(Some(&"a".repeat(257)))
+      .is_err_and(|e| e.error_type.eq(&LemmyErrorType::InvalidUserAgent)));
+  }
+
   #[test]
   fn test_valid_slur_regex() {
     let valid_regexes = [&None, &Some(""), &Some("(foo|bar)")];
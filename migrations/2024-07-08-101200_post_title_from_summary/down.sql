@@ -0,0 +1,4 @@
+ALTER TABLE local_site
+    DROP COLUMN post_title_from_summary,
+    DROP COLUMN post_title_content_length;
+
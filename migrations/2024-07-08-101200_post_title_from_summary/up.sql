@@ -0,0 +1,4 @@
+ALTER TABLE local_site
+    ADD COLUMN post_title_from_summary boolean NOT NULL DEFAULT TRUE,
+    ADD COLUMN post_title_content_length integer NOT NULL DEFAULT 200;
+
@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    ADD COLUMN anonymize_votes boolean NOT NULL DEFAULT FALSE;
+
@@ -0,0 +1,4 @@
+ALTER TABLE local_site
+    DROP COLUMN thumbnail_max_size,
+    DROP COLUMN thumbnail_format;
+
@@ -0,0 +1,4 @@
+ALTER TABLE local_site
+    ADD COLUMN thumbnail_max_size integer,
+    ADD COLUMN thumbnail_format text;
+
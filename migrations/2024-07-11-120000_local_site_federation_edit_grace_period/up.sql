@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    ADD COLUMN federation_edit_grace_period integer NOT NULL DEFAULT 300;
+
@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    ADD COLUMN vote_cooldown_hours integer NOT NULL DEFAULT 0;
+
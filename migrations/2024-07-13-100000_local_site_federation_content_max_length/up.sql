@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    ADD COLUMN federation_content_max_length integer;
+
@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    DROP COLUMN max_comment_fetch_depth;
+
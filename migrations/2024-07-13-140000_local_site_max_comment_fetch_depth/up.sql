@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    ADD COLUMN max_comment_fetch_depth integer NOT NULL DEFAULT 100;
+
@@ -0,0 +1,3 @@
+ALTER TABLE instance
+    ADD COLUMN vote_weight integer NOT NULL DEFAULT 1 CHECK (vote_weight >= 0);
+
@@ -0,0 +1,4 @@
+ALTER TABLE community
+    DROP COLUMN posting_window_start,
+    DROP COLUMN posting_window_end;
+
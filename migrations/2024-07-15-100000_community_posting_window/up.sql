@@ -0,0 +1,4 @@
+ALTER TABLE community
+    ADD COLUMN posting_window_start smallint NOT NULL DEFAULT 0,
+    ADD COLUMN posting_window_end smallint NOT NULL DEFAULT 0;
+
@@ -0,0 +1,4 @@
+ALTER TABLE community
+    DROP COLUMN federation_paused,
+    DROP COLUMN federation_incoming_paused;
+
@@ -0,0 +1,4 @@
+ALTER TABLE community
+    ADD COLUMN federation_paused boolean NOT NULL DEFAULT FALSE,
+    ADD COLUMN federation_incoming_paused boolean NOT NULL DEFAULT FALSE;
+
@@ -0,0 +1,3 @@
+ALTER TABLE post
+    ADD COLUMN remote_comments bigint;
+
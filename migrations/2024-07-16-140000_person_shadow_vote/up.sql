@@ -0,0 +1,3 @@
+ALTER TABLE person
+    ADD COLUMN shadow_vote boolean NOT NULL DEFAULT FALSE;
+
@@ -0,0 +1,6 @@
+ALTER TABLE post
+    DROP COLUMN featured_position;
+
+ALTER TABLE post_aggregates
+    DROP COLUMN featured_position;
+
@@ -0,0 +1,32 @@
+ALTER TABLE post
+    ADD COLUMN featured_position int NOT NULL DEFAULT 0;
+
+ALTER TABLE post_aggregates
+    ADD COLUMN featured_position int NOT NULL DEFAULT 0;
+
+-- Keep the previous order of featured posts, which was newest first
+UPDATE
+    post
+SET
+    featured_position = featured.position
+FROM (
+    SELECT
+        id,
+        row_number() OVER (PARTITION BY community_id ORDER BY published) AS position
+    FROM
+        post
+    WHERE
+        featured_community) AS featured
+WHERE
+    post.id = featured.id;
+
+UPDATE
+    post_aggregates
+SET
+    featured_position = post.featured_position
+FROM
+    post
+WHERE
+    post_aggregates.post_id = post.id
+    AND post.featured_position != 0;
+
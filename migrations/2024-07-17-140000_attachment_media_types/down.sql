@@ -0,0 +1,4 @@
+ALTER TABLE local_site
+    DROP COLUMN attachment_allowed_types,
+    DROP COLUMN attachment_blocked_types;
+
@@ -0,0 +1,4 @@
+ALTER TABLE local_site
+    ADD COLUMN attachment_allowed_types text[] NOT NULL DEFAULT '{}',
+    ADD COLUMN attachment_blocked_types text[] NOT NULL DEFAULT '{}';
+
@@ -0,0 +1,3 @@
+ALTER TABLE community
+    ADD COLUMN slur_filter_regex text;
+
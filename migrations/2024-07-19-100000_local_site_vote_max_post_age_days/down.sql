@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    DROP COLUMN vote_max_post_age_days;
+
@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    ADD COLUMN vote_max_post_age_days integer;
+
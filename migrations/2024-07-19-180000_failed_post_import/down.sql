@@ -0,0 +1,2 @@
+DROP TABLE failed_post_import;
+
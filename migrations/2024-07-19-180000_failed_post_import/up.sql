@@ -0,0 +1,11 @@
+-- Incoming posts which failed to import because of a temporary problem, so that admins can
+-- reprocess them later
+CREATE TABLE failed_post_import (
+    id serial PRIMARY KEY,
+    ap_id text NOT NULL UNIQUE,
+    data text NOT NULL,
+    error text NOT NULL,
+    published timestamptz NOT NULL DEFAULT now(),
+    updated timestamptz
+);
+
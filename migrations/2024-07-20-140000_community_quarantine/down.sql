@@ -0,0 +1,6 @@
+ALTER TABLE local_site
+    DROP COLUMN federation_community_quarantine_days;
+
+ALTER TABLE community
+    DROP COLUMN quarantined_until;
+
@@ -0,0 +1,6 @@
+ALTER TABLE local_site
+    ADD COLUMN federation_community_quarantine_days integer;
+
+ALTER TABLE community
+    ADD COLUMN quarantined_until timestamptz;
+
@@ -0,0 +1,5 @@
+ALTER TABLE post
+    DROP COLUMN source_format;
+
+DROP TYPE post_source_format_enum;
+
@@ -0,0 +1,8 @@
+CREATE TYPE post_source_format_enum AS enum (
+    'Markdown',
+    'ConvertedHtml'
+);
+
+ALTER TABLE post
+    ADD COLUMN source_format post_source_format_enum DEFAULT 'Markdown' NOT NULL;
+
@@ -0,0 +1,3 @@
+ALTER TABLE community
+    ADD COLUMN allow_nsfw boolean DEFAULT TRUE NOT NULL;
+
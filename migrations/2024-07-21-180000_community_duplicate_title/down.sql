@@ -0,0 +1,6 @@
+ALTER TABLE community
+    DROP COLUMN duplicate_title_hours,
+    DROP COLUMN duplicate_title_action;
+
+DROP TYPE duplicate_title_action_enum;
+
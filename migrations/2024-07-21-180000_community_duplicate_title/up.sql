@@ -0,0 +1,9 @@
+CREATE TYPE duplicate_title_action_enum AS enum (
+    'Report',
+    'Remove'
+);
+
+ALTER TABLE community
+    ADD COLUMN duplicate_title_hours integer DEFAULT 0 NOT NULL,
+    ADD COLUMN duplicate_title_action duplicate_title_action_enum DEFAULT 'Report' NOT NULL;
+
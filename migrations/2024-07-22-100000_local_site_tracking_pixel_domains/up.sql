@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    ADD COLUMN tracking_pixel_domains text[] NOT NULL DEFAULT '{}';
+
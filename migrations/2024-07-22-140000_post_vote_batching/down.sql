@@ -0,0 +1,5 @@
+ALTER TABLE local_site
+    DROP COLUMN vote_batching_threshold;
+
+DROP TABLE post_vote_batching, post_vote_queue;
+
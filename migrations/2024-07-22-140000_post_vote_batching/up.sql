@@ -0,0 +1,17 @@
+ALTER TABLE local_site
+    ADD COLUMN vote_batching_threshold integer;
+
+-- Posts whose votes are added to the aggregates in batches
+CREATE TABLE post_vote_batching (
+    post_id int PRIMARY KEY REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+-- Votes of batched posts which aren't included in the aggregates yet. There is no foreign key, because
+-- votes are also queued while the votes of a deleted post are removed.
+CREATE TABLE post_vote_queue (
+    id serial PRIMARY KEY,
+    post_id int NOT NULL,
+    upvotes bigint NOT NULL,
+    downvotes bigint NOT NULL
+);
+
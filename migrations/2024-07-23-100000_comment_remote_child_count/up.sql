@@ -0,0 +1,3 @@
+ALTER TABLE comment
+    ADD COLUMN remote_child_count bigint;
+
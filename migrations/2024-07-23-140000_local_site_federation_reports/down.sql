@@ -0,0 +1,4 @@
+ALTER TABLE local_site
+    DROP COLUMN federation_reports,
+    DROP COLUMN federation_reports_anonymous;
+
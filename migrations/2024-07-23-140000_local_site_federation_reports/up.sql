@@ -0,0 +1,4 @@
+ALTER TABLE local_site
+    ADD COLUMN federation_reports boolean NOT NULL DEFAULT TRUE,
+    ADD COLUMN federation_reports_anonymous boolean NOT NULL DEFAULT FALSE;
+
@@ -0,0 +1,3 @@
+ALTER TABLE community
+    ADD COLUMN min_post_karma integer DEFAULT 0 NOT NULL;
+
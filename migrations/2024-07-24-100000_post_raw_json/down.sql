@@ -0,0 +1,4 @@
+DROP TABLE post_raw_json;
+
+ALTER TABLE local_site
+    DROP COLUMN federation_store_raw_json;
@@ -0,0 +1,12 @@
+-- Raw json of received posts, so that admins can debug federation issues. Rows are removed after
+-- a few days.
+CREATE TABLE post_raw_json (
+    post_id int PRIMARY KEY REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE,
+    data text NOT NULL,
+    published timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_post_raw_json_published ON post_raw_json (published);
+
+ALTER TABLE local_site
+    ADD COLUMN federation_store_raw_json boolean NOT NULL DEFAULT FALSE;
@@ -0,0 +1,5 @@
+ALTER TABLE community
+    DROP COLUMN post_interval_secs;
+
+DROP INDEX idx_post_community_creator_published;
+
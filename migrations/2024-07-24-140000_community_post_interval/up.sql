@@ -0,0 +1,6 @@
+ALTER TABLE community
+    ADD COLUMN post_interval_secs integer DEFAULT 0 NOT NULL;
+
+-- For the last post of a user in a community
+CREATE INDEX idx_post_community_creator_published ON post (community_id, creator_id, published DESC);
+
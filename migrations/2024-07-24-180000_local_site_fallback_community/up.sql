@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    ADD COLUMN federation_fallback_community_id int REFERENCES community ON UPDATE CASCADE ON DELETE SET NULL;
+
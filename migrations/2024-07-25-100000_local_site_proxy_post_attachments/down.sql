@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    DROP COLUMN proxy_post_attachments;
+
@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    ADD COLUMN proxy_post_attachments boolean NOT NULL DEFAULT FALSE;
+
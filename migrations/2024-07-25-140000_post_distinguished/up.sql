@@ -0,0 +1,3 @@
+ALTER TABLE post
+    ADD COLUMN distinguished boolean NOT NULL DEFAULT FALSE;
+
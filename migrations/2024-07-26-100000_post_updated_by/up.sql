@@ -0,0 +1,3 @@
+ALTER TABLE post
+    ADD COLUMN updated_by int REFERENCES person ON UPDATE CASCADE ON DELETE SET NULL;
+
@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    ADD COLUMN federation_future_tolerance int NOT NULL DEFAULT 300;
+
@@ -0,0 +1,3 @@
+ALTER TABLE post
+    ADD COLUMN original_language_id int REFERENCES LANGUAGE ON UPDATE CASCADE ON DELETE SET NULL;
+
@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    ADD COLUMN normalize_titles boolean NOT NULL DEFAULT FALSE;
+
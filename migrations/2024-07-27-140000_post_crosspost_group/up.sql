@@ -0,0 +1,5 @@
+ALTER TABLE post
+    ADD COLUMN crosspost_group text;
+
+CREATE INDEX idx_post_crosspost_group ON post (crosspost_group);
+
@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    ADD COLUMN federation_group_posts boolean NOT NULL DEFAULT FALSE;
+
@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    DROP COLUMN thumbnail_min_size;
+
@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    ADD COLUMN thumbnail_min_size integer;
+
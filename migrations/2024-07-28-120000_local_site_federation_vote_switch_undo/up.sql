@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    ADD COLUMN federation_vote_switch_undo boolean NOT NULL DEFAULT FALSE;
+
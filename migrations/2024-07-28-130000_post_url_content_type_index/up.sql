@@ -0,0 +1,3 @@
+-- Pattern ops allow prefix matches like `video/%` to use the index
+CREATE INDEX idx_post_url_content_type ON post (url_content_type text_pattern_ops);
+
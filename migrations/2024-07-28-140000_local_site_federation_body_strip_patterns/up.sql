@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    ADD COLUMN federation_body_strip_patterns text[] NOT NULL DEFAULT '{}';
+
@@ -0,0 +1,3 @@
+ALTER TABLE post
+    ADD COLUMN original_content boolean NOT NULL DEFAULT FALSE;
+
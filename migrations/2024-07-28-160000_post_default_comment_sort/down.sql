@@ -0,0 +1,5 @@
+ALTER TABLE post
+    DROP COLUMN default_comment_sort;
+
+DROP TYPE comment_sort_type_enum;
+
@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    ADD COLUMN federation_page_types text[] NOT NULL DEFAULT '{}';
+
@@ -0,0 +1,5 @@
+DROP INDEX idx_post_close_at;
+
+ALTER TABLE post
+    DROP COLUMN close_at;
+
@@ -0,0 +1,7 @@
+ALTER TABLE post
+    ADD COLUMN close_at timestamptz;
+
+CREATE INDEX idx_post_close_at ON post (close_at)
+WHERE
+    close_at IS NOT NULL AND NOT locked;
+
@@ -0,0 +1,6 @@
+ALTER TABLE local_site
+    DROP COLUMN federation_preserve_html;
+
+ALTER TABLE post
+    DROP COLUMN content_html;
+
@@ -0,0 +1,6 @@
+ALTER TABLE local_site
+    ADD COLUMN federation_preserve_html boolean NOT NULL DEFAULT FALSE;
+
+ALTER TABLE post
+    ADD COLUMN content_html text;
+
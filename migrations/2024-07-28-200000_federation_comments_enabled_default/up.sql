@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    ADD COLUMN federation_comments_enabled_default boolean NOT NULL DEFAULT TRUE;
+
@@ -0,0 +1,8 @@
+-- Title and body of posts before their last edit, so that mods can review what changed
+CREATE TABLE post_previous_version (
+    post_id int PRIMARY KEY REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE,
+    name varchar(200) NOT NULL,
+    body text,
+    published timestamptz NOT NULL DEFAULT now()
+);
+
@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    DROP COLUMN backfill_max_comments_per_post;
+
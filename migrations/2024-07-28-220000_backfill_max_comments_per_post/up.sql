@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    ADD COLUMN backfill_max_comments_per_post integer;
+
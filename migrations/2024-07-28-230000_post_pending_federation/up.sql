@@ -0,0 +1,8 @@
+-- Local posts whose Create activity is held back during the edit grace period
+CREATE TABLE post_pending_federation (
+    post_id int PRIMARY KEY REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE,
+    send_at timestamptz NOT NULL
+);
+
+CREATE INDEX idx_post_pending_federation_send_at ON post_pending_federation (send_at);
+
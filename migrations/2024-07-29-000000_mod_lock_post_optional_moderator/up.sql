@@ -0,0 +1,4 @@
+-- Automatic locks, eg after the close time of a post, are not made by any moderator
+ALTER TABLE mod_lock_post
+    ALTER COLUMN mod_person_id DROP NOT NULL;
+
@@ -0,0 +1,41 @@
+-- Featured posts in a community are sorted by their position before the main sort
+DROP INDEX idx_post_aggregates_featured_community_active;
+
+DROP INDEX idx_post_aggregates_featured_community_controversy;
+
+DROP INDEX idx_post_aggregates_featured_community_hot;
+
+DROP INDEX idx_post_aggregates_featured_community_most_comments;
+
+DROP INDEX idx_post_aggregates_featured_community_newest_comment_time;
+
+DROP INDEX idx_post_aggregates_featured_community_newest_comment_time_necr;
+
+DROP INDEX idx_post_aggregates_featured_community_published;
+
+DROP INDEX idx_post_aggregates_featured_community_published_asc;
+
+DROP INDEX idx_post_aggregates_featured_community_scaled;
+
+DROP INDEX idx_post_aggregates_featured_community_score;
+
+CREATE INDEX idx_post_aggregates_featured_community_active ON public.post_aggregates USING btree (community_id, featured_community DESC, featured_position DESC, hot_rank_active DESC, published DESC, post_id DESC);
+
+CREATE INDEX idx_post_aggregates_featured_community_controversy ON public.post_aggregates USING btree (community_id, featured_community DESC, featured_position DESC, controversy_rank DESC, post_id DESC);
+
+CREATE INDEX idx_post_aggregates_featured_community_hot ON public.post_aggregates USING btree (community_id, featured_community DESC, featured_position DESC, hot_rank DESC, published DESC, post_id DESC);
+
+CREATE INDEX idx_post_aggregates_featured_community_most_comments ON public.post_aggregates USING btree (community_id, featured_community DESC, featured_position DESC, comments DESC, published DESC, post_id DESC);
+
+CREATE INDEX idx_post_aggregates_featured_community_newest_comment_time ON public.post_aggregates USING btree (community_id, featured_community DESC, featured_position DESC, newest_comment_time DESC, post_id DESC);
+
+CREATE INDEX idx_post_aggregates_featured_community_newest_comment_time_necr ON public.post_aggregates USING btree (community_id, featured_community DESC, featured_position DESC, newest_comment_time_necro DESC, post_id DESC);
+
+CREATE INDEX idx_post_aggregates_featured_community_published ON public.post_aggregates USING btree (community_id, featured_community DESC, featured_position DESC, published DESC, post_id DESC);
+
+CREATE INDEX idx_post_aggregates_featured_community_published_asc ON public.post_aggregates USING btree (community_id, featured_community DESC, featured_position DESC, public.reverse_timestamp_sort (published) DESC, post_id DESC);
+
+CREATE INDEX idx_post_aggregates_featured_community_scaled ON public.post_aggregates USING btree (community_id, featured_community DESC, featured_position DESC, scaled_rank DESC, published DESC, post_id DESC);
+
+CREATE INDEX idx_post_aggregates_featured_community_score ON public.post_aggregates USING btree (community_id, featured_community DESC, featured_position DESC, score DESC, published DESC, post_id DESC);
+
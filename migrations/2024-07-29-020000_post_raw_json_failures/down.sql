@@ -0,0 +1,10 @@
+DROP TABLE post_raw_json;
+
+CREATE TABLE post_raw_json (
+    post_id int PRIMARY KEY REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE,
+    data text NOT NULL,
+    published timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_post_raw_json_published ON post_raw_json (published);
+
@@ -0,0 +1,16 @@
+-- Also keep the json of posts which failed to import, so rows are identified by the ap_id. Stored
+-- rows are only kept for a few days, so they are dropped.
+DROP TABLE post_raw_json;
+
+CREATE TABLE post_raw_json (
+    ap_id text PRIMARY KEY,
+    post_id int REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE,
+    data text NOT NULL,
+    error text,
+    published timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_post_raw_json_post ON post_raw_json (post_id);
+
+CREATE INDEX idx_post_raw_json_published ON post_raw_json (published);
+
@@ -0,0 +1,5 @@
+-- Marks posts which were removed because their community was in quarantine, so that approving the
+-- quarantine only restores these.
+ALTER TABLE post
+    ADD COLUMN quarantined boolean NOT NULL DEFAULT FALSE;
+
@@ -0,0 +1,6 @@
+DELETE FROM mod_remove_post
+WHERE mod_person_id IS NULL;
+
+ALTER TABLE mod_remove_post
+    ALTER COLUMN mod_person_id SET NOT NULL;
+
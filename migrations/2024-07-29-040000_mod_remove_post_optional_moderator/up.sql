@@ -0,0 +1,4 @@
+-- Automatic removals, eg by the community slur filter, are not made by any moderator
+ALTER TABLE mod_remove_post
+    ALTER COLUMN mod_person_id DROP NOT NULL;
+
@@ -0,0 +1,6 @@
+DELETE FROM post_report
+WHERE creator_id IS NULL;
+
+ALTER TABLE post_report
+    ALTER COLUMN creator_id SET NOT NULL;
+
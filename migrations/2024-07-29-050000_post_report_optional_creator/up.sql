@@ -0,0 +1,4 @@
+-- Automatic reports, eg for duplicate titles, are not made by any user
+ALTER TABLE post_report
+    ALTER COLUMN creator_id DROP NOT NULL;
+
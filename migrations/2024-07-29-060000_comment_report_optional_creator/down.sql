@@ -0,0 +1,6 @@
+DELETE FROM comment_report
+WHERE creator_id IS NULL;
+
+ALTER TABLE comment_report
+    ALTER COLUMN creator_id SET NOT NULL;
+
@@ -0,0 +1,4 @@
+-- Anonymous reports from other instances are sent by the instance actor, not by a user
+ALTER TABLE comment_report
+    ALTER COLUMN creator_id DROP NOT NULL;
+
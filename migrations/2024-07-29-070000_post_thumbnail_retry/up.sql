@@ -0,0 +1,9 @@
+-- Posts whose thumbnail couldn't be generated because pictrs was unavailable
+CREATE TABLE post_thumbnail_retry (
+    post_id int PRIMARY KEY REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE,
+    image_url text NOT NULL,
+    retry_at timestamptz NOT NULL
+);
+
+CREATE INDEX idx_post_thumbnail_retry_retry_at ON post_thumbnail_retry (retry_at);
+
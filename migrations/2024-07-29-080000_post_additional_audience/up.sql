@@ -0,0 +1,4 @@
+-- Further communities which a remote post is addressed to, besides the one it belongs to
+ALTER TABLE post
+    ADD COLUMN additional_audience text[] NOT NULL DEFAULT '{}';
+
@@ -4,6 +4,7 @@ use lemmy_api::{
     distinguish::distinguish_comment,
     like::like_comment,
     list_comment_likes::list_comment_likes,
+    pin::pin_comment,
     save::save_comment,
   },
   comment_report::{
@@ -13,10 +14,12 @@ use lemmy_api::{
   },
   community::{
     add_mod::add_mod_to_community,
+    approve_quarantine::approve_community_quarantine,
     ban::ban_from_community,
     block::block_community,
     follow::follow_community,
     hide::hide_community,
+    post_frequency::get_community_post_frequency,
     transfer::transfer_community,
   },
   local_user::{
@@ -27,6 +30,7 @@ use lemmy_api::{
     change_password_after_reset::change_password_after_reset,
     generate_totp_secret::generate_totp_secret,
     get_captcha::get_captcha,
+    get_my_activities::get_my_activities,
     list_banned::list_banned_users,
     list_logins::list_logins,
     list_media::list_media,
@@ -40,22 +44,32 @@ use lemmy_api::{
       mark_reply_read::mark_reply_as_read,
       unread_count::unread_count,
     },
+    post_communities::get_person_post_communities,
     report_count::report_count,
     reset_password::reset_password,
     save_settings::save_user_settings,
+    shadow_vote_person::shadow_vote_person,
     update_totp::update_totp,
     validate_auth::validate_auth,
     verify_email::verify_email,
   },
   post::{
+    distinguish::distinguish_post,
+    edit_diff::get_post_edit_diff,
     feature::feature_post,
+    get_by_instance::get_posts_by_instance,
     get_link_metadata::get_link_metadata,
     hide::hide_post,
+    instance_downvoted::get_instance_downvoted_posts,
     like::like_post,
     list_post_likes::list_post_likes,
     lock::lock_post,
     mark_read::mark_post_as_read,
+    move_post::move_post,
     save::save_post,
+    save_many::save_posts,
+    saved_ids::list_saved_post_ids,
+    vote_delta::get_post_vote_delta,
   },
   post_report::{
     create::create_post_report,
@@ -71,8 +85,10 @@ use lemmy_api::{
   site::{
     block::block_instance,
     federated_instances::get_federated_instances,
+    get_post_raw_json::get_post_raw_json,
     leave_admin::leave_admin,
     list_all_media::list_all_media,
+    list_failed_post_imports::list_failed_post_imports,
     mod_log::get_mod_log,
     purge::{
       comment::purge_comment,
@@ -80,11 +96,15 @@ use lemmy_api::{
       person::purge_person,
       post::purge_post,
     },
+    recompute_post_aggregate::recompute_post_aggregate,
     registration_applications::{
       approve::approve_registration_application,
       list::list_registration_applications,
       unread_count::get_unread_registration_application_count,
     },
+    render_markdown::render_markdown,
+    set_instance_vote_weight::set_instance_vote_weight,
+    test_federation::test_federation,
   },
   sitemap::get_sitemap,
 };
@@ -125,11 +145,17 @@ use lemmy_api_crud::{
   user::{create::register, delete::delete_account},
 };
 use lemmy_apub::api::{
+  backfill_community::backfill_community,
+  backfill_instance::backfill_instance,
+  export_post::export_post,
+  fetch_comment_replies::fetch_comment_replies,
   list_comments::list_comments,
   list_posts::list_posts,
   read_community::get_community,
   read_person::read_person,
+  reprocess_failed_post_import::reprocess_failed_post_import,
   resolve_object::resolve_object,
+  resolve_objects::resolve_objects,
   search::search,
   user_settings_backup::{export_settings, import_settings},
 };
@@ -148,7 +174,8 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           // Admin Actions
           .route("", web::post().to(create_site))
           .route("", web::put().to(update_site))
-          .route("/block", web::post().to(block_instance)),
+          .route("/block", web::post().to(block_instance))
+          .route("/render_markdown", web::post().to(render_markdown)),
       )
       .service(
         web::resource("/modlog")
@@ -165,6 +192,11 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .wrap(rate_limit.message())
           .route(web::get().to(resolve_object)),
       )
+      .service(
+        web::resource("/resolve_objects")
+          .wrap(rate_limit.search())
+          .route(web::post().to(resolve_objects)),
+      )
       // Community
       .service(
         web::resource("/community")
@@ -178,6 +210,10 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("", web::get().to(get_community))
           .route("", web::put().to(update_community))
           .route("/hide", web::put().to(hide_community))
+          .route(
+            "/approve_quarantine",
+            web::post().to(approve_community_quarantine),
+          )
           .route("/list", web::get().to(list_communities))
           .route("/follow", web::post().to(follow_community))
           .route("/block", web::post().to(block_community))
@@ -186,7 +222,11 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("/remove", web::post().to(remove_community))
           .route("/transfer", web::post().to(transfer_community))
           .route("/ban_user", web::post().to(ban_from_community))
-          .route("/mod", web::post().to(add_mod_to_community)),
+          .route("/mod", web::post().to(add_mod_to_community))
+          .route(
+            "/post_frequency",
+            web::get().to(get_community_post_frequency),
+          ),
       )
       .service(
         web::scope("/federated_instances")
@@ -212,10 +252,22 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("/hide", web::post().to(hide_post))
           .route("/lock", web::post().to(lock_post))
           .route("/feature", web::post().to(feature_post))
+          .route("/distinguish", web::post().to(distinguish_post))
+          .route("/export", web::get().to(export_post))
+          .route("/move", web::post().to(move_post))
           .route("/list", web::get().to(list_posts))
+          .route("/list_by_instance", web::get().to(get_posts_by_instance))
+          .route("/edit_diff", web::get().to(get_post_edit_diff))
+          .route(
+            "/downvoted_by_instance",
+            web::get().to(get_instance_downvoted_posts),
+          )
           .route("/like", web::post().to(like_post))
           .route("/like/list", web::get().to(list_post_likes))
+          .route("/like/delta", web::get().to(get_post_vote_delta))
           .route("/save", web::put().to(save_post))
+          .route("/save_many", web::put().to(save_posts))
+          .route("/saved_ids", web::get().to(list_saved_post_ids))
           .route("/report", web::post().to(create_post_report))
           .route("/report/resolve", web::put().to(resolve_post_report))
           .route("/report/list", web::get().to(list_post_reports))
@@ -238,10 +290,12 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("/remove", web::post().to(remove_comment))
           .route("/mark_as_read", web::post().to(mark_reply_as_read))
           .route("/distinguish", web::post().to(distinguish_comment))
+          .route("/pin", web::post().to(pin_comment))
           .route("/like", web::post().to(like_comment))
           .route("/like/list", web::get().to(list_comment_likes))
           .route("/save", web::put().to(save_comment))
           .route("/list", web::get().to(list_comments))
+          .route("/replies/fetch", web::get().to(fetch_comment_replies))
           .route("/report", web::post().to(create_comment_report))
           .route("/report/resolve", web::put().to(resolve_comment_report))
           .route("/report/list", web::get().to(list_comment_reports)),
@@ -304,7 +358,8 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
       .service(
         web::scope("/account")
           .wrap(rate_limit.message())
-          .route("/list_media", web::get().to(list_media)),
+          .route("/list_media", web::get().to(list_media))
+          .route("/activities", web::get().to(get_my_activities)),
       )
       // User actions
       .service(
@@ -317,6 +372,10 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
             web::post().to(mark_person_mention_as_read),
           )
           .route("/replies", web::get().to(list_replies))
+          .route(
+            "/post_communities",
+            web::get().to(get_person_post_communities),
+          )
           // Admin action. I don't like that it's in /user
           .route("/ban", web::post().to(ban_from_site))
           .route("/banned", web::get().to(list_banned_users))
@@ -349,6 +408,7 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
         web::scope("/admin")
           .wrap(rate_limit.message())
           .route("/add", web::post().to(add_admin))
+          .route("/shadow_vote", web::post().to(shadow_vote_person))
           .route(
             "/registration_application/count",
             web::get().to(get_unread_registration_application_count),
@@ -362,6 +422,26 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
             web::put().to(approve_registration_application),
           )
           .route("/list_all_media", web::get().to(list_all_media))
+          .route("/backfill_instance", web::post().to(backfill_instance))
+          .route("/backfill_community", web::post().to(backfill_community))
+          .route(
+            "/failed_post_import/list",
+            web::get().to(list_failed_post_imports),
+          )
+          .route(
+            "/failed_post_import/reprocess",
+            web::post().to(reprocess_failed_post_import),
+          )
+          .route("/post_raw_json", web::get().to(get_post_raw_json))
+          .route(
+            "/recompute_post_aggregate",
+            web::post().to(recompute_post_aggregate),
+          )
+          .route("/test_federation", web::get().to(test_federation))
+          .route(
+            "/instance_vote_weight",
+            web::put().to(set_instance_vote_weight),
+          )
           .service(
             web::scope("/purge")
               .route("/person", web::post().to(purge_person))
@@ -161,7 +161,7 @@ pub async fn start_lemmy_server(args: CmdArgs) -> LemmyResult<()> {
     SETTINGS.bind, SETTINGS.port
   );
 
-  let client = ClientBuilder::new(client_builder(&SETTINGS).build()?)
+  let client = ClientBuilder::new(client_builder(&SETTINGS, Some(&local_site)).build()?)
     .with(TracingMiddleware::default())
     .build();
   let context = LemmyContext::create(
@@ -171,11 +171,6 @@ pub async fn start_lemmy_server(args: CmdArgs) -> LemmyResult<()> {
     rate_limit_cell.clone(),
   );
 
-  let scheduled_tasks = (!args.disable_scheduled_tasks).then(|| {
-    // Schedules various cleanup tasks for the DB
-    tokio::task::spawn(scheduled_tasks::setup(context.clone()))
-  });
-
   if let Some(prometheus) = SETTINGS.prometheus.clone() {
     serve_prometheus(prometheus, context.clone())?;
   }
@@ -203,6 +198,11 @@ pub async fn start_lemmy_server(args: CmdArgs) -> LemmyResult<()> {
   let request_data = federation_config.to_request_data();
   let outgoing_activities_task = tokio::task::spawn(handle_outgoing_activities(request_data));
 
+  let scheduled_tasks = (!args.disable_scheduled_tasks).then(|| {
+    // Schedules various cleanup tasks for the DB
+    tokio::task::spawn(scheduled_tasks::setup(federation_config.clone()))
+  });
+
   let server = if !args.disable_http_server {
     if let Some(startup_server_handle) = startup_server_handle {
       startup_server_handle.stop(true).await;
@@ -300,7 +300,7 @@ fn create_http_server(
   let rate_limit_cell = federation_config.rate_limit_cell().clone();
 
   // Pictrs cannot use proxy
-  let pictrs_client = ClientBuilder::new(client_builder(&SETTINGS).no_proxy().build()?)
+  let pictrs_client = ClientBuilder::new(client_builder(&SETTINGS, None).no_proxy().build()?)
     .with(TracingMiddleware::default())
     .build();
 
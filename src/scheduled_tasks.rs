@@ -1,3 +1,4 @@
+use activitypub_federation::config::{Data, FederationConfig};
 use chrono::{DateTime, TimeZone, Utc};
 use clokwerk::{AsyncScheduler, TimeUnits as CTimeUnits};
 use diesel::{
@@ -6,36 +7,58 @@ use diesel::{
   sql_types::{Integer, Timestamptz},
   ExpressionMethods,
   NullableExpressionMethods,
+  OptionalExtension,
   QueryDsl,
   QueryableByName,
 };
 use diesel_async::{AsyncPgConnection, RunQueryDsl};
-use lemmy_api_common::context::LemmyContext;
+use lemmy_api_common::{
+  context::LemmyContext,
+  request::retry_pictrs_thumbnails,
+  send_activity::{ActivityChannel, SendActivityData},
+};
 use lemmy_db_schema::{
+  aggregates::structs::PostAggregates,
+  newtypes::{DbUrl, PostId},
   schema::{
     captcha_answer,
     comment,
+    comment_like,
     community_person_ban,
     instance,
+    mod_feature_post,
+    mod_lock_post,
     person,
     post,
+    post_like,
+    post_raw_json,
     received_activity,
     sent_activity,
   },
   source::{
+    comment::Comment,
+    community::Community,
+    failed_post_import::FailedPostImport,
     instance::{Instance, InstanceForm},
+    local_site::LocalSite,
     local_user::LocalUser,
+    moderator::{ModLockPost, ModLockPostForm},
+    person::Person,
+    post::Post,
+    post_pending_federation::PostPendingFederation,
   },
+  traits::Crud,
   utils::{get_conn, naive_now, now, DbPool, DELETED_REPLACEMENT_TEXT},
 };
 use lemmy_routes::nodeinfo::{NodeInfo, NodeInfoWellKnown};
-use lemmy_utils::error::LemmyResult;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
 use reqwest_middleware::ClientWithMiddleware;
-use std::time::Duration;
+use std::{ops::Deref, time::Duration};
 use tracing::{error, info, warn};
 
 /// Schedules various cleanup tasks for lemmy in a background thread
-pub async fn setup(context: LemmyContext) -> LemmyResult<()> {
+pub async fn setup(federation_config: FederationConfig<LemmyContext>) -> LemmyResult<()> {
+  let context = federation_config.deref().clone();
   // Setup the connections
   let mut scheduler = AsyncScheduler::new();
   startup_jobs(&mut context.pool()).await;
@@ -61,6 +84,57 @@ pub async fn setup(context: LemmyContext) -> LemmyResult<()> {
     }
   });
 
+  let context_1 = context.clone();
+  // Add votes of batched posts to the aggregates every 5 seconds
+  scheduler.every(CTimeUnits::seconds(5)).run(move || {
+    let context = context_1.clone();
+
+    async move {
+      flush_batched_votes(&mut context.pool())
+        .await
+        .map_err(|e| warn!("Failed to flush batched votes: {e}"))
+        .ok();
+    }
+  });
+
+  let federation_config_1 = federation_config.clone();
+  // Send Create activities of posts whose edit grace period is over, every ten seconds
+  scheduler.every(CTimeUnits::seconds(10)).run(move || {
+    let context = federation_config_1.to_request_data();
+
+    async move {
+      send_pending_posts(&context)
+        .await
+        .map_err(|e| warn!("Failed to send pending posts: {e}"))
+        .ok();
+    }
+  });
+
+  // Lock posts whose close time has passed, every minute
+  scheduler.every(CTimeUnits::minutes(1)).run(move || {
+    let context = federation_config.to_request_data();
+
+    async move {
+      lock_closed_posts(&context)
+        .await
+        .map_err(|e| warn!("Failed to lock closed posts: {e}"))
+        .ok();
+    }
+  });
+
+  let context_1 = context.clone();
+  // Retry thumbnail generation for posts where pictrs was unavailable, every minute
+  scheduler.every(CTimeUnits::minutes(1)).run(move || {
+    let context = context_1.clone();
+
+    async move {
+      retry_pictrs_thumbnails(&context)
+        .await
+        .map_err(|e| warn!("Failed to retry thumbnails: {e}"))
+        .ok();
+    }
+  });
+
   let context_1 = context.clone();
   // Delete any captcha answers older than ten minutes, every ten minutes
   scheduler.every(CTimeUnits::minutes(10)).run(move || {
@@ -85,6 +159,7 @@ pub async fn setup(context: LemmyContext) -> LemmyResult<()> {
   // Daily tasks:
   // - Overwrite deleted & removed posts and comments every day
   // - Delete old denied users
+  // - Delete old failed post imports
   // - Update instance software
   scheduler.every(CTimeUnits::days(1)).run(move || {
     let context = context_1.clone();
@@ -92,6 +167,7 @@ pub async fn setup(context: LemmyContext) -> LemmyResult<()> {
     async move {
       overwrite_deleted_posts_and_comments(&mut context.pool()).await;
       delete_old_denied_users(&mut context.pool()).await;
+      delete_old_failed_post_imports(&mut context.pool()).await;
       update_instance_software(&mut context.pool(), context.client())
         .await
         .map_err(|e| warn!("Failed to update instance software: {e}"))
@@ -309,9 +385,17 @@ async fn clear_old_activities(pool: &mut DbPool<'_>) {
       )
       .execute(&mut conn)
       .await
-      .map(|_| info!("Done."))
       .map_err(|e| error!("Failed to clear old received activities: {e}"))
       .ok();
+
+      diesel::delete(
+        post_raw_json::table.filter(post_raw_json::published.lt(now() - IntervalDsl::days(7))),
+      )
+      .execute(&mut conn)
+      .await
+      .map(|_| info!("Done."))
+      .map_err(|e| error!("Failed to clear old post raw json: {e}"))
+      .ok();
     }
     Err(e) => {
       error!("Failed to get connection from pool: {e}");
@@ -329,6 +413,17 @@ async fn delete_old_denied_users(pool: &mut DbPool<'_>) {
     .ok();
 }
 
+async fn delete_old_failed_post_imports(pool: &mut DbPool<'_>) {
+  info!("Deleting old failed post imports...");
+  FailedPostImport::delete_old(pool)
+    .await
+    .map(|_| {
+      info!("Done.");
+    })
+    .map_err(|e| error!("Failed to delete old failed post imports: {e}"))
+    .ok();
+}
+
 /// overwrite posts and comments 30d after deletion
 async fn overwrite_deleted_posts_and_comments(pool: &mut DbPool<'_>) {
   info!("Overwriting deleted posts...");
@@ -418,6 +513,160 @@ async fn active_counts(pool: &mut DbPool<'_>) {
   }
 }
 
+/// Updates which posts get batched vote updates, and adds their queued votes to the aggregates.
+async fn flush_batched_votes(pool: &mut DbPool<'_>) -> LemmyResult<()> {
+  // LocalSite may be missing before the setup is done
+  let threshold = LocalSite::read(pool)
+    .await
+    .ok()
+    .and_then(|l| l.vote_batching_threshold)
+    .filter(|t| *t > 0);
+  PostAggregates::update_vote_batching(pool, threshold).await?;
+  PostAggregates::flush_vote_queue(pool).await?;
+  Ok(())
+}
+
+/// Locks posts whose close time has passed. The locks are federated for posts in local communities.
+async fn lock_closed_posts(context: &Data<LemmyContext>) -> LemmyResult<()> {
+  for post in Post::lock_closed(&mut context.pool()).await? {
+    // The posts are already locked, so a failure must not prevent handling the others
+    let post_id = post.id;
+    if let Err(e) = log_and_send_close(post, context).await {
+      warn!("Failed to handle closing of post {post_id:?}: {e}");
+    }
+  }
+  Ok(())
+}
+
+async fn log_and_send_close(post: Post, context: &Data<LemmyContext>) -> LemmyResult<()> {
+  // No moderator is involved in the automatic lock
+  let form = ModLockPostForm {
+    mod_person_id: None,
+    post_id: post.id,
+    locked: Some(true),
+  };
+  ModLockPost::create(&mut context.pool(), &form).await?;
+  ActivityChannel::submit_activity(SendActivityData::ClosePost(post), context).await?;
+  Ok(())
+}
+
+/// Sends the Create activities of posts whose edit grace period is over. Activities for comments,
+/// votes, locks and features which were held back in the meantime are sent afterwards, based on
+/// the current state.
+async fn send_pending_posts(context: &Data<LemmyContext>) -> LemmyResult<()> {
+  for pending in PostPendingFederation::take_due(&mut context.pool()).await? {
+    // The entries are already removed, so a failure must not prevent sending the other posts
+    if let Err(e) = send_pending_post(pending.post_id, context).await {
+      warn!("Failed to send pending post {:?}: {e}", pending.post_id);
+    }
+  }
+  Ok(())
+}
+
+async fn send_pending_post(post_id: PostId, context: &Data<LemmyContext>) -> LemmyResult<()> {
+  let Some(post) = Post::read(&mut context.pool(), post_id).await? else {
+    return Ok(());
+  };
+  if post.deleted || post.removed {
+    return Ok(());
+  }
+  let community = Community::read(&mut context.pool(), post.community_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindCommunity)?;
+  let mut activities = vec![SendActivityData::CreatePost(post.clone())];
+  let pool = &mut context.pool();
+  let conn = &mut get_conn(pool).await?;
+
+  // In order of creation, so that parents are sent before their replies
+  let comments = comment::table
+    .filter(comment::post_id.eq(post.id))
+    .filter(comment::local.eq(true))
+    .filter(comment::deleted.eq(false))
+    .filter(comment::removed.eq(false))
+    .order_by(comment::id)
+    .load::<Comment>(conn)
+    .await?;
+  activities.extend(comments.into_iter().map(SendActivityData::CreateComment));
+
+  for (object_id, actor, score) in pending_votes(&post, conn).await? {
+    activities.push(SendActivityData::LikePostOrComment {
+      object_id,
+      actor,
+      community: community.clone(),
+      score,
+      replaces_vote: false,
+    });
+  }
+
+  if post.locked {
+    let moderator = mod_lock_post::table
+      .inner_join(person::table)
+      .filter(mod_lock_post::post_id.eq(post.id))
+      .order_by(mod_lock_post::id.desc())
+      .select(person::all_columns)
+      .first::<Person>(conn)
+      .await
+      .optional()?;
+    activities.push(match moderator {
+      Some(moderator) => SendActivityData::LockPost(post.clone(), moderator, true),
+      // Locked automatically after the close time
+      None => SendActivityData::ClosePost(post.clone()),
+    });
+  }
+  if post.featured_community {
+    let moderator = mod_feature_post::table
+      .inner_join(person::table)
+      .filter(mod_feature_post::post_id.eq(post.id))
+      .filter(mod_feature_post::is_featured_community.eq(true))
+      .order_by(mod_feature_post::id.desc())
+      .select(person::all_columns)
+      .first::<Person>(conn)
+      .await
+      .optional()?;
+    if let Some(moderator) = moderator {
+      activities.push(SendActivityData::FeaturePost(post.clone(), moderator, true));
+    }
+  }
+
+  for activity in activities {
+    ActivityChannel::submit_activity(activity, context).await?;
+  }
+  Ok(())
+}
+
+/// Votes on a pending post and its comments which need to be federated, as object id, voter and
+/// score. Votes of creators on their own content are included in the Create activities, and votes
+/// of shadow voters are never federated.
+async fn pending_votes(
+  post: &Post,
+  conn: &mut AsyncPgConnection,
+) -> LemmyResult<Vec<(DbUrl, Person, i16)>> {
+  let post_votes = post_like::table
+    .inner_join(person::table)
+    .filter(post_like::post_id.eq(post.id))
+    .filter(post_like::person_id.ne(post.creator_id))
+    .filter(person::local.eq(true))
+    .filter(person::shadow_vote.eq(false))
+    .select((person::all_columns, post_like::score))
+    .load::<(Person, i16)>(conn)
+    .await?
+    .into_iter()
+    .map(|(actor, score)| (post.ap_id.clone(), actor, score));
+  let comment_votes = comment_like::table
+    .inner_join(person::table)
+    .inner_join(comment::table)
+    .filter(comment_like::post_id.eq(post.id))
+    .filter(comment_like::person_id.ne(comment::creator_id))
+    .filter(person::local.eq(true))
+    .filter(person::shadow_vote.eq(false))
+    .filter(comment::deleted.eq(false))
+    .filter(comment::removed.eq(false))
+    .select((comment::ap_id, person::all_columns, comment_like::score))
+    .load::<(DbUrl, Person, i16)>(conn)
+    .await?;
+  Ok(post_votes.chain(comment_votes).collect())
+}
+
 /// Set banned to false after ban expires
 async fn update_banned_when_expired(pool: &mut DbPool<'_>) {
   info!("Updating banned column if it expires ...");
@@ -550,8 +799,19 @@ async fn build_update_instance_form(
 #[allow(clippy::indexing_slicing)]
 mod tests {
 
-  use crate::scheduled_tasks::build_update_instance_form;
+  use crate::scheduled_tasks::{build_update_instance_form, pending_votes};
   use lemmy_api_common::request::client_builder;
+  use lemmy_db_schema::{
+    source::{
+      comment::{Comment, CommentInsertForm, CommentLike, CommentLikeForm},
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm, PersonUpdateForm},
+      post::{Post, PostInsertForm, PostLike, PostLikeForm},
+    },
+    traits::{Crud, Likeable},
+    utils::{build_db_pool_for_tests, get_conn},
+  };
   use lemmy_utils::{error::LemmyResult, settings::structs::Settings, LemmyErrorType};
   use pretty_assertions::assert_eq;
   use reqwest_middleware::ClientBuilder;
@@ -560,7 +820,7 @@ mod tests {
   #[tokio::test]
   #[serial]
   async fn test_nodeinfo_lemmy_ml() -> LemmyResult<()> {
-    let client = ClientBuilder::new(client_builder(&Settings::default()).build()?).build();
+    let client = ClientBuilder::new(client_builder(&Settings::default(), None).build()?).build();
     let form = build_update_instance_form("lemmy.ml", &client)
       .await
       .ok_or(LemmyErrorType::CouldntFindObject)?;
@@ -574,7 +834,7 @@ mod tests {
   #[tokio::test]
   #[serial]
   async fn test_nodeinfo_mastodon_social() -> LemmyResult<()> {
-    let client = ClientBuilder::new(client_builder(&Settings::default()).build()?).build();
+    let client = ClientBuilder::new(client_builder(&Settings::default(), None).build()?).build();
     let form = build_update_instance_form("mastodon.social", &client)
       .await
       .ok_or(LemmyErrorType::CouldntFindObject)?;
@@ -584,4 +844,78 @@ mod tests {
     );
     Ok(())
   }
+  #[tokio::test]
+  #[serial]
+  async fn test_pending_votes() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let creator =
+      Person::create(pool, &PersonInsertForm::test_form(instance.id, "creator")).await?;
+    let voter = Person::create(pool, &PersonInsertForm::test_form(instance.id, "voter")).await?;
+    let shadow_voter =
+      Person::create(pool, &PersonInsertForm::test_form(instance.id, "shadow")).await?;
+    let form = PersonUpdateForm {
+      shadow_vote: Some(true),
+      ..Default::default()
+    };
+    Person::update(pool, shadow_voter.id, &form).await?;
+    let form = CommunityInsertForm::builder()
+      .name("test_pending_votes".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &form).await?;
+    let form = PostInsertForm::builder()
+      .name("A test post".into())
+      .creator_id(creator.id)
+      .community_id(community.id)
+      .build();
+    let post = Post::create(pool, &form).await?;
+    let form = CommentInsertForm::builder()
+      .content("A test comment".into())
+      .creator_id(creator.id)
+      .post_id(post.id)
+      .build();
+    let comment = Comment::create(pool, &form, None).await?;
+    for person in [&creator, &voter, &shadow_voter] {
+      let form = PostLikeForm {
+        post_id: post.id,
+        person_id: person.id,
+        score: 1,
+      };
+      PostLike::like(pool, &form).await?;
+      let form = CommentLikeForm {
+        person_id: person.id,
+        comment_id: comment.id,
+        post_id: post.id,
+        score: 1,
+      };
+      CommentLike::like(pool, &form).await?;
+    }
+
+    // Votes of the creator and of shadow voters are left out
+    let conn = &mut get_conn(pool).await?;
+    let votes: Vec<_> = pending_votes(&post, conn)
+      .await?
+      .into_iter()
+      .map(|(object_id, actor, score)| (object_id, actor.id, score))
+      .collect();
+    assert_eq!(
+      vec![
+        (post.ap_id.clone(), voter.id, 1),
+        (comment.ap_id.clone(), voter.id, 1)
+      ],
+      votes
+    );
+
+    Community::delete(pool, community.id).await?;
+    for person in [creator, voter, shadow_voter] {
+      Person::delete(pool, person.id).await?;
+    }
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
 }